@@ -274,6 +274,7 @@ pub enum ExprPrecedence {
 
     Array,
     Repeat,
+    ConstBlock,
     Tup,
     Lit,
     Path,
@@ -338,6 +339,7 @@ impl ExprPrecedence {
             // Never need parens
             ExprPrecedence::Array |
             ExprPrecedence::Repeat |
+            ExprPrecedence::ConstBlock |
             ExprPrecedence::Tup |
             ExprPrecedence::Lit |
             ExprPrecedence::Path |
@@ -1705,7 +1705,7 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
                 },
             )
         });
-        let init = l.init.as_ref().map(|e| self.lower_expr(e));
+        let init = l.kind.init().map(|e| self.lower_expr(e));
         (
             hir::Local {
                 hir_id: self.lower_node_id(l.id),
@@ -2301,29 +2301,146 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
     }
 
     fn lower_block_noalloc(&mut self, b: &Block, targeted_by_break: bool) -> hir::Block<'hir> {
+        let (stmts, expr) = self.lower_stmts(&b.stmts);
+        hir::Block {
+            hir_id: self.lower_node_id(b.id),
+            stmts,
+            expr,
+            rules: self.lower_block_check_mode(&b.rules),
+            span: b.span,
+            targeted_by_break,
+        }
+    }
+
+    fn lower_stmts(
+        &mut self,
+        ast_stmts: &[Stmt],
+    ) -> (&'hir [hir::Stmt<'hir>], Option<&'hir hir::Expr<'hir>>) {
         let mut stmts = vec![];
         let mut expr: Option<&'hir _> = None;
 
-        for (index, stmt) in b.stmts.iter().enumerate() {
-            if index == b.stmts.len() - 1 {
-                if let StmtKind::Expr(ref e) = stmt.kind {
+        for (index, stmt) in ast_stmts.iter().enumerate() {
+            match stmt.kind {
+                StmtKind::Local(ref local) if matches!(local.kind, LocalKind::InitElse(..)) => {
+                    // The remaining statements end up inside the desugared `let...else`.
+                    let (init_stmt, let_else) =
+                        self.lower_let_else(stmt, local, &ast_stmts[index + 1..]);
+                    stmts.extend(init_stmt);
+                    expr = Some(let_else);
+                    break;
+                }
+                StmtKind::Expr(ref e) if index == ast_stmts.len() - 1 => {
                     expr = Some(self.lower_expr(e));
-                } else {
-                    stmts.extend(self.lower_stmt(stmt));
                 }
-            } else {
-                stmts.extend(self.lower_stmt(stmt));
+                _ => stmts.extend(self.lower_stmt(stmt)),
             }
         }
 
-        hir::Block {
-            hir_id: self.lower_node_id(b.id),
-            stmts: self.arena.alloc_from_iter(stmts),
+        (self.arena.alloc_from_iter(stmts), expr)
+    }
+
+    /// Desugars `let PAT: TY = INIT else { ELSE }; STMTS` to:
+    ///
+    /// ```ignore (pseudo-rust)
+    /// let mut __let_else_init: TY = INIT;
+    /// match __let_else_init {
+    ///     PAT => { STMTS },
+    ///     _ => { let _: ! = { ELSE }; }
+    /// }
+    /// ```
+    ///
+    /// where `STMTS` are the statements following the `let...else` in the enclosing block.
+    /// Evaluating `INIT` in its own statement drops its temporaries at the end of the
+    /// `let...else`, as for a plain `let`, instead of keeping them alive for all of `STMTS`.
+    /// When `INIT` is a plain place, such as a local or one of its fields, there are no
+    /// temporaries, so it is matched on directly to let `PAT` borrow from it.
+    fn lower_let_else(
+        &mut self,
+        stmt: &Stmt,
+        local: &Local,
+        following: &[Stmt],
+    ) -> (Option<hir::Stmt<'hir>>, &'hir hir::Expr<'hir>) {
+        let (init, els) = match local.kind {
+            LocalKind::InitElse(ref init, ref els) => (init, els),
+            _ => panic!("`lower_let_else` called on a `let` without an `else` block"),
+        };
+        let span = self.mark_span_with_reason(DesugaringKind::LetElse, local.span, None);
+
+        let ty = local
+            .ty
+            .as_ref()
+            .map(|ty| self.lower_ty(ty, ImplTraitContext::Disallowed(ImplTraitPosition::Binding)));
+        let (init_stmt, scrutinee) = if is_plain_place(init) {
+            let mut scrutinee = self.lower_expr(init);
+            if let Some(ty) = ty {
+                let kind = hir::ExprKind::Type(scrutinee, ty);
+                scrutinee = self.arena.alloc(self.expr(init.span, kind, AttrVec::new()));
+            }
+            (None, scrutinee)
+        } else {
+            // `let mut __let_else_init: TY = INIT;`, mutable so that `PAT` may use `ref mut`.
+            let init_span = self.mark_span_with_reason(DesugaringKind::LetElse, init.span, None);
+            let ident = Ident::with_dummy_span(sym::__let_else_init);
+            let (init_pat, init_hir_id) =
+                self.pat_ident_binding_mode(init_span, ident, hir::BindingAnnotation::Mutable);
+            let init_local = hir::Local {
+                hir_id: self.next_id(),
+                ty,
+                pat: init_pat,
+                init: Some(self.lower_expr(init)),
+                span: init_span,
+                attrs: AttrVec::new(),
+                source: hir::LocalSource::Normal,
+            };
+            let init_stmt =
+                self.stmt(init_span, hir::StmtKind::Local(self.arena.alloc(init_local)));
+            (Some(init_stmt), self.expr_ident(init_span, ident, init_hir_id))
+        };
+        let pat = self.lower_pat(&local.pat);
+
+        // `PAT => { STMTS }`, with the bindings of `PAT` in scope for `STMTS`.
+        let (stmts, expr) = self.lower_stmts(following);
+        let then_span = following.iter().fold(stmt.span.shrink_to_hi(), |sp, s| sp.to(s.span));
+        let then_block = self.arena.alloc(hir::Block {
+            hir_id: self.lower_node_id(stmt.id),
+            stmts,
             expr,
-            rules: self.lower_block_check_mode(&b.rules),
-            span: b.span,
-            targeted_by_break,
-        }
+            rules: hir::BlockCheckMode::DefaultBlock,
+            span: then_span,
+            targeted_by_break: false,
+        });
+        let then_expr = self.arena.alloc(self.expr_block(then_block, AttrVec::new()));
+        let then_arm = self.arm(pat, then_expr);
+
+        // `_ => { let _: ! = { ELSE }; }`, checking that the `else` block diverges.
+        let else_span = self.mark_span_with_reason(DesugaringKind::LetElse, els.span, None);
+        let else_block = self.arena.alloc(self.lower_block_expr(els));
+        let never_ty = self.arena.alloc(self.ty(else_span, hir::TyKind::Never));
+        let never_local = hir::Local {
+            hir_id: self.next_id(),
+            ty: Some(never_ty),
+            pat: self.pat_wild(else_span),
+            init: Some(else_block),
+            span: else_span,
+            attrs: AttrVec::new(),
+            source: hir::LocalSource::LetElseDesugar,
+        };
+        let never_stmt = self.stmt(else_span, hir::StmtKind::Local(self.arena.alloc(never_local)));
+        let else_expr = {
+            let block = self.block_all(else_span, arena_vec![self; never_stmt], None);
+            self.arena.alloc(self.expr_block(block, AttrVec::new()))
+        };
+        let wild = self.pat_wild(span);
+        let else_arm = self.arm(wild, else_expr);
+
+        let arms = arena_vec![self; then_arm, else_arm];
+        let expr = self.arena.alloc(hir::Expr {
+            hir_id: self.lower_node_id(local.id),
+            kind: hir::ExprKind::Match(scrutinee, arms, hir::MatchSource::LetElseDesugar),
+            span,
+            attrs: local.attrs.clone(),
+        });
+        (init_stmt, expr)
     }
 
     /// Lowers a block directly to an expression, presuming that it
@@ -2717,6 +2834,17 @@ fn body_ids(bodies: &BTreeMap<hir::BodyId, hir::Body<'_>>) -> Vec<hir::BodyId> {
     body_ids
 }
 
+/// Whether `e` is a place expression that cannot create temporaries, such as `x`, `x.0` or `*x`.
+fn is_plain_place(e: &Expr) -> bool {
+    match e.kind {
+        ExprKind::Path(..) => true,
+        ExprKind::Field(ref base, _)
+        | ExprKind::Paren(ref base)
+        | ExprKind::Unary(UnOp::Deref, ref base) => is_plain_place(base),
+        _ => false,
+    }
+}
+
 /// Helper struct for delayed construction of GenericArgs.
 struct GenericArgsCtor<'hir> {
     args: SmallVec<[hir::GenericArg<'hir>; 4]>,
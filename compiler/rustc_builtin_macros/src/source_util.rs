@@ -7,6 +7,7 @@ use rustc_expand::base::{self, *};
 use rustc_expand::module::DirectoryOwnership;
 use rustc_parse::{self, new_parser_from_file, parser::Parser};
 use rustc_session::lint::builtin::INCOMPLETE_INCLUDE;
+use rustc_span::source_map::RemapPathScopes;
 use rustc_span::symbol::Symbol;
 use rustc_span::{self, Pos, Span};
 
@@ -62,7 +63,8 @@ pub fn expand_file(
 
     let topmost = cx.expansion_cause().unwrap_or(sp);
     let loc = cx.source_map().lookup_char_pos(topmost.lo());
-    base::MacEager::expr(cx.expr_str(topmost, Symbol::intern(&loc.file.name.to_string())))
+    let file = cx.source_map().filename_for_scope(&loc.file, RemapPathScopes::MACRO);
+    base::MacEager::expr(cx.expr_str(topmost, Symbol::intern(&file.to_string())))
 }
 
 pub fn expand_stringify(
@@ -0,0 +1,261 @@
+use crate::builder::Builder;
+use crate::context::CodegenCx;
+use crate::type_::Type;
+use crate::type_of::LayoutClifExt;
+use crate::value::Value;
+
+use cranelift_codegen::ir::{AbiParam, ArgumentExtension, Signature};
+use rustc_codegen_ssa::mir::operand::OperandValue;
+use rustc_codegen_ssa::mir::place::PlaceRef;
+use rustc_codegen_ssa::traits::*;
+use rustc_codegen_ssa::MemFlags;
+use rustc_middle::bug;
+use rustc_middle::ty::Ty;
+pub use rustc_target::abi::call::*;
+use rustc_target::abi::LayoutOf;
+
+pub trait CastTargetExt {
+    fn clif_type(&self, cx: &CodegenCx<'_>) -> Type;
+}
+
+impl CastTargetExt for CastTarget {
+    fn clif_type(&self, cx: &CodegenCx<'_>) -> Type {
+        let rest_unit = cx.reg_backend_type(&self.rest.unit);
+        let (rest_count, rem_bytes) = if self.rest.unit.size.bytes() == 0 {
+            (0, 0)
+        } else {
+            (
+                self.rest.total.bytes() / self.rest.unit.size.bytes(),
+                self.rest.total.bytes() % self.rest.unit.size.bytes(),
+            )
+        };
+
+        if self.prefix.iter().all(|x| x.is_none()) {
+            // Simplify to a single unit when there is no prefix and size <= unit size
+            if self.rest.total <= self.rest.unit.size {
+                return rest_unit;
+            }
+
+            // Simplify to array when all chunks are the same size and type
+            if rem_bytes == 0 {
+                return cx.type_array(rest_unit, rest_count);
+            }
+        }
+
+        // Create list of fields in the main structure
+        let mut args: Vec<_> = self
+            .prefix
+            .iter()
+            .flat_map(|option_kind| {
+                option_kind.map(|kind| cx.reg_backend_type(&Reg { kind, size: self.prefix_chunk }))
+            })
+            .chain((0..rest_count).map(|_| rest_unit))
+            .collect();
+
+        // Append final integer
+        if rem_bytes != 0 {
+            // Only integers can be really split further.
+            assert_eq!(self.rest.unit.kind, RegKind::Integer);
+            args.push(cx.type_ix(rem_bytes * 8));
+        }
+
+        cx.type_struct(&args, false)
+    }
+}
+
+pub trait ArgAbiExt<'tcx> {
+    fn memory_ty(&self, cx: &CodegenCx<'tcx>) -> Type;
+    fn store(&self, bx: &mut Builder<'_, 'tcx>, val: Value, dst: PlaceRef<'tcx, Value>);
+    fn store_fn_arg(&self, bx: &mut Builder<'_, 'tcx>, idx: &mut usize, dst: PlaceRef<'tcx, Value>);
+}
+
+impl ArgAbiExt<'tcx> for ArgAbi<'tcx, Ty<'tcx>> {
+    /// Gets the backend type for a place of the original Rust type of
+    /// this argument/return, i.e., the result of `type_of::type_of`.
+    fn memory_ty(&self, cx: &CodegenCx<'tcx>) -> Type {
+        self.layout.clif_type(cx)
+    }
+
+    /// Stores a direct/indirect value described by this ArgAbi into a
+    /// place for the original Rust type of this argument/return.
+    /// Can be used for both storing formal arguments into Rust variables
+    /// or results of call/invoke instructions into their destinations.
+    fn store(&self, bx: &mut Builder<'_, 'tcx>, val: Value, dst: PlaceRef<'tcx, Value>) {
+        if self.is_ignore() {
+            return;
+        }
+        if self.is_sized_indirect() {
+            OperandValue::Ref(val, None, self.layout.align.abi).store(bx, dst)
+        } else if self.is_unsized_indirect() {
+            bug!("unsized `ArgAbi` must be handled through `store_fn_arg`");
+        } else if let PassMode::Cast(cast) = self.mode {
+            // The cast type may be larger than the Rust type, so store it into a scratch
+            // slot of the right size first and only copy the bytes the Rust type covers.
+            let scratch_size = cast.size(bx);
+            let scratch_align = cast.align(bx);
+            let scratch = bx.alloca(cast.clif_type(bx), scratch_align);
+            bx.lifetime_start(scratch, scratch_size);
+            bx.store(val, scratch, scratch_align);
+            bx.memcpy(
+                dst.llval,
+                self.layout.align.abi,
+                scratch,
+                scratch_align,
+                bx.const_usize(self.layout.size.bytes()),
+                MemFlags::empty(),
+            );
+            bx.lifetime_end(scratch, scratch_size);
+        } else {
+            OperandValue::Immediate(val).store(bx, dst);
+        }
+    }
+
+    fn store_fn_arg(
+        &self,
+        bx: &mut Builder<'_, 'tcx>,
+        idx: &mut usize,
+        dst: PlaceRef<'tcx, Value>,
+    ) {
+        let mut next = || {
+            let val = bx.get_param(*idx);
+            *idx += 1;
+            val
+        };
+        match self.mode {
+            PassMode::Ignore => {}
+            PassMode::Pair(..) => {
+                OperandValue::Pair(next(), next()).store(bx, dst);
+            }
+            PassMode::Indirect(_, Some(_)) => {
+                OperandValue::Ref(next(), Some(next()), self.layout.align.abi).store(bx, dst);
+            }
+            PassMode::Direct(_) | PassMode::Indirect(_, None) | PassMode::Cast(_) => {
+                let next_arg = next();
+                self.store(bx, next_arg, dst);
+            }
+        }
+    }
+}
+
+impl ArgAbiMethods<'tcx> for Builder<'a, 'tcx> {
+    fn store_fn_arg(
+        &mut self,
+        arg_abi: &ArgAbi<'tcx, Ty<'tcx>>,
+        idx: &mut usize,
+        dst: PlaceRef<'tcx, Self::Value>,
+    ) {
+        arg_abi.store_fn_arg(self, idx, dst)
+    }
+    fn store_arg(
+        &mut self,
+        arg_abi: &ArgAbi<'tcx, Ty<'tcx>>,
+        val: Value,
+        dst: PlaceRef<'tcx, Value>,
+    ) {
+        arg_abi.store(self, val, dst)
+    }
+    fn arg_memory_ty(&self, arg_abi: &ArgAbi<'tcx, Ty<'tcx>>) -> Type {
+        arg_abi.memory_ty(self)
+    }
+}
+
+/// The backend types of the arguments or return values of a function, along with the
+/// integer extension the ABI requires for them.
+type ClifArgs = Vec<(Type, ArgumentExtension)>;
+
+fn extension(attrs: &ArgAttributes) -> ArgumentExtension {
+    if attrs.regular.contains(ArgAttribute::ZExt) {
+        ArgumentExtension::Uext
+    } else if attrs.regular.contains(ArgAttribute::SExt) {
+        ArgumentExtension::Sext
+    } else {
+        ArgumentExtension::None
+    }
+}
+
+pub trait FnAbiClifExt<'tcx> {
+    fn clif_args(&self, cx: &CodegenCx<'tcx>) -> (ClifArgs, (Type, ArgumentExtension));
+    fn clif_type(&self, cx: &CodegenCx<'tcx>) -> Type;
+    fn clif_signature(&self, cx: &CodegenCx<'tcx>) -> Signature;
+}
+
+impl<'tcx> FnAbiClifExt<'tcx> for FnAbi<'tcx, Ty<'tcx>> {
+    fn clif_args(&self, cx: &CodegenCx<'tcx>) -> (ClifArgs, (Type, ArgumentExtension)) {
+        let mut args = Vec::with_capacity(self.args.len() + 1);
+        let no_ext = ArgumentExtension::None;
+
+        let ret = match self.ret.mode {
+            PassMode::Ignore => (cx.type_void(), no_ext),
+            PassMode::Direct(ref attrs) => {
+                (self.ret.layout.immediate_clif_type(cx), extension(attrs))
+            }
+            PassMode::Pair(..) => (self.ret.layout.immediate_clif_type(cx), no_ext),
+            PassMode::Cast(cast) => (cast.clif_type(cx), no_ext),
+            PassMode::Indirect(..) => {
+                args.push((cx.type_ptr_to(self.ret.memory_ty(cx)), no_ext));
+                (cx.type_void(), no_ext)
+            }
+        };
+
+        for arg in &self.args {
+            // add padding
+            if let Some(ty) = arg.pad {
+                args.push((cx.reg_backend_type(&ty), no_ext));
+            }
+
+            match arg.mode {
+                PassMode::Ignore => {}
+                PassMode::Direct(ref attrs) => {
+                    args.push((arg.layout.immediate_clif_type(cx), extension(attrs)));
+                }
+                PassMode::Pair(..) => {
+                    for i in 0..2 {
+                        let ty = arg.layout.scalar_pair_element_clif_type(cx, i, true);
+                        args.push((ty, no_ext));
+                    }
+                }
+                PassMode::Indirect(_, Some(_)) => {
+                    let ptr_layout = cx.layout_of(cx.tcx.mk_mut_ptr(arg.layout.ty));
+                    for i in 0..2 {
+                        let ty = ptr_layout.scalar_pair_element_clif_type(cx, i, true);
+                        args.push((ty, no_ext));
+                    }
+                }
+                PassMode::Cast(cast) => args.push((cast.clif_type(cx), no_ext)),
+                PassMode::Indirect(_, None) => {
+                    args.push((cx.type_ptr_to(arg.memory_ty(cx)), no_ext));
+                }
+            }
+        }
+
+        (args, ret)
+    }
+
+    fn clif_type(&self, cx: &CodegenCx<'tcx>) -> Type {
+        let (args, (ret, _)) = self.clif_args(cx);
+        let args: Vec<_> = args.into_iter().map(|(ty, _)| ty).collect();
+        if self.c_variadic { cx.type_variadic_func(&args, ret) } else { cx.type_func(&args, ret) }
+    }
+
+    /// The Cranelift signature of this function. Unlike `CodegenCx::signature`, this applies
+    /// the integer extensions the C calling convention requires for small integers.
+    fn clif_signature(&self, cx: &CodegenCx<'tcx>) -> Signature {
+        let (args, ret) = self.clif_args(cx);
+        let mut sig = Signature::new(cx.module.borrow().isa().default_call_conv());
+        let params = |(ty, extension): (Type, ArgumentExtension)| {
+            let leaves = cx.clif_leaves(ty).into_iter();
+            leaves.map(move |leaf| AbiParam { extension, ..AbiParam::new(leaf) })
+        };
+        sig.params.extend(args.into_iter().flat_map(params));
+        sig.returns.extend(params(ret));
+        sig
+    }
+}
+
+impl AbiBuilderMethods<'tcx> for Builder<'a, 'tcx> {
+    fn apply_attrs_callsite(&mut self, _fn_abi: &FnAbi<'tcx, Ty<'tcx>>, _callsite: Self::Value) {}
+
+    fn get_param(&self, index: usize) -> Self::Value {
+        self.cx.bodies.borrow()[&self.func()].params[index]
+    }
+}
@@ -0,0 +1,72 @@
+use crate::context::new_module;
+use crate::ModuleClif;
+
+use cranelift_codegen::binemit::NullTrapSink;
+use cranelift_codegen::cursor::{Cursor, FuncCursor};
+use cranelift_codegen::ir::{self, AbiParam, ExternalName, InstBuilder, Signature};
+use cranelift_codegen::Context;
+use cranelift_module::{Linkage, Module};
+use rustc_ast::expand::allocator::{AllocatorKind, AllocatorTy, ALLOCATOR_METHODS};
+use rustc_middle::ty::TyCtxt;
+
+/// Defines the `__rust_*` allocator shims, which forward to the functions of `kind`.
+pub fn codegen(tcx: TyCtxt<'_>, mods: &mut ModuleClif, kind: AllocatorKind) {
+    let mut module = new_module(tcx.sess, &mods.name);
+    let usize = module.target_config().pointer_type();
+    let call_conv = module.isa().default_call_conv();
+
+    for method in ALLOCATOR_METHODS {
+        let mut sig = Signature::new(call_conv);
+        for ty in method.inputs.iter() {
+            match *ty {
+                AllocatorTy::Layout => {
+                    sig.params.push(AbiParam::new(usize)); // size
+                    sig.params.push(AbiParam::new(usize)); // align
+                }
+                AllocatorTy::Ptr | AllocatorTy::Usize => sig.params.push(AbiParam::new(usize)),
+
+                AllocatorTy::ResultPtr | AllocatorTy::Unit => panic!("invalid allocator arg"),
+            }
+        }
+        match method.output {
+            AllocatorTy::ResultPtr => sig.returns.push(AbiParam::new(usize)),
+            AllocatorTy::Unit => {}
+
+            AllocatorTy::Layout | AllocatorTy::Usize | AllocatorTy::Ptr => {
+                panic!("invalid allocator output")
+            }
+        }
+
+        let name = format!("__rust_{}", method.name);
+        let linkage = if tcx.sess.target.target.options.default_hidden_visibility {
+            Linkage::Hidden
+        } else {
+            Linkage::Export
+        };
+        let func_id = module.declare_function(&name, linkage, &sig).unwrap();
+        let callee = kind.fn_name(method.name);
+        let callee_id = module.declare_function(&callee, Linkage::Import, &sig).unwrap();
+
+        let mut func =
+            ir::Function::with_name_signature(ExternalName::user(0, func_id.as_u32()), sig);
+        let block = func.dfg.make_block();
+        func.dfg.append_block_params_for_function_params(block);
+        let callee_ref = module.declare_func_in_func(callee_id, &mut func);
+
+        let mut pos = FuncCursor::new(&mut func);
+        pos.insert_block(block);
+        let args = pos.func.dfg.block_params(block).to_vec();
+        let call = pos.ins().call(callee_ref, &args);
+        let results = pos.func.dfg.inst_results(call).to_vec();
+        pos.ins().return_(&results);
+
+        let mut ctx = Context::for_function(func);
+        if let Err(e) = module.define_function(func_id, &mut ctx, &mut NullTrapSink {}) {
+            tcx.sess.fatal(&format!("failed to compile `{}`: {}", name, e));
+        }
+    }
+
+    mods.object = module.finish().emit().unwrap_or_else(|e| {
+        tcx.sess.fatal(&format!("failed to emit allocator object file: {}", e))
+    });
+}
@@ -0,0 +1,54 @@
+//! Inline assembly.
+//!
+//! Cranelift has no assembler, so inline assembly is rejected. The only exception is the
+//! empty `llvm_asm!` that `core::hint::black_box` and friends use as an optimization barrier,
+//! which Cranelift does not need.
+
+use crate::builder::Builder;
+use crate::context::CodegenCx;
+use crate::value::Value;
+
+use rustc_ast::{InlineAsmOptions, InlineAsmTemplatePiece};
+use rustc_codegen_ssa::mir::place::PlaceRef;
+use rustc_codegen_ssa::traits::*;
+use rustc_hir as hir;
+use rustc_span::{Span, DUMMY_SP};
+
+impl AsmBuilderMethods<'tcx> for Builder<'a, 'tcx> {
+    fn codegen_llvm_inline_asm(
+        &mut self,
+        ia: &hir::LlvmInlineAsmInner,
+        outputs: Vec<PlaceRef<'tcx, Value>>,
+        _inputs: Vec<Value>,
+        span: Span,
+    ) -> bool {
+        if ia.asm.as_str().trim().is_empty() && outputs.is_empty() {
+            return true;
+        }
+        self.sess().span_fatal(span, "inline assembly is not supported by the Cranelift backend")
+    }
+
+    fn codegen_inline_asm(
+        &mut self,
+        template: &[InlineAsmTemplatePiece],
+        operands: &[InlineAsmOperandRef<'tcx, Self>],
+        _options: InlineAsmOptions,
+        line_spans: &[Span],
+    ) {
+        let is_empty = template.iter().all(|piece| match piece {
+            InlineAsmTemplatePiece::String(s) => s.trim().is_empty(),
+            InlineAsmTemplatePiece::Placeholder { .. } => false,
+        });
+        if is_empty && operands.is_empty() {
+            return;
+        }
+        let span = line_spans.first().copied().unwrap_or(DUMMY_SP);
+        self.sess().span_fatal(span, "inline assembly is not supported by the Cranelift backend")
+    }
+}
+
+impl AsmMethods for CodegenCx<'tcx> {
+    fn codegen_global_asm(&self, _ga: &hir::GlobalAsm) {
+        self.sess().fatal("`global_asm!` is not supported by the Cranelift backend")
+    }
+}
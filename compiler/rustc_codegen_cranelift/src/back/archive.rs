@@ -0,0 +1,195 @@
+//! A helper class for dealing with static archives, written with the `ar` crate as there is no
+//! LLVM to do it for us.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use rustc_codegen_ssa::back::archive::{find_library, ArchiveBuilder};
+use rustc_codegen_ssa::{looks_like_rust_object_file, METADATA_FILENAME};
+use rustc_session::Session;
+use rustc_span::symbol::Symbol;
+
+/// Helper for adding many files to an archive.
+///
+/// The members are read into memory as they are added, and written out by `build`.
+#[must_use = "must call build() to finish building the archive"]
+pub struct ArArchiveBuilder<'a> {
+    sess: &'a Session,
+    dst: PathBuf,
+    lib_search_paths: Vec<PathBuf>,
+    entries: Vec<(String, Vec<u8>)>,
+    should_update_symbols: bool,
+}
+
+fn read_archive(
+    path: &Path,
+    mut f: impl FnMut(String, Vec<u8>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut archive = ar::Archive::new(File::open(path)?);
+    while let Some(entry) = archive.next_entry() {
+        let mut entry = entry?;
+        let name = String::from_utf8(entry.header().identifier().to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // Skip the symbol table, it is regenerated by `build` if needed.
+        if name.contains("SYMDEF") || name == "/" || name == "//" {
+            continue;
+        }
+        let mut data = Vec::with_capacity(entry.header().size() as usize);
+        entry.read_to_end(&mut data)?;
+        f(name, data)?;
+    }
+    Ok(())
+}
+
+impl ArArchiveBuilder<'_> {
+    fn add_archive<F>(&mut self, archive: &Path, mut skip: F) -> io::Result<()>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let entries = &mut self.entries;
+        read_archive(archive, |name, data| {
+            if !skip(&name) {
+                entries.push((name, data));
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<'a> ArchiveBuilder<'a> for ArArchiveBuilder<'a> {
+    /// Creates a new static archive, ready for modifying the archive specified
+    /// by `config`.
+    fn new(sess: &'a Session, output: &Path, input: Option<&Path>) -> ArArchiveBuilder<'a> {
+        use rustc_codegen_ssa::back::link::archive_search_paths;
+        let mut builder = ArArchiveBuilder {
+            sess,
+            dst: output.to_path_buf(),
+            lib_search_paths: archive_search_paths(sess),
+            entries: Vec::new(),
+            should_update_symbols: false,
+        };
+        if let Some(input) = input {
+            builder.add_archive(input, |_| false).unwrap_or_else(|e| {
+                sess.fatal(&format!("failed to read archive `{}`: {}", input.display(), e))
+            });
+        }
+        builder
+    }
+
+    /// Removes a file from this archive
+    fn remove_file(&mut self, file: &str) {
+        self.entries.retain(|(name, _)| name != file);
+    }
+
+    /// Lists all files in an archive
+    fn src_files(&mut self) -> Vec<String> {
+        self.entries.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    fn iter<F>(&mut self, mut f: F) -> io::Result<()>
+    where
+        F: FnMut(&str, &[u8]) -> io::Result<()>,
+        Self: Sized,
+    {
+        for (name, data) in &self.entries {
+            f(name, data)?;
+        }
+        Ok(())
+    }
+
+    /// Adds all of the contents of a native library to this archive. This will
+    /// search in the relevant locations for a library named `name`.
+    fn add_native_library(&mut self, name: Symbol) {
+        let location = find_library(name, &self.lib_search_paths, self.sess);
+        self.add_archive(&location, |_| false).unwrap_or_else(|e| {
+            self.sess.fatal(&format!(
+                "failed to add native library {}: {}",
+                location.to_string_lossy(),
+                e
+            ));
+        });
+    }
+
+    /// Adds all of the contents of the rlib at the specified path to this
+    /// archive.
+    ///
+    /// This ignores adding the metadata from the rlib, and if LTO is enabled
+    /// then the object file also isn't added.
+    fn add_rlib(
+        &mut self,
+        rlib: &Path,
+        name: &str,
+        lto: bool,
+        skip_objects: bool,
+    ) -> io::Result<()> {
+        // Ignoring obj file starting with the crate name
+        // as simple comparison is not enough - there
+        // might be also an extra name suffix
+        let obj_start = name.to_owned();
+
+        self.add_archive(rlib, move |fname: &str| {
+            // Ignore metadata files, no matter the name.
+            if fname == METADATA_FILENAME {
+                return true;
+            }
+
+            // Don't include Rust objects if LTO is enabled
+            if lto && looks_like_rust_object_file(fname) {
+                return true;
+            }
+
+            // Otherwise if this is *not* a rust object and we're skipping
+            // objects then skip this file
+            if skip_objects && (!fname.starts_with(&obj_start) || !fname.ends_with(".o")) {
+                return true;
+            }
+
+            // ok, don't skip this
+            false
+        })
+    }
+
+    /// Adds an arbitrary file to this archive
+    fn add_file(&mut self, file: &Path) {
+        let name = file.file_name().unwrap().to_str().unwrap().to_owned();
+        let data = std::fs::read(file).unwrap_or_else(|e| {
+            self.sess.fatal(&format!("failed to read `{}`: {}", file.display(), e))
+        });
+        self.entries.push((name, data));
+    }
+
+    /// Indicate that the next call to `build` should update all symbols in
+    /// the archive (equivalent to running 'ar s' over it).
+    fn update_symbols(&mut self) {
+        self.should_update_symbols = true;
+    }
+
+    /// Combine the provided files, rlibs, and native libraries into a single
+    /// `Archive`.
+    fn build(self) {
+        let sess = self.sess;
+        let write = || -> io::Result<()> {
+            let identifiers = self.entries.iter().map(|(name, _)| name.as_bytes().to_vec());
+            let mut builder = ar::GnuBuilder::new(File::create(&self.dst)?, identifiers.collect());
+            for (name, data) in &self.entries {
+                let header = ar::Header::new(name.as_bytes().to_vec(), data.len() as u64);
+                builder.append(&header, &data[..])?;
+            }
+            Ok(())
+        };
+        write().unwrap_or_else(|e| {
+            sess.fatal(&format!("failed to build archive `{}`: {}", self.dst.display(), e))
+        });
+
+        // The `ar` crate can't write a symbol table, so leave that to `ranlib`.
+        if self.should_update_symbols {
+            match Command::new("ranlib").arg(&self.dst).status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => sess.fatal(&format!("`ranlib` failed with {}", status)),
+                Err(e) => sess.fatal(&format!("failed to run `ranlib`: {}", e)),
+            }
+        }
+    }
+}
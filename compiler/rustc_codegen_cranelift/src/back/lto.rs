@@ -0,0 +1,88 @@
+//! LTO for the Cranelift backend.
+//!
+//! There is no IR to optimize across modules, so only the ThinLTO that is enabled by default
+//! for optimized builds with several codegen units is supported, and it passes every module
+//! through unchanged. Fat LTO and `-C lto=thin` are rejected by `ClifCodegenBackend::init`.
+
+use crate::ClifCodegenBackend;
+use crate::ModuleClif;
+
+use rustc_codegen_ssa::back::lto::{LtoModuleCodegen, SerializedModule, ThinModule, ThinShared};
+use rustc_codegen_ssa::back::write::{CodegenContext, FatLTOInput};
+use rustc_codegen_ssa::traits::*;
+use rustc_codegen_ssa::{ModuleCodegen, ModuleKind};
+use rustc_errors::FatalError;
+use rustc_middle::dep_graph::WorkProduct;
+
+use std::ffi::CString;
+use std::sync::Arc;
+
+/// The object file of a module, see `ModuleClif`.
+pub struct ModuleBuffer(Vec<u8>);
+
+impl ModuleBufferMethods for ModuleBuffer {
+    fn data(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+pub struct ThinBuffer(Vec<u8>);
+
+impl ThinBufferMethods for ThinBuffer {
+    fn data(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+crate fn prepare_thin(module: ModuleCodegen<ModuleClif>) -> (String, ThinBuffer) {
+    (module.name, ThinBuffer(module.module_llvm.object))
+}
+
+crate fn serialize_module(module: ModuleCodegen<ModuleClif>) -> (String, ModuleBuffer) {
+    (module.name, ModuleBuffer(module.module_llvm.object))
+}
+
+crate fn run_fat(
+    cgcx: &CodegenContext<ClifCodegenBackend>,
+    _modules: Vec<FatLTOInput<ClifCodegenBackend>>,
+    _cached_modules: Vec<(SerializedModule<ModuleBuffer>, WorkProduct)>,
+) -> Result<LtoModuleCodegen<ClifCodegenBackend>, FatalError> {
+    let diag_handler = cgcx.create_diag_handler();
+    diag_handler.err("fat LTO is not supported by the Cranelift backend");
+    Err(FatalError)
+}
+
+/// Turns every module, including the ones from the incremental cache, into a `ThinModule`
+/// that `optimize_thin` returns as is.
+crate fn run_thin(
+    _cgcx: &CodegenContext<ClifCodegenBackend>,
+    modules: Vec<(String, ThinBuffer)>,
+    cached_modules: Vec<(SerializedModule<ModuleBuffer>, WorkProduct)>,
+) -> Result<(Vec<LtoModuleCodegen<ClifCodegenBackend>>, Vec<WorkProduct>), FatalError> {
+    let mut thin_buffers = Vec::with_capacity(modules.len());
+    let mut module_names = Vec::with_capacity(modules.len() + cached_modules.len());
+    for (name, buffer) in modules {
+        module_names.push(CString::new(name).unwrap());
+        thin_buffers.push(buffer);
+    }
+    let mut serialized_modules = Vec::with_capacity(cached_modules.len());
+    for (module, wp) in cached_modules {
+        module_names.push(CString::new(wp.cgu_name).unwrap());
+        serialized_modules.push(module);
+    }
+
+    let shared = Arc::new(ThinShared { data: (), thin_buffers, serialized_modules, module_names });
+    let modules = (0..shared.module_names.len())
+        .map(|idx| LtoModuleCodegen::Thin(ThinModule { shared: shared.clone(), idx }))
+        .collect();
+    Ok((modules, Vec::new()))
+}
+
+crate fn optimize_thin_module(
+    thin_module: &mut ThinModule<ClifCodegenBackend>,
+) -> Result<ModuleCodegen<ModuleClif>, FatalError> {
+    let name = thin_module.name().to_string();
+    let object = thin_module.data().to_vec();
+    let module_llvm = ModuleClif { name: name.clone(), object };
+    Ok(ModuleCodegen { name, module_llvm, kind: ModuleKind::Regular })
+}
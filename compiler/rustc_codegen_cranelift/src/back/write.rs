@@ -0,0 +1,51 @@
+use crate::ClifCodegenBackend;
+use crate::ModuleClif;
+
+use rustc_codegen_ssa::back::write::{CodegenContext, EmitObj, ModuleConfig, TargetMachineFactoryFn};
+use rustc_codegen_ssa::{CompiledModule, ModuleCodegen};
+use rustc_errors::{FatalError, Handler};
+use rustc_session::config::OutputType;
+
+use std::fs;
+use std::sync::Arc;
+
+/// Cranelift has no target machine, the ISA is created by `context::build_isa` whenever a
+/// module is.
+pub fn target_machine_factory() -> TargetMachineFactoryFn<ClifCodegenBackend> {
+    Arc::new(|_config| Ok(()))
+}
+
+/// Merging object files is the linker's job, so `-Z combine-cgu` can't be supported.
+crate fn link(
+    diag_handler: &Handler,
+    _modules: Vec<ModuleCodegen<ModuleClif>>,
+) -> Result<ModuleCodegen<ModuleClif>, FatalError> {
+    diag_handler.err("`-Z combine-cgu` is not supported by the Cranelift backend");
+    Err(FatalError)
+}
+
+/// Writes the object file of `module`. The module was already compiled by Cranelift when it
+/// was created, see `base::compile_codegen_unit`.
+crate fn codegen(
+    cgcx: &CodegenContext<ClifCodegenBackend>,
+    diag_handler: &Handler,
+    module: ModuleCodegen<ModuleClif>,
+    config: &ModuleConfig,
+) -> Result<CompiledModule, FatalError> {
+    let _timer = cgcx.prof.generic_activity_with_arg("clif_module_codegen", &module.name[..]);
+
+    if config.emit_obj != EmitObj::None {
+        let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, Some(&module.name));
+        if let Err(e) = fs::write(&obj_out, &module.module_llvm.object) {
+            diag_handler.err(&format!("failed to write `{}`: {}", obj_out.display(), e));
+            return Err(FatalError);
+        }
+    }
+
+    Ok(module.into_compiled_module(
+        config.emit_obj != EmitObj::None,
+        false,
+        false,
+        &cgcx.output_filenames,
+    ))
+}
@@ -0,0 +1,97 @@
+//! Codegen of whole codegen units to Cranelift IR, and from there to object files.
+//!
+//! Unlike the LLVM backend, where a module stays in memory as IR until `back::write` runs the
+//! optimization and code generation passes, Cranelift compiles each function as soon as the
+//! codegen unit has been defined. A `ModuleClif` is therefore already a finished object file.
+
+use crate::builder::Builder;
+use crate::context::{new_module, CodegenCx};
+use crate::ModuleClif;
+
+use cranelift_module::Linkage as ClifLinkage;
+use rustc_codegen_ssa::base::maybe_create_entry_wrapper;
+use rustc_codegen_ssa::mono_item::MonoItemExt;
+use rustc_codegen_ssa::traits::*;
+use rustc_codegen_ssa::{ModuleCodegen, ModuleKind};
+use rustc_middle::dep_graph;
+use rustc_middle::mir::mono::{Linkage, Visibility};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::DebugInfo;
+use rustc_span::symbol::Symbol;
+
+use std::time::Instant;
+
+pub fn compile_codegen_unit(
+    tcx: TyCtxt<'tcx>,
+    cgu_name: Symbol,
+) -> (ModuleCodegen<ModuleClif>, u64) {
+    let prof_timer = tcx.prof.generic_activity_with_arg("codegen_module", cgu_name.to_string());
+    let start_time = Instant::now();
+
+    let dep_node = tcx.codegen_unit(cgu_name).codegen_dep_node(tcx);
+    let (module, _) =
+        tcx.dep_graph.with_task(dep_node, tcx, cgu_name, module_codegen, dep_graph::hash_result);
+    let time_to_codegen = start_time.elapsed();
+    drop(prof_timer);
+
+    // Cranelift already ran as part of `module_codegen`, so there is nothing left to do for
+    // `back::write` but writing the object file. The cost only decides the order of that.
+    let cost = time_to_codegen.as_nanos() as u64;
+
+    fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleClif> {
+        let cgu = tcx.codegen_unit(cgu_name);
+        let name = cgu_name.to_string();
+        let cx = CodegenCx::new(tcx, cgu, new_module(tcx.sess, &name));
+
+        // Instantiate monomorphizations without filling out definitions yet...
+        let mono_items = cx.codegen_unit.items_in_deterministic_order(cx.tcx);
+        for &(mono_item, (linkage, visibility)) in &mono_items {
+            mono_item.predefine::<Builder<'_, '_>>(&cx, linkage, visibility);
+        }
+
+        // ... and now that we have everything pre-defined, fill out those definitions.
+        for &(mono_item, _) in &mono_items {
+            mono_item.define::<Builder<'_, '_>>(&cx);
+        }
+
+        // If this codegen unit contains the main function, also create the
+        // wrapper here
+        maybe_create_entry_wrapper::<Builder<'_, '_>>(&cx);
+
+        if cx.sess().opts.debuginfo != DebugInfo::None {
+            cx.debuginfo_finalize();
+        }
+
+        let object = cx.finish().emit().unwrap_or_else(|e| {
+            tcx.sess.fatal(&format!("failed to emit object file for `{}`: {}", name, e))
+        });
+        let module_llvm = ModuleClif { name: name.clone(), object };
+        ModuleCodegen { name, module_llvm, kind: ModuleKind::Regular }
+    }
+
+    (module, cost)
+}
+
+/// Maps the linkage and visibility of a mono item to a Cranelift linkage.
+///
+/// Cranelift has no equivalent of the various weak and "once" linkages. All of them become
+/// `Preemptible`, a weak definition, which is what the linker needs to merge duplicates.
+pub fn linkage_to_clif(
+    cx: &CodegenCx<'_>,
+    linkage: Linkage,
+    visibility: Visibility,
+) -> ClifLinkage {
+    match linkage {
+        Linkage::External => match visibility {
+            Visibility::Hidden => ClifLinkage::Hidden,
+            Visibility::Default | Visibility::Protected => ClifLinkage::Export,
+        },
+        Linkage::Internal | Linkage::Private => ClifLinkage::Local,
+        Linkage::WeakAny | Linkage::WeakODR | Linkage::LinkOnceAny | Linkage::LinkOnceODR => {
+            ClifLinkage::Preemptible
+        }
+        _ => cx
+            .sess()
+            .fatal(&format!("linkage `{:?}` is not supported by the Cranelift backend", linkage)),
+    }
+}
@@ -0,0 +1,1682 @@
+//! The function builder.
+//!
+//! `rustc_codegen_ssa` emits code one LLVM-like instruction at a time. Each of them is lowered
+//! to Cranelift IR right away and appended to the body of the function being built, which is
+//! kept in `CodegenCx::bodies` until the codegen unit is finished.
+
+use crate::context::{CodegenCx, FunctionBody};
+use crate::int128::Shift;
+use crate::type_::{Type, TypeData};
+use crate::value::{BasicBlock, Function, Value, ValueKind};
+
+use cranelift_codegen::cursor::{Cursor, FuncCursor};
+use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
+use cranelift_codegen::ir::immediates::{Ieee32, Ieee64};
+use cranelift_codegen::ir::{self, types, AbiParam, InstBuilder, Signature};
+use cranelift_codegen::ir::{AtomicRmwOp, StackSlotData, StackSlotKind, TrapCode};
+use cranelift_module::{DataId, Module};
+use rustc_codegen_ssa::common::{
+    AtomicOrdering, AtomicRmwBinOp, IntPredicate, RealPredicate, SynchronizationScope,
+};
+use rustc_codegen_ssa::mir::operand::{OperandRef, OperandValue};
+use rustc_codegen_ssa::mir::place::PlaceRef;
+use rustc_codegen_ssa::traits::*;
+use rustc_codegen_ssa::MemFlags;
+use rustc_middle::bug;
+use rustc_middle::ty::layout::TyAndLayout;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_target::abi::{self, Align, Size};
+use rustc_target::spec::{AddrSpaceIdx, HasTargetSpec, Target};
+
+use std::ops::{Deref, Range};
+
+// All Builders must have an llfn associated with them
+#[must_use]
+pub struct Builder<'a, 'tcx> {
+    pub cx: &'a CodegenCx<'tcx>,
+    /// The block instructions are appended to. Only `None` right after `with_cx`.
+    block: Option<BasicBlock>,
+    /// The source location attached to new instructions, see `set_source_location`.
+    crate srcloc: ir::SourceLoc,
+}
+
+impl BackendTypes for Builder<'_, 'tcx> {
+    type Value = <CodegenCx<'tcx> as BackendTypes>::Value;
+    type Function = <CodegenCx<'tcx> as BackendTypes>::Function;
+    type BasicBlock = <CodegenCx<'tcx> as BackendTypes>::BasicBlock;
+    type Type = <CodegenCx<'tcx> as BackendTypes>::Type;
+    type Funclet = <CodegenCx<'tcx> as BackendTypes>::Funclet;
+
+    type DIScope = <CodegenCx<'tcx> as BackendTypes>::DIScope;
+    type DILocation = <CodegenCx<'tcx> as BackendTypes>::DILocation;
+    type DIVariable = <CodegenCx<'tcx> as BackendTypes>::DIVariable;
+}
+
+impl abi::HasDataLayout for Builder<'_, '_> {
+    fn data_layout(&self) -> &abi::TargetDataLayout {
+        self.cx.data_layout()
+    }
+}
+
+impl ty::layout::HasTyCtxt<'tcx> for Builder<'_, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.cx.tcx
+    }
+}
+
+impl ty::layout::HasParamEnv<'tcx> for Builder<'_, 'tcx> {
+    fn param_env(&self) -> ty::ParamEnv<'tcx> {
+        self.cx.param_env()
+    }
+}
+
+impl HasTargetSpec for Builder<'_, 'tcx> {
+    fn target_spec(&self) -> &Target {
+        &self.cx.target_spec()
+    }
+}
+
+impl abi::LayoutOf for Builder<'_, 'tcx> {
+    type Ty = Ty<'tcx>;
+    type TyAndLayout = TyAndLayout<'tcx>;
+
+    fn layout_of(&self, ty: Ty<'tcx>) -> Self::TyAndLayout {
+        self.cx.layout_of(ty)
+    }
+}
+
+impl Deref for Builder<'_, 'tcx> {
+    type Target = CodegenCx<'tcx>;
+
+    fn deref(&self) -> &Self::Target {
+        self.cx
+    }
+}
+
+impl HasCodegen<'tcx> for Builder<'_, 'tcx> {
+    type CodegenCx = CodegenCx<'tcx>;
+}
+
+/// The flags of ordinary loads and stores. `rustc_codegen_ssa` only accesses valid memory,
+/// but the access may be misaligned for packed types.
+fn mem_flags() -> ir::MemFlags {
+    let mut flags = ir::MemFlags::new();
+    flags.set_notrap();
+    flags
+}
+
+fn zero(pos: &mut FuncCursor<'_>, ty: ir::Type) -> ir::Value {
+    match ty {
+        types::F32 => pos.ins().f32const(Ieee32::with_bits(0)),
+        types::F64 => pos.ins().f64const(Ieee64::with_bits(0)),
+        _ => pos.ins().iconst(ty, 0),
+    }
+}
+
+/// Loads an integer of `size` bytes into a value of type `ty`. Integers with an odd number of
+/// bytes (e.g. the `i24` of a `CastTarget`) are loaded in power of two pieces.
+fn load_sized(
+    pos: &mut FuncCursor<'_>,
+    ty: ir::Type,
+    size: u64,
+    addr: ir::Value,
+    offset: u64,
+) -> ir::Value {
+    let flags = mem_flags();
+    if size == u64::from(ty.bytes()) {
+        return pos.ins().load(ty, flags, addr, offset as i32);
+    }
+    let mut result = None;
+    let mut done = 0;
+    for &piece in &[4, 2, 1] {
+        while size - done >= piece {
+            let offset = (offset + done) as i32;
+            let part = match piece {
+                4 => pos.ins().uload32(flags, addr, offset),
+                2 => pos.ins().uload16(ty, flags, addr, offset),
+                _ => pos.ins().uload8(ty, flags, addr, offset),
+            };
+            let part = if done == 0 { part } else { pos.ins().ishl_imm(part, done as i64 * 8) };
+            result = Some(match result {
+                Some(result) => pos.ins().bor(result, part),
+                None => part,
+            });
+            done += piece;
+        }
+    }
+    result.unwrap()
+}
+
+/// The counterpart of `load_sized`.
+fn store_sized(pos: &mut FuncCursor<'_>, val: ir::Value, size: u64, addr: ir::Value, offset: u64) {
+    let flags = mem_flags();
+    let ty = pos.func.dfg.value_type(val);
+    if size == u64::from(ty.bytes()) {
+        pos.ins().store(flags, val, addr, offset as i32);
+        return;
+    }
+    let mut done = 0;
+    for &piece in &[4, 2, 1] {
+        while size - done >= piece {
+            let offset = (offset + done) as i32;
+            let part = if done == 0 { val } else { pos.ins().ushr_imm(val, done as i64 * 8) };
+            match piece {
+                4 => pos.ins().istore32(flags, part, addr, offset),
+                2 => pos.ins().istore16(flags, part, addr, offset),
+                _ => pos.ins().istore8(flags, part, addr, offset),
+            };
+            done += piece;
+        }
+    }
+}
+
+fn int_cc(op: IntPredicate) -> IntCC {
+    match op {
+        IntPredicate::IntEQ => IntCC::Equal,
+        IntPredicate::IntNE => IntCC::NotEqual,
+        IntPredicate::IntUGT => IntCC::UnsignedGreaterThan,
+        IntPredicate::IntUGE => IntCC::UnsignedGreaterThanOrEqual,
+        IntPredicate::IntULT => IntCC::UnsignedLessThan,
+        IntPredicate::IntULE => IntCC::UnsignedLessThanOrEqual,
+        IntPredicate::IntSGT => IntCC::SignedGreaterThan,
+        IntPredicate::IntSGE => IntCC::SignedGreaterThanOrEqual,
+        IntPredicate::IntSLT => IntCC::SignedLessThan,
+        IntPredicate::IntSLE => IntCC::SignedLessThanOrEqual,
+    }
+}
+
+/// The Cranelift condition of a floating point comparison. `None` for the comparisons
+/// that are always false or always true.
+fn float_cc(op: &RealPredicate) -> Option<FloatCC> {
+    Some(match *op {
+        RealPredicate::RealPredicateFalse | RealPredicate::RealPredicateTrue => return None,
+        RealPredicate::RealOEQ => FloatCC::Equal,
+        RealPredicate::RealOGT => FloatCC::GreaterThan,
+        RealPredicate::RealOGE => FloatCC::GreaterThanOrEqual,
+        RealPredicate::RealOLT => FloatCC::LessThan,
+        RealPredicate::RealOLE => FloatCC::LessThanOrEqual,
+        RealPredicate::RealONE => FloatCC::OrderedNotEqual,
+        RealPredicate::RealORD => FloatCC::Ordered,
+        RealPredicate::RealUNO => FloatCC::Unordered,
+        RealPredicate::RealUEQ => FloatCC::UnorderedOrEqual,
+        RealPredicate::RealUGT => FloatCC::UnorderedOrGreaterThan,
+        RealPredicate::RealUGE => FloatCC::UnorderedOrGreaterThanOrEqual,
+        RealPredicate::RealULT => FloatCC::UnorderedOrLessThan,
+        RealPredicate::RealULE => FloatCC::UnorderedOrLessThanOrEqual,
+        RealPredicate::RealUNE => FloatCC::NotEqual,
+    })
+}
+
+impl CodegenCx<'tcx> {
+    /// Appends a new block to the body of `func`. The body is created on first use, with the
+    /// parameters of the function as the parameters of its entry block.
+    crate fn append_block(&self, func: Function) -> ir::Block {
+        if let Some(body) = self.bodies.borrow_mut().get_mut(&func) {
+            let block = body.func.dfg.make_block();
+            body.func.layout.append_block(block);
+            return block;
+        }
+
+        let (id, fn_ty) = self.func_data(func, |data| (data.id, data.ty));
+        let sig = self.sig_of(func);
+        let name = ir::ExternalName::user(0, id.as_u32());
+        let mut clif_func = ir::Function::with_name_signature(name, sig);
+        let block = clif_func.dfg.make_block();
+        clif_func.layout.append_block(block);
+        clif_func.dfg.append_block_params_for_function_params(block);
+
+        let mut leaves = clif_func.dfg.block_params(block).to_vec().into_iter();
+        let params = self
+            .func_params_types(fn_ty)
+            .into_iter()
+            .map(|ty| self.value_from_leaves(func, ty, &mut leaves))
+            .collect();
+
+        let body = FunctionBody {
+            func: clif_func,
+            params,
+            func_refs: Default::default(),
+            global_values: Default::default(),
+        };
+        self.bodies.borrow_mut().insert(func, body);
+        block
+    }
+
+    /// Reassembles a value of type `ty` from its Cranelift scalars, see `clif_leaves`.
+    crate fn value_from_leaves(
+        &self,
+        func: Function,
+        ty: Type,
+        leaves: &mut impl Iterator<Item = ir::Value>,
+    ) -> Value {
+        if self.clif_type(ty).is_some() {
+            return Value::new(ValueKind::Ssa(func, leaves.next().unwrap()), ty);
+        }
+        match self.type_data(ty) {
+            TypeData::Void => Value::new(ValueKind::Undef, ty),
+            TypeData::Int(128) => {
+                let i64_ty = self.type_i64();
+                let lo = Value::new(ValueKind::Ssa(func, leaves.next().unwrap()), i64_ty);
+                let hi = Value::new(ValueKind::Ssa(func, leaves.next().unwrap()), i64_ty);
+                self.aggregate(vec![lo, hi], ty)
+            }
+            TypeData::Struct { .. } | TypeData::Array(..) => {
+                let fields = self
+                    .aggregate_fields(ty)
+                    .into_iter()
+                    .map(|field| self.value_from_leaves(func, field, leaves))
+                    .collect();
+                self.aggregate(fields, ty)
+            }
+            data => {
+                self.sess().fatal(&format!("the Cranelift backend does not support {:?}", data))
+            }
+        }
+    }
+
+    /// The scalars of a value of type `ty` as it is laid out in memory, as
+    /// `(offset, Cranelift type, size in bytes)` triples in the order of `clif_leaves`.
+    fn memory_leaves(&self, ty: Type, offset: u64, leaves: &mut Vec<(u64, ir::Type, u64)>) {
+        if let Some(clif_ty) = self.clif_type(ty) {
+            leaves.push((offset, clif_ty, self.type_size(ty)));
+            return;
+        }
+        match self.type_data(ty) {
+            TypeData::Int(128) => {
+                leaves.push((offset, types::I64, 8));
+                leaves.push((offset + 8, types::I64, 8));
+            }
+            TypeData::Struct { .. } | TypeData::Array(..) => {
+                for (i, field) in self.aggregate_fields(ty).into_iter().enumerate() {
+                    self.memory_leaves(field, offset + self.field_offset(ty, i as u64), leaves);
+                }
+            }
+            data => {
+                self.sess().fatal(&format!("the Cranelift backend does not support {:?}", data))
+            }
+        }
+    }
+}
+
+impl Builder<'a, 'tcx> {
+    crate fn func(&self) -> Function {
+        self.llbb().func
+    }
+
+    /// Runs `f` with a cursor at the end of the current block.
+    crate fn ins<R>(&mut self, f: impl FnOnce(&mut FuncCursor<'_>) -> R) -> R {
+        let cx = self.cx;
+        let mut bb = self.llbb();
+        let mut bodies = cx.bodies.borrow_mut();
+        let func = &mut bodies.get_mut(&bb.func).unwrap().func;
+
+        // Code after a terminator (e.g. after an `abort`) is unreachable, but Cranelift
+        // does not allow it in the same block, so start a new one.
+        if let Some(inst) = func.layout.last_inst(bb.block) {
+            if func.dfg[inst].opcode().is_terminator() {
+                bb.block = func.dfg.make_block();
+                func.layout.append_block(bb.block);
+                self.block = Some(bb);
+            }
+        }
+
+        let mut pos = FuncCursor::new(func).with_srcloc(self.srcloc).at_bottom(bb.block);
+        f(&mut pos)
+    }
+
+    crate fn ssa(&self, v: ir::Value, ty: Type) -> Value {
+        Value::new(ValueKind::Ssa(self.func(), v), ty)
+    }
+
+    fn func_ref(&mut self, func: Function) -> ir::FuncRef {
+        let id = self.func_data(func, |data| data.id);
+        let mut bodies = self.cx.bodies.borrow_mut();
+        let body = bodies.get_mut(&self.func()).unwrap();
+        if let Some(&func_ref) = body.func_refs.get(&func) {
+            return func_ref;
+        }
+        let func_ref = self.module.borrow_mut().declare_func_in_func(id, &mut body.func);
+        body.func_refs.insert(func, func_ref);
+        func_ref
+    }
+
+    fn global_value(&mut self, id: DataId) -> ir::GlobalValue {
+        let mut bodies = self.cx.bodies.borrow_mut();
+        let body = bodies.get_mut(&self.func()).unwrap();
+        if let Some(&gv) = body.global_values.get(&id) {
+            return gv;
+        }
+        let gv = self.module.borrow_mut().declare_data_in_func(id, &mut body.func);
+        body.global_values.insert(id, gv);
+        gv
+    }
+
+    /// The Cranelift value of a scalar, materializing constants in the current block.
+    crate fn val(&mut self, v: Value) -> ir::Value {
+        let ptr_ty = self.pointer_type;
+        match v.kind {
+            ValueKind::Ssa(func, val) => {
+                assert_eq!(func, self.func(), "value {:?} used in another function", v);
+                val
+            }
+            ValueKind::ConstInt(c) => {
+                let ty =
+                    self.clif_type(v.ty).unwrap_or_else(|| bug!("val: {:?} is not a scalar", v));
+                self.ins(|pos| match ty {
+                    types::F32 => pos.ins().f32const(Ieee32::with_bits(c as u32)),
+                    types::F64 => pos.ins().f64const(Ieee64::with_bits(c as u64)),
+                    _ => pos.ins().iconst(ty, c as u64 as i64),
+                })
+            }
+            ValueKind::ConstFloat(bits) => match self.clif_type(v.ty) {
+                Some(types::F32) => {
+                    self.ins(|pos| pos.ins().f32const(Ieee32::with_bits(bits as u32)))
+                }
+                Some(types::F64) => self.ins(|pos| pos.ins().f64const(Ieee64::with_bits(bits))),
+                _ => bug!("val: {:?} is not a float", v),
+            },
+            ValueKind::Undef => {
+                let ty =
+                    self.clif_type(v.ty).unwrap_or_else(|| bug!("val: {:?} is not a scalar", v));
+                self.ins(|pos| zero(pos, ty))
+            }
+            ValueKind::FuncAddr(func) => {
+                let func_ref = self.func_ref(func);
+                self.ins(|pos| pos.ins().func_addr(ptr_ty, func_ref))
+            }
+            ValueKind::DataAddr(id, offset) => {
+                let gv = self.global_value(id);
+                let tls = self.thread_locals.borrow().contains(&id);
+                self.ins(|pos| {
+                    let addr = if tls {
+                        pos.ins().tls_value(ptr_ty, gv)
+                    } else {
+                        pos.ins().symbol_value(ptr_ty, gv)
+                    };
+                    if offset == 0 { addr } else { pos.ins().iadd_imm(addr, offset) }
+                })
+            }
+            ValueKind::Aggregate(_) => bug!("val: {:?} is not a scalar", v),
+        }
+    }
+
+    /// The Cranelift scalars of a value of any type, see `clif_leaves`.
+    crate fn leaves(&mut self, v: Value) -> Vec<ir::Value> {
+        if self.clif_type(v.ty).is_some() {
+            return vec![self.val(v)];
+        }
+        match v.kind {
+            ValueKind::ConstInt(c) if self.is_int128(v.ty) => self.ins(|pos| {
+                vec![
+                    pos.ins().iconst(types::I64, c as u64 as i64),
+                    pos.ins().iconst(types::I64, (c >> 64) as u64 as i64),
+                ]
+            }),
+            ValueKind::Undef if self.is_int128(v.ty) => {
+                self.ins(|pos| vec![zero(pos, types::I64), zero(pos, types::I64)])
+            }
+            _ => {
+                let fields = self.aggregate_values(v);
+                fields.into_iter().flat_map(|field| self.leaves(field)).collect()
+            }
+        }
+    }
+
+    crate fn from_leaves(&self, ty: Type, leaves: Vec<ir::Value>) -> Value {
+        let mut leaves = leaves.into_iter();
+        let v = self.value_from_leaves(self.func(), ty, &mut leaves);
+        assert!(leaves.next().is_none(), "from_leaves: too many values for {:?}", ty);
+        v
+    }
+
+    /// Calls a function of the C runtime or of `compiler_builtins`.
+    crate fn call_libfn(&mut self, name: &str, args: &[Value], ret: Type) -> Value {
+        let arg_tys: Vec<_> = args.iter().map(|arg| arg.ty).collect();
+        let fn_ty = self.type_func(&arg_tys, ret);
+        let func = self.declare_cfn(name, fn_ty);
+        self.call(self.fn_value(func), args, None)
+    }
+
+    fn unary(
+        &mut self,
+        v: Value,
+        f: impl FnOnce(&mut FuncCursor<'_>, ir::Value) -> ir::Value,
+    ) -> Value {
+        let v_val = self.val(v);
+        let r = self.ins(|pos| f(pos, v_val));
+        self.ssa(r, v.ty)
+    }
+
+    fn binop(
+        &mut self,
+        lhs: Value,
+        rhs: Value,
+        f: impl FnOnce(&mut FuncCursor<'_>, ir::Value, ir::Value) -> ir::Value,
+    ) -> Value {
+        let (a, b) = (self.val(lhs), self.val(rhs));
+        let r = self.ins(|pos| f(pos, a, b));
+        self.ssa(r, lhs.ty)
+    }
+
+    fn float_libcall(&mut self, f32_name: &str, f64_name: &str, args: &[Value]) -> Value {
+        let ty = args[0].ty;
+        let name = if self.float_width(ty) == 32 { f32_name } else { f64_name };
+        self.call_libfn(name, args, ty)
+    }
+
+    /// Converts an integer to another integer type, extending or truncating it as needed.
+    crate fn int_resize(&mut self, val: Value, dest_ty: Type, signed: bool) -> Value {
+        let from = self.int_width(val.ty);
+        let to = self.int_width(dest_ty);
+        if from == to {
+            return val.with_type(dest_ty);
+        }
+
+        if let ValueKind::ConstInt(c) = val.kind {
+            let c = if signed && from < 128 && (c >> (from - 1)) & 1 == 1 {
+                c | (u128::MAX << from)
+            } else {
+                c
+            };
+            let c = if to < 128 { c & ((1 << to) - 1) } else { c };
+            return Value::new(ValueKind::ConstInt(c), dest_ty);
+        }
+        if self.is_int128(val.ty) || self.is_int128(dest_ty) {
+            return self.int_resize_i128(val, dest_ty, signed);
+        }
+
+        let v = self.val(val);
+        let from_ty = self.clif_type(val.ty).unwrap();
+        let to_ty = self.clif_type(dest_ty).unwrap();
+        let r = self.ins(|pos| {
+            // `sext i1` produces `0` or `-1`.
+            let v = if signed && from == 1 && to > 1 { pos.ins().ineg(v) } else { v };
+            let v = if to_ty.bits() > from_ty.bits() {
+                if signed { pos.ins().sextend(to_ty, v) } else { pos.ins().uextend(to_ty, v) }
+            } else if to_ty.bits() < from_ty.bits() {
+                pos.ins().ireduce(to_ty, v)
+            } else {
+                v
+            };
+            if to < from && to < u64::from(to_ty.bits()) {
+                pos.ins().band_imm(v, ((1u64 << to) - 1) as i64)
+            } else {
+                v
+            }
+        });
+        self.ssa(r, dest_ty)
+    }
+
+    /// Emits an unconditional branch that passes `args` to the parameters of `dest`.
+    fn br_with_args(&mut self, dest: BasicBlock, args: &[Value]) {
+        let mut vals = vec![];
+        for &arg in args {
+            vals.extend(self.leaves(arg));
+        }
+        self.ins(|pos| pos.ins().jump(dest.block, &vals));
+    }
+
+    /// Adds a parameter to the (otherwise unused) current block.
+    fn block_param(&mut self, ty: Type) -> Value {
+        let clif_ty = self.clif_type(ty).unwrap_or_else(|| bug!("block_param: {:?}", ty));
+        let block = self.llbb().block;
+        let v = self.ins(|pos| pos.func.dfg.append_block_param(block, clif_ty));
+        self.ssa(v, ty)
+    }
+}
+
+impl BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
+    fn new_block<'b>(cx: &'a CodegenCx<'tcx>, llfn: Function, _name: &'b str) -> Self {
+        let block = cx.append_block(llfn);
+        let mut bx = Builder::with_cx(cx);
+        bx.position_at_end(BasicBlock { func: llfn, block });
+        bx
+    }
+
+    fn with_cx(cx: &'a CodegenCx<'tcx>) -> Self {
+        Builder { cx, block: None, srcloc: ir::SourceLoc::default() }
+    }
+
+    fn build_sibling_block(&self, name: &str) -> Self {
+        Builder::new_block(self.cx, self.func(), name)
+    }
+
+    fn cx(&self) -> &CodegenCx<'tcx> {
+        self.cx
+    }
+
+    fn llbb(&self) -> BasicBlock {
+        self.block.expect("builder is not positioned in a block")
+    }
+
+    fn position_at_end(&mut self, llbb: BasicBlock) {
+        self.block = Some(llbb);
+    }
+
+    fn ret_void(&mut self) {
+        self.ins(|pos| pos.ins().return_(&[]));
+    }
+
+    fn ret(&mut self, v: Value) {
+        let vals = self.leaves(v);
+        self.ins(|pos| pos.ins().return_(&vals));
+    }
+
+    fn br(&mut self, dest: BasicBlock) {
+        self.br_with_args(dest, &[]);
+    }
+
+    fn cond_br(&mut self, cond: Value, then_llbb: BasicBlock, else_llbb: BasicBlock) {
+        match cond.kind {
+            ValueKind::ConstInt(c) => self.br(if c != 0 { then_llbb } else { else_llbb }),
+            _ => {
+                let c = self.val(cond);
+                self.ins(|pos| {
+                    pos.ins().brnz(c, then_llbb.block, &[]);
+                    pos.ins().jump(else_llbb.block, &[]);
+                });
+            }
+        }
+    }
+
+    fn switch(
+        &mut self,
+        v: Value,
+        else_llbb: BasicBlock,
+        cases: impl ExactSizeIterator<Item = (u128, BasicBlock)>,
+    ) {
+        // Lowered to a chain of comparisons, the Cranelift optimizer does not need more.
+        for (on_val, dest) in cases {
+            let on_val = self.const_uint_big(v.ty, on_val);
+            let cond = self.icmp(IntPredicate::IntEQ, v, on_val);
+            let next = self.build_sibling_block("switch_next").llbb();
+            self.cond_br(cond, dest, next);
+            self.position_at_end(next);
+        }
+        self.br(else_llbb);
+    }
+
+    fn invoke(
+        &mut self,
+        llfn: Value,
+        args: &[Value],
+        then: BasicBlock,
+        _catch: BasicBlock,
+        funclet: Option<&()>,
+    ) -> Value {
+        // Unwinding is not supported, so the landing pad is never entered.
+        let ret = self.call(llfn, args, funclet);
+        self.br(then);
+        ret
+    }
+
+    fn unreachable(&mut self) {
+        self.ins(|pos| pos.ins().trap(TrapCode::UnreachableCodeReached));
+    }
+
+    fn add(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.add_i128(lhs, rhs);
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().iadd(a, b))
+    }
+
+    fn fadd(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().fadd(a, b))
+    }
+
+    fn fadd_fast(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.fadd(lhs, rhs)
+    }
+
+    fn sub(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.sub_i128(lhs, rhs);
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().isub(a, b))
+    }
+
+    fn fsub(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().fsub(a, b))
+    }
+
+    fn fsub_fast(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.fsub(lhs, rhs)
+    }
+
+    fn mul(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.mul_i128(lhs, rhs);
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().imul(a, b))
+    }
+
+    fn fmul(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().fmul(a, b))
+    }
+
+    fn fmul_fast(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.fmul(lhs, rhs)
+    }
+
+    fn udiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.call_libfn("__udivti3", &[lhs, rhs], lhs.ty);
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().udiv(a, b))
+    }
+
+    fn exactudiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.udiv(lhs, rhs)
+    }
+
+    fn sdiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.call_libfn("__divti3", &[lhs, rhs], lhs.ty);
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().sdiv(a, b))
+    }
+
+    fn exactsdiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.sdiv(lhs, rhs)
+    }
+
+    fn fdiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().fdiv(a, b))
+    }
+
+    fn fdiv_fast(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.fdiv(lhs, rhs)
+    }
+
+    fn urem(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.call_libfn("__umodti3", &[lhs, rhs], lhs.ty);
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().urem(a, b))
+    }
+
+    fn srem(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.call_libfn("__modti3", &[lhs, rhs], lhs.ty);
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().srem(a, b))
+    }
+
+    fn frem(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.float_libcall("fmodf", "fmod", &[lhs, rhs])
+    }
+
+    fn frem_fast(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.frem(lhs, rhs)
+    }
+
+    fn shl(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.shift_i128(lhs, rhs, Shift::Left);
+        }
+        // Cranelift masks the shift amount to the width of the type, but `x86_64` masks 8 and
+        // 16 bit shifts to 32 bits. Masking explicitly gives the same result everywhere.
+        let bits = i64::from(self.clif_type(lhs.ty).unwrap().bits());
+        self.binop(lhs, rhs, |pos, a, b| {
+            let b = pos.ins().band_imm(b, bits - 1);
+            pos.ins().ishl(a, b)
+        })
+    }
+
+    fn lshr(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.shift_i128(lhs, rhs, Shift::LogicalRight);
+        }
+        let bits = i64::from(self.clif_type(lhs.ty).unwrap().bits());
+        self.binop(lhs, rhs, |pos, a, b| {
+            let b = pos.ins().band_imm(b, bits - 1);
+            pos.ins().ushr(a, b)
+        })
+    }
+
+    fn ashr(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.shift_i128(lhs, rhs, Shift::ArithmeticRight);
+        }
+        let bits = i64::from(self.clif_type(lhs.ty).unwrap().bits());
+        self.binop(lhs, rhs, |pos, a, b| {
+            let b = pos.ins().band_imm(b, bits - 1);
+            pos.ins().sshr(a, b)
+        })
+    }
+
+    fn unchecked_sadd(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.add(lhs, rhs)
+    }
+
+    fn unchecked_uadd(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.add(lhs, rhs)
+    }
+
+    fn unchecked_ssub(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.sub(lhs, rhs)
+    }
+
+    fn unchecked_usub(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.sub(lhs, rhs)
+    }
+
+    fn unchecked_smul(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.mul(lhs, rhs)
+    }
+
+    fn unchecked_umul(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.mul(lhs, rhs)
+    }
+
+    fn and(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.bitwise_i128(lhs, rhs, |pos, a, b| pos.ins().band(a, b));
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().band(a, b))
+    }
+
+    fn or(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.bitwise_i128(lhs, rhs, |pos, a, b| pos.ins().bor(a, b));
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().bor(a, b))
+    }
+
+    fn xor(&mut self, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.bitwise_i128(lhs, rhs, |pos, a, b| pos.ins().bxor(a, b));
+        }
+        self.binop(lhs, rhs, |pos, a, b| pos.ins().bxor(a, b))
+    }
+
+    fn neg(&mut self, v: Value) -> Value {
+        if self.is_int128(v.ty) {
+            let zero = self.const_null(v.ty);
+            return self.sub_i128(zero, v);
+        }
+        self.unary(v, |pos, a| pos.ins().ineg(a))
+    }
+
+    fn fneg(&mut self, v: Value) -> Value {
+        self.unary(v, |pos, a| pos.ins().fneg(a))
+    }
+
+    fn not(&mut self, v: Value) -> Value {
+        if self.is_int128(v.ty) {
+            let ones = self.const_uint_big(v.ty, u128::MAX);
+            return self.bitwise_i128(v, ones, |pos, a, b| pos.ins().bxor(a, b));
+        }
+        if v.ty == self.type_i1() {
+            // Booleans are `i8`s holding `0` or `1`.
+            return self.unary(v, |pos, a| pos.ins().bxor_imm(a, 1));
+        }
+        self.unary(v, |pos, a| pos.ins().bnot(a))
+    }
+
+    fn checked_binop(
+        &mut self,
+        oop: OverflowOp,
+        ty: Ty<'_>,
+        lhs: Self::Value,
+        rhs: Self::Value,
+    ) -> (Self::Value, Self::Value) {
+        let signed = match ty.kind() {
+            ty::Int(_) => true,
+            ty::Uint(_) => false,
+            _ => bug!("checked_binop on non-integer type {:?}", ty),
+        };
+        if self.is_int128(lhs.ty) {
+            return self.checked_binop_i128(oop, signed, lhs, rhs);
+        }
+
+        let clif_ty = self.clif_type(lhs.ty).unwrap();
+        let (a, b) = (self.val(lhs), self.val(rhs));
+        let (result, overflow) = self.ins(|pos| {
+            let (result, overflow) = match oop {
+                OverflowOp::Add => {
+                    let result = pos.ins().iadd(a, b);
+                    let overflow = if signed {
+                        // The sign of the result differs from the sign of both operands.
+                        let x = pos.ins().bxor(result, a);
+                        let y = pos.ins().bxor(result, b);
+                        let both = pos.ins().band(x, y);
+                        pos.ins().icmp_imm(IntCC::SignedLessThan, both, 0)
+                    } else {
+                        pos.ins().icmp(IntCC::UnsignedLessThan, result, a)
+                    };
+                    (result, overflow)
+                }
+                OverflowOp::Sub => {
+                    let result = pos.ins().isub(a, b);
+                    let overflow = if signed {
+                        // The operands have different signs and the sign of the result
+                        // differs from the sign of `a`.
+                        let x = pos.ins().bxor(a, b);
+                        let y = pos.ins().bxor(a, result);
+                        let both = pos.ins().band(x, y);
+                        pos.ins().icmp_imm(IntCC::SignedLessThan, both, 0)
+                    } else {
+                        pos.ins().icmp(IntCC::UnsignedLessThan, a, b)
+                    };
+                    (result, overflow)
+                }
+                OverflowOp::Mul if clif_ty == types::I64 => {
+                    let result = pos.ins().imul(a, b);
+                    let overflow = if signed {
+                        let hi = pos.ins().smulhi(a, b);
+                        let sign = pos.ins().sshr_imm(result, 63);
+                        pos.ins().icmp(IntCC::NotEqual, hi, sign)
+                    } else {
+                        let hi = pos.ins().umulhi(a, b);
+                        pos.ins().icmp_imm(IntCC::NotEqual, hi, 0)
+                    };
+                    (result, overflow)
+                }
+                OverflowOp::Mul => {
+                    // Multiply in 64 bits and check that the result survives a round trip
+                    // through the narrow type.
+                    let extend = |pos: &mut FuncCursor<'_>, v| {
+                        if signed {
+                            pos.ins().sextend(types::I64, v)
+                        } else {
+                            pos.ins().uextend(types::I64, v)
+                        }
+                    };
+                    let (wide_a, wide_b) = (extend(pos, a), extend(pos, b));
+                    let wide = pos.ins().imul(wide_a, wide_b);
+                    let result = pos.ins().ireduce(clif_ty, wide);
+                    let round_trip = extend(pos, result);
+                    (result, pos.ins().icmp(IntCC::NotEqual, round_trip, wide))
+                }
+            };
+            (result, pos.ins().bint(types::I8, overflow))
+        });
+        (self.ssa(result, lhs.ty), self.ssa(overflow, self.type_i1()))
+    }
+
+    fn from_immediate(&mut self, val: Self::Value) -> Self::Value {
+        if self.cx().val_ty(val) == self.cx().type_i1() {
+            self.zext(val, self.cx().type_i8())
+        } else {
+            val
+        }
+    }
+
+    fn to_immediate_scalar(&mut self, val: Self::Value, scalar: &abi::Scalar) -> Self::Value {
+        if scalar.is_bool() {
+            return self.trunc(val, self.cx().type_i1());
+        }
+        val
+    }
+
+    fn alloca(&mut self, ty: Type, align: Align) -> Value {
+        let size = self.type_size(ty) as u32;
+        let align = align.bytes() as u32;
+        let ptr_ty = self.pointer_type;
+        let addr = self.ins(|pos| {
+            if align <= 8 {
+                let data = StackSlotData::new(StackSlotKind::ExplicitSlot, size);
+                let slot = pos.func.create_stack_slot(data);
+                pos.ins().stack_addr(ptr_ty, slot, 0)
+            } else {
+                // Stack slots are only 8 byte aligned, so over-allocate and align the
+                // address by hand.
+                let data = StackSlotData::new(StackSlotKind::ExplicitSlot, size + align - 8);
+                let slot = pos.func.create_stack_slot(data);
+                let addr = pos.ins().stack_addr(ptr_ty, slot, 0);
+                let addr = pos.ins().iadd_imm(addr, i64::from(align) - 1);
+                pos.ins().band_imm(addr, -i64::from(align))
+            }
+        });
+        self.ssa(addr, self.type_ptr_to(ty))
+    }
+
+    fn dynamic_alloca(&mut self, _ty: Type, _align: Align) -> Value {
+        self.sess().fatal("unsized locals are not supported by the Cranelift backend")
+    }
+
+    fn array_alloca(&mut self, _ty: Type, _len: Value, _align: Align) -> Value {
+        self.sess().fatal("unsized locals are not supported by the Cranelift backend")
+    }
+
+    fn load(&mut self, ptr: Value, _align: Align) -> Value {
+        let ty = self.element_type(ptr.ty);
+        let addr = self.val(ptr);
+        let mut leaves = vec![];
+        self.memory_leaves(ty, 0, &mut leaves);
+        let vals = self.ins(|pos| {
+            leaves
+                .into_iter()
+                .map(|(offset, clif_ty, size)| load_sized(pos, clif_ty, size, addr, offset))
+                .collect()
+        });
+        self.from_leaves(ty, vals)
+    }
+
+    fn volatile_load(&mut self, ptr: Value) -> Value {
+        // Cranelift never removes or merges loads.
+        self.load(ptr, Align::from_bytes(1).unwrap())
+    }
+
+    fn atomic_load(&mut self, ptr: Value, _order: AtomicOrdering, _size: Size) -> Value {
+        // All atomic operations of Cranelift are sequentially consistent.
+        let ty = self.element_type(ptr.ty);
+        let clif_ty = self.atomic_clif_type(ty);
+        let addr = self.val(ptr);
+        let v = self.ins(|pos| pos.ins().atomic_load(clif_ty, ir::MemFlags::trusted(), addr));
+        self.ssa(v, ty)
+    }
+
+    fn load_operand(&mut self, place: PlaceRef<'tcx, Value>) -> OperandRef<'tcx, Value> {
+        assert_eq!(place.llextra.is_some(), place.layout.is_unsized());
+
+        if place.layout.is_zst() {
+            return OperandRef::new_zst(self, place.layout);
+        }
+
+        let val = if let Some(llextra) = place.llextra {
+            OperandValue::Ref(place.llval, Some(llextra), place.align)
+        } else if self.is_backend_immediate(place.layout) {
+            let llval = self.load(place.llval, place.align);
+            OperandValue::Immediate(self.to_immediate(llval, place.layout))
+        } else if let abi::Abi::ScalarPair(ref a, ref b) = place.layout.abi {
+            let b_offset = a.value.size(self).align_to(b.value.align(self).abi);
+
+            let mut load = |i, scalar: &abi::Scalar, align| {
+                let llptr = self.struct_gep(place.llval, i as u64);
+                let load = self.load(llptr, align);
+                self.to_immediate_scalar(load, scalar)
+            };
+
+            OperandValue::Pair(
+                load(0, a, place.align),
+                load(1, b, place.align.restrict_for_offset(b_offset)),
+            )
+        } else {
+            OperandValue::Ref(place.llval, None, place.align)
+        };
+
+        OperandRef { val, layout: place.layout }
+    }
+
+    fn write_operand_repeatedly(
+        mut self,
+        cg_elem: OperandRef<'tcx, Value>,
+        count: u64,
+        dest: PlaceRef<'tcx, Value>,
+    ) -> Self {
+        let zero = self.const_usize(0);
+        let count = self.const_usize(count);
+        let start = dest.project_index(&mut self, zero).llval;
+        let end = dest.project_index(&mut self, count).llval;
+
+        let mut header_bx = self.build_sibling_block("repeat_loop_header");
+        let mut body_bx = self.build_sibling_block("repeat_loop_body");
+        let next_bx = self.build_sibling_block("repeat_loop_next");
+
+        // The loop variable is a parameter of the header block.
+        let current = header_bx.block_param(self.val_ty(start));
+        self.br_with_args(header_bx.llbb(), &[start]);
+
+        let keep_going = header_bx.icmp(IntPredicate::IntNE, current, end);
+        header_bx.cond_br(keep_going, body_bx.llbb(), next_bx.llbb());
+
+        let align = dest.align.restrict_for_offset(dest.layout.field(self.cx(), 0).size);
+        cg_elem
+            .val
+            .store(&mut body_bx, PlaceRef::new_sized_aligned(current, cg_elem.layout, align));
+
+        let next = body_bx.inbounds_gep(current, &[self.const_usize(1)]);
+        body_bx.br_with_args(header_bx.llbb(), &[next]);
+
+        next_bx
+    }
+
+    fn range_metadata(&mut self, _load: Value, _range: Range<u128>) {}
+
+    fn nonnull_metadata(&mut self, _load: Value) {}
+
+    fn store(&mut self, val: Value, ptr: Value, align: Align) -> Value {
+        self.store_with_flags(val, ptr, align, MemFlags::empty())
+    }
+
+    fn store_with_flags(
+        &mut self,
+        val: Value,
+        ptr: Value,
+        _align: Align,
+        _flags: MemFlags,
+    ) -> Value {
+        // Volatile and nontemporal stores need no special treatment, Cranelift never removes
+        // or merges stores.
+        let addr = self.val(ptr);
+        let mut leaves = vec![];
+        self.memory_leaves(val.ty, 0, &mut leaves);
+        let vals = self.leaves(val);
+        self.ins(|pos| {
+            for ((offset, _, size), v) in leaves.into_iter().zip(vals) {
+                store_sized(pos, v, size, addr, offset);
+            }
+        });
+        Value::new(ValueKind::Undef, self.type_void())
+    }
+
+    fn atomic_store(&mut self, val: Value, ptr: Value, _order: AtomicOrdering, _size: Size) {
+        self.atomic_clif_type(val.ty);
+        let (v, addr) = (self.val(val), self.val(ptr));
+        self.ins(|pos| pos.ins().atomic_store(ir::MemFlags::trusted(), v, addr));
+    }
+
+    fn gep(&mut self, ptr: Value, indices: &[Value]) -> Value {
+        let mut ty = self.element_type(ptr.ty);
+        let mut const_offset: i64 = 0;
+        let mut dyn_offset = None;
+        for (i, &idx) in indices.iter().enumerate() {
+            let stride = if i == 0 {
+                self.type_stride(ty)
+            } else {
+                match self.type_data(ty) {
+                    TypeData::Struct { .. } => {
+                        let idx = self
+                            .const_to_opt_uint(idx)
+                            .unwrap_or_else(|| bug!("gep: non-constant struct index {:?}", idx));
+                        const_offset += self.field_offset(ty, idx) as i64;
+                        ty = self.field_type(ty, idx);
+                        continue;
+                    }
+                    TypeData::Array(elem, _) | TypeData::Vector(elem, _) => {
+                        ty = elem;
+                        self.type_stride(elem)
+                    }
+                    data => bug!("gep: cannot index into {:?}", data),
+                }
+            };
+            match self.const_to_opt_u128(idx, true) {
+                Some(idx) => {
+                    const_offset =
+                        const_offset.wrapping_add((idx as i64).wrapping_mul(stride as i64))
+                }
+                None => {
+                    let idx = self.int_resize(idx, self.type_isize(), true);
+                    let idx = self.val(idx);
+                    dyn_offset = Some(self.ins(|pos| {
+                        let scaled = pos.ins().imul_imm(idx, stride as i64);
+                        match dyn_offset {
+                            Some(offset) => pos.ins().iadd(offset, scaled),
+                            None => scaled,
+                        }
+                    }));
+                }
+            }
+        }
+
+        let result_ty = self.type_ptr_to(ty);
+        if let (ValueKind::DataAddr(id, offset), None) = (ptr.kind, dyn_offset) {
+            return Value::new(ValueKind::DataAddr(id, offset + const_offset), result_ty);
+        }
+        let base = self.val(ptr);
+        let addr = self.ins(|pos| {
+            let addr = match dyn_offset {
+                Some(offset) => pos.ins().iadd(base, offset),
+                None => base,
+            };
+            if const_offset == 0 { addr } else { pos.ins().iadd_imm(addr, const_offset) }
+        });
+        self.ssa(addr, result_ty)
+    }
+
+    fn inbounds_gep(&mut self, ptr: Value, indices: &[Value]) -> Value {
+        self.gep(ptr, indices)
+    }
+
+    fn struct_gep(&mut self, ptr: Value, idx: u64) -> Value {
+        assert_eq!(idx as u32 as u64, idx);
+        let idx = self.const_u32(idx as u32);
+        let zero = self.const_u32(0);
+        self.gep(ptr, &[zero, idx])
+    }
+
+    /* Casts */
+    fn trunc(&mut self, val: Value, dest_ty: Type) -> Value {
+        self.int_resize(val, dest_ty, false)
+    }
+
+    fn sext(&mut self, val: Value, dest_ty: Type) -> Value {
+        self.int_resize(val, dest_ty, true)
+    }
+
+    fn fptoui_sat(&mut self, val: Value, dest_ty: Type) -> Option<Value> {
+        match self.clif_type(dest_ty) {
+            Some(ty @ types::I32) | Some(ty @ types::I64) => {
+                Some(self.unary(val, |pos, v| pos.ins().fcvt_to_uint_sat(ty, v)).with_type(dest_ty))
+            }
+            _ => None,
+        }
+    }
+
+    fn fptosi_sat(&mut self, val: Value, dest_ty: Type) -> Option<Value> {
+        match self.clif_type(dest_ty) {
+            Some(ty @ types::I32) | Some(ty @ types::I64) => {
+                Some(self.unary(val, |pos, v| pos.ins().fcvt_to_sint_sat(ty, v)).with_type(dest_ty))
+            }
+            _ => None,
+        }
+    }
+
+    fn fptosui_may_trap(&self, _val: Value, _dest_ty: Type) -> bool {
+        // `fptoui` and `fptosi` are lowered to saturating conversions.
+        false
+    }
+
+    fn fptoui(&mut self, val: Value, dest_ty: Type) -> Value {
+        if self.is_int128(dest_ty) {
+            return self.float_libcall_to(val, dest_ty, "__fixunssfti", "__fixunsdfti");
+        }
+        self.fptoint(val, dest_ty, false)
+    }
+
+    fn fptosi(&mut self, val: Value, dest_ty: Type) -> Value {
+        if self.is_int128(dest_ty) {
+            return self.float_libcall_to(val, dest_ty, "__fixsfti", "__fixdfti");
+        }
+        self.fptoint(val, dest_ty, true)
+    }
+
+    fn uitofp(&mut self, val: Value, dest_ty: Type) -> Value {
+        if self.is_int128(val.ty) {
+            let name =
+                if self.float_width(dest_ty) == 32 { "__floatuntisf" } else { "__floatuntidf" };
+            return self.call_libfn(name, &[val], dest_ty);
+        }
+        self.inttofp(val, dest_ty, false)
+    }
+
+    fn sitofp(&mut self, val: Value, dest_ty: Type) -> Value {
+        if self.is_int128(val.ty) {
+            let name = if self.float_width(dest_ty) == 32 { "__floattisf" } else { "__floattidf" };
+            return self.call_libfn(name, &[val], dest_ty);
+        }
+        self.inttofp(val, dest_ty, true)
+    }
+
+    fn fptrunc(&mut self, val: Value, dest_ty: Type) -> Value {
+        self.unary(val, |pos, v| pos.ins().fdemote(types::F32, v)).with_type(dest_ty)
+    }
+
+    fn fpext(&mut self, val: Value, dest_ty: Type) -> Value {
+        self.unary(val, |pos, v| pos.ins().fpromote(types::F64, v)).with_type(dest_ty)
+    }
+
+    fn ptrtoint(&mut self, val: Value, dest_ty: Type) -> Value {
+        let isize_ty = self.type_isize();
+        self.int_resize(val.with_type(isize_ty), dest_ty, false)
+    }
+
+    fn inttoptr(&mut self, val: Value, dest_ty: Type) -> Value {
+        let isize_ty = self.type_isize();
+        self.int_resize(val, isize_ty, false).with_type(dest_ty)
+    }
+
+    fn bitcast(&mut self, val: Value, dest_ty: Type) -> Value {
+        let from = self.clif_type(val.ty);
+        let to = self.clif_type(dest_ty);
+        match (from, to) {
+            (Some(from), Some(to)) if from.is_float() != to.is_float() => match val.kind {
+                ValueKind::ConstInt(c) => Value::new(ValueKind::ConstFloat(c as u64), dest_ty),
+                ValueKind::ConstFloat(c) => Value::new(ValueKind::ConstInt(u128::from(c)), dest_ty),
+                _ => self.unary(val, |pos, v| pos.ins().bitcast(to, v)).with_type(dest_ty),
+            },
+            (Some(from), Some(to)) if from == to => val.with_type(dest_ty),
+            _ if self.type_size(val.ty) == self.type_size(dest_ty)
+                && self.clif_leaves(val.ty) == self.clif_leaves(dest_ty) =>
+            {
+                let leaves = self.leaves(val);
+                self.from_leaves(dest_ty, leaves)
+            }
+            _ => bug!("bitcast: cannot cast {:?} to {:?}", val.ty, dest_ty),
+        }
+    }
+
+    fn intcast(&mut self, val: Value, dest_ty: Type, is_signed: bool) -> Value {
+        self.int_resize(val, dest_ty, is_signed)
+    }
+
+    fn pointercast(&mut self, val: Value, dest_ty: Type) -> Value {
+        val.with_type(dest_ty)
+    }
+
+    fn as_ptr_cast(&mut self, val: Value, _addr_space: AddrSpaceIdx, dest_ty: Type) -> Value {
+        val.with_type(dest_ty)
+    }
+
+    fn addrspace_cast(&mut self, val: Value, _dest: AddrSpaceIdx) -> Value {
+        val
+    }
+
+    fn flat_addr_cast(&mut self, val: Value) -> Value {
+        val
+    }
+
+    fn flat_as_ptr_cast(&mut self, val: Value, dest_ty: Type) -> Value {
+        val.with_type(dest_ty)
+    }
+
+    /* Comparisons */
+    fn icmp(&mut self, op: IntPredicate, lhs: Value, rhs: Value) -> Value {
+        if self.is_int128(lhs.ty) {
+            return self.icmp_i128(op, lhs, rhs);
+        }
+        let cc = int_cc(op);
+        let (a, b) = (self.val(lhs), self.val(rhs));
+        let r = self.ins(|pos| {
+            let cmp = pos.ins().icmp(cc, a, b);
+            pos.ins().bint(types::I8, cmp)
+        });
+        self.ssa(r, self.type_i1())
+    }
+
+    fn fcmp(&mut self, op: RealPredicate, lhs: Value, rhs: Value) -> Value {
+        let cc = match float_cc(&op) {
+            Some(cc) => cc,
+            None => return self.const_bool(matches!(op, RealPredicate::RealPredicateTrue)),
+        };
+        let (a, b) = (self.val(lhs), self.val(rhs));
+        let r = self.ins(|pos| {
+            let cmp = pos.ins().fcmp(cc, a, b);
+            pos.ins().bint(types::I8, cmp)
+        });
+        self.ssa(r, self.type_i1())
+    }
+
+    /* Miscellaneous instructions */
+    fn memcpy(
+        &mut self,
+        dst: Value,
+        _dst_align: Align,
+        src: Value,
+        _src_align: Align,
+        size: Value,
+        _flags: MemFlags,
+    ) {
+        let size = self.intcast(size, self.type_isize(), false);
+        let dst = self.pointercast(dst, self.type_i8p());
+        let src = self.pointercast(src, self.type_i8p());
+        self.call_libfn("memcpy", &[dst, src, size], self.type_i8p());
+    }
+
+    fn memmove(
+        &mut self,
+        dst: Value,
+        _dst_align: Align,
+        src: Value,
+        _src_align: Align,
+        size: Value,
+        _flags: MemFlags,
+    ) {
+        let size = self.intcast(size, self.type_isize(), false);
+        let dst = self.pointercast(dst, self.type_i8p());
+        let src = self.pointercast(src, self.type_i8p());
+        self.call_libfn("memmove", &[dst, src, size], self.type_i8p());
+    }
+
+    fn memset(
+        &mut self,
+        ptr: Value,
+        fill_byte: Value,
+        size: Value,
+        _align: Align,
+        _flags: MemFlags,
+    ) {
+        let size = self.intcast(size, self.type_isize(), false);
+        let ptr = self.pointercast(ptr, self.type_i8p());
+        // The C function takes the byte as an `int`.
+        let fill = self.zext(fill_byte, self.type_i32());
+        self.call_libfn("memset", &[ptr, fill, size], self.type_i8p());
+    }
+
+    fn select(&mut self, cond: Value, then_val: Value, else_val: Value) -> Value {
+        if let ValueKind::ConstInt(c) = cond.kind {
+            return if c != 0 { then_val } else { else_val };
+        }
+        let c = self.val(cond);
+        let then_leaves = self.leaves(then_val);
+        let else_leaves = self.leaves(else_val);
+        let vals = self.ins(|pos| {
+            then_leaves
+                .into_iter()
+                .zip(else_leaves)
+                .map(|(a, b)| pos.ins().select(c, a, b))
+                .collect()
+        });
+        self.from_leaves(then_val.ty, vals)
+    }
+
+    fn va_arg(&mut self, _list: Value, _ty: Type) -> Value {
+        self.sess().fatal("C-variadic functions are not supported by the Cranelift backend")
+    }
+
+    fn extract_element(&mut self, _vec: Value, _idx: Value) -> Value {
+        self.sess().fatal("SIMD vectors are not supported by the Cranelift backend")
+    }
+
+    fn vector_splat(&mut self, _num_elts: usize, _elt: Value) -> Value {
+        self.sess().fatal("SIMD vectors are not supported by the Cranelift backend")
+    }
+
+    fn extract_value(&mut self, agg_val: Value, idx: u64) -> Value {
+        self.aggregate_values(agg_val)[idx as usize]
+    }
+
+    fn insert_value(&mut self, agg_val: Value, elt: Value, idx: u64) -> Value {
+        let mut fields = self.aggregate_values(agg_val);
+        fields[idx as usize] = elt;
+        self.aggregate(fields, agg_val.ty)
+    }
+
+    fn landing_pad(&mut self, ty: Type, _pers_fn: Value, _num_clauses: usize) -> Value {
+        // Unwinding is not supported, landing pads are unreachable.
+        Value::new(ValueKind::Undef, ty)
+    }
+
+    fn set_cleanup(&mut self, _landing_pad: Value) {}
+
+    fn resume(&mut self, _exn: Value) -> Value {
+        self.ins(|pos| pos.ins().trap(TrapCode::UnreachableCodeReached));
+        Value::new(ValueKind::Undef, self.type_void())
+    }
+
+    fn cleanup_pad(&mut self, _parent: Option<Value>, _args: &[Value]) {
+        bug!("funclets are not supported by the Cranelift backend")
+    }
+
+    fn cleanup_ret(&mut self, _funclet: &(), _unwind: Option<BasicBlock>) -> Value {
+        bug!("funclets are not supported by the Cranelift backend")
+    }
+
+    fn catch_pad(&mut self, _parent: Value, _args: &[Value]) {
+        bug!("funclets are not supported by the Cranelift backend")
+    }
+
+    fn catch_switch(
+        &mut self,
+        _parent: Option<Value>,
+        _unwind: Option<BasicBlock>,
+        _num_handlers: usize,
+    ) -> Value {
+        bug!("funclets are not supported by the Cranelift backend")
+    }
+
+    fn add_handler(&mut self, _catch_switch: Value, _handler: BasicBlock) {
+        bug!("funclets are not supported by the Cranelift backend")
+    }
+
+    fn set_personality_fn(&mut self, _personality: Value) {}
+
+    // Atomic Operations
+    fn atomic_cmpxchg(
+        &mut self,
+        dst: Value,
+        cmp: Value,
+        src: Value,
+        _order: AtomicOrdering,
+        _failure_order: AtomicOrdering,
+        _weak: bool,
+    ) -> Value {
+        self.atomic_clif_type(cmp.ty);
+        let (addr, expected, new) = (self.val(dst), self.val(cmp), self.val(src));
+        let (prev, success) = self.ins(|pos| {
+            let prev = pos.ins().atomic_cas(ir::MemFlags::trusted(), addr, expected, new);
+            let success = pos.ins().icmp(IntCC::Equal, prev, expected);
+            (prev, pos.ins().bint(types::I8, success))
+        });
+        let fields = vec![self.ssa(prev, cmp.ty), self.ssa(success, self.type_i1())];
+        let ty = self.type_struct(&[cmp.ty, self.type_i1()], false);
+        self.aggregate(fields, ty)
+    }
+
+    fn atomic_rmw(
+        &mut self,
+        op: AtomicRmwBinOp,
+        dst: Value,
+        src: Value,
+        _order: AtomicOrdering,
+    ) -> Value {
+        let clif_ty = self.atomic_clif_type(src.ty);
+        let (addr, x) = (self.val(dst), self.val(src));
+        let native = match op {
+            AtomicRmwBinOp::AtomicXchg => Some(AtomicRmwOp::Xchg),
+            AtomicRmwBinOp::AtomicAdd => Some(AtomicRmwOp::Add),
+            AtomicRmwBinOp::AtomicSub => Some(AtomicRmwOp::Sub),
+            AtomicRmwBinOp::AtomicAnd => Some(AtomicRmwOp::And),
+            AtomicRmwBinOp::AtomicOr => Some(AtomicRmwOp::Or),
+            AtomicRmwBinOp::AtomicXor => Some(AtomicRmwOp::Xor),
+            AtomicRmwBinOp::AtomicNand
+            | AtomicRmwBinOp::AtomicMax
+            | AtomicRmwBinOp::AtomicMin
+            | AtomicRmwBinOp::AtomicUMax
+            | AtomicRmwBinOp::AtomicUMin => None,
+        };
+        let flags = ir::MemFlags::trusted();
+        if let Some(native) = native {
+            let prev = self.ins(|pos| pos.ins().atomic_rmw(clif_ty, flags, native, addr, x));
+            return self.ssa(prev, src.ty);
+        }
+
+        // The remaining operations are implemented with a compare and swap loop.
+        let mut loop_bx = self.build_sibling_block("atomic_rmw_loop");
+        let next = self.build_sibling_block("atomic_rmw_next").llbb();
+        let loop_block = loop_bx.llbb().block;
+
+        let current = loop_bx.block_param(src.ty);
+        let initial = self.ins(|pos| pos.ins().atomic_load(clif_ty, flags, addr));
+        let initial = self.ssa(initial, src.ty);
+        self.br_with_args(loop_bx.llbb(), &[initial]);
+
+        let current = loop_bx.val(current);
+        let prev = loop_bx.ins(|pos| {
+            let new = match op {
+                AtomicRmwBinOp::AtomicNand => {
+                    let and = pos.ins().band(current, x);
+                    pos.ins().bnot(and)
+                }
+                _ => {
+                    let cc = match op {
+                        AtomicRmwBinOp::AtomicMax => IntCC::SignedGreaterThan,
+                        AtomicRmwBinOp::AtomicMin => IntCC::SignedLessThan,
+                        AtomicRmwBinOp::AtomicUMax => IntCC::UnsignedGreaterThan,
+                        _ => IntCC::UnsignedLessThan,
+                    };
+                    let keep = pos.ins().icmp(cc, current, x);
+                    pos.ins().select(keep, current, x)
+                }
+            };
+            let prev = pos.ins().atomic_cas(flags, addr, current, new);
+            let retry = pos.ins().icmp(IntCC::NotEqual, prev, current);
+            pos.ins().brnz(retry, loop_block, &[prev]);
+            pos.ins().jump(next.block, &[]);
+            prev
+        });
+
+        self.position_at_end(next);
+        self.ssa(prev, src.ty)
+    }
+
+    fn atomic_fence(&mut self, _order: AtomicOrdering, _scope: SynchronizationScope) {
+        self.ins(|pos| pos.ins().fence());
+    }
+
+    fn set_invariant_load(&mut self, _load: Value) {}
+
+    fn lifetime_start(&mut self, _ptr: Value, _size: Size) {}
+
+    fn lifetime_end(&mut self, _ptr: Value, _size: Size) {}
+
+    fn instrprof_increment(
+        &mut self,
+        _fn_name: Value,
+        _hash: Value,
+        _num_counters: Value,
+        _index: Value,
+    ) {
+        bug!("coverage instrumentation is not supported by the Cranelift backend")
+    }
+
+    fn call(&mut self, llfn: Value, args: &[Value], _funclet: Option<&()>) -> Value {
+        let fn_ty = self.element_type(llfn.ty);
+        let ret_ty = self.func_return_type(fn_ty);
+
+        if let ValueKind::FuncAddr(func) = llfn.kind {
+            let name = self.func_data(func, |data| data.name.clone());
+            if name.starts_with("llvm.") {
+                return self.call_llvm_intrinsic(&name, args, ret_ty);
+            }
+        }
+
+        let mut arg_vals = vec![];
+        for &arg in args {
+            arg_vals.extend(self.leaves(arg));
+        }
+        let ret_leaves = self.clif_leaves(ret_ty);
+
+        // C-variadic functions are called through a trampoline that passes a bound on the
+        // number of vector registers used by the arguments, see `emit_variadic_trampoline`.
+        // The trampoline jumps to a known symbol, so there is none for function pointers.
+        let llfn = if self.func_is_variadic(fn_ty) {
+            match llfn.kind {
+                ValueKind::FuncAddr(func) => self.fn_value(self.variadic_trampoline(func)),
+                _ => self.sess().fatal(
+                    "calls to C-variadic function pointers are not supported by the Cranelift \
+                     backend",
+                ),
+            }
+        } else {
+            llfn
+        };
+
+        // Call the function directly if the arguments match its declaration. This is not the
+        // case for variadic functions, whose trampolines are declared without parameters, or
+        // when a function is declared more than once with different types, e.g. by two
+        // `extern` blocks. Those go through a signature built from the actual arguments.
+        let direct = match llfn.kind {
+            ValueKind::FuncAddr(func) => {
+                let sig = self.sig_of(func);
+                let bodies = self.cx.bodies.borrow();
+                let dfg = &bodies[&self.func()].func.dfg;
+                let params_match = sig.params.len() == arg_vals.len()
+                    && sig
+                        .params
+                        .iter()
+                        .zip(&arg_vals)
+                        .all(|(p, &v)| p.value_type == dfg.value_type(v));
+                let returns_match =
+                    sig.returns.iter().map(|r| r.value_type).eq(ret_leaves.iter().copied());
+                if params_match && returns_match { Some(func) } else { None }
+            }
+            _ => None,
+        };
+
+        let results = if let Some(func) = direct {
+            let func_ref = self.func_ref(func);
+            self.ins(|pos| {
+                let call = pos.ins().call(func_ref, &arg_vals);
+                pos.func.dfg.inst_results(call).to_vec()
+            })
+        } else {
+            let callee = self.val(llfn);
+            let call_conv = self.module.borrow().isa().default_call_conv();
+            self.ins(|pos| {
+                let mut sig = Signature::new(call_conv);
+                sig.params
+                    .extend(arg_vals.iter().map(|&v| AbiParam::new(pos.func.dfg.value_type(v))));
+                sig.returns.extend(ret_leaves.into_iter().map(AbiParam::new));
+                let sig_ref = pos.func.import_signature(sig);
+                let call = pos.ins().call_indirect(sig_ref, callee, &arg_vals);
+                pos.func.dfg.inst_results(call).to_vec()
+            })
+        };
+        self.from_leaves(ret_ty, results)
+    }
+
+    fn zext(&mut self, val: Value, dest_ty: Type) -> Value {
+        self.int_resize(val, dest_ty, false)
+    }
+
+    unsafe fn delete_basic_block(&mut self, bb: BasicBlock) {
+        let mut bodies = self.cx.bodies.borrow_mut();
+        let func = &mut bodies.get_mut(&bb.func).unwrap().func;
+        if func.layout.first_inst(bb.block).is_none() {
+            func.layout.remove_block(bb.block);
+        }
+    }
+
+    fn do_not_inline(&mut self, _llret: Value) {}
+}
+
+impl Builder<'a, 'tcx> {
+    /// The Cranelift type of the operand of an atomic operation.
+    fn atomic_clif_type(&self, ty: Type) -> ir::Type {
+        self.clif_type(ty).filter(|ty| ty.is_int()).unwrap_or_else(|| {
+            self.sess().fatal(&format!(
+                "atomic operations on {:?} are not supported by the Cranelift backend",
+                self.type_data(ty)
+            ))
+        })
+    }
+
+    fn fptoint(&mut self, val: Value, dest_ty: Type, signed: bool) -> Value {
+        // Cranelift only converts to 32 and 64 bit integers. The conversion saturates,
+        // and `rustc_codegen_ssa` clamps the result to the range of the type.
+        let to = self.clif_type(dest_ty).unwrap();
+        let wide = if to.bits() < 32 { types::I32 } else { to };
+        self.unary(val, |pos, v| {
+            let r = if signed {
+                pos.ins().fcvt_to_sint_sat(wide, v)
+            } else {
+                pos.ins().fcvt_to_uint_sat(wide, v)
+            };
+            if wide == to { r } else { pos.ins().ireduce(to, r) }
+        })
+        .with_type(dest_ty)
+    }
+
+    fn inttofp(&mut self, val: Value, dest_ty: Type, signed: bool) -> Value {
+        let from = self.clif_type(val.ty).unwrap();
+        let to = self.clif_type(dest_ty).unwrap();
+        self.unary(val, |pos, v| {
+            // Cranelift only converts from 32 and 64 bit integers.
+            let v = match (from.bits() < 32, signed) {
+                (true, true) => pos.ins().sextend(types::I32, v),
+                (true, false) => pos.ins().uextend(types::I32, v),
+                (false, _) => v,
+            };
+            if signed { pos.ins().fcvt_from_sint(to, v) } else { pos.ins().fcvt_from_uint(to, v) }
+        })
+        .with_type(dest_ty)
+    }
+
+    fn float_libcall_to(
+        &mut self,
+        val: Value,
+        dest_ty: Type,
+        f32_name: &str,
+        f64_name: &str,
+    ) -> Value {
+        let name = if self.float_width(val.ty) == 32 { f32_name } else { f64_name };
+        self.call_libfn(name, &[val], dest_ty)
+    }
+
+    /// Calls to LLVM intrinsics through `extern` declarations, which `core::arch` uses.
+    fn call_llvm_intrinsic(&mut self, name: &str, _args: &[Value], ret_ty: Type) -> Value {
+        match name {
+            "llvm.x86.sse2.pause" => {}
+            "llvm.trap" => self.abort(),
+            _ => self.sess().fatal(&format!(
+                "the LLVM intrinsic `{}` is not supported by the Cranelift backend",
+                name
+            )),
+        }
+        Value::new(ValueKind::Undef, ret_ty)
+    }
+}
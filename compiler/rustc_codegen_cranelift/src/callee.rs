@@ -0,0 +1,43 @@
+//! Handles codegen of callees as well as other call-related
+//! things. Callees are a superset of normal rust values and sometimes
+//! have different representations. In particular, top-level fn items
+//! and methods are represented as just a fn ptr and not a full
+//! closure.
+
+use crate::abi::FnAbi;
+use crate::context::CodegenCx;
+use crate::value::Function;
+
+use cranelift_module::Linkage;
+use rustc_middle::ty::layout::FnAbiExt;
+use rustc_middle::ty::{Instance, TypeFoldable};
+
+/// Codegens a reference to a fn/method item, monomorphizing and
+/// inlining as it goes.
+///
+/// Functions that are not defined in the current codegen unit are declared as imports.
+/// Everything defined here has already been declared by `predefine_fn`, with the right
+/// linkage and visibility, so it is found in the `instances` cache.
+pub fn get_fn(cx: &CodegenCx<'tcx>, instance: Instance<'tcx>) -> Function {
+    let tcx = cx.tcx;
+
+    assert!(!instance.substs.needs_infer());
+    assert!(!instance.substs.has_escaping_bound_vars());
+
+    if let Some(&func) = cx.instances.borrow().get(&instance) {
+        return func;
+    }
+
+    let sym = tcx.symbol_name(instance).name;
+    let fn_abi = FnAbi::of_instance(cx, instance, &[]);
+
+    // If a function with this name was already declared with a different signature, e.g.
+    // because two crates bind the same C function with different types, the existing
+    // declaration is returned. Calls then go through a signature built from the arguments,
+    // see `Builder::call`.
+    let func = cx.declare_fn(&sym, &fn_abi, Linkage::Import);
+
+    cx.instances.borrow_mut().insert(instance, func);
+
+    func
+}
@@ -0,0 +1,200 @@
+//! Code that is useful in various codegen modules.
+
+use crate::callee::get_fn;
+use crate::context::{CodegenCx, DataInit};
+use crate::type_::{LayoutClifExt, Type, TypeData};
+use crate::value::{Value, ValueKind};
+
+use rustc_codegen_ssa::mir::place::PlaceRef;
+use rustc_codegen_ssa::traits::*;
+use rustc_middle::bug;
+use rustc_middle::mir::interpret::{Allocation, GlobalAlloc, Scalar};
+use rustc_middle::ty::layout::TyAndLayout;
+use rustc_span::symbol::Symbol;
+use rustc_target::abi::{self, HasDataLayout, LayoutOf, Size};
+use rustc_target::spec::AddrSpaceIdx;
+
+impl CodegenCx<'tcx> {
+    /// Truncates `u` to the width of the integer type `t`.
+    fn const_int_masked(&self, t: Type, u: u128) -> Value {
+        let u = match self.type_data(t) {
+            TypeData::Int(bits) if bits < 128 => u & ((1 << bits) - 1),
+            _ => u,
+        };
+        Value::new(ValueKind::ConstInt(u), t)
+    }
+
+    fn is_int_type(&self, ty: Type) -> bool {
+        matches!(self.type_data(ty), TypeData::Int(_))
+    }
+}
+
+impl ConstMethods<'tcx> for CodegenCx<'tcx> {
+    fn const_null(&self, t: Type) -> Value {
+        match self.type_data(t) {
+            TypeData::F32 | TypeData::F64 => Value::new(ValueKind::ConstFloat(0), t),
+            // Aggregates are expanded by `aggregate_values` when needed.
+            _ => Value::new(ValueKind::ConstInt(0), t),
+        }
+    }
+
+    fn const_undef(&self, t: Type) -> Value {
+        Value::new(ValueKind::Undef, t)
+    }
+
+    fn const_int(&self, t: Type, i: i64) -> Value {
+        self.const_int_masked(t, i as i128 as u128)
+    }
+
+    fn const_uint(&self, t: Type, i: u64) -> Value {
+        self.const_int_masked(t, u128::from(i))
+    }
+
+    fn const_uint_big(&self, t: Type, u: u128) -> Value {
+        self.const_int_masked(t, u)
+    }
+
+    fn const_bool(&self, val: bool) -> Value {
+        self.const_uint(self.type_i1(), val as u64)
+    }
+
+    fn const_i32(&self, i: i32) -> Value {
+        self.const_int(self.type_i32(), i as i64)
+    }
+
+    fn const_u32(&self, i: u32) -> Value {
+        self.const_uint(self.type_i32(), i as u64)
+    }
+
+    fn const_u64(&self, i: u64) -> Value {
+        self.const_uint(self.type_i64(), i)
+    }
+
+    fn const_usize(&self, i: u64) -> Value {
+        let bit_size = self.data_layout().pointer_size.bits();
+        if bit_size < 64 {
+            // make sure it doesn't overflow
+            assert!(i < (1 << bit_size));
+        }
+
+        self.const_uint(self.type_isize(), i)
+    }
+
+    fn const_u8(&self, i: u8) -> Value {
+        self.const_uint(self.type_i8(), i as u64)
+    }
+
+    fn const_real(&self, t: Type, val: f64) -> Value {
+        let bits = match self.float_width(t) {
+            32 => u64::from((val as f32).to_bits()),
+            _ => val.to_bits(),
+        };
+        Value::new(ValueKind::ConstFloat(bits), t)
+    }
+
+    fn const_str(&self, s: Symbol) -> (Value, Value) {
+        let len = s.as_str().len();
+        let str_ty = self.type_ptr_to(self.layout_of(self.tcx.types.str_).clif_type(self));
+        if let Some(&cs) = self.const_str_cache.borrow().get(&s) {
+            return (cs, self.const_usize(len as u64));
+        }
+
+        let id = self.declare_anon_data(false);
+        let init = DataInit { bytes: s.as_str().as_bytes().to_vec(), relocs: vec![] };
+        self.define_data(id, init, 1, None);
+        let cs = Value::new(ValueKind::DataAddr(id, 0), str_ty);
+        self.const_str_cache.borrow_mut().insert(s, cs);
+        (cs, self.const_usize(len as u64))
+    }
+
+    fn const_struct(&self, elts: &[Value], packed: bool) -> Value {
+        let tys: Vec<_> = elts.iter().map(|elt| elt.ty).collect();
+        self.aggregate(elts.to_vec(), self.type_struct(&tys, packed))
+    }
+
+    fn const_to_opt_uint(&self, v: Value) -> Option<u64> {
+        match v.kind {
+            ValueKind::ConstInt(c) if self.is_int_type(v.ty) => Some(c as u64),
+            _ => None,
+        }
+    }
+
+    fn const_to_opt_u128(&self, v: Value, sign_ext: bool) -> Option<u128> {
+        match (v.kind, self.type_data(v.ty)) {
+            (ValueKind::ConstInt(c), TypeData::Int(bits)) => {
+                if sign_ext && bits < 128 && (c >> (bits - 1)) & 1 == 1 {
+                    Some(c | (u128::MAX << bits))
+                } else {
+                    Some(c)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn const_as_cast(&self, val: Value, _addr_space: AddrSpaceIdx) -> Value {
+        val
+    }
+
+    fn scalar_to_backend(&self, cv: Scalar, layout: &abi::Scalar, llty: Type) -> Value {
+        match cv {
+            Scalar::Raw { size: 0, .. } => {
+                assert_eq!(0, layout.value.size(self).bytes());
+                self.const_undef(self.type_ix(0))
+            }
+            Scalar::Raw { data, size } => {
+                assert_eq!(size as u64, layout.value.size(self).bytes());
+                match self.type_data(llty) {
+                    TypeData::F32 | TypeData::F64 => {
+                        Value::new(ValueKind::ConstFloat(data as u64), llty)
+                    }
+                    _ => Value::new(ValueKind::ConstInt(data), llty),
+                }
+            }
+            Scalar::Ptr(ptr) => {
+                let offset = ptr.offset.bytes() as i64;
+                let kind = match self.tcx.global_alloc(ptr.alloc_id) {
+                    GlobalAlloc::Memory(alloc) => {
+                        ValueKind::DataAddr(self.const_alloc_data(alloc), offset)
+                    }
+                    GlobalAlloc::Function(fn_instance) => {
+                        if offset != 0 {
+                            bug!("scalar_to_backend: offset {} into a function", offset);
+                        }
+                        ValueKind::FuncAddr(get_fn(self, fn_instance.polymorphize(self.tcx)))
+                    }
+                    GlobalAlloc::Static(def_id) => {
+                        assert!(self.tcx.is_static(def_id));
+                        assert!(!self.tcx.is_thread_local_static(def_id));
+                        let id = self.data_id_of(self.get_static(def_id));
+                        ValueKind::DataAddr(id, offset)
+                    }
+                };
+                // Pointers used as integers keep their kind, `Builder::val` materializes them
+                // as pointer-sized integers either way.
+                Value::new(kind, llty)
+            }
+        }
+    }
+
+    fn from_const_alloc(
+        &self,
+        layout: TyAndLayout<'tcx>,
+        alloc: &Allocation,
+        offset: Size,
+    ) -> PlaceRef<'tcx, Value> {
+        assert_eq!(alloc.align, layout.align.abi);
+        let llty = self.type_ptr_to(layout.clif_type(self));
+        let llval = if layout.size == Size::ZERO {
+            self.const_usize(alloc.align.bytes()).with_type(llty)
+        } else {
+            let id = self.const_alloc_data(alloc);
+            Value::new(ValueKind::DataAddr(id, offset.bytes() as i64), llty)
+        };
+        PlaceRef::new_sized(llval, layout)
+    }
+
+    fn const_ptrcast(&self, val: Value, ty: Type) -> Value {
+        val.with_type(ty)
+    }
+}
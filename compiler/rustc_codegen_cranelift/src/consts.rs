@@ -0,0 +1,185 @@
+use crate::builder::Builder;
+use crate::callee::get_fn;
+use crate::context::{CodegenCx, DataInit, RelocTarget};
+use crate::type_of::LayoutClifExt;
+use crate::value::{Value, ValueKind};
+
+use cranelift_module::{DataId, Linkage};
+use rustc_ast::Mutability;
+use rustc_codegen_ssa::traits::*;
+use rustc_hir::def_id::DefId;
+use rustc_middle::bug;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::interpret::{read_target_uint, Allocation, GlobalAlloc};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::{self, Instance};
+use rustc_target::abi::{Align, HasDataLayout, LayoutOf};
+
+impl CodegenCx<'tcx> {
+    /// The data object holding a constant allocation, created on first use.
+    crate fn const_alloc_data(&self, alloc: &Allocation) -> DataId {
+        let key = alloc as *const Allocation;
+        if let Some(&id) = self.const_allocs.borrow().get(&key) {
+            return id;
+        }
+        let writable = alloc.mutability == Mutability::Mut;
+        let id = self.declare_anon_data(writable);
+        // Insert before serializing, the allocation may (indirectly) point to itself.
+        self.const_allocs.borrow_mut().insert(key, id);
+        let init = self.alloc_to_data(alloc);
+        self.define_data(id, init, alloc.align.bytes(), None);
+        id
+    }
+
+    /// The bytes and relocations of an allocation. Relocated pointers are zeroed, their
+    /// offset becomes the addend of the relocation.
+    crate fn alloc_to_data(&self, alloc: &Allocation) -> DataInit {
+        let dl = self.data_layout();
+        let pointer_size = dl.pointer_size.bytes() as usize;
+
+        // This `inspect` is okay since it is within the bounds of the allocation, it doesn't
+        // affect interpreter execution (we inspect the result after interpreter execution).
+        // Any undef byte is replaced with some arbitrary byte value, and the bytes of
+        // relocations are replaced below.
+        let mut bytes =
+            alloc.inspect_with_uninit_and_ptr_outside_interpreter(0..alloc.len()).to_vec();
+        let mut relocs = Vec::with_capacity(alloc.relocations().len());
+        for &(offset, ((), alloc_id)) in alloc.relocations().iter() {
+            let offset = offset.bytes() as usize;
+            let ptr_bytes = &mut bytes[offset..offset + pointer_size];
+            let addend = read_target_uint(dl.endian, ptr_bytes)
+                .expect("alloc_to_data: could not read relocation pointer")
+                as i64;
+            ptr_bytes.iter_mut().for_each(|b| *b = 0);
+
+            let target = match self.tcx.global_alloc(alloc_id) {
+                GlobalAlloc::Memory(alloc) => RelocTarget::Data(self.const_alloc_data(alloc)),
+                GlobalAlloc::Function(fn_instance) => {
+                    if addend != 0 {
+                        bug!("alloc_to_data: offset {} into a function", addend);
+                    }
+                    RelocTarget::Func(get_fn(self, fn_instance.polymorphize(self.tcx)))
+                }
+                GlobalAlloc::Static(def_id) => {
+                    assert!(!self.tcx.is_thread_local_static(def_id));
+                    RelocTarget::Data(self.data_id_of(self.get_static(def_id)))
+                }
+            };
+            relocs.push((offset as u32, target, addend));
+        }
+        DataInit { bytes, relocs }
+    }
+
+    /// Serializes a constant value, as built by `ConstMethods`, in its in-memory layout.
+    fn value_to_data(&self, v: Value, offset: u64, init: &mut DataInit) {
+        let size = self.type_size(v.ty) as usize;
+        let offset = offset as usize;
+        match v.kind {
+            ValueKind::ConstInt(c) => {
+                init.bytes[offset..offset + size].copy_from_slice(&c.to_le_bytes()[..size]);
+            }
+            ValueKind::ConstFloat(bits) => {
+                init.bytes[offset..offset + size].copy_from_slice(&bits.to_le_bytes()[..size]);
+            }
+            ValueKind::Undef => {}
+            ValueKind::Aggregate(_) => {
+                for (i, field) in self.aggregate_values(v).into_iter().enumerate() {
+                    let field_offset = self.field_offset(v.ty, i as u64);
+                    self.value_to_data(field, (offset as u64) + field_offset, init);
+                }
+            }
+            ValueKind::FuncAddr(func) => {
+                init.relocs.push((offset as u32, RelocTarget::Func(func), 0));
+            }
+            ValueKind::DataAddr(id, addend) => {
+                init.relocs.push((offset as u32, RelocTarget::Data(id), addend));
+            }
+            ValueKind::Ssa(..) => bug!("value_to_data: {:?} is not a constant", v),
+        }
+    }
+
+    crate fn get_static(&self, def_id: DefId) -> Value {
+        if let Some(&g) = self.statics.borrow().get(&def_id) {
+            return g;
+        }
+
+        let defined_in_current_codegen_unit =
+            self.codegen_unit.items().contains_key(&MonoItem::Static(def_id));
+        assert!(
+            !defined_in_current_codegen_unit,
+            "consts::get_static() should always hit the cache for \
+                 statics defined in the same CGU, but did not for `{:?}`",
+            def_id
+        );
+
+        let instance = Instance::mono(self.tcx, def_id);
+        let ty = instance.ty(self.tcx, ty::ParamEnv::reveal_all());
+        let sym = self.tcx.symbol_name(instance).name;
+        let attrs = self.tcx.codegen_fn_attrs(def_id);
+
+        if attrs.linkage.is_some() {
+            self.sess().span_fatal(
+                self.tcx.def_span(def_id),
+                "`#[linkage]` on statics is not supported by the Cranelift backend",
+            );
+        }
+
+        let clif_ty = self.layout_of(ty).clif_type(self);
+        let writable = self.tcx.is_mutable_static(def_id) || !self.type_is_freeze(ty);
+        // Thread-local statics in some other crate need to *always* be linked
+        // against in a thread-local fashion.
+        let tls = attrs.flags.contains(CodegenFnAttrFlags::THREAD_LOCAL);
+        let g = self.declare_data(sym, clif_ty, Linkage::Import, writable, tls);
+
+        self.statics.borrow_mut().insert(def_id, g);
+        g
+    }
+}
+
+impl StaticMethods for CodegenCx<'tcx> {
+    fn static_addr_of(&self, cv: Value, align: Align, _kind: Option<&str>) -> Value {
+        if let Some(&gv) = self.const_globals.borrow().get(&(cv, align)) {
+            return gv;
+        }
+        let id = self.declare_anon_data(false);
+        let mut init = DataInit { bytes: vec![0; self.type_size(cv.ty) as usize], relocs: vec![] };
+        self.value_to_data(cv, 0, &mut init);
+        self.define_data(id, init, align.bytes(), None);
+        let gv = Value::new(ValueKind::DataAddr(id, 0), self.type_ptr_to(cv.ty));
+        self.const_globals.borrow_mut().insert((cv, align), gv);
+        gv
+    }
+
+    fn codegen_static(&self, def_id: DefId, _is_mutable: bool) {
+        let attrs = self.tcx.codegen_fn_attrs(def_id);
+
+        let alloc = match self.tcx.eval_static_initializer(def_id) {
+            Ok(alloc) => alloc,
+            // Error has already been reported
+            Err(_) => return,
+        };
+
+        // Whether the static is writable was decided by `predefine_static`.
+        let g = self.get_static(def_id);
+        let instance = Instance::mono(self.tcx, def_id);
+        let ty = instance.ty(self.tcx, ty::ParamEnv::reveal_all());
+
+        let init = self.alloc_to_data(alloc);
+        let section = attrs.link_section.map(|section| section.as_str());
+        self.define_data(self.data_id_of(g), init, self.align_of(ty).bytes(), section.as_deref());
+
+        if attrs.flags.contains(CodegenFnAttrFlags::USED) {
+            self.add_used_global(g);
+        }
+    }
+
+    fn add_used_global(&self, global: Value) {
+        self.used_statics.borrow_mut().push(global);
+    }
+}
+
+impl StaticBuilderMethods for Builder<'a, 'tcx> {
+    fn get_static(&mut self, def_id: DefId) -> Value {
+        self.cx.get_static(def_id)
+    }
+}
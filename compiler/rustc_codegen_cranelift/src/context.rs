@@ -0,0 +1,500 @@
+use crate::callee::get_fn;
+use crate::debuginfo::DebugContext;
+use crate::type_::{Type, TypeData};
+use crate::value::{BasicBlock, Function, Value, ValueKind};
+
+use cranelift_codegen::binemit::NullTrapSink;
+use cranelift_codegen::cursor::{Cursor, FuncCursor};
+use cranelift_codegen::ir::{self, InstBuilder};
+use cranelift_codegen::isa::TargetIsa;
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_codegen::Context;
+use cranelift_module::{DataContext, DataId, FuncId, Linkage, Module};
+use cranelift_object::{ObjectBuilder, ObjectModule, ObjectProduct};
+use object::write::{Relocation, StandardSection, SymbolSection};
+use object::{RelocationEncoding, RelocationKind, SymbolKind};
+use rustc_codegen_ssa::traits::*;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexSet};
+use rustc_hir::def_id::DefId;
+use rustc_index::vec::IndexVec;
+use rustc_middle::bug;
+use rustc_middle::mir::interpret::Allocation;
+use rustc_middle::mir::mono::CodegenUnit;
+use rustc_middle::ty::layout::{HasParamEnv, LayoutError, TyAndLayout};
+use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
+use rustc_session::config::{self, DebugInfo};
+use rustc_session::Session;
+use rustc_span::source_map::{Span, DUMMY_SP};
+use rustc_target::abi::{
+    Align, HasDataLayout, LayoutOf, PointeeInfo, Size, TargetDataLayout, VariantIdx,
+};
+use rustc_target::spec::{HasTargetSpec, RelocModel, Target};
+
+use std::cell::{Cell, RefCell};
+use std::str::FromStr;
+
+/// A function declared in the current codegen unit.
+pub struct FunctionData {
+    pub name: String,
+    pub id: FuncId,
+    /// The backend function type, see `FnAbiClifExt::clif_type`.
+    pub ty: Type,
+}
+
+/// The Cranelift IR of a function that is being defined in the current codegen unit.
+pub struct FunctionBody {
+    pub func: ir::Function,
+    /// The parameters of the function, in the order of the backend function type.
+    /// Aggregate parameters are reassembled from the Cranelift block parameters.
+    pub params: Vec<Value>,
+    /// The functions referenced from this body, see `Builder::val`.
+    pub func_refs: FxHashMap<Function, ir::FuncRef>,
+    /// The data objects referenced from this body.
+    pub global_values: FxHashMap<DataId, ir::GlobalValue>,
+}
+
+/// The contents of a data object, see `CodegenCx::define_data`.
+#[derive(Default)]
+pub struct DataInit {
+    pub bytes: Vec<u8>,
+    pub relocs: Vec<(u32, RelocTarget, i64)>,
+}
+
+#[derive(Copy, Clone)]
+pub enum RelocTarget {
+    Func(Function),
+    Data(DataId),
+}
+
+/// There is one `CodegenCx` per compilation unit. Each one has its own Cranelift module,
+/// which is turned into an object file once all items of the unit have been defined.
+pub struct CodegenCx<'tcx> {
+    pub tcx: TyCtxt<'tcx>,
+    pub check_overflow: bool,
+    pub codegen_unit: &'tcx CodegenUnit<'tcx>,
+
+    pub module: RefCell<ObjectModule>,
+    pub pointer_type: ir::Type,
+
+    /// All backend types, see `type_.rs`.
+    pub types: RefCell<FxIndexSet<TypeData>>,
+    pub struct_bodies: RefCell<FxHashMap<u32, (Vec<Type>, bool)>>,
+    pub named_struct_count: Cell<u32>,
+    pub types_of: RefCell<FxHashMap<(Ty<'tcx>, Option<VariantIdx>), Type>>,
+    pub scalar_types: RefCell<FxHashMap<Ty<'tcx>, Type>>,
+    pub pointee_infos: RefCell<FxHashMap<(Ty<'tcx>, Size), Option<PointeeInfo>>>,
+
+    /// The fields of aggregate values, see `ValueKind::Aggregate`.
+    pub aggregates: RefCell<Vec<Vec<Value>>>,
+
+    pub functions: RefCell<IndexVec<Function, FunctionData>>,
+    pub function_names: RefCell<FxHashMap<String, Function>>,
+    /// The bodies of the functions defined in this unit. They are compiled by `finish`.
+    pub bodies: RefCell<FxHashMap<Function, FunctionBody>>,
+    /// Cache instances of monomorphic and polymorphic items
+    pub instances: RefCell<FxHashMap<Instance<'tcx>, Function>>,
+
+    /// Cache of data objects for statics, both defined and declared.
+    pub statics: RefCell<FxHashMap<DefId, Value>>,
+    /// Data objects that hold thread local statics. Their address is computed at runtime.
+    pub thread_locals: RefCell<FxHashSet<DataId>>,
+    /// Cache of emitted const globals ((value, alignment) -> global). The alignment is part of
+    /// the key, as data objects cannot be realigned once defined.
+    pub const_globals: RefCell<FxHashMap<(Value, Align), Value>>,
+    /// Cache of constant allocations, keyed by their (interned) address.
+    pub const_allocs: RefCell<FxHashMap<*const Allocation, DataId>>,
+    pub const_str_cache: RefCell<FxHashMap<rustc_span::Symbol, Value>>,
+    pub anon_data_count: Cell<usize>,
+
+    /// Cache of emitted vtables
+    pub vtables: RefCell<FxHashMap<(Ty<'tcx>, Option<ty::PolyExistentialTraitRef<'tcx>>), Value>>,
+    /// Statics that will be placed in the `llvm.used` variable of the LLVM backend. Object files
+    /// have no such concept, and we never remove unused data objects, so they are only recorded.
+    pub used_statics: RefCell<Vec<Value>>,
+    /// The trampolines through which C-variadic functions are called, keyed by the function
+    /// they call, see `CodegenCx::variadic_trampoline`.
+    pub variadic_trampolines: RefCell<FxHashMap<Function, Function>>,
+
+    pub dbg_cx: Option<RefCell<DebugContext>>,
+}
+
+pub fn build_isa(sess: &Session) -> Box<dyn TargetIsa> {
+    let mut flags_builder = settings::builder();
+    if sess.relocation_model() == RelocModel::Pic {
+        flags_builder.enable("is_pic").unwrap();
+    }
+    flags_builder.set("enable_probestack", "false").unwrap();
+    flags_builder.set("tls_model", "elf_gd").unwrap();
+    flags_builder
+        .set("enable_verifier", if cfg!(debug_assertions) { "true" } else { "false" })
+        .unwrap();
+    let opt_level = match sess.opts.optimize {
+        config::OptLevel::No => "none",
+        config::OptLevel::Less | config::OptLevel::Default | config::OptLevel::Aggressive => {
+            "speed"
+        }
+        config::OptLevel::Size | config::OptLevel::SizeMin => "speed_and_size",
+    };
+    flags_builder.set("opt_level", opt_level).unwrap();
+    let flags = settings::Flags::new(flags_builder);
+
+    let triple = target_lexicon::Triple::from_str(&sess.target.target.llvm_target)
+        .unwrap_or_else(|e| sess.fatal(&format!("unsupported target triple: {}", e)));
+    let isa_builder = cranelift_codegen::isa::lookup(triple)
+        .unwrap_or_else(|e| sess.fatal(&format!("unsupported target: {}", e)));
+    isa_builder.finish(flags)
+}
+
+pub fn new_module(sess: &Session, name: &str) -> ObjectModule {
+    let libcall_names = cranelift_module::default_libcall_names();
+    let builder = ObjectBuilder::new(build_isa(sess), name.to_string(), libcall_names)
+        .unwrap_or_else(|e| sess.fatal(&format!("failed to create object module: {}", e)));
+    ObjectModule::new(builder)
+}
+
+impl<'tcx> CodegenCx<'tcx> {
+    crate fn new(
+        tcx: TyCtxt<'tcx>,
+        codegen_unit: &'tcx CodegenUnit<'tcx>,
+        module: ObjectModule,
+    ) -> Self {
+        let check_overflow = tcx.sess.overflow_checks();
+        let pointer_type = module.target_config().pointer_type();
+        let dbg_cx = if tcx.sess.opts.debuginfo != DebugInfo::None {
+            Some(RefCell::new(DebugContext::new(tcx, &codegen_unit.name().as_str())))
+        } else {
+            None
+        };
+
+        CodegenCx {
+            tcx,
+            check_overflow,
+            codegen_unit,
+            module: RefCell::new(module),
+            pointer_type,
+            types: Default::default(),
+            struct_bodies: Default::default(),
+            named_struct_count: Cell::new(0),
+            types_of: Default::default(),
+            scalar_types: Default::default(),
+            pointee_infos: Default::default(),
+            aggregates: Default::default(),
+            functions: Default::default(),
+            function_names: Default::default(),
+            bodies: Default::default(),
+            instances: Default::default(),
+            statics: Default::default(),
+            thread_locals: Default::default(),
+            const_globals: Default::default(),
+            const_allocs: Default::default(),
+            const_str_cache: Default::default(),
+            anon_data_count: Cell::new(0),
+            vtables: Default::default(),
+            used_statics: Default::default(),
+            variadic_trampolines: Default::default(),
+            dbg_cx,
+        }
+    }
+
+    crate fn aggregate(&self, fields: Vec<Value>, ty: Type) -> Value {
+        let mut aggregates = self.aggregates.borrow_mut();
+        aggregates.push(fields);
+        Value::new(ValueKind::Aggregate(aggregates.len() - 1), ty)
+    }
+
+    /// The fields of an aggregate value. `undef` aggregates are expanded to `undef` fields.
+    crate fn aggregate_values(&self, v: Value) -> Vec<Value> {
+        match v.kind {
+            ValueKind::Aggregate(idx) => self.aggregates.borrow()[idx].clone(),
+            ValueKind::Undef => self
+                .aggregate_fields(v.ty)
+                .into_iter()
+                .map(|ty| Value::new(ValueKind::Undef, ty))
+                .collect(),
+            ValueKind::ConstInt(0) => {
+                self.aggregate_fields(v.ty).into_iter().map(|ty| self.const_null(ty)).collect()
+            }
+            _ => bug!("aggregate_values: {:?} is not an aggregate", v),
+        }
+    }
+
+    /// Whether `v` is a constant, i.e. it does not depend on a function body.
+    crate fn is_const_value(&self, v: Value) -> bool {
+        match v.kind {
+            ValueKind::Ssa(..) => false,
+            ValueKind::Aggregate(_) => {
+                self.aggregate_values(v).into_iter().all(|field| self.is_const_value(field))
+            }
+            _ => true,
+        }
+    }
+
+    crate fn func_data<R>(&self, func: Function, f: impl FnOnce(&FunctionData) -> R) -> R {
+        f(&self.functions.borrow()[func])
+    }
+
+    crate fn get_declared_fn(&self, name: &str) -> Option<Function> {
+        self.function_names.borrow().get(name).copied()
+    }
+
+    crate fn fn_value(&self, func: Function) -> Value {
+        let ty = self.func_data(func, |data| data.ty);
+        Value::new(ValueKind::FuncAddr(func), self.type_ptr_to(ty))
+    }
+
+    /// Creates a data object that is private to this codegen unit.
+    crate fn declare_anon_data(&self, writable: bool) -> DataId {
+        let idx = self.anon_data_count.get();
+        self.anon_data_count.set(idx + 1);
+        let name = format!("anon.{}.{}", self.codegen_unit.name(), idx);
+        self.module
+            .borrow_mut()
+            .declare_data(&name, Linkage::Local, writable, false)
+            .unwrap_or_else(|e| bug!("failed to declare `{}`: {}", name, e))
+    }
+
+    crate fn define_data(
+        &self,
+        id: DataId,
+        init: DataInit,
+        align: u64,
+        section: Option<&str>,
+    ) {
+        let mut data_ctx = DataContext::new();
+        data_ctx.set_align(align);
+        if let Some(section) = section {
+            let (segment, section) = match section.find(',') {
+                Some(idx) => (&section[..idx], &section[idx + 1..]),
+                None => ("", section),
+            };
+            data_ctx.set_segment_section(segment, section);
+        }
+        let mut module = self.module.borrow_mut();
+        for &(offset, target, addend) in &init.relocs {
+            match target {
+                RelocTarget::Func(func) => {
+                    let func_id = self.func_data(func, |data| data.id);
+                    let func_ref = module.declare_func_in_data(func_id, &mut data_ctx);
+                    data_ctx.write_function_addr(offset, func_ref);
+                }
+                RelocTarget::Data(data_id) => {
+                    let gv = module.declare_data_in_data(data_id, &mut data_ctx);
+                    data_ctx.write_data_addr(offset, gv, addend);
+                }
+            }
+        }
+        data_ctx.define(init.bytes.into_boxed_slice());
+        if let Err(e) = module.define_data(id, &data_ctx) {
+            self.sess().fatal(&format!("failed to define data object: {}", e));
+        }
+    }
+
+    /// Compiles all function bodies and emits the debuginfo of this codegen unit.
+    crate fn finish(self) -> ObjectProduct {
+        let bodies = self.bodies.replace(Default::default());
+        let mut bodies: Vec<_> = bodies.into_iter().collect();
+        bodies.sort_by_key(|&(func, _)| func);
+
+        for (func, body) in bodies {
+            let (name, id) = self.func_data(func, |data| (data.name.clone(), data.id));
+            let mut ctx = Context::for_function(body.func);
+            seal_blocks(&mut ctx.func);
+
+            let mut module = self.module.borrow_mut();
+            if let Err(err) = module.define_function(id, &mut ctx, &mut NullTrapSink {}) {
+                let msg = match err {
+                    cranelift_module::ModuleError::Compilation(err) => {
+                        cranelift_codegen::print_errors::pretty_error(&ctx.func, None, err)
+                    }
+                    err => err.to_string(),
+                };
+                self.sess().fatal(&format!("failed to compile `{}`: {}", name, msg));
+            }
+            drop(module);
+
+            if let Some(dbg_cx) = &self.dbg_cx {
+                dbg_cx.borrow_mut().add_function(id, &name, &ctx);
+            }
+        }
+
+        let trampolines = self.variadic_trampolines.replace(Default::default());
+        let mut trampolines: Vec<_> = trampolines
+            .into_iter()
+            .map(|(callee, trampoline)| {
+                let id = |func| self.func_data(func, |data| data.id);
+                (id(trampoline), id(callee))
+            })
+            .collect();
+        trampolines.sort_by_key(|&(trampoline, _)| trampoline);
+
+        let mut product = self.module.into_inner().finish();
+        for (trampoline, callee) in trampolines {
+            emit_variadic_trampoline(&mut product, trampoline, callee);
+        }
+        if let Some(dbg_cx) = self.dbg_cx {
+            dbg_cx.into_inner().emit(self.tcx, &mut product);
+        }
+        product
+    }
+}
+
+/// Defines the code of a trampoline that calls the C-variadic function `callee`. The x86_64
+/// System V ABI passes an upper bound of the number of vector registers used by the arguments
+/// of a variadic call in `%al`, which can't be expressed in Cranelift IR. The trampoline sets
+/// it to 8, the largest bound allowed, and jumps to `callee`, leaving the arguments and the
+/// return address in place.
+fn emit_variadic_trampoline(product: &mut ObjectProduct, trampoline: FuncId, callee: FuncId) {
+    // mov al, 8
+    // jmp callee
+    const CODE: [u8; 7] = [0xb0, 0x08, 0xe9, 0, 0, 0, 0];
+    const JMP_OFFSET: u64 = 3;
+
+    let callee = product.function_symbol(callee);
+    let symbol = product.function_symbol(trampoline);
+    let section = product.object.section_id(StandardSection::Text);
+    let offset = product.object.append_section_data(section, &CODE, 16);
+    product
+        .object
+        .add_relocation(
+            section,
+            Relocation {
+                offset: offset + JMP_OFFSET,
+                size: 32,
+                kind: RelocationKind::PltRelative,
+                encoding: RelocationEncoding::Generic,
+                symbol: callee,
+                addend: -4,
+            },
+        )
+        .unwrap();
+    let symbol = product.object.symbol_mut(symbol);
+    symbol.section = SymbolSection::Section(section);
+    symbol.value = offset;
+    symbol.size = CODE.len() as u64;
+    symbol.kind = SymbolKind::Text;
+}
+
+/// Makes sure every block of `func` ends in a terminator, as Cranelift requires.
+/// `rustc_codegen_ssa` leaves unreachable blocks unterminated, e.g. after a diverging call.
+fn seal_blocks(func: &mut ir::Function) {
+    let blocks: Vec<_> = func.layout.blocks().collect();
+    for block in blocks {
+        match func.layout.last_inst(block) {
+            Some(inst) if func.dfg[inst].opcode().is_terminator() => {}
+            _ => {
+                let mut pos = FuncCursor::new(func).at_bottom(block);
+                pos.ins().trap(ir::TrapCode::UnreachableCodeReached);
+            }
+        }
+    }
+}
+
+impl MiscMethods<'tcx> for CodegenCx<'tcx> {
+    fn vtables(
+        &self,
+    ) -> &RefCell<FxHashMap<(Ty<'tcx>, Option<ty::PolyExistentialTraitRef<'tcx>>), Value>> {
+        &self.vtables
+    }
+
+    fn get_fn(&self, instance: Instance<'tcx>) -> Function {
+        get_fn(self, instance)
+    }
+
+    fn get_fn_addr(&self, instance: Instance<'tcx>) -> Value {
+        self.fn_value(get_fn(self, instance))
+    }
+
+    fn eh_personality(&self) -> Value {
+        // Landing pads are never entered, as this backend does not support unwinding,
+        // so there is no need to reference the real personality function.
+        self.const_null(self.type_i8p())
+    }
+
+    fn sess(&self) -> &Session {
+        &self.tcx.sess
+    }
+
+    fn check_overflow(&self) -> bool {
+        self.check_overflow
+    }
+
+    fn codegen_unit(&self) -> &'tcx CodegenUnit<'tcx> {
+        self.codegen_unit
+    }
+
+    fn used_statics(&self) -> &RefCell<Vec<Value>> {
+        &self.used_statics
+    }
+
+    fn set_frame_pointer_elimination(&self, _llfn: Function) {}
+
+    fn apply_target_cpu_attr(&self, _llfn: Function) {}
+
+    fn create_used_variable(&self) {}
+
+    fn declare_c_main(&self, fn_type: Type) -> Option<Function> {
+        if self.get_declared_fn("main").is_none() {
+            Some(self.declare_fn_with_linkage("main", fn_type, Linkage::Export))
+        } else {
+            // If the symbol already exists, it is an error: for example, the user wrote
+            // #[no_mangle] extern "C" fn main(..) {..}
+            // instead of #[start]
+            None
+        }
+    }
+}
+
+impl BackendTypes for CodegenCx<'tcx> {
+    type Value = Value;
+    type Function = Function;
+    type BasicBlock = BasicBlock;
+    type Type = Type;
+    type Funclet = ();
+
+    type DIScope = ();
+    type DILocation = ();
+    type DIVariable = ();
+}
+
+impl HasDataLayout for CodegenCx<'tcx> {
+    fn data_layout(&self) -> &TargetDataLayout {
+        &self.tcx.data_layout
+    }
+}
+
+impl HasTargetSpec for CodegenCx<'tcx> {
+    fn target_spec(&self) -> &Target {
+        &self.tcx.sess.target.target
+    }
+}
+
+impl ty::layout::HasTyCtxt<'tcx> for CodegenCx<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+}
+
+impl LayoutOf for CodegenCx<'tcx> {
+    type Ty = Ty<'tcx>;
+    type TyAndLayout = TyAndLayout<'tcx>;
+
+    fn layout_of(&self, ty: Ty<'tcx>) -> Self::TyAndLayout {
+        self.spanned_layout_of(ty, DUMMY_SP)
+    }
+
+    fn spanned_layout_of(&self, ty: Ty<'tcx>, span: Span) -> Self::TyAndLayout {
+        self.tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)).unwrap_or_else(|e| {
+            if let LayoutError::SizeOverflow(_) = e {
+                self.sess().span_fatal(span, &e.to_string())
+            } else {
+                bug!("failed to get layout for `{}`: {}", ty, e)
+            }
+        })
+    }
+}
+
+impl<'tcx> HasParamEnv<'tcx> for CodegenCx<'tcx> {
+    fn param_env(&self) -> ty::ParamEnv<'tcx> {
+        ty::ParamEnv::reveal_all()
+    }
+}
@@ -0,0 +1,57 @@
+//! `-Z instrument-coverage` relies on LLVM's coverage mapping format and runtime, so it is
+//! rejected by `CraneliftCodegenBackend::init`. Nothing here is ever called.
+
+use crate::builder::Builder;
+use crate::context::CodegenCx;
+use crate::value::Value;
+
+use rustc_codegen_ssa::traits::*;
+use rustc_middle::bug;
+use rustc_middle::mir::coverage::*;
+use rustc_middle::ty::Instance;
+
+impl CoverageInfoMethods for CodegenCx<'tcx> {
+    fn coverageinfo_finalize(&self) {}
+}
+
+impl CoverageInfoBuilderMethods<'tcx> for Builder<'a, 'tcx> {
+    fn create_pgo_func_name_var(&self, _instance: Instance<'tcx>) -> Value {
+        bug!("coverage instrumentation is not supported by the Cranelift backend")
+    }
+
+    fn add_counter_region(
+        &mut self,
+        _instance: Instance<'tcx>,
+        _function_source_hash: u64,
+        _id: CounterValueReference,
+        _region: CodeRegion,
+    ) {
+        bug!("coverage instrumentation is not supported by the Cranelift backend")
+    }
+
+    fn add_counter_expression_region(
+        &mut self,
+        _instance: Instance<'tcx>,
+        _id: InjectedExpressionIndex,
+        _lhs: ExpressionOperandId,
+        _op: Op,
+        _rhs: ExpressionOperandId,
+        _region: Option<CodeRegion>,
+    ) {
+        bug!("coverage instrumentation is not supported by the Cranelift backend")
+    }
+
+    fn add_unreachable_region(&mut self, _instance: Instance<'tcx>, _region: CodeRegion) {
+        bug!("coverage instrumentation is not supported by the Cranelift backend")
+    }
+
+    fn add_branch_region(
+        &mut self,
+        _instance: Instance<'tcx>,
+        _true_id: ExpressionOperandId,
+        _false_id: ExpressionOperandId,
+        _region: CodeRegion,
+    ) {
+        bug!("coverage instrumentation is not supported by the Cranelift backend")
+    }
+}
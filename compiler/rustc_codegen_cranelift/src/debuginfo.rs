@@ -0,0 +1,371 @@
+//! Debuginfo for the Cranelift backend.
+//!
+//! Only line tables are emitted: every function gets a line program sequence built from the
+//! source locations Cranelift records for the machine code it generates. There is no type or
+//! variable information yet, so debuggers can step through code and show backtraces, but
+//! can't print values.
+
+use crate::builder::Builder;
+use crate::context::CodegenCx;
+use crate::value::{Function, Value};
+
+use cranelift_codegen::ir::SourceLoc;
+use cranelift_codegen::Context;
+use cranelift_module::FuncId;
+use cranelift_object::ObjectProduct;
+use gimli::write::{
+    Address, AttributeValue, DwarfUnit, EndianVec, FileId, LineProgram, LineString, Range,
+    RangeList, Sections, Writer,
+};
+use gimli::{Encoding, Format, LineEncoding, RunTimeEndian, SectionId};
+use object::write::{Relocation, SectionId as ObjectSectionId};
+use object::{RelocationEncoding, RelocationKind, SectionKind};
+use rustc_codegen_ssa::mir::debuginfo::{DebugScope, FunctionDebugContext, VariableKind};
+use rustc_codegen_ssa::traits::*;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir;
+use rustc_middle::ty::{Instance, Ty, TyCtxt};
+use rustc_span::source_map::{RemapPathScopes, SourceMap};
+use rustc_span::{BytePos, FileName, SourceFile, Span, Symbol};
+use rustc_target::abi::call::FnAbi;
+use rustc_target::abi::Size;
+
+use std::path::PathBuf;
+
+/// The DWARF unit of a codegen unit, filled in as its functions are compiled.
+pub struct DebugContext {
+    dwarf: DwarfUnit,
+    unit_range_list: RangeList,
+    files: FxHashMap<FileName, FileId>,
+    source_map: Lrc<SourceMap>,
+    endian: RunTimeEndian,
+}
+
+impl DebugContext {
+    pub fn new(tcx: TyCtxt<'_>, codegen_unit_name: &str) -> Self {
+        let encoding = Encoding { format: Format::Dwarf32, version: 4, address_size: 8 };
+        let mut dwarf = DwarfUnit::new(encoding);
+
+        let mut name_in_debuginfo =
+            match tcx.sess.local_crate_source_file_for_scope(RemapPathScopes::DEBUGINFO) {
+                Some(path) => path.to_path_buf(),
+                None => PathBuf::from(&*tcx.crate_name(LOCAL_CRATE).as_str()),
+            };
+        // See `compile_unit_metadata` in the LLVM backend.
+        if tcx.sess.target.target.options.is_like_osx {
+            name_in_debuginfo.push("@");
+            name_in_debuginfo.push(codegen_unit_name);
+        }
+        let name = name_in_debuginfo.to_string_lossy().into_owned();
+        let comp_dir = tcx.sess.working_dir_for_scope(RemapPathScopes::DEBUGINFO);
+        let comp_dir = comp_dir.to_string_lossy().into_owned();
+        let producer =
+            format!("rustc version {}", option_env!("CFG_VERSION").expect("CFG_VERSION"));
+
+        dwarf.unit.line_program = LineProgram::new(
+            encoding,
+            LineEncoding::default(),
+            LineString::new(comp_dir.as_bytes(), encoding, &mut dwarf.line_strings),
+            LineString::new(name.as_bytes(), encoding, &mut dwarf.line_strings),
+            None,
+        );
+
+        let root = dwarf.unit.root();
+        let root = dwarf.unit.get_mut(root);
+        root.set(gimli::DW_AT_producer, AttributeValue::StringRef(dwarf.strings.add(producer)));
+        root.set(gimli::DW_AT_language, AttributeValue::Language(gimli::DW_LANG_Rust));
+        root.set(gimli::DW_AT_name, AttributeValue::StringRef(dwarf.strings.add(name)));
+        root.set(gimli::DW_AT_comp_dir, AttributeValue::StringRef(dwarf.strings.add(comp_dir)));
+        root.set(gimli::DW_AT_low_pc, AttributeValue::Address(Address::Constant(0)));
+
+        let endian = match tcx.data_layout.endian {
+            rustc_target::abi::Endian::Little => RunTimeEndian::Little,
+            rustc_target::abi::Endian::Big => RunTimeEndian::Big,
+        };
+
+        DebugContext {
+            dwarf,
+            unit_range_list: RangeList(Vec::new()),
+            files: Default::default(),
+            source_map: tcx.sess.parse_sess.clone_source_map(),
+            endian,
+        }
+    }
+
+    fn file_id(&mut self, file: &SourceFile) -> FileId {
+        if let Some(&id) = self.files.get(&file.name) {
+            return id;
+        }
+        let encoding = self.dwarf.unit.encoding();
+        let line_program = &mut self.dwarf.unit.line_program;
+        let dir_id = line_program.default_directory();
+        let file_name = self.source_map.filename_for_scope(file, RemapPathScopes::DEBUGINFO);
+        let file_name = file_name.to_string();
+        let file_name =
+            LineString::new(file_name.as_bytes(), encoding, &mut self.dwarf.line_strings);
+        let id = line_program.add_file(file_name, dir_id, None);
+        self.files.insert(file.name.clone(), id);
+        id
+    }
+
+    /// Adds the line program sequence of a function that has just been compiled by `ctx`.
+    pub fn add_function(&mut self, id: FuncId, _name: &str, ctx: &Context) {
+        let result = ctx.mach_compile_result.as_ref().expect("function was not compiled");
+        let address = Address::Symbol { symbol: id.as_u32() as usize, addend: 0 };
+        let size = u64::from(result.buffer.total_size());
+
+        self.dwarf.unit.line_program.begin_sequence(Some(address));
+        for srcloc in result.buffer.get_srclocs_sorted() {
+            if srcloc.loc == SourceLoc::default() {
+                continue;
+            }
+            let loc = self.source_map.lookup_char_pos(BytePos(srcloc.loc.bits()));
+            let file_id = self.file_id(&loc.file);
+            let row = self.dwarf.unit.line_program.row();
+            row.address_offset = u64::from(srcloc.start);
+            row.file = file_id;
+            row.line = loc.line as u64;
+            row.column = loc.col.to_u32() as u64 + 1;
+            self.dwarf.unit.line_program.generate_row();
+        }
+        self.dwarf.unit.line_program.end_sequence(size);
+
+        self.unit_range_list.0.push(Range::StartLength { begin: address, length: size });
+    }
+
+    /// Writes the DWARF sections of this unit into `product`.
+    pub fn emit(mut self, tcx: TyCtxt<'_>, product: &mut ObjectProduct) {
+        let unit_range_list_id = self.dwarf.unit.ranges.add(self.unit_range_list);
+        let root = self.dwarf.unit.root();
+        self.dwarf
+            .unit
+            .get_mut(root)
+            .set(gimli::DW_AT_ranges, AttributeValue::RangeListRef(unit_range_list_id));
+
+        let mut sections = Sections::new(WriterRelocate::new(self.endian));
+        if let Err(e) = self.dwarf.write(&mut sections) {
+            tcx.sess.fatal(&format!("failed to write debuginfo: {}", e));
+        }
+
+        let mut section_map = FxHashMap::default();
+        let _: gimli::write::Result<()> = sections.for_each(|id, section| {
+            if !section.writer.slice().is_empty() {
+                let name = id.name().as_bytes().to_vec();
+                let section_id = product.object.add_section(Vec::new(), name, SectionKind::Debug);
+                product.object.set_section_data(section_id, section.writer.slice().to_vec(), 1);
+                section_map.insert(id, section_id);
+            }
+            Ok(())
+        });
+
+        let _: gimli::write::Result<()> = sections.for_each(|id, section| {
+            if let Some(&section_id) = section_map.get(&id) {
+                for reloc in &section.relocs {
+                    product.add_debug_reloc(&section_map, section_id, reloc);
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+/// A relocation in a debug section, to be resolved when the section is added to the object.
+#[derive(Clone)]
+struct DebugReloc {
+    offset: u32,
+    size: u8,
+    target: DebugRelocTarget,
+    addend: i64,
+}
+
+#[derive(Clone)]
+enum DebugRelocTarget {
+    Function(FuncId),
+    Section(SectionId),
+}
+
+trait ObjectProductExt {
+    fn add_debug_reloc(
+        &mut self,
+        section_map: &FxHashMap<SectionId, ObjectSectionId>,
+        from: ObjectSectionId,
+        reloc: &DebugReloc,
+    );
+}
+
+impl ObjectProductExt for ObjectProduct {
+    fn add_debug_reloc(
+        &mut self,
+        section_map: &FxHashMap<SectionId, ObjectSectionId>,
+        from: ObjectSectionId,
+        reloc: &DebugReloc,
+    ) {
+        let symbol = match reloc.target {
+            DebugRelocTarget::Function(id) => self.function_symbol(id),
+            DebugRelocTarget::Section(id) => self.object.section_symbol(section_map[&id]),
+        };
+        self.object
+            .add_relocation(
+                from,
+                Relocation {
+                    offset: u64::from(reloc.offset),
+                    size: reloc.size * 8,
+                    kind: RelocationKind::Absolute,
+                    encoding: RelocationEncoding::Generic,
+                    symbol,
+                    addend: reloc.addend,
+                },
+            )
+            .unwrap();
+    }
+}
+
+/// A `gimli` writer that records relocations instead of resolving addresses and section
+/// offsets, as neither is known before the object file is linked.
+#[derive(Clone)]
+struct WriterRelocate {
+    relocs: Vec<DebugReloc>,
+    writer: EndianVec<RunTimeEndian>,
+}
+
+impl WriterRelocate {
+    fn new(endian: RunTimeEndian) -> Self {
+        WriterRelocate { relocs: Vec::new(), writer: EndianVec::new(endian) }
+    }
+}
+
+impl Writer for WriterRelocate {
+    type Endian = RunTimeEndian;
+
+    fn endian(&self) -> Self::Endian {
+        self.writer.endian()
+    }
+
+    fn len(&self) -> usize {
+        self.writer.len()
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.writer.write(bytes)
+    }
+
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.writer.write_at(offset, bytes)
+    }
+
+    fn write_address(&mut self, address: Address, size: u8) -> gimli::write::Result<()> {
+        match address {
+            Address::Constant(val) => self.write_udata(val, size),
+            Address::Symbol { symbol, addend } => {
+                let offset = self.len() as u32;
+                let target = DebugRelocTarget::Function(FuncId::from_u32(symbol as u32));
+                self.relocs.push(DebugReloc { offset, size, target, addend });
+                self.write_udata(0, size)
+            }
+        }
+    }
+
+    fn write_offset(
+        &mut self,
+        val: usize,
+        section: SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        let offset = self.len() as u32;
+        let target = DebugRelocTarget::Section(section);
+        self.relocs.push(DebugReloc { offset, size, target, addend: val as i64 });
+        self.write_udata(0, size)
+    }
+
+    fn write_offset_at(
+        &mut self,
+        offset: usize,
+        val: usize,
+        section: SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        let target = DebugRelocTarget::Section(section);
+        self.relocs.push(DebugReloc { offset: offset as u32, size, target, addend: val as i64 });
+        self.write_udata_at(offset, 0, size)
+    }
+}
+
+impl DebugInfoMethods<'tcx> for CodegenCx<'tcx> {
+    fn create_vtable_metadata(&self, _ty: Ty<'tcx>, _vtable: Value) {}
+
+    fn create_function_debug_context(
+        &self,
+        instance: Instance<'tcx>,
+        _fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+        _llfn: Function,
+        mir: &mir::Body<'tcx>,
+    ) -> Option<FunctionDebugContext<Self::DIScope, Self::DILocation>> {
+        self.dbg_cx.as_ref()?;
+
+        // There are no lexical scopes in the line tables, and the file of a location is
+        // looked up when the function is added to the line program. So a single scope that
+        // covers all files is enough, `extend_scope_to_file` is never needed.
+        let scope = DebugScope {
+            scope_metadata: Some(()),
+            inlined_at: None,
+            file_start_pos: BytePos(0),
+            file_end_pos: BytePos(u32::MAX),
+        };
+        Some(FunctionDebugContext {
+            scopes: IndexVec::from_elem(scope, &mir.source_scopes),
+            defining_crate: instance.def_id().krate,
+        })
+    }
+
+    fn extend_scope_to_file(
+        &self,
+        scope_metadata: Self::DIScope,
+        _file: &SourceFile,
+        _defining_crate: CrateNum,
+    ) -> Self::DIScope {
+        scope_metadata
+    }
+
+    fn debuginfo_finalize(&self) {}
+
+    fn create_dbg_var(
+        &self,
+        _dbg_context: &FunctionDebugContext<Self::DIScope, Self::DILocation>,
+        _variable_name: Symbol,
+        _variable_type: Ty<'tcx>,
+        _scope_metadata: Self::DIScope,
+        _variable_kind: VariableKind,
+        _span: Span,
+    ) -> Self::DIVariable {
+    }
+}
+
+impl DebugInfoBuilderMethods for Builder<'a, 'tcx> {
+    fn dbg_var_addr(
+        &mut self,
+        _dbg_var: Self::DIVariable,
+        _scope_metadata: Self::DIScope,
+        _inlined_at: Option<Self::DILocation>,
+        _variable_alloca: Value,
+        _direct_offset: Size,
+        _indirect_offsets: &[Size],
+        _span: Span,
+    ) {
+    }
+
+    fn set_source_location(
+        &mut self,
+        _scope: Self::DIScope,
+        _inlined_at: Option<Self::DILocation>,
+        span: Span,
+    ) {
+        self.srcloc = SourceLoc::new(span.lo().0);
+    }
+
+    fn insert_reference_to_gdb_debug_scripts_section_global(&mut self) {}
+
+    fn set_var_name(&mut self, _value: Value, _name: &str) {}
+}
@@ -0,0 +1,144 @@
+//! Declare various Cranelift values.
+//!
+//! Prefer using functions and methods from this module rather than calling
+//! `declare_function` and `declare_data` on the module directly, as the
+//! functions here keep the caches of `CodegenCx` in sync with the module.
+
+use crate::abi::{FnAbi, FnAbiClifExt};
+use crate::context::{CodegenCx, FunctionData};
+use crate::type_::Type;
+use crate::value::{Function, Value, ValueKind};
+
+use cranelift_codegen::ir::{AbiParam, Signature};
+use cranelift_module::{DataId, Linkage, Module};
+use rustc_codegen_ssa::traits::*;
+use rustc_middle::bug;
+use rustc_middle::ty::Ty;
+
+impl CodegenCx<'tcx> {
+    /// The Cranelift signature of a backend function type. Every argument and the return
+    /// value are flattened into their scalar leaves.
+    crate fn signature(&self, fn_type: Type) -> Signature {
+        let mut sig = Signature::new(self.module.borrow().isa().default_call_conv());
+        for arg in self.func_params_types(fn_type) {
+            sig.params.extend(self.clif_leaves(arg).into_iter().map(AbiParam::new));
+        }
+        let ret = self.func_return_type(fn_type);
+        sig.returns.extend(self.clif_leaves(ret).into_iter().map(AbiParam::new));
+        sig
+    }
+
+    fn declare_raw_fn(
+        &self,
+        name: &str,
+        fn_type: Type,
+        sig: Signature,
+        linkage: Linkage,
+    ) -> Function {
+        let id =
+            self.module.borrow_mut().declare_function(name, linkage, &sig).unwrap_or_else(|e| {
+                self.sess().fatal(&format!("failed to declare `{}`: {}", name, e))
+            });
+        if let Some(func) = self.get_declared_fn(name) {
+            return func;
+        }
+        let func = self.functions.borrow_mut().push(FunctionData {
+            name: name.to_string(),
+            id,
+            ty: fn_type,
+        });
+        self.function_names.borrow_mut().insert(name.to_string(), func);
+        func
+    }
+
+    /// Declare a function with the given linkage.
+    ///
+    /// If there's a value with the same name already declared, the function will
+    /// return it, after merging the linkages.
+    crate fn declare_fn_with_linkage(
+        &self,
+        name: &str,
+        fn_type: Type,
+        linkage: Linkage,
+    ) -> Function {
+        let sig = match self.get_declared_fn(name) {
+            Some(func) => self.sig_of(func),
+            None => self.signature(fn_type),
+        };
+        self.declare_raw_fn(name, fn_type, sig, linkage)
+    }
+
+    /// Declare a C ABI function.
+    ///
+    /// Only use this for foreign function ABIs and glue. For Rust functions use
+    /// `declare_fn` instead.
+    crate fn declare_cfn(&self, name: &str, fn_type: Type) -> Function {
+        self.declare_fn_with_linkage(name, fn_type, Linkage::Import)
+    }
+
+    /// Declare a Rust function.
+    ///
+    /// If there's a value with the same name already declared, the function will
+    /// return it.
+    crate fn declare_fn(
+        &self,
+        name: &str,
+        fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+        linkage: Linkage,
+    ) -> Function {
+        let sig = match self.get_declared_fn(name) {
+            Some(func) => self.sig_of(func),
+            None => fn_abi.clif_signature(self),
+        };
+        self.declare_raw_fn(name, fn_abi.clif_type(self), sig, linkage)
+    }
+
+    /// The trampoline through which the C-variadic function `callee` is called, see
+    /// `emit_variadic_trampoline`. It is local to the codegen unit, and its code is only
+    /// emitted once the unit is finished, so it has no signature of its own: calls go through
+    /// its address with a signature built from the actual arguments.
+    crate fn variadic_trampoline(&self, callee: Function) -> Function {
+        if let Some(&trampoline) = self.variadic_trampolines.borrow().get(&callee) {
+            return trampoline;
+        }
+        let (name, ty) = self.func_data(callee, |data| (data.name.clone(), data.ty));
+        let sig = Signature::new(self.module.borrow().isa().default_call_conv());
+        let trampoline =
+            self.declare_raw_fn(&format!("{}.variadic", name), ty, sig, Linkage::Local);
+        self.variadic_trampolines.borrow_mut().insert(callee, trampoline);
+        trampoline
+    }
+
+    /// The signature a function was declared with.
+    crate fn sig_of(&self, func: Function) -> Signature {
+        let id = self.func_data(func, |data| data.id);
+        self.module.borrow().declarations().get_function_decl(id).signature.clone()
+    }
+
+    /// Declare a data object with the given linkage, returning a pointer to it typed as a
+    /// pointer to `ty`.
+    crate fn declare_data(
+        &self,
+        name: &str,
+        ty: Type,
+        linkage: Linkage,
+        writable: bool,
+        tls: bool,
+    ) -> Value {
+        let id =
+            self.module.borrow_mut().declare_data(name, linkage, writable, tls).unwrap_or_else(
+                |e| self.sess().fatal(&format!("failed to declare `{}`: {}", name, e)),
+            );
+        if tls {
+            self.thread_locals.borrow_mut().insert(id);
+        }
+        Value::new(ValueKind::DataAddr(id, 0), self.type_ptr_to(ty))
+    }
+
+    crate fn data_id_of(&self, v: Value) -> DataId {
+        match v.kind {
+            ValueKind::DataAddr(id, 0) => id,
+            _ => bug!("data_id_of: {:?} is not the address of a data object", v),
+        }
+    }
+}
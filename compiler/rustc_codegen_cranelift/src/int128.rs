@@ -0,0 +1,282 @@
+//! 128-bit integer arithmetic.
+//!
+//! The x86_64 backend of Cranelift has no support for `i128`, so 128-bit integers are
+//! represented as a pair of `i64` values (low half first) and every operation on them is
+//! lowered to operations on the halves here. Division and the rare operations that are
+//! expensive to open code call into `compiler_builtins`, just like LLVM does.
+
+use crate::builder::Builder;
+use crate::type_::Type;
+use crate::value::Value;
+
+use cranelift_codegen::cursor::FuncCursor;
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::ir::{self, types, InstBuilder};
+use rustc_codegen_ssa::common::IntPredicate;
+use rustc_codegen_ssa::traits::*;
+use rustc_target::abi::Align;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+crate enum Shift {
+    Left,
+    LogicalRight,
+    ArithmeticRight,
+}
+
+/// The unsigned comparison used for the low halves of two integers compared with `op`.
+fn unsigned_cc(op: &IntPredicate) -> IntCC {
+    match *op {
+        IntPredicate::IntEQ => IntCC::Equal,
+        IntPredicate::IntNE => IntCC::NotEqual,
+        IntPredicate::IntUGT | IntPredicate::IntSGT => IntCC::UnsignedGreaterThan,
+        IntPredicate::IntUGE | IntPredicate::IntSGE => IntCC::UnsignedGreaterThanOrEqual,
+        IntPredicate::IntULT | IntPredicate::IntSLT => IntCC::UnsignedLessThan,
+        IntPredicate::IntULE | IntPredicate::IntSLE => IntCC::UnsignedLessThanOrEqual,
+    }
+}
+
+fn signed_cc(op: &IntPredicate) -> IntCC {
+    match *op {
+        IntPredicate::IntSGT => IntCC::SignedGreaterThan,
+        IntPredicate::IntSGE => IntCC::SignedGreaterThanOrEqual,
+        IntPredicate::IntSLT => IntCC::SignedLessThan,
+        IntPredicate::IntSLE => IntCC::SignedLessThanOrEqual,
+        _ => unsigned_cc(op),
+    }
+}
+
+impl Builder<'a, 'tcx> {
+    /// The low and high halves of a 128-bit integer.
+    crate fn halves(&mut self, v: Value) -> (ir::Value, ir::Value) {
+        let leaves = self.leaves(v);
+        assert_eq!(leaves.len(), 2, "halves: {:?} is not a 128-bit integer", v);
+        (leaves[0], leaves[1])
+    }
+
+    crate fn from_halves(&self, ty: Type, lo: ir::Value, hi: ir::Value) -> Value {
+        self.from_leaves(ty, vec![lo, hi])
+    }
+
+    fn bool_value(&self, v: ir::Value) -> Value {
+        self.ssa(v, self.type_i1())
+    }
+
+    crate fn add_i128(&mut self, lhs: Value, rhs: Value) -> Value {
+        let (a_lo, a_hi) = self.halves(lhs);
+        let (b_lo, b_hi) = self.halves(rhs);
+        let (lo, hi) = self.ins(|pos| {
+            let lo = pos.ins().iadd(a_lo, b_lo);
+            let carry = pos.ins().icmp(IntCC::UnsignedLessThan, lo, a_lo);
+            let carry = pos.ins().bint(types::I64, carry);
+            let hi = pos.ins().iadd(a_hi, b_hi);
+            (lo, pos.ins().iadd(hi, carry))
+        });
+        self.from_halves(lhs.ty, lo, hi)
+    }
+
+    crate fn sub_i128(&mut self, lhs: Value, rhs: Value) -> Value {
+        let (a_lo, a_hi) = self.halves(lhs);
+        let (b_lo, b_hi) = self.halves(rhs);
+        let (lo, hi) = self.ins(|pos| {
+            let lo = pos.ins().isub(a_lo, b_lo);
+            let borrow = pos.ins().icmp(IntCC::UnsignedLessThan, a_lo, b_lo);
+            let borrow = pos.ins().bint(types::I64, borrow);
+            let hi = pos.ins().isub(a_hi, b_hi);
+            (lo, pos.ins().isub(hi, borrow))
+        });
+        self.from_halves(lhs.ty, lo, hi)
+    }
+
+    crate fn mul_i128(&mut self, lhs: Value, rhs: Value) -> Value {
+        let (a_lo, a_hi) = self.halves(lhs);
+        let (b_lo, b_hi) = self.halves(rhs);
+        let (lo, hi) = self.ins(|pos| {
+            let lo = pos.ins().imul(a_lo, b_lo);
+            let hi = pos.ins().umulhi(a_lo, b_lo);
+            let cross_a = pos.ins().imul(a_hi, b_lo);
+            let cross_b = pos.ins().imul(a_lo, b_hi);
+            let hi = pos.ins().iadd(hi, cross_a);
+            (lo, pos.ins().iadd(hi, cross_b))
+        });
+        self.from_halves(lhs.ty, lo, hi)
+    }
+
+    crate fn bitwise_i128(
+        &mut self,
+        lhs: Value,
+        rhs: Value,
+        f: impl Fn(&mut FuncCursor<'_>, ir::Value, ir::Value) -> ir::Value,
+    ) -> Value {
+        let (a_lo, a_hi) = self.halves(lhs);
+        let (b_lo, b_hi) = self.halves(rhs);
+        let (lo, hi) = self.ins(|pos| (f(pos, a_lo, b_lo), f(pos, a_hi, b_hi)));
+        self.from_halves(lhs.ty, lo, hi)
+    }
+
+    /// Shifts by `amount % 128`. The shift amount has the same type as the shifted value,
+    /// as `rustc_codegen_ssa` casts it before shifting.
+    crate fn shift_i128(&mut self, lhs: Value, rhs: Value, shift: Shift) -> Value {
+        let (lo, hi) = self.halves(lhs);
+        let (amount, _) = self.halves(rhs);
+        let (lo, hi) = self.ins(|pos| {
+            let amount = pos.ins().band_imm(amount, 127);
+            let big = pos.ins().icmp_imm(IntCC::UnsignedGreaterThanOrEqual, amount, 64);
+            let s = pos.ins().band_imm(amount, 63);
+            // The bits that move from one half to the other, computed as `x >> 1 >> (63 - s)`
+            // so that a shift by 0 does not need a shift by 64.
+            let inverse = pos.ins().bxor_imm(s, 63);
+            let (small_lo, small_hi, big_lo, big_hi) = match shift {
+                Shift::Left => {
+                    let carried = pos.ins().ushr_imm(lo, 1);
+                    let carried = pos.ins().ushr(carried, inverse);
+                    let small_hi = pos.ins().ishl(hi, s);
+                    let small_hi = pos.ins().bor(small_hi, carried);
+                    let small_lo = pos.ins().ishl(lo, s);
+                    let big_hi = pos.ins().ishl(lo, s);
+                    let big_lo = pos.ins().iconst(types::I64, 0);
+                    (small_lo, small_hi, big_lo, big_hi)
+                }
+                Shift::LogicalRight | Shift::ArithmeticRight => {
+                    let carried = pos.ins().ishl_imm(hi, 1);
+                    let carried = pos.ins().ishl(carried, inverse);
+                    let small_lo = pos.ins().ushr(lo, s);
+                    let small_lo = pos.ins().bor(small_lo, carried);
+                    if shift == Shift::LogicalRight {
+                        let small_hi = pos.ins().ushr(hi, s);
+                        let big_lo = pos.ins().ushr(hi, s);
+                        let big_hi = pos.ins().iconst(types::I64, 0);
+                        (small_lo, small_hi, big_lo, big_hi)
+                    } else {
+                        let small_hi = pos.ins().sshr(hi, s);
+                        let big_lo = pos.ins().sshr(hi, s);
+                        let big_hi = pos.ins().sshr_imm(hi, 63);
+                        (small_lo, small_hi, big_lo, big_hi)
+                    }
+                }
+            };
+            let lo = pos.ins().select(big, big_lo, small_lo);
+            let hi = pos.ins().select(big, big_hi, small_hi);
+            (lo, hi)
+        });
+        self.from_halves(lhs.ty, lo, hi)
+    }
+
+    crate fn icmp_i128(&mut self, op: IntPredicate, lhs: Value, rhs: Value) -> Value {
+        let (a_lo, a_hi) = self.halves(lhs);
+        let (b_lo, b_hi) = self.halves(rhs);
+        let r = self.ins(|pos| {
+            let r = match op {
+                IntPredicate::IntEQ | IntPredicate::IntNE => {
+                    let lo = pos.ins().bxor(a_lo, b_lo);
+                    let hi = pos.ins().bxor(a_hi, b_hi);
+                    let diff = pos.ins().bor(lo, hi);
+                    pos.ins().icmp_imm(unsigned_cc(&op), diff, 0)
+                }
+                _ => {
+                    // The high halves decide, unless they are equal.
+                    let hi_eq = pos.ins().icmp(IntCC::Equal, a_hi, b_hi);
+                    let hi = pos.ins().icmp(signed_cc(&op), a_hi, b_hi);
+                    let lo = pos.ins().icmp(unsigned_cc(&op), a_lo, b_lo);
+                    pos.ins().select(hi_eq, lo, hi)
+                }
+            };
+            pos.ins().bint(types::I8, r)
+        });
+        self.bool_value(r)
+    }
+
+    /// Casts from or to a 128-bit integer.
+    crate fn int_resize_i128(&mut self, val: Value, dest_ty: Type, signed: bool) -> Value {
+        if self.is_int128(val.ty) {
+            let (lo, _) = self.halves(val);
+            let lo = self.ssa(lo, self.type_i64());
+            return self.int_resize(lo, dest_ty, false);
+        }
+        let lo = self.int_resize(val, self.type_i64(), signed);
+        let lo = self.val(lo);
+        let hi = self.ins(|pos| {
+            if signed { pos.ins().sshr_imm(lo, 63) } else { pos.ins().iconst(types::I64, 0) }
+        });
+        self.from_halves(dest_ty, lo, hi)
+    }
+
+    crate fn checked_binop_i128(
+        &mut self,
+        oop: OverflowOp,
+        signed: bool,
+        lhs: Value,
+        rhs: Value,
+    ) -> (Value, Value) {
+        let ty = lhs.ty;
+        let is_add = matches!(oop, OverflowOp::Add);
+        match oop {
+            OverflowOp::Add | OverflowOp::Sub => {
+                let result = if is_add { self.add_i128(lhs, rhs) } else { self.sub_i128(lhs, rhs) };
+                if !signed {
+                    let overflow = if is_add {
+                        self.icmp_i128(IntPredicate::IntULT, result, lhs)
+                    } else {
+                        self.icmp_i128(IntPredicate::IntULT, lhs, rhs)
+                    };
+                    return (result, overflow);
+                }
+                // Same as for the smaller integers, only the sign bits matter.
+                let (_, a) = self.halves(lhs);
+                let (_, b) = self.halves(rhs);
+                let (_, r) = self.halves(result);
+                let overflow = self.ins(|pos| {
+                    let (x, y) = if is_add {
+                        (pos.ins().bxor(r, a), pos.ins().bxor(r, b))
+                    } else {
+                        (pos.ins().bxor(a, b), pos.ins().bxor(a, r))
+                    };
+                    let both = pos.ins().band(x, y);
+                    let overflow = pos.ins().icmp_imm(IntCC::SignedLessThan, both, 0);
+                    pos.ins().bint(types::I8, overflow)
+                });
+                (result, self.bool_value(overflow))
+            }
+            OverflowOp::Mul if signed => {
+                // `__muloti4` reports the overflow through an `i32` out parameter.
+                let i32_ty = self.type_i32();
+                let overflow_ptr = self.alloca(i32_ty, Align::from_bytes(4).unwrap());
+                let args = [lhs, rhs, overflow_ptr];
+                let result = self.call_libfn("__muloti4", &args, ty);
+                let overflow = self.load(overflow_ptr, Align::from_bytes(4).unwrap());
+                let zero = self.const_i32(0);
+                let overflow = self.icmp(IntPredicate::IntNE, overflow, zero);
+                (result, overflow)
+            }
+            OverflowOp::Mul => {
+                let (a_lo, a_hi) = self.halves(lhs);
+                let (b_lo, b_hi) = self.halves(rhs);
+                let (lo, hi, overflow) = self.ins(|pos| {
+                    // If both high halves are non-zero the product needs at least 129 bits.
+                    // Otherwise at most one of the cross products is non-zero, and it must
+                    // fit into 64 bits.
+                    let a_hi_set = pos.ins().icmp_imm(IntCC::NotEqual, a_hi, 0);
+                    let b_hi_set = pos.ins().icmp_imm(IntCC::NotEqual, b_hi, 0);
+                    let both_set = pos.ins().band(a_hi_set, b_hi_set);
+                    let cross_a_hi = pos.ins().umulhi(a_hi, b_lo);
+                    let cross_a_overflow = pos.ins().icmp_imm(IntCC::NotEqual, cross_a_hi, 0);
+                    let cross_b_hi = pos.ins().umulhi(a_lo, b_hi);
+                    let cross_b_overflow = pos.ins().icmp_imm(IntCC::NotEqual, cross_b_hi, 0);
+
+                    let lo = pos.ins().imul(a_lo, b_lo);
+                    let lo_hi = pos.ins().umulhi(a_lo, b_lo);
+                    let cross_a = pos.ins().imul(a_hi, b_lo);
+                    let cross_b = pos.ins().imul(a_lo, b_hi);
+                    let cross = pos.ins().iadd(cross_a, cross_b);
+                    let hi = pos.ins().iadd(lo_hi, cross);
+                    let carry = pos.ins().icmp(IntCC::UnsignedLessThan, hi, lo_hi);
+
+                    let overflow = pos.ins().bor(both_set, cross_a_overflow);
+                    let overflow = pos.ins().bor(overflow, cross_b_overflow);
+                    let overflow = pos.ins().bor(overflow, carry);
+                    (lo, hi, pos.ins().bint(types::I8, overflow))
+                });
+                (self.from_halves(ty, lo, hi), self.bool_value(overflow))
+            }
+        }
+    }
+}
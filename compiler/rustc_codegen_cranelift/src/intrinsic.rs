@@ -0,0 +1,447 @@
+use crate::abi::{CastTargetExt, FnAbi, PassMode};
+use crate::builder::Builder;
+use crate::context::CodegenCx;
+use crate::type_of::LayoutClifExt;
+use crate::value::Value;
+
+use cranelift_codegen::cursor::FuncCursor;
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::ir::{self, types, InstBuilder, TrapCode};
+use rustc_codegen_ssa::common::span_invalid_monomorphization_error;
+use rustc_codegen_ssa::common::IntPredicate;
+use rustc_codegen_ssa::mir::operand::OperandRef;
+use rustc_codegen_ssa::mir::place::PlaceRef;
+use rustc_codegen_ssa::traits::*;
+use rustc_middle::bug;
+use rustc_middle::ty::{self, Ty};
+use rustc_span::{sym, symbol::kw, Span, Symbol};
+use rustc_target::abi::LayoutOf;
+
+/// The C library function implementing a floating point intrinsic, for the intrinsics
+/// Cranelift has no (portable) instruction for.
+fn libm_name(name: Symbol) -> Option<&'static str> {
+    Some(match name {
+        sym::powif32 => "__powisf2",
+        sym::powif64 => "__powidf2",
+        sym::sinf32 => "sinf",
+        sym::sinf64 => "sin",
+        sym::cosf32 => "cosf",
+        sym::cosf64 => "cos",
+        sym::powf32 => "powf",
+        sym::powf64 => "pow",
+        sym::expf32 => "expf",
+        sym::expf64 => "exp",
+        sym::exp2f32 => "exp2f",
+        sym::exp2f64 => "exp2",
+        sym::logf32 => "logf",
+        sym::logf64 => "log",
+        sym::log10f32 => "log10f",
+        sym::log10f64 => "log10",
+        sym::log2f32 => "log2f",
+        sym::log2f64 => "log2",
+        sym::fmaf32 => "fmaf",
+        sym::fmaf64 => "fma",
+        sym::minnumf32 => "fminf",
+        sym::minnumf64 => "fmin",
+        sym::maxnumf32 => "fmaxf",
+        sym::maxnumf64 => "fmax",
+        sym::floorf32 => "floorf",
+        sym::floorf64 => "floor",
+        sym::ceilf32 => "ceilf",
+        sym::ceilf64 => "ceil",
+        sym::truncf32 => "truncf",
+        sym::truncf64 => "trunc",
+        sym::rintf32 => "rintf",
+        sym::rintf64 => "rint",
+        sym::nearbyintf32 => "nearbyintf",
+        sym::nearbyintf64 => "nearbyint",
+        sym::roundf32 => "roundf",
+        sym::roundf64 => "round",
+        _ => return None,
+    })
+}
+
+/// Reverses the bytes of `v`, one byte at a time.
+fn bswap(pos: &mut FuncCursor<'_>, v: ir::Value) -> ir::Value {
+    let ty = pos.func.dfg.value_type(v);
+    let bytes = i64::from(ty.bytes());
+    let mut result = pos.ins().iconst(ty, 0);
+    for i in 0..bytes {
+        let byte = if i == 0 { v } else { pos.ins().ushr_imm(v, i * 8) };
+        let byte = pos.ins().band_imm(byte, 0xff);
+        let shift = (bytes - 1 - i) * 8;
+        let byte = if shift == 0 { byte } else { pos.ins().ishl_imm(byte, shift) };
+        result = pos.ins().bor(result, byte);
+    }
+    result
+}
+
+/// Counts the leading or trailing zeros of a value of type `i8` or wider. Cranelift only
+/// counts bits of `i32` and `i64` values.
+fn count_zeros(pos: &mut FuncCursor<'_>, v: ir::Value, leading: bool) -> ir::Value {
+    let ty = pos.func.dfg.value_type(v);
+    if ty.bits() >= 32 {
+        return if leading { pos.ins().clz(v) } else { pos.ins().ctz(v) };
+    }
+    let wide = pos.ins().uextend(types::I32, v);
+    let count = if leading {
+        let count = pos.ins().clz(wide);
+        pos.ins().iadd_imm(count, -(32 - i64::from(ty.bits())))
+    } else {
+        // Stop at the width of the narrow type if `v` is zero.
+        let wide = pos.ins().bor_imm(wide, 1 << ty.bits());
+        pos.ins().ctz(wide)
+    };
+    pos.ins().ireduce(ty, count)
+}
+
+fn popcnt(pos: &mut FuncCursor<'_>, v: ir::Value) -> ir::Value {
+    let ty = pos.func.dfg.value_type(v);
+    if ty.bits() >= 32 {
+        return pos.ins().popcnt(v);
+    }
+    let wide = pos.ins().uextend(types::I32, v);
+    let count = pos.ins().popcnt(wide);
+    pos.ins().ireduce(ty, count)
+}
+
+impl IntrinsicCallMethods<'tcx> for Builder<'a, 'tcx> {
+    fn codegen_intrinsic_call(
+        &mut self,
+        instance: ty::Instance<'tcx>,
+        fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+        args: &[OperandRef<'tcx, Value>],
+        llresult: Value,
+        span: Span,
+    ) {
+        let tcx = self.tcx;
+        let callee_ty = instance.ty(tcx, ty::ParamEnv::reveal_all());
+
+        let (def_id, substs) = match *callee_ty.kind() {
+            ty::FnDef(def_id, substs) => (def_id, substs),
+            _ => bug!("expected fn item type, found {}", callee_ty),
+        };
+
+        let sig = callee_ty.fn_sig(tcx);
+        let sig = tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), &sig);
+        let arg_tys = sig.inputs();
+        let ret_ty = sig.output();
+        let name = tcx.item_name(def_id);
+        let name_str = &*name.as_str();
+
+        let llret_ty = self.layout_of(ret_ty).clif_type(self);
+        let result = PlaceRef::new_sized(llresult, fn_abi.ret.layout);
+
+        let llval = match name {
+            _ if libm_name(name).is_some() => {
+                let args: Vec<_> = args.iter().map(|arg| arg.immediate()).collect();
+                self.call_libfn(libm_name(name).unwrap(), &args, llret_ty)
+            }
+            sym::sqrtf32 | sym::sqrtf64 => {
+                let x = self.val(args[0].immediate());
+                let r = self.ins(|pos| pos.ins().sqrt(x));
+                self.ssa(r, llret_ty)
+            }
+            sym::fabsf32 | sym::fabsf64 => {
+                let x = self.val(args[0].immediate());
+                let r = self.ins(|pos| pos.ins().fabs(x));
+                self.ssa(r, llret_ty)
+            }
+            sym::copysignf32 | sym::copysignf64 => {
+                let x = self.val(args[0].immediate());
+                let y = self.val(args[1].immediate());
+                let r = self.ins(|pos| pos.ins().fcopysign(x, y));
+                self.ssa(r, llret_ty)
+            }
+            sym::likely | sym::unlikely => args[0].immediate(),
+            kw::Try => {
+                // Unwinding is not supported, so the catch function is never called.
+                let try_func = args[0].immediate();
+                let data = args[1].immediate();
+                self.call(try_func, &[data], None);
+                let ret_align = self.tcx().data_layout.i32_align.abi;
+                self.store(self.const_i32(0), llresult, ret_align);
+                return;
+            }
+            sym::breakpoint => {
+                self.ins(|pos| pos.ins().debugtrap());
+                return;
+            }
+            sym::va_copy | sym::va_arg => self.sess().span_fatal(
+                span,
+                "C-variadic functions are not supported by the Cranelift backend",
+            ),
+
+            sym::volatile_load | sym::unaligned_volatile_load => {
+                let tp_ty = substs.type_at(0);
+                let mut ptr = args[0].immediate();
+                if let PassMode::Cast(ty) = fn_abi.ret.mode {
+                    ptr = self.pointercast(ptr, self.type_ptr_to(ty.clif_type(self)));
+                }
+                let load = self.volatile_load(ptr);
+                self.to_immediate(load, self.layout_of(tp_ty))
+            }
+            sym::volatile_store => {
+                let dst = args[0].deref(self.cx());
+                args[1].val.volatile_store(self, dst);
+                return;
+            }
+            sym::unaligned_volatile_store => {
+                let dst = args[0].deref(self.cx());
+                args[1].val.unaligned_volatile_store(self, dst);
+                return;
+            }
+            sym::prefetch_read_data
+            | sym::prefetch_write_data
+            | sym::prefetch_read_instruction
+            | sym::prefetch_write_instruction => {
+                // Prefetching is only a hint.
+                return;
+            }
+            sym::ctlz
+            | sym::ctlz_nonzero
+            | sym::cttz
+            | sym::cttz_nonzero
+            | sym::ctpop
+            | sym::bswap
+            | sym::bitreverse
+            | sym::rotate_left
+            | sym::rotate_right
+            | sym::saturating_add
+            | sym::saturating_sub => {
+                let ty = arg_tys[0];
+                match int_type_width_signed(ty, self) {
+                    Some((width, signed)) => {
+                        let x = args[0].immediate();
+                        if width == 128 {
+                            self.int128_intrinsic(name, signed, args)
+                        } else {
+                            match name {
+                                sym::ctlz | sym::ctlz_nonzero => {
+                                    let x = self.val(x);
+                                    let r = self.ins(|pos| count_zeros(pos, x, true));
+                                    self.ssa(r, llret_ty)
+                                }
+                                sym::cttz | sym::cttz_nonzero => {
+                                    let x = self.val(x);
+                                    let r = self.ins(|pos| count_zeros(pos, x, false));
+                                    self.ssa(r, llret_ty)
+                                }
+                                sym::ctpop => {
+                                    let x = self.val(x);
+                                    let r = self.ins(|pos| popcnt(pos, x));
+                                    self.ssa(r, llret_ty)
+                                }
+                                sym::bswap => {
+                                    let x = self.val(x);
+                                    let r = self.ins(|pos| bswap(pos, x));
+                                    self.ssa(r, llret_ty)
+                                }
+                                sym::bitreverse => {
+                                    let x = self.val(x);
+                                    let r = self.ins(|pos| pos.ins().bitrev(x));
+                                    self.ssa(r, llret_ty)
+                                }
+                                sym::rotate_left | sym::rotate_right => {
+                                    let is_left = name == sym::rotate_left;
+                                    let x = self.val(x);
+                                    let shift = self.val(args[1].immediate());
+                                    let r = self.ins(|pos| {
+                                        if is_left {
+                                            pos.ins().rotl(x, shift)
+                                        } else {
+                                            pos.ins().rotr(x, shift)
+                                        }
+                                    });
+                                    self.ssa(r, llret_ty)
+                                }
+                                sym::saturating_add | sym::saturating_sub => {
+                                    self.saturating_binop(name, signed, ty, args)
+                                }
+                                _ => bug!(),
+                            }
+                        }
+                    }
+                    None => {
+                        span_invalid_monomorphization_error(
+                            tcx.sess,
+                            span,
+                            &format!(
+                                "invalid monomorphization of `{}` intrinsic: \
+                                      expected basic integer type, found `{}`",
+                                name, ty
+                            ),
+                        );
+                        return;
+                    }
+                }
+            }
+
+            _ if name_str.starts_with("simd_") => self
+                .sess()
+                .span_fatal(span, "SIMD intrinsics are not supported by the Cranelift backend"),
+            _ if name_str.starts_with("atomic_scoped_fence_") => self.sess().span_fatal(
+                span,
+                "scoped atomic fences are not supported by the Cranelift backend",
+            ),
+            sym::amdgcn_dispatch_ptr | sym::amdgcn_queue_ptr => self
+                .sess()
+                .span_fatal(span, "AMDGPU intrinsics are not supported by the Cranelift backend"),
+
+            _ => bug!("unknown intrinsic '{}'", name),
+        };
+
+        if !fn_abi.ret.is_ignore() {
+            if let PassMode::Cast(ty) = fn_abi.ret.mode {
+                let ptr_llty = self.type_ptr_to(ty.clif_type(self));
+                let ptr = self.pointercast(result.llval, ptr_llty);
+                self.store(llval, ptr, result.align);
+            } else {
+                OperandRef::from_immediate_or_packed_pair(self, llval, result.layout)
+                    .val
+                    .store(self, result);
+            }
+        }
+    }
+
+    fn abort(&mut self) {
+        self.ins(|pos| pos.ins().trap(TrapCode::User(0)));
+    }
+
+    fn assume(&mut self, _val: Value) {}
+
+    fn expect(&mut self, cond: Value, _expected: bool) -> Value {
+        cond
+    }
+
+    fn type_test(&mut self, _pointer: Value, _typeid: Value) -> Value {
+        self.sess().fatal("control flow integrity is not supported by the Cranelift backend")
+    }
+
+    fn sideeffect(&mut self) {
+        // Cranelift never removes infinite loops.
+    }
+
+    fn va_start(&mut self, _va_list: Value) -> Value {
+        self.sess().fatal("C-variadic functions are not supported by the Cranelift backend")
+    }
+
+    fn va_end(&mut self, _va_list: Value) -> Value {
+        self.sess().fatal("C-variadic functions are not supported by the Cranelift backend")
+    }
+}
+
+impl Builder<'a, 'tcx> {
+    /// `saturating_add` and `saturating_sub`, in terms of the overflow checking operations.
+    fn saturating_binop(
+        &mut self,
+        name: Symbol,
+        signed: bool,
+        ty: Ty<'tcx>,
+        args: &[OperandRef<'tcx, Value>],
+    ) -> Value {
+        let is_add = name == sym::saturating_add;
+        let lhs = args[0].immediate();
+        let rhs = args[1].immediate();
+        let oop = if is_add { OverflowOp::Add } else { OverflowOp::Sub };
+        let (result, overflow) = self.checked_binop(oop, ty, lhs, rhs);
+
+        let width = self.int_width(lhs.ty);
+        let (min, max) = if signed {
+            let max = u128::MAX >> (129 - width);
+            (max + 1, max)
+        } else {
+            (0, u128::MAX >> (128 - width))
+        };
+        let (min, max) = (self.const_uint_big(lhs.ty, min), self.const_uint_big(lhs.ty, max));
+        let bound = if signed {
+            // Overflowing signed additions go past the bound in the direction of the sign
+            // of `rhs`, subtractions in the opposite one.
+            let zero = self.const_null(lhs.ty);
+            let rhs_negative = self.icmp(IntPredicate::IntSLT, rhs, zero);
+            if is_add {
+                self.select(rhs_negative, min, max)
+            } else {
+                self.select(rhs_negative, max, min)
+            }
+        } else if is_add {
+            max
+        } else {
+            min
+        };
+        self.select(overflow, bound, result)
+    }
+
+    /// The bit manipulation intrinsics on 128-bit integers, in terms of the two halves.
+    fn int128_intrinsic(
+        &mut self,
+        name: Symbol,
+        signed: bool,
+        args: &[OperandRef<'tcx, Value>],
+    ) -> Value {
+        let x = args[0].immediate();
+        let ty = x.ty;
+        match name {
+            sym::rotate_left | sym::rotate_right => {
+                // `shift_i128` only looks at the shift amount modulo 128, so
+                // `rotate_left(x, n) = (x << n) | (x >> -n)`.
+                let shift = args[1].immediate();
+                let zero = self.const_null(ty);
+                let neg_shift = self.sub(zero, shift);
+                let (forward, backward) = if name == sym::rotate_left {
+                    (self.shl(x, shift), self.lshr(x, neg_shift))
+                } else {
+                    (self.lshr(x, shift), self.shl(x, neg_shift))
+                };
+                self.or(forward, backward)
+            }
+            sym::saturating_add | sym::saturating_sub => {
+                let int_ty = if signed { self.tcx.types.i128 } else { self.tcx.types.u128 };
+                self.saturating_binop(name, signed, int_ty, args)
+            }
+            _ => {
+                let (lo, hi) = self.halves(x);
+                let (lo, hi) = self.ins(|pos| match name {
+                    sym::ctlz | sym::ctlz_nonzero => {
+                        let hi_zero = pos.ins().icmp_imm(IntCC::Equal, hi, 0);
+                        let lo_count = pos.ins().clz(lo);
+                        let lo_count = pos.ins().iadd_imm(lo_count, 64);
+                        let hi_count = pos.ins().clz(hi);
+                        let count = pos.ins().select(hi_zero, lo_count, hi_count);
+                        (count, pos.ins().iconst(types::I64, 0))
+                    }
+                    sym::cttz | sym::cttz_nonzero => {
+                        let lo_zero = pos.ins().icmp_imm(IntCC::Equal, lo, 0);
+                        let hi_count = pos.ins().ctz(hi);
+                        let hi_count = pos.ins().iadd_imm(hi_count, 64);
+                        let lo_count = pos.ins().ctz(lo);
+                        let count = pos.ins().select(lo_zero, hi_count, lo_count);
+                        (count, pos.ins().iconst(types::I64, 0))
+                    }
+                    sym::ctpop => {
+                        let lo_count = pos.ins().popcnt(lo);
+                        let hi_count = pos.ins().popcnt(hi);
+                        let count = pos.ins().iadd(lo_count, hi_count);
+                        (count, pos.ins().iconst(types::I64, 0))
+                    }
+                    sym::bswap => (bswap(pos, hi), bswap(pos, lo)),
+                    sym::bitreverse => (pos.ins().bitrev(hi), pos.ins().bitrev(lo)),
+                    _ => bug!(),
+                });
+                self.from_halves(ty, lo, hi)
+            }
+        }
+    }
+}
+
+fn int_type_width_signed(ty: Ty<'_>, cx: &CodegenCx<'_>) -> Option<(u64, bool)> {
+    match ty.kind() {
+        ty::Int(t) => {
+            Some((t.bit_width().unwrap_or(u64::from(cx.tcx.sess.target.ptr_width)), true))
+        }
+        ty::Uint(t) => {
+            Some((t.bit_width().unwrap_or(u64::from(cx.tcx.sess.target.ptr_width)), false))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,348 @@
+//! A codegen backend that uses Cranelift instead of LLVM, for faster debug builds.
+//!
+//! It implements the `rustc_codegen_ssa` traits like `rustc_codegen_llvm` does, so most of the
+//! work of lowering MIR is shared between the two. Only x86_64 Linux is supported, and the
+//! generated code is not meant to be fast:
+//!
+//! * There is no unwinding: landing pads are never entered, so a panic that would unwind
+//!   through code compiled by this backend aborts the process instead.
+//! * Debuginfo is limited to line tables, see `debuginfo.rs`.
+//! * SIMD intrinsics, inline assembly and LTO are not supported.
+//! * C-variadic functions can be called, but not defined or called through function pointers.
+//!
+//! The backend is built as a dylib and loaded with `-Z codegen-backend=cranelift`.
+//!
+//! # Note
+//!
+//! This API is completely unstable and subject to change.
+
+#![doc(html_root_url = "https://doc.rust-lang.org/nightly/nightly-rustc/")]
+#![feature(crate_visibility_modifier)]
+#![feature(in_band_lifetimes)]
+#![feature(nll)]
+#![feature(or_patterns)]
+#![feature(rustc_private)]
+#![recursion_limit = "256"]
+
+extern crate rustc_ast;
+extern crate rustc_codegen_ssa;
+extern crate rustc_data_structures;
+// Linking against `rustc_driver` makes sure the backend uses the same copies of the compiler
+// crates as the rustc that loads it, instead of statically linking its own.
+extern crate rustc_driver;
+extern crate rustc_errors;
+extern crate rustc_hir;
+extern crate rustc_incremental;
+extern crate rustc_index;
+extern crate rustc_middle;
+extern crate rustc_serialize;
+extern crate rustc_session;
+extern crate rustc_span;
+extern crate rustc_target;
+extern crate tracing;
+
+use rustc_ast::expand::allocator::AllocatorKind;
+use rustc_codegen_ssa::back::lto::{LtoModuleCodegen, SerializedModule, ThinModule};
+use rustc_codegen_ssa::back::write::{
+    CodegenContext, FatLTOInput, ModuleConfig, TargetMachineFactoryFn,
+};
+use rustc_codegen_ssa::traits::*;
+use rustc_codegen_ssa::ModuleCodegen;
+use rustc_codegen_ssa::{CodegenResults, CompiledModule};
+use rustc_errors::{ErrorReported, FatalError, Handler};
+use rustc_middle::dep_graph::{DepGraph, WorkProduct};
+use rustc_middle::middle::cstore::{EncodedMetadata, MetadataLoaderDyn};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_serialize::json;
+use rustc_session::config::{self, Lto, OptLevel, OutputFilenames, OutputType};
+use rustc_session::Session;
+use rustc_span::symbol::Symbol;
+use rustc_target::spec::SanitizerSet;
+
+use std::any::Any;
+use std::fs;
+
+mod back {
+    pub mod archive;
+    pub mod lto;
+    pub mod write;
+}
+
+mod abi;
+mod allocator;
+mod asm;
+mod base;
+mod builder;
+mod callee;
+mod common;
+mod consts;
+mod context;
+mod coverageinfo;
+mod debuginfo;
+mod declare;
+mod int128;
+mod intrinsic;
+mod metadata;
+mod mono_item;
+mod type_;
+mod type_of;
+mod value;
+
+#[derive(Clone)]
+pub struct ClifCodegenBackend(());
+
+impl ExtraBackendMethods for ClifCodegenBackend {
+    fn new_metadata(&self, _tcx: TyCtxt<'_>, mod_name: &str) -> ModuleClif {
+        ModuleClif { name: mod_name.to_string(), object: Vec::new() }
+    }
+
+    fn write_compressed_metadata<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        metadata: &EncodedMetadata,
+        module: &mut ModuleClif,
+    ) {
+        metadata::write_compressed_metadata(tcx, metadata, module)
+    }
+    fn codegen_allocator<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        mods: &mut ModuleClif,
+        kind: AllocatorKind,
+    ) {
+        allocator::codegen(tcx, mods, kind)
+    }
+    fn compile_codegen_unit(
+        &self,
+        tcx: TyCtxt<'_>,
+        cgu_name: Symbol,
+    ) -> (ModuleCodegen<ModuleClif>, u64) {
+        base::compile_codegen_unit(tcx, cgu_name)
+    }
+    fn target_machine_factory(
+        &self,
+        _sess: &Session,
+        _optlvl: OptLevel,
+    ) -> TargetMachineFactoryFn<Self> {
+        back::write::target_machine_factory()
+    }
+    fn target_cpu<'b>(&self, _sess: &'b Session) -> &'b str {
+        "generic"
+    }
+}
+
+impl WriteBackendMethods for ClifCodegenBackend {
+    type Module = ModuleClif;
+    type ModuleBuffer = back::lto::ModuleBuffer;
+    type Context = ();
+    type TargetMachine = ();
+    type ThinData = ();
+    type ThinBuffer = back::lto::ThinBuffer;
+    fn print_pass_timings(&self) {}
+    fn run_link(
+        _cgcx: &CodegenContext<Self>,
+        diag_handler: &Handler,
+        modules: Vec<ModuleCodegen<Self::Module>>,
+    ) -> Result<ModuleCodegen<Self::Module>, FatalError> {
+        back::write::link(diag_handler, modules)
+    }
+    fn run_fat_lto(
+        cgcx: &CodegenContext<Self>,
+        modules: Vec<FatLTOInput<Self>>,
+        cached_modules: Vec<(SerializedModule<Self::ModuleBuffer>, WorkProduct)>,
+    ) -> Result<LtoModuleCodegen<Self>, FatalError> {
+        back::lto::run_fat(cgcx, modules, cached_modules)
+    }
+    fn run_thin_lto(
+        cgcx: &CodegenContext<Self>,
+        modules: Vec<(String, Self::ThinBuffer)>,
+        cached_modules: Vec<(SerializedModule<Self::ModuleBuffer>, WorkProduct)>,
+    ) -> Result<(Vec<LtoModuleCodegen<Self>>, Vec<WorkProduct>), FatalError> {
+        back::lto::run_thin(cgcx, modules, cached_modules)
+    }
+    unsafe fn optimize(
+        _cgcx: &CodegenContext<Self>,
+        _diag_handler: &Handler,
+        _module: &ModuleCodegen<Self::Module>,
+        _config: &ModuleConfig,
+    ) -> Result<(), FatalError> {
+        // Cranelift optimizes each function as it compiles it, see `context::build_isa`.
+        Ok(())
+    }
+    unsafe fn optimize_thin(
+        _cgcx: &CodegenContext<Self>,
+        thin: &mut ThinModule<Self>,
+    ) -> Result<ModuleCodegen<Self::Module>, FatalError> {
+        back::lto::optimize_thin_module(thin)
+    }
+    unsafe fn codegen(
+        cgcx: &CodegenContext<Self>,
+        diag_handler: &Handler,
+        module: ModuleCodegen<Self::Module>,
+        config: &ModuleConfig,
+    ) -> Result<CompiledModule, FatalError> {
+        back::write::codegen(cgcx, diag_handler, module, config)
+    }
+    fn prepare_thin(module: ModuleCodegen<Self::Module>) -> (String, Self::ThinBuffer) {
+        back::lto::prepare_thin(module)
+    }
+    fn serialize_module(module: ModuleCodegen<Self::Module>) -> (String, Self::ModuleBuffer) {
+        back::lto::serialize_module(module)
+    }
+    fn run_lto_pass_manager(
+        _cgcx: &CodegenContext<Self>,
+        _module: &ModuleCodegen<Self::Module>,
+        _config: &ModuleConfig,
+        _thin: bool,
+    ) {
+    }
+}
+
+impl ClifCodegenBackend {
+    pub fn new() -> Box<dyn CodegenBackend> {
+        Box::new(ClifCodegenBackend(()))
+    }
+}
+
+impl CodegenBackend for ClifCodegenBackend {
+    fn init(&self, sess: &Session) {
+        let target = &sess.target.target;
+        if target.arch != "x86_64" || target.target_os != "linux" {
+            sess.fatal(&format!(
+                "the Cranelift backend only supports x86_64 Linux, not `{}`",
+                target.llvm_target
+            ));
+        }
+
+        if sess.opts.debugging_opts.instrument_coverage {
+            sess.fatal("`-Z instrument-coverage` is not supported by the Cranelift backend");
+        }
+        if sess.opts.debugging_opts.sanitizer != SanitizerSet::empty() {
+            sess.fatal("sanitizers are not supported by the Cranelift backend");
+        }
+        match sess.lto() {
+            // Automatic ThinLTO of the codegen units of a crate is a no-op, see `back::lto`.
+            Lto::No | Lto::ThinLocal => {}
+            Lto::Thin | Lto::Fat => sess.fatal("LTO is not supported by the Cranelift backend"),
+        }
+        if sess.opts.cg.linker_plugin_lto.enabled() {
+            sess.fatal("`-C linker-plugin-lto` is not supported by the Cranelift backend");
+        }
+        for output_type in sess.opts.output_types.keys() {
+            let emit = match output_type {
+                OutputType::Assembly => "asm",
+                OutputType::LlvmAssembly => "llvm-ir",
+                OutputType::Bitcode => "llvm-bc",
+                OutputType::Object
+                | OutputType::Metadata
+                | OutputType::Mir
+                | OutputType::Exe
+                | OutputType::DepInfo => continue,
+            };
+            sess.fatal(&format!("`--emit={}` is not supported by the Cranelift backend", emit));
+        }
+    }
+
+    fn metadata_loader(&self) -> Box<MetadataLoaderDyn> {
+        Box::new(metadata::ClifMetadataLoader)
+    }
+
+    fn provide(&self, providers: &mut ty::query::Providers) {
+        // `#[target_feature]` can't be honored by Cranelift, so no feature is supported.
+        providers.supported_target_features = |_tcx, _cnum| Default::default();
+    }
+
+    fn provide_extern(&self, _providers: &mut ty::query::Providers) {}
+
+    fn codegen_crate<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        metadata: EncodedMetadata,
+        need_metadata_module: bool,
+    ) -> Box<dyn Any> {
+        Box::new(rustc_codegen_ssa::base::codegen_crate(
+            ClifCodegenBackend(()),
+            tcx,
+            metadata,
+            need_metadata_module,
+        ))
+    }
+
+    fn join_codegen(
+        &self,
+        ongoing_codegen: Box<dyn Any>,
+        sess: &Session,
+        dep_graph: &DepGraph,
+    ) -> Result<Box<dyn Any>, ErrorReported> {
+        let (codegen_results, work_products) = ongoing_codegen
+            .downcast::<rustc_codegen_ssa::back::write::OngoingCodegen<ClifCodegenBackend>>()
+            .expect("Expected ClifCodegenBackend's OngoingCodegen, found Box<Any>")
+            .join(sess);
+        if sess.opts.debugging_opts.incremental_info {
+            rustc_codegen_ssa::back::write::dump_incremental_data(&codegen_results);
+        }
+
+        sess.time("serialize_work_products", move || {
+            rustc_incremental::save_work_product_index(sess, &dep_graph, work_products)
+        });
+
+        sess.compile_status()?;
+
+        Ok(Box::new(codegen_results))
+    }
+
+    fn link(
+        &self,
+        sess: &Session,
+        codegen_results: Box<dyn Any>,
+        outputs: &OutputFilenames,
+    ) -> Result<(), ErrorReported> {
+        let codegen_results = codegen_results
+            .downcast::<CodegenResults>()
+            .expect("Expected CodegenResults, found Box<Any>");
+
+        if sess.opts.debugging_opts.no_link {
+            // FIXME: use a binary format to encode the `.rlink` file
+            let rlink_data = json::encode(&codegen_results).map_err(|err| {
+                sess.fatal(&format!("failed to encode rlink: {}", err));
+            })?;
+            let rlink_file = outputs.with_extension(config::RLINK_EXT);
+            fs::write(&rlink_file, rlink_data).map_err(|err| {
+                sess.fatal(&format!("failed to write file {}: {}", rlink_file.display(), err));
+            })?;
+            return Ok(());
+        }
+
+        sess.time("link_crate", || {
+            use crate::back::archive::ArArchiveBuilder;
+            use rustc_codegen_ssa::back::link::link_binary;
+
+            link_binary::<ArArchiveBuilder<'_>>(
+                sess,
+                &codegen_results,
+                outputs,
+                &codegen_results.crate_name.as_str(),
+                "generic",
+            );
+        });
+
+        // Now that we won't touch anything in the incremental compilation directory
+        // any more, we can finalize it (which involves renaming it)
+        rustc_incremental::finalize_session_directory(sess, codegen_results.crate_hash);
+
+        Ok(())
+    }
+}
+
+/// A codegen unit compiled by Cranelift: the bytes of its object file, see `base.rs`.
+pub struct ModuleClif {
+    name: String,
+    object: Vec<u8>,
+}
+
+/// This is the entrypoint for a dylib codegen backend, see
+/// `rustc_interface::util::load_backend_from_dylib`.
+#[no_mangle]
+pub fn __rustc_codegen_backend() -> Box<dyn CodegenBackend> {
+    ClifCodegenBackend::new()
+}
@@ -0,0 +1,111 @@
+//! Reading and writing the crate metadata, which is stored in a `.rustc` section of an object
+//! file, as with the LLVM backend.
+
+use crate::context::new_module;
+use crate::ModuleClif;
+
+use cranelift_module::{DataContext, Linkage, Module};
+use object::{Object, ObjectSection};
+use rustc_codegen_ssa::METADATA_FILENAME;
+use rustc_data_structures::owning_ref::OwningRef;
+use rustc_data_structures::rustc_erase_owner;
+use rustc_middle::middle::cstore::{EncodedMetadata, MetadataLoader};
+use rustc_middle::middle::exported_symbols;
+use rustc_middle::ty::TyCtxt;
+use rustc_target::spec::Target;
+use tracing::debug;
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+pub use rustc_data_structures::sync::MetadataRef;
+
+pub struct ClifMetadataLoader;
+
+impl MetadataLoader for ClifMetadataLoader {
+    fn get_rlib_metadata(&self, _: &Target, filename: &Path) -> Result<MetadataRef, String> {
+        let file = File::open(filename).map_err(|e| {
+            format!("failed to read rlib metadata in '{}': {}", filename.display(), e)
+        })?;
+        let mut archive = ar::Archive::new(file);
+        while let Some(entry) = archive.next_entry() {
+            let mut entry = entry.map_err(|e| {
+                format!("failed to read rlib metadata in '{}': {}", filename.display(), e)
+            })?;
+            if entry.header().identifier() == METADATA_FILENAME.as_bytes() {
+                let mut buf = Vec::with_capacity(entry.header().size() as usize);
+                entry.read_to_end(&mut buf).map_err(|e| {
+                    format!("failed to read rlib metadata in '{}': {}", filename.display(), e)
+                })?;
+                let buf: OwningRef<Vec<u8>, [u8]> = OwningRef::new(buf);
+                return Ok(rustc_erase_owner!(buf.map_owner_box()));
+            }
+        }
+        debug!("didn't find '{}' in the archive", METADATA_FILENAME);
+        Err(format!("failed to read rlib metadata: '{}'", filename.display()))
+    }
+
+    fn get_dylib_metadata(&self, _target: &Target, filename: &Path) -> Result<MetadataRef, String> {
+        let data = std::fs::read(filename)
+            .map_err(|e| format!("error reading library: '{}': {}", filename.display(), e))?;
+        let buf: OwningRef<Vec<u8>, [u8]> = OwningRef::new(data).try_map(|data| {
+            let file = object::File::parse(data).map_err(|_| {
+                format!("provided path not an object file: '{}'", filename.display())
+            })?;
+            let section = file
+                .section_by_name(".rustc")
+                .ok_or_else(|| format!("metadata not found: '{}'", filename.display()))?;
+            let range = section
+                .file_range()
+                .ok_or_else(|| format!("metadata not found: '{}'", filename.display()))?;
+            Ok(&data[range.0 as usize..(range.0 + range.1) as usize])
+        })?;
+        Ok(rustc_erase_owner!(buf.map_owner_box()))
+    }
+}
+
+/// Creates the metadata module, an object file containing only the compressed metadata in
+/// a `.rustc` section. See `write_compressed_metadata` in the LLVM backend for the format.
+pub fn write_compressed_metadata(
+    tcx: TyCtxt<'_>,
+    metadata: &EncodedMetadata,
+    module: &mut ModuleClif,
+) {
+    use snap::write::FrameEncoder;
+    use std::io::Write;
+
+    let mut compressed = tcx.metadata_encoding_version();
+
+    let sym_name = exported_symbols::metadata_symbol_name(tcx);
+    let sym_name_len = sym_name.len() as u32;
+    compressed.write_all(&sym_name_len.to_le_bytes()).unwrap();
+    compressed.write_all(&sym_name.as_bytes()).unwrap();
+
+    let len_pos = compressed.len();
+    compressed.write_all(&0u64.to_le_bytes()).unwrap();
+    let compressed_start = compressed.len();
+
+    FrameEncoder::new(&mut compressed).write_all(&metadata.raw_data).unwrap();
+
+    let compressed_len = (compressed.len() - compressed_start) as u64;
+    if compressed_len != 0 {
+        compressed[len_pos..compressed_start].copy_from_slice(&compressed_len.to_le_bytes());
+    }
+
+    let mut object_module = new_module(tcx.sess, &module.name);
+    let id = object_module
+        .declare_data(&sym_name, Linkage::Export, false, false)
+        .unwrap_or_else(|e| tcx.sess.fatal(&format!("failed to declare metadata: {}", e)));
+    let mut data_ctx = DataContext::new();
+    data_ctx.set_segment_section("", ".rustc");
+    data_ctx.define(compressed.into_boxed_slice());
+    object_module
+        .define_data(id, &data_ctx)
+        .unwrap_or_else(|e| tcx.sess.fatal(&format!("failed to define metadata: {}", e)));
+
+    module.object = object_module
+        .finish()
+        .emit()
+        .unwrap_or_else(|e| tcx.sess.fatal(&format!("failed to emit metadata object: {}", e)));
+}
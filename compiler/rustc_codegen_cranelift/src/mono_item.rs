@@ -0,0 +1,67 @@
+use crate::abi::FnAbi;
+use crate::base;
+use crate::context::CodegenCx;
+use crate::type_of::LayoutClifExt;
+use rustc_codegen_ssa::traits::*;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+pub use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::mir::mono::{Linkage, Visibility};
+use rustc_middle::ty::layout::FnAbiExt;
+use rustc_middle::ty::{self, Instance, TypeFoldable};
+use rustc_target::abi::LayoutOf;
+
+impl PreDefineMethods<'tcx> for CodegenCx<'tcx> {
+    fn predefine_static(
+        &self,
+        def_id: DefId,
+        linkage: Linkage,
+        visibility: Visibility,
+        symbol_name: &str,
+    ) {
+        let instance = Instance::mono(self.tcx, def_id);
+        let ty = instance.ty(self.tcx, ty::ParamEnv::reveal_all());
+        let clif_ty = self.layout_of(ty).clif_type(self);
+        let writable = self.tcx.is_mutable_static(def_id) || !self.type_is_freeze(ty);
+        let attrs = self.tcx.codegen_fn_attrs(def_id);
+        let tls = attrs.flags.contains(CodegenFnAttrFlags::THREAD_LOCAL);
+
+        let linkage = base::linkage_to_clif(self, linkage, visibility);
+        let g = self.declare_data(symbol_name, clif_ty, linkage, writable, tls);
+
+        self.statics.borrow_mut().insert(def_id, g);
+    }
+
+    fn predefine_fn(
+        &self,
+        instance: Instance<'tcx>,
+        linkage: Linkage,
+        visibility: Visibility,
+        symbol_name: &str,
+    ) {
+        assert!(!instance.substs.needs_infer());
+
+        let fn_abi = FnAbi::of_instance(self, instance, &[]);
+        let visibility = if linkage != Linkage::Internal
+            && linkage != Linkage::Private
+            && self.tcx.is_compiler_builtins(LOCAL_CRATE)
+        {
+            Visibility::Hidden
+        } else {
+            visibility
+        };
+
+        let attrs = self.tcx.codegen_fn_attrs(instance.def_id());
+        if attrs.link_section.is_some() {
+            self.sess().span_warn(
+                self.tcx.def_span(instance.def_id()),
+                "`#[link_section]` is ignored on functions by the Cranelift backend",
+            );
+        }
+
+        let linkage = base::linkage_to_clif(self, linkage, visibility);
+        let func = self.declare_fn(symbol_name, &fn_abi, linkage);
+
+        self.instances.borrow_mut().insert(instance, func);
+    }
+}
@@ -0,0 +1,466 @@
+//! The backend types handed to `rustc_codegen_ssa`.
+//!
+//! Cranelift only knows about scalar types, but the generic codegen code is written against
+//! LLVM's type system: it expects structs, arrays, function types and pointers that remember
+//! their pointee. We therefore keep an interned LLVM-like type system of our own, which is only
+//! used to drive loads, stores, `getelementptr` style address computations and the lowering of
+//! function signatures. Every type is eventually flattened into a list of Cranelift scalars.
+
+pub use crate::type_of::LayoutClifExt;
+
+use crate::abi::{CastTargetExt, FnAbiClifExt};
+use crate::context::CodegenCx;
+use crate::value::Value;
+
+use cranelift_codegen::ir;
+use rustc_codegen_ssa::common::TypeKind;
+use rustc_codegen_ssa::traits::*;
+use rustc_middle::bug;
+use rustc_middle::ty::layout::TyAndLayout;
+use rustc_middle::ty::Ty;
+use rustc_target::abi::call::{CastTarget, FnAbi, Reg, RegKind};
+use rustc_target::abi::{Align, Integer, Size};
+use rustc_target::spec::AddrSpaceIdx;
+
+use std::fmt;
+
+/// An interned backend type, see `CodegenCx::types`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Type(u32);
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TypeData {
+    Void,
+    /// An integer with the given number of bits. Besides the usual power of two sizes this
+    /// is also used for `i1` (`bool` immediates) and for the odd sizes `CastTarget` may need.
+    Int(u64),
+    F32,
+    F64,
+    Pointer(Type),
+    Struct {
+        fields: Vec<Type>,
+        packed: bool,
+    },
+    /// A struct whose body is set later, which allows recursive types.
+    /// The `u32` is a unique id so that two named structs are never interned together.
+    Named(u32),
+    Array(Type, u64),
+    Vector(Type, u64),
+    Function {
+        args: Vec<Type>,
+        ret: Type,
+        variadic: bool,
+    },
+}
+
+impl fmt::Debug for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ty{}", self.0)
+    }
+}
+
+impl CodegenCx<'tcx> {
+    crate fn intern_type(&self, data: TypeData) -> Type {
+        let (index, _) = self.types.borrow_mut().insert_full(data);
+        Type(index as u32)
+    }
+
+    crate fn type_data(&self, ty: Type) -> TypeData {
+        let data = self.types.borrow()[ty.0 as usize].clone();
+        match data {
+            TypeData::Named(id) => match self.struct_bodies.borrow().get(&id) {
+                Some((fields, packed)) => {
+                    TypeData::Struct { fields: fields.clone(), packed: *packed }
+                }
+                None => bug!("named struct {:?} used before its body was set", ty),
+            },
+            data => data,
+        }
+    }
+
+    crate fn type_named_struct(&self) -> Type {
+        let id = self.named_struct_count.get();
+        self.named_struct_count.set(id + 1);
+        self.intern_type(TypeData::Named(id))
+    }
+
+    crate fn set_struct_body(&self, ty: Type, fields: &[Type], packed: bool) {
+        let id = match self.types.borrow()[ty.0 as usize] {
+            TypeData::Named(id) => id,
+            ref data => bug!("set_struct_body: {:?} is not a named struct", data),
+        };
+        self.struct_bodies.borrow_mut().insert(id, (fields.to_vec(), packed));
+    }
+
+    crate fn type_void(&self) -> Type {
+        self.intern_type(TypeData::Void)
+    }
+
+    /// Creates an integer type with the given number of bits, e.g., i24
+    crate fn type_ix(&self, num_bits: u64) -> Type {
+        self.intern_type(TypeData::Int(num_bits))
+    }
+
+    crate fn type_array(&self, ty: Type, len: u64) -> Type {
+        self.intern_type(TypeData::Array(ty, len))
+    }
+
+    crate fn type_vector(&self, ty: Type, len: u64) -> Type {
+        self.intern_type(TypeData::Vector(ty, len))
+    }
+
+    crate fn type_variadic_func(&self, args: &[Type], ret: Type) -> Type {
+        self.intern_type(TypeData::Function { args: args.to_vec(), ret, variadic: true })
+    }
+
+    crate fn type_pointee_for_align(&self, align: Align) -> Type {
+        let ity = Integer::approximate_align(self, align);
+        self.type_from_integer(ity)
+    }
+
+    /// Return a type that has at most the required alignment,
+    /// and exactly the required size, as a best-effort padding array.
+    crate fn type_padding_filler(&self, size: Size, align: Align) -> Type {
+        let unit = Integer::approximate_align(self, align);
+        let size = size.bytes();
+        let unit_size = unit.size().bytes();
+        assert_eq!(size % unit_size, 0);
+        self.type_array(self.type_from_integer(unit), size / unit_size)
+    }
+
+    crate fn opt_type_padding_filler(&self, size: Size, align: Align) -> Option<Type> {
+        if size == Size::ZERO { None } else { Some(self.type_padding_filler(size, align)) }
+    }
+
+    crate fn func_params_types(&self, ty: Type) -> Vec<Type> {
+        match self.type_data(ty) {
+            TypeData::Function { args, .. } => args,
+            data => bug!("func_params_types: {:?} is not a function type", data),
+        }
+    }
+
+    crate fn func_return_type(&self, ty: Type) -> Type {
+        match self.type_data(ty) {
+            TypeData::Function { ret, .. } => ret,
+            data => bug!("func_return_type: {:?} is not a function type", data),
+        }
+    }
+
+    crate fn func_is_variadic(&self, ty: Type) -> bool {
+        match self.type_data(ty) {
+            TypeData::Function { variadic, .. } => variadic,
+            data => bug!("func_is_variadic: {:?} is not a function type", data),
+        }
+    }
+
+    /// The fields of an aggregate type, with arrays expanded to one field per element.
+    crate fn aggregate_fields(&self, ty: Type) -> Vec<Type> {
+        match self.type_data(ty) {
+            TypeData::Struct { fields, .. } => fields,
+            TypeData::Array(elem, len) => vec![elem; len as usize],
+            data => bug!("aggregate_fields: {:?} is not an aggregate", data),
+        }
+    }
+
+    crate fn is_aggregate(&self, ty: Type) -> bool {
+        matches!(self.type_data(ty), TypeData::Struct { .. } | TypeData::Array(..))
+    }
+
+    /// The in-memory size of a type, following LLVM's rules for the x86_64 data layout.
+    crate fn type_size(&self, ty: Type) -> u64 {
+        match self.type_data(ty) {
+            TypeData::Void | TypeData::Function { .. } => 0,
+            TypeData::Int(bits) => (bits + 7) / 8,
+            TypeData::F32 => 4,
+            TypeData::F64 => 8,
+            TypeData::Pointer(_) => self.tcx.data_layout.pointer_size.bytes(),
+            TypeData::Array(elem, len) | TypeData::Vector(elem, len) => {
+                self.type_stride(elem) * len
+            }
+            TypeData::Struct { ref fields, packed } => {
+                let mut offset = 0;
+                for &field in fields {
+                    if !packed {
+                        offset = align_to(offset, self.type_align(field));
+                    }
+                    offset += self.type_size(field);
+                }
+                if packed { offset } else { align_to(offset, self.type_align(ty)) }
+            }
+            TypeData::Named(_) => unreachable!(),
+        }
+    }
+
+    /// The distance between two consecutive elements of an array of `ty`.
+    crate fn type_stride(&self, ty: Type) -> u64 {
+        align_to(self.type_size(ty), self.type_align(ty))
+    }
+
+    crate fn type_align(&self, ty: Type) -> u64 {
+        let dl = &self.tcx.data_layout;
+        match self.type_data(ty) {
+            TypeData::Void | TypeData::Function { .. } => 1,
+            TypeData::Int(bits) => match bits {
+                0..=8 => dl.i8_align.abi.bytes(),
+                9..=16 => dl.i16_align.abi.bytes(),
+                17..=32 => dl.i32_align.abi.bytes(),
+                33..=64 => dl.i64_align.abi.bytes(),
+                _ => dl.i128_align.abi.bytes(),
+            },
+            TypeData::F32 => dl.f32_align.abi.bytes(),
+            TypeData::F64 => dl.f64_align.abi.bytes(),
+            TypeData::Pointer(_) => dl.pointer_align.abi.bytes(),
+            TypeData::Array(elem, _) => self.type_align(elem),
+            TypeData::Vector(..) => self.type_size(ty).next_power_of_two(),
+            TypeData::Struct { ref fields, packed } => {
+                if packed {
+                    1
+                } else {
+                    fields.iter().map(|&field| self.type_align(field)).max().unwrap_or(1)
+                }
+            }
+            TypeData::Named(_) => unreachable!(),
+        }
+    }
+
+    /// The byte offset of field `idx` of the aggregate `ty`.
+    crate fn field_offset(&self, ty: Type, idx: u64) -> u64 {
+        match self.type_data(ty) {
+            TypeData::Array(elem, _) | TypeData::Vector(elem, _) => self.type_stride(elem) * idx,
+            TypeData::Struct { fields, packed } => {
+                let mut offset = 0;
+                for (i, &field) in fields.iter().enumerate() {
+                    if !packed {
+                        offset = align_to(offset, self.type_align(field));
+                    }
+                    if i as u64 == idx {
+                        return offset;
+                    }
+                    offset += self.type_size(field);
+                }
+                bug!("field_offset: field {} out of range for {:?}", idx, ty)
+            }
+            data => bug!("field_offset: {:?} is not an aggregate", data),
+        }
+    }
+
+    crate fn field_type(&self, ty: Type, idx: u64) -> Type {
+        match self.type_data(ty) {
+            TypeData::Array(elem, _) | TypeData::Vector(elem, _) => elem,
+            TypeData::Struct { fields, .. } => fields[idx as usize],
+            data => bug!("field_type: {:?} is not an aggregate", data),
+        }
+    }
+
+    /// The Cranelift type used for a scalar backend type, or `None` for aggregates and `void`.
+    ///
+    /// `i1` is represented as an `i8` holding `0` or `1`, and integers with an odd number of
+    /// bytes use the next larger Cranelift type. 128-bit integers are not scalars as far as
+    /// Cranelift is concerned, see `int128.rs`.
+    crate fn clif_type(&self, ty: Type) -> Option<ir::Type> {
+        Some(match self.type_data(ty) {
+            TypeData::Int(bits) => match bits {
+                0..=8 => ir::types::I8,
+                9..=16 => ir::types::I16,
+                17..=32 => ir::types::I32,
+                33..=64 => ir::types::I64,
+                _ => return None,
+            },
+            TypeData::F32 => ir::types::F32,
+            TypeData::F64 => ir::types::F64,
+            TypeData::Pointer(_) => self.pointer_type,
+            _ => return None,
+        })
+    }
+
+    /// The Cranelift scalars a value of type `ty` is made of, in order.
+    crate fn clif_leaves(&self, ty: Type) -> Vec<ir::Type> {
+        let mut leaves = vec![];
+        self.push_clif_leaves(ty, &mut leaves);
+        leaves
+    }
+
+    fn push_clif_leaves(&self, ty: Type, leaves: &mut Vec<ir::Type>) {
+        if let Some(clif_ty) = self.clif_type(ty) {
+            leaves.push(clif_ty);
+            return;
+        }
+        match self.type_data(ty) {
+            TypeData::Void => {}
+            TypeData::Int(128) => leaves.extend_from_slice(&[ir::types::I64, ir::types::I64]),
+            TypeData::Struct { .. } | TypeData::Array(..) => {
+                for field in self.aggregate_fields(ty) {
+                    self.push_clif_leaves(field, leaves);
+                }
+            }
+            data => {
+                self.sess().fatal(&format!("the Cranelift backend does not support {:?}", data))
+            }
+        }
+    }
+
+    crate fn is_int128(&self, ty: Type) -> bool {
+        self.type_data(ty) == TypeData::Int(128)
+    }
+}
+
+fn align_to(offset: u64, align: u64) -> u64 {
+    (offset + align - 1) / align * align
+}
+
+impl BaseTypeMethods<'tcx> for CodegenCx<'tcx> {
+    fn type_i1(&self) -> Type {
+        self.type_ix(1)
+    }
+
+    fn type_i8(&self) -> Type {
+        self.type_ix(8)
+    }
+
+    fn type_i16(&self) -> Type {
+        self.type_ix(16)
+    }
+
+    fn type_i32(&self) -> Type {
+        self.type_ix(32)
+    }
+
+    fn type_i64(&self) -> Type {
+        self.type_ix(64)
+    }
+
+    fn type_i128(&self) -> Type {
+        self.type_ix(128)
+    }
+
+    fn type_isize(&self) -> Type {
+        self.type_ix(self.tcx.data_layout.pointer_size.bits())
+    }
+
+    fn type_f32(&self) -> Type {
+        self.intern_type(TypeData::F32)
+    }
+
+    fn type_f64(&self) -> Type {
+        self.intern_type(TypeData::F64)
+    }
+
+    fn type_func(&self, args: &[Type], ret: Type) -> Type {
+        self.intern_type(TypeData::Function { args: args.to_vec(), ret, variadic: false })
+    }
+
+    fn type_struct(&self, els: &[Type], packed: bool) -> Type {
+        self.intern_type(TypeData::Struct { fields: els.to_vec(), packed })
+    }
+
+    fn type_kind(&self, ty: Type) -> TypeKind {
+        match self.type_data(ty) {
+            TypeData::Void => TypeKind::Void,
+            TypeData::Int(_) => TypeKind::Integer,
+            TypeData::F32 => TypeKind::Float,
+            TypeData::F64 => TypeKind::Double,
+            TypeData::Pointer(_) => TypeKind::Pointer,
+            TypeData::Struct { .. } | TypeData::Named(_) => TypeKind::Struct,
+            TypeData::Array(..) => TypeKind::Array,
+            TypeData::Vector(..) => TypeKind::Vector,
+            TypeData::Function { .. } => TypeKind::Function,
+        }
+    }
+
+    fn type_as_ptr_to(&self, ty: Type, _addr_space: AddrSpaceIdx) -> Type {
+        self.intern_type(TypeData::Pointer(ty))
+    }
+
+    fn element_type(&self, ty: Type) -> Type {
+        match self.type_data(ty) {
+            TypeData::Pointer(elem) | TypeData::Array(elem, _) | TypeData::Vector(elem, _) => elem,
+            data => bug!("element_type called on {:?}", data),
+        }
+    }
+
+    fn vector_length(&self, ty: Type) -> usize {
+        match self.type_data(ty) {
+            TypeData::Vector(_, len) => len as usize,
+            data => bug!("vector_length called on {:?}", data),
+        }
+    }
+
+    fn float_width(&self, ty: Type) -> usize {
+        match self.type_data(ty) {
+            TypeData::F32 => 32,
+            TypeData::F64 => 64,
+            data => bug!("float_width called on {:?}", data),
+        }
+    }
+
+    fn int_width(&self, ty: Type) -> u64 {
+        match self.type_data(ty) {
+            TypeData::Int(bits) => bits,
+            data => bug!("int_width called on {:?}", data),
+        }
+    }
+
+    fn val_ty(&self, v: Value) -> Type {
+        v.ty
+    }
+
+    fn type_addr_space(&self, ty: Type) -> Option<AddrSpaceIdx> {
+        match self.type_data(ty) {
+            TypeData::Pointer(_) => Some(AddrSpaceIdx::default()),
+            _ => None,
+        }
+    }
+}
+
+impl LayoutTypeMethods<'tcx> for CodegenCx<'tcx> {
+    fn backend_type(&self, layout: TyAndLayout<'tcx>) -> Type {
+        layout.clif_type(self)
+    }
+    fn immediate_backend_type(&self, layout: TyAndLayout<'tcx>) -> Type {
+        layout.immediate_clif_type(self)
+    }
+    fn is_backend_immediate(&self, layout: TyAndLayout<'tcx>) -> bool {
+        layout.is_clif_immediate()
+    }
+    fn is_backend_scalar_pair(&self, layout: TyAndLayout<'tcx>) -> bool {
+        layout.is_clif_scalar_pair()
+    }
+    fn backend_field_index(&self, layout: TyAndLayout<'tcx>, index: usize) -> u64 {
+        layout.clif_field_index(index)
+    }
+    fn scalar_pair_element_backend_type(
+        &self,
+        layout: TyAndLayout<'tcx>,
+        index: usize,
+        immediate: bool,
+    ) -> Type {
+        layout.scalar_pair_element_clif_type(self, index, immediate)
+    }
+    fn cast_backend_type(&self, ty: &CastTarget) -> Type {
+        ty.clif_type(self)
+    }
+    fn fn_ptr_backend_type(&self, ty: &FnAbi<'tcx, Ty<'tcx>>) -> Type {
+        self.type_ptr_to(ty.clif_type(self))
+    }
+    fn reg_backend_type(&self, ty: &Reg) -> Type {
+        match ty.kind {
+            RegKind::Integer => self.type_ix(ty.size.bits()),
+            RegKind::Float => match ty.size.bits() {
+                32 => self.type_f32(),
+                64 => self.type_f64(),
+                _ => bug!("unsupported float: {:?}", ty),
+            },
+            RegKind::Vector => self.type_vector(self.type_i8(), ty.size.bytes()),
+        }
+    }
+}
+
+impl TypeMembershipMethods<'tcx> for CodegenCx<'tcx> {
+    fn add_type_metadata(&self, _function: crate::value::Function, _typeid: String) {
+        self.sess().fatal("control flow integrity is not supported by the Cranelift backend")
+    }
+
+    fn typeid_metadata(&self, _typeid: String) -> Value {
+        self.sess().fatal("control flow integrity is not supported by the Cranelift backend")
+    }
+}
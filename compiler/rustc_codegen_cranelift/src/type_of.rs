@@ -0,0 +1,270 @@
+use crate::context::CodegenCx;
+use crate::type_::Type;
+use rustc_codegen_ssa::traits::*;
+use rustc_middle::bug;
+use rustc_middle::ty::layout::{FnAbiExt, TyAndLayout};
+use rustc_middle::ty::{self, Ty, TypeFoldable};
+use rustc_target::abi::call::FnAbi;
+use rustc_target::abi::{Abi, FieldsShape};
+use rustc_target::abi::{Int, Pointer, F32, F64};
+use rustc_target::abi::{LayoutOf, PointeeInfo, Scalar, Size, TyAndLayoutMethods, Variants};
+
+fn uncached_clif_type<'tcx>(
+    cx: &CodegenCx<'tcx>,
+    layout: TyAndLayout<'tcx>,
+    defer: &mut Option<(Type, TyAndLayout<'tcx>)>,
+) -> Type {
+    match layout.abi {
+        Abi::Scalar(_) => bug!("handled elsewhere"),
+        Abi::Vector { ref element, count } => {
+            let element = layout.scalar_clif_type_at(cx, element, Size::ZERO);
+            return cx.type_vector(element, count);
+        }
+        Abi::ScalarPair(..) => {
+            return cx.type_struct(
+                &[
+                    layout.scalar_pair_element_clif_type(cx, 0, false),
+                    layout.scalar_pair_element_clif_type(cx, 1, false),
+                ],
+                false,
+            );
+        }
+        Abi::Uninhabited | Abi::Aggregate { .. } => {}
+    }
+
+    match layout.fields {
+        FieldsShape::Primitive | FieldsShape::Union(_) => {
+            let fill = cx.type_padding_filler(layout.size, layout.align.abi);
+            cx.type_struct(&[fill], false)
+        }
+        FieldsShape::Array { count, .. } => cx.type_array(layout.field(cx, 0).clif_type(cx), count),
+        FieldsShape::Arbitrary { .. } => {
+            // Structs are always created as named structs, so that recursive types (through
+            // pointers) can refer to themselves before their body is known.
+            let ty = cx.type_named_struct();
+            *defer = Some((ty, layout));
+            ty
+        }
+    }
+}
+
+/// Note: this must match the expected padding from LayoutCx::padded_indices.
+fn struct_clif_fields<'tcx>(cx: &CodegenCx<'tcx>, layout: TyAndLayout<'tcx>) -> (Vec<Type>, bool) {
+    let field_count = layout.fields.padded_field_count();
+
+    let mut packed = false;
+    let mut offset = Size::ZERO;
+    let mut prev_effective_align = layout.align.abi;
+    let mut result: Vec<_> = Vec::with_capacity(field_count);
+    for i in layout.fields.index_by_increasing_offset() {
+        let target_offset = layout.fields.offset(i as usize);
+        let field = layout.field(cx, i);
+        let effective_field_align =
+            layout.align.abi.min(field.align.abi).restrict_for_offset(target_offset);
+        packed |= effective_field_align < field.align.abi;
+
+        assert!(target_offset >= offset);
+        let padding = target_offset - offset;
+        let padding_align = prev_effective_align.min(effective_field_align);
+        assert_eq!(offset.align_to(padding_align) + padding, target_offset);
+        result.extend(cx.opt_type_padding_filler(padding, padding_align));
+
+        result.push(field.clif_type(cx));
+        offset = target_offset + field.size;
+        prev_effective_align = effective_field_align;
+    }
+    if !layout.is_unsized() && field_count > 0 {
+        if offset > layout.size {
+            bug!("layout: {:#?} stride: {:?} offset: {:?}", layout, layout.size, offset);
+        }
+        let padding = layout.size - offset;
+        let padding_align = prev_effective_align;
+        assert_eq!(offset.align_to(padding_align) + padding, layout.size);
+        result.extend(cx.opt_type_padding_filler(padding, padding_align));
+    }
+
+    (result, packed)
+}
+
+pub trait LayoutClifExt<'tcx> {
+    fn is_clif_immediate(&self) -> bool;
+    fn is_clif_scalar_pair(&self) -> bool;
+    fn clif_type(&self, cx: &CodegenCx<'tcx>) -> Type;
+    fn immediate_clif_type(&self, cx: &CodegenCx<'tcx>) -> Type;
+    fn scalar_clif_type_at(&self, cx: &CodegenCx<'tcx>, scalar: &Scalar, offset: Size) -> Type;
+    fn scalar_pair_element_clif_type(
+        &self,
+        cx: &CodegenCx<'tcx>,
+        index: usize,
+        immediate: bool,
+    ) -> Type;
+    fn clif_field_index(&self, index: usize) -> u64;
+    fn pointee_info_at(&self, cx: &CodegenCx<'tcx>, offset: Size) -> Option<PointeeInfo>;
+}
+
+impl<'tcx> LayoutClifExt<'tcx> for TyAndLayout<'tcx> {
+    fn is_clif_immediate(&self) -> bool {
+        match self.abi {
+            Abi::Scalar(_) | Abi::Vector { .. } => true,
+            Abi::ScalarPair(..) => false,
+            Abi::Uninhabited | Abi::Aggregate { .. } => self.is_zst(),
+        }
+    }
+
+    fn is_clif_scalar_pair(&self) -> bool {
+        match self.abi {
+            Abi::ScalarPair(..) => true,
+            Abi::Uninhabited | Abi::Scalar(_) | Abi::Vector { .. } | Abi::Aggregate { .. } => false,
+        }
+    }
+
+    /// Gets the backend type corresponding to a Rust type, with the same conventions as the
+    /// LLVM backend: unsized types are represented by their "minimal unit", e.g. `[T]` becomes
+    /// `T` and `str` becomes `i8`, and `bool` is an `i8` in memory.
+    fn clif_type(&self, cx: &CodegenCx<'tcx>) -> Type {
+        if let Abi::Scalar(ref scalar) = self.abi {
+            // Use a different cache for scalars because pointers to DSTs
+            // can be either fat or thin (data pointers of fat pointers).
+            if let Some(&ty) = cx.scalar_types.borrow().get(&self.ty) {
+                return ty;
+            }
+            let ty = match *self.ty.kind() {
+                ty::Ref(_, ty, _) | ty::RawPtr(ty::TypeAndMut { ty, .. }) => {
+                    cx.type_ptr_to(cx.layout_of(ty).clif_type(cx))
+                }
+                ty::Adt(def, _) if def.is_box() => {
+                    cx.type_ptr_to(cx.layout_of(self.ty.boxed_ty()).clif_type(cx))
+                }
+                ty::FnPtr(sig) => cx.fn_ptr_backend_type(&FnAbi::of_fn_ptr(cx, sig, &[])),
+                _ => self.scalar_clif_type_at(cx, scalar, Size::ZERO),
+            };
+            cx.scalar_types.borrow_mut().insert(self.ty, ty);
+            return ty;
+        }
+
+        // Check the cache.
+        let variant_index = match self.variants {
+            Variants::Single { index } => Some(index),
+            _ => None,
+        };
+        if let Some(&ty) = cx.types_of.borrow().get(&(self.ty, variant_index)) {
+            return ty;
+        }
+
+        assert!(!self.ty.has_escaping_bound_vars(), "{:?} has escaping bound vars", self.ty);
+
+        // Make sure lifetimes are erased, to avoid generating distinct
+        // types for Rust types that only differ in the choice of lifetimes.
+        let normal_ty = cx.tcx.erase_regions(&self.ty);
+
+        let mut defer = None;
+        let ty = if self.ty != normal_ty {
+            let mut layout = cx.layout_of(normal_ty);
+            if let Some(v) = variant_index {
+                layout = layout.for_variant(cx, v);
+            }
+            layout.clif_type(cx)
+        } else {
+            uncached_clif_type(cx, *self, &mut defer)
+        };
+
+        cx.types_of.borrow_mut().insert((self.ty, variant_index), ty);
+
+        if let Some((ty, layout)) = defer {
+            let (fields, packed) = struct_clif_fields(cx, layout);
+            cx.set_struct_body(ty, &fields, packed)
+        }
+
+        ty
+    }
+
+    fn immediate_clif_type(&self, cx: &CodegenCx<'tcx>) -> Type {
+        if let Abi::Scalar(ref scalar) = self.abi {
+            if scalar.is_bool() {
+                return cx.type_i1();
+            }
+        }
+        self.clif_type(cx)
+    }
+
+    fn scalar_clif_type_at(&self, cx: &CodegenCx<'tcx>, scalar: &Scalar, offset: Size) -> Type {
+        match scalar.value {
+            Int(i, _) => cx.type_from_integer(i),
+            F32 => cx.type_f32(),
+            F64 => cx.type_f64(),
+            Pointer => {
+                // If we know the alignment, pick something better than i8.
+                let pointee = if let Some(pointee) = self.pointee_info_at(cx, offset) {
+                    cx.type_pointee_for_align(pointee.align)
+                } else {
+                    cx.type_i8()
+                };
+                cx.type_ptr_to(pointee)
+            }
+        }
+    }
+
+    fn scalar_pair_element_clif_type(
+        &self,
+        cx: &CodegenCx<'tcx>,
+        index: usize,
+        immediate: bool,
+    ) -> Type {
+        // Special-case fat pointers, to avoid pointer casts on every `OperandRef::deref`.
+        match self.ty.kind() {
+            ty::Ref(..) | ty::RawPtr(_) => {
+                return self.field(cx, index).clif_type(cx);
+            }
+            ty::Adt(def, _) if def.is_box() => {
+                let ptr_ty = cx.tcx.mk_mut_ptr(self.ty.boxed_ty());
+                return cx.layout_of(ptr_ty).scalar_pair_element_clif_type(cx, index, immediate);
+            }
+            _ => {}
+        }
+
+        let (a, b) = match self.abi {
+            Abi::ScalarPair(ref a, ref b) => (a, b),
+            _ => bug!("TyAndLayout::scalar_pair_element_clif_type({:?}): not applicable", self),
+        };
+        let scalar = [a, b][index];
+
+        // Make sure to return the same type `immediate_clif_type` would when
+        // dealing with an immediate pair.
+        if immediate && scalar.is_bool() {
+            return cx.type_i1();
+        }
+
+        let offset =
+            if index == 0 { Size::ZERO } else { a.value.size(cx).align_to(b.value.align(cx).abi) };
+        self.scalar_clif_type_at(cx, scalar, offset)
+    }
+
+    fn clif_field_index(&self, index: usize) -> u64 {
+        match self.abi {
+            Abi::Scalar(_) | Abi::ScalarPair(..) => {
+                bug!("TyAndLayout::clif_field_index({:?}): not applicable", self)
+            }
+            _ => {}
+        }
+        match self.fields {
+            FieldsShape::Primitive | FieldsShape::Union(_) => {
+                bug!("TyAndLayout::clif_field_index({:?}): not applicable", self)
+            }
+
+            FieldsShape::Array { .. } => index as u64,
+
+            FieldsShape::Arbitrary { .. } => self.fields.padded_memory_index(index) as u64,
+        }
+    }
+
+    fn pointee_info_at(&self, cx: &CodegenCx<'tcx>, offset: Size) -> Option<PointeeInfo> {
+        if let Some(&pointee) = cx.pointee_infos.borrow().get(&(self.ty, offset)) {
+            return pointee;
+        }
+
+        let result = Ty::pointee_info_at(*self, cx, offset);
+
+        cx.pointee_infos.borrow_mut().insert((self.ty, offset), result);
+        result
+    }
+}
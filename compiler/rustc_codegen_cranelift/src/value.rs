@@ -0,0 +1,59 @@
+//! The backend values handed to `rustc_codegen_ssa`.
+//!
+//! A `Value` is either an SSA value of the function being built, or a constant. Constants are
+//! not tied to any function: they are materialized into Cranelift instructions by the builder
+//! where they are used, or serialized into a data object by `static_addr_of` and friends.
+//! Values of aggregate type (`{T, U}` return values, `insert_value` results, constant structs)
+//! are kept as a list of their fields in `CodegenCx::aggregates`.
+
+use crate::type_::Type;
+
+use cranelift_codegen::ir;
+use cranelift_module::DataId;
+
+rustc_index::newtype_index! {
+    /// A function declared in the current codegen unit, see `CodegenCx::functions`.
+    pub struct Function {
+        DEBUG_FORMAT = "fn{}"
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Value {
+    pub kind: ValueKind,
+    pub ty: Type,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ValueKind {
+    /// An SSA value defined in the body of the given function.
+    Ssa(Function, ir::Value),
+    /// An aggregate, the fields are stored in `CodegenCx::aggregates`.
+    Aggregate(usize),
+    /// An integer or pointer constant, zero-extended to 128 bits.
+    ConstInt(u128),
+    /// A floating point constant, stored as its bit pattern.
+    ConstFloat(u64),
+    Undef,
+    /// The address of a function.
+    FuncAddr(Function),
+    /// The address of a data object, plus a byte offset.
+    DataAddr(DataId, i64),
+}
+
+impl Value {
+    pub fn new(kind: ValueKind, ty: Type) -> Value {
+        Value { kind, ty }
+    }
+
+    pub fn with_type(self, ty: Type) -> Value {
+        Value { kind: self.kind, ty }
+    }
+}
+
+/// A basic block of a function in the current codegen unit.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BasicBlock {
+    pub func: Function,
+    pub block: ir::Block,
+}
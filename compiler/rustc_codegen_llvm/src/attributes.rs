@@ -11,8 +11,9 @@ use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc_middle::ty::layout::HasTyCtxt;
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, TyCtxt};
-use rustc_session::config::{OptLevel, SanitizerSet};
+use rustc_session::config::OptLevel;
 use rustc_session::Session;
+use rustc_target::spec::{SanitizerSet, StackProtector};
 
 use crate::attributes;
 use crate::llvm::AttributePlace::Function;
@@ -56,6 +57,21 @@ pub fn sanitize(cx: &CodegenCx<'ll, '_>, no_sanitize: SanitizerSet, llfn: &'ll V
     if enabled.contains(SanitizerSet::THREAD) {
         llvm::Attribute::SanitizeThread.apply_llfn(Function, llfn);
     }
+    if enabled.contains(SanitizerSet::HWADDRESS) {
+        llvm::Attribute::SanitizeHWAddress.apply_llfn(Function, llfn);
+    }
+}
+
+/// Apply the LLVM stack protector attribute matching `-Z stack-protector`.
+fn stack_protector(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
+    let sspattr = match cx.sess().stack_protector() {
+        StackProtector::None => return,
+        StackProtector::All => Attribute::StackProtectReq,
+        StackProtector::Strong => Attribute::StackProtectStrong,
+        StackProtector::Basic => Attribute::StackProtect,
+    };
+
+    sspattr.apply_llfn(Function, llfn)
 }
 
 /// Tell LLVM to emit or not emit the information necessary to unwind the stack for the function.
@@ -252,6 +268,14 @@ pub fn from_fn_attrs(cx: &CodegenCx<'ll, 'tcx>, llfn: &'ll Value, instance: ty::
 
     inline(cx, llfn, codegen_fn_attrs.inline.clone());
 
+    // eBPF's `exit` only ends the program in the entry point, so the Geobacter
+    // intrinsic shims, which is where the suicide exits, must not stay calls.
+    if cx.tcx.sess.target.target.arch == "bpf" {
+        if let ty::InstanceDef::Intrinsic(_) = instance.def {
+            inline(cx, llfn, attributes::InlineAttr::Always);
+        }
+    }
+
     // The `uwtable` attribute according to LLVM is:
     //
     //     This attribute indicates that the ABI being targeted requires that an
@@ -298,20 +322,26 @@ pub fn from_fn_attrs(cx: &CodegenCx<'ll, 'tcx>, llfn: &'ll Value, instance: ty::
         llvm::AddFunctionAttrString(llfn, Function, const_cstr!("cmse_nonsecure_entry"));
     }
     sanitize(cx, codegen_fn_attrs.no_sanitize, llfn);
+    stack_protector(cx, llfn);
 
     // Always annotate functions with the target-cpu they are compiled for.
     // Without this, ThinLTO won't inline Rust functions into Clang generated
     // functions (because Clang annotates functions this way too).
     apply_target_cpu_attr(cx, llfn);
 
-    let features = llvm_target_features(cx.tcx.sess)
+    let mut features = llvm_target_features(cx.tcx.sess)
         .map(|s| s.to_string())
         .chain(codegen_fn_attrs.target_features.iter().map(|f| {
             let feature = &f.as_str();
             format!("+{}", llvm_util::to_llvm_feature(cx.tcx.sess, feature))
         }))
-        .collect::<Vec<String>>()
-        .join(",");
+        .collect::<Vec<String>>();
+    // HWASan tags the addresses of globals as well, which requires the backend to materialize
+    // them with the tag in place.
+    if cx.tcx.sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::HWADDRESS) {
+        features.push("+tagged-globals".to_string());
+    }
+    let features = features.join(",");
 
     if !features.is_empty() {
         let val = CString::new(features).unwrap();
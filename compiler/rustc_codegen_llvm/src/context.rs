@@ -903,6 +903,7 @@ impl CodegenCx<'b, 'tcx> {
         ifn!("llvm.x86.seh.recoverfp", fn(i8p, i8p) -> i8p);
 
         ifn!("llvm.assume", fn(i1) -> void);
+        ifn!("llvm.type.test", fn(i8p, self.type_metadata()) -> i1);
         ifn!("llvm.prefetch", fn(i8p, t_i32, t_i32, t_i32) -> void);
 
         // intrinsics which have address space requirements.
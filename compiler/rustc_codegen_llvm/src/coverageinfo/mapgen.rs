@@ -42,11 +42,16 @@ pub fn finalize<'ll, 'tcx>(cx: &CodegenCx<'ll, 'tcx>) {
 
             let mangled_function_name = cx.tcx.symbol_name(instance).to_string();
             let function_source_hash = function_coverage.source_hash();
-            let (expressions, counter_regions) =
+            let (expressions, counter_regions, branch_regions) =
                 function_coverage.get_expressions_and_counter_regions();
 
             let old_len = coverage_mappings_buffer.len();
-            mapgen.write_coverage_mappings(expressions, counter_regions, coverage_mappings_buffer);
+            mapgen.write_coverage_mappings(
+                expressions,
+                counter_regions,
+                branch_regions,
+                coverage_mappings_buffer,
+            );
             let mapping_data_size = coverage_mappings_buffer.len() - old_len;
             debug_assert!(
                 mapping_data_size > 0,
@@ -86,17 +91,23 @@ impl CoverageMapGenerator {
         Self { filenames: FxIndexSet::default() }
     }
 
-    /// Using the `expressions` and `counter_regions` collected for the current function, generate
-    /// the `mapping_regions` and `virtual_file_mapping`, and capture any new filenames. Then use
-    /// LLVM APIs to encode the `virtual_file_mapping`, `expressions`, and `mapping_regions` into
-    /// the given `coverage_mappings` byte buffer, compliant with the LLVM Coverage Mapping format.
+    /// Using the `expressions`, `counter_regions` and `branch_regions` collected for the current
+    /// function, generate the `mapping_regions` and `virtual_file_mapping`, and capture any new
+    /// filenames. Then use LLVM APIs to encode the `virtual_file_mapping`, `expressions`, and
+    /// `mapping_regions` into the given `coverage_mappings` byte buffer, compliant with the LLVM
+    /// Coverage Mapping format.
     fn write_coverage_mappings(
         &mut self,
         expressions: Vec<CounterExpression>,
         counter_regions: impl Iterator<Item = (Counter, &'a CodeRegion)>,
+        branch_regions: Vec<(Counter, Counter, &'a CodeRegion)>,
         coverage_mappings_buffer: &RustString,
     ) {
-        let mut counter_regions = counter_regions.collect::<Vec<_>>();
+        // A branch region is a code region with an additional "not taken" counter.
+        let mut counter_regions = counter_regions
+            .map(|(counter, region)| (counter, None, region))
+            .chain(branch_regions.into_iter().map(|(t, f, region)| (t, Some(f), region)))
+            .collect::<Vec<_>>();
         if counter_regions.is_empty() {
             return;
         }
@@ -111,8 +122,8 @@ impl CoverageMapGenerator {
         // `file_id` (indexing files referenced by the current function), and construct the
         // function-specific `virtual_file_mapping` from `file_id` to its index in the module's
         // `filenames` array.
-        counter_regions.sort_unstable_by_key(|(_counter, region)| *region);
-        for (counter, region) in counter_regions {
+        counter_regions.sort_unstable_by_key(|(_counter, _false_counter, region)| *region);
+        for (counter, false_counter, region) in counter_regions {
             let CodeRegion { file_name, start_line, start_col, end_line, end_col } = *region;
             let same_file = current_file_name.as_ref().map_or(false, |p| *p == file_name);
             if !same_file {
@@ -126,14 +137,25 @@ impl CoverageMapGenerator {
                 let (filenames_index, _) = self.filenames.insert_full(c_filename);
                 virtual_file_mapping.push(filenames_index as u32);
             }
-            mapping_regions.push(CounterMappingRegion::code_region(
-                counter,
-                current_file_id,
-                start_line,
-                start_col,
-                end_line,
-                end_col,
-            ));
+            mapping_regions.push(match false_counter {
+                None => CounterMappingRegion::code_region(
+                    counter,
+                    current_file_id,
+                    start_line,
+                    start_col,
+                    end_line,
+                    end_col,
+                ),
+                Some(false_counter) => CounterMappingRegion::branch_region(
+                    counter,
+                    false_counter,
+                    current_file_id,
+                    start_line,
+                    start_col,
+                    end_line,
+                    end_col,
+                ),
+            });
         }
 
         // Encode and append the current function's coverage mapping data
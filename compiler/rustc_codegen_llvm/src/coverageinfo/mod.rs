@@ -84,7 +84,7 @@ impl CoverageInfoBuilderMethods<'tcx> for Builder<'a, 'll, 'tcx> {
         lhs: ExpressionOperandId,
         op: Op,
         rhs: ExpressionOperandId,
-        region: CodeRegion,
+        region: Option<CodeRegion>,
     ) {
         debug!(
             "adding counter expression to coverage_regions: instance={:?}, id={:?}, {:?} {:?} {:?}, \
@@ -109,6 +109,24 @@ impl CoverageInfoBuilderMethods<'tcx> for Builder<'a, 'll, 'tcx> {
             .or_insert_with(|| FunctionCoverage::new(self.tcx, instance))
             .add_unreachable_region(region);
     }
+
+    fn add_branch_region(
+        &mut self,
+        instance: Instance<'tcx>,
+        true_id: ExpressionOperandId,
+        false_id: ExpressionOperandId,
+        region: CodeRegion,
+    ) {
+        debug!(
+            "adding branch to coverage_regions: instance={:?}, true: {:?}, false: {:?}, at {:?}",
+            instance, true_id, false_id, region,
+        );
+        let mut coverage_regions = self.coverage_context().function_coverage_map.borrow_mut();
+        coverage_regions
+            .entry(instance)
+            .or_insert_with(|| FunctionCoverage::new(self.tcx, instance))
+            .add_branch_region(true_id, false_id, region);
+    }
 }
 
 pub(crate) fn write_filenames_section_to_buffer<'a>(
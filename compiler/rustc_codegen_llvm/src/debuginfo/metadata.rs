@@ -37,6 +37,7 @@ use rustc_middle::ty::Instance;
 use rustc_middle::ty::{self, AdtKind, GeneratorSubsts, ParamEnv, Ty, TyCtxt};
 use rustc_middle::{bug, span_bug};
 use rustc_session::config::{self, DebugInfo};
+use rustc_span::source_map::RemapPathScopes;
 use rustc_span::symbol::{Interner, Symbol};
 use rustc_span::{self, SourceFile, SourceFileHash, Span};
 use rustc_target::abi::{Abi, Align, HasDataLayout, Integer, LayoutOf, TagEncoding};
@@ -768,9 +769,12 @@ pub fn file_metadata(
     debug!("file_metadata: file_name: {}, defining_crate: {}", source_file.name, defining_crate);
 
     let hash = Some(&source_file.src_hash);
-    let file_name = Some(source_file.name.to_string());
+    let source_map = cx.sess().source_map();
+    let file_name =
+        Some(source_map.filename_for_scope(source_file, RemapPathScopes::DEBUGINFO).to_string());
     let directory = if defining_crate == LOCAL_CRATE {
-        Some(cx.sess().working_dir.0.to_string_lossy().to_string())
+        let working_dir = cx.sess().working_dir_for_scope(RemapPathScopes::DEBUGINFO);
+        Some(working_dir.to_string_lossy().to_string())
     } else {
         // If the path comes from an upstream crate we assume it has been made
         // independent of the compiler's working directory one way or another.
@@ -976,10 +980,11 @@ pub fn compile_unit_metadata(
     codegen_unit_name: &str,
     debug_context: &CrateDebugContext<'ll, '_>,
 ) -> &'ll DIDescriptor {
-    let mut name_in_debuginfo = match tcx.sess.local_crate_source_file {
-        Some(ref path) => path.clone(),
-        None => PathBuf::from(&*tcx.crate_name(LOCAL_CRATE).as_str()),
-    };
+    let mut name_in_debuginfo =
+        match tcx.sess.local_crate_source_file_for_scope(RemapPathScopes::DEBUGINFO) {
+            Some(path) => path.to_path_buf(),
+            None => PathBuf::from(&*tcx.crate_name(LOCAL_CRATE).as_str()),
+        };
 
     // The OSX linker has an idiosyncrasy where it will ignore some debuginfo
     // if multiple object files with the same `DW_AT_name` are linked together.
@@ -997,9 +1002,28 @@ pub fn compile_unit_metadata(
     let producer = format!("clang LLVM ({})", rustc_producer);
 
     let name_in_debuginfo = name_in_debuginfo.to_string_lossy();
-    let work_dir = tcx.sess.working_dir.0.to_string_lossy();
+    let work_dir = tcx.sess.working_dir_for_scope(RemapPathScopes::DEBUGINFO).to_string_lossy();
     let flags = "\0";
-    let split_name = "";
+
+    let split_name = if tcx.sess.target_can_use_split_dwarf() {
+        tcx.output_filenames(LOCAL_CRATE)
+            .split_dwarf_path(
+                tcx.sess.split_debuginfo(),
+                tcx.sess.opts.debugging_opts.split_dwarf,
+                Some(codegen_unit_name),
+            )
+            // We get a path relative to the working directory from split_dwarf_path
+            .map(|f| {
+                tcx.sess
+                    .source_map()
+                    .path_mapping()
+                    .map_prefix_for_scope(f, RemapPathScopes::OBJECT)
+            })
+    } else {
+        None
+    }
+    .unwrap_or_default();
+    let split_name = split_name.to_string_lossy();
 
     // FIXME(#60020):
     //
@@ -1043,6 +1067,8 @@ pub fn compile_unit_metadata(
             split_name.as_ptr().cast(),
             split_name.len(),
             kind,
+            0,
+            tcx.sess.opts.debugging_opts.split_dwarf_inlining,
         );
 
         if tcx.sess.opts.debugging_opts.profile {
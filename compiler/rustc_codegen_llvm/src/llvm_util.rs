@@ -36,6 +36,13 @@ pub(crate) fn init(sess: &Session) {
             bug!("couldn't enable multi-threaded LLVM");
         }
     }
+
+    if sess.opts.debugging_opts.coverage_branches && get_major_version() < 12 {
+        sess.warn(
+            "`-Z coverage-branches` requires LLVM 12 or later, branch regions will be left out \
+             of the coverage map",
+        );
+    }
 }
 
 fn require_inited() {
@@ -81,6 +88,10 @@ unsafe fn configure_llvm(sess: &Session) {
         if sess.print_llvm_passes() {
             add("-debug-pass=Structure", false);
         }
+        if sess.opts.debugging_opts.print_stack_protectors {
+            // The stack protector pass only reports which functions it protects through remarks.
+            add("-pass-remarks=stack-protector", false);
+        }
         if !sess.opts.debugging_opts.no_generate_arange_section {
             add("-generate-arange-section", false);
         }
@@ -4,8 +4,8 @@ use rustc_fs_util::fix_windows_verbatim_for_gcc;
 use rustc_hir::def_id::CrateNum;
 use rustc_middle::middle::cstore::{EncodedMetadata, LibSource, NativeLib};
 use rustc_middle::middle::dependency_format::Linkage;
-use rustc_session::config::{self, CFGuard, CrateType, DebugInfo};
-use rustc_session::config::{OutputFilenames, OutputType, PrintRequest, SanitizerSet};
+use rustc_session::config::{self, CFGuard, CrateType, DebugInfo, SplitDwarfKind};
+use rustc_session::config::{OutputFilenames, OutputType, PrintRequest};
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
 use rustc_session::search_paths::PathKind;
 use rustc_session::utils::NativeLibKind;
@@ -15,7 +15,7 @@ use rustc_session::{filesearch, Session};
 use rustc_span::symbol::Symbol;
 use rustc_target::spec::crt_objects::{CrtObjects, CrtObjectsFallback};
 use rustc_target::spec::{LinkOutputKind, LinkerFlavor, LldFlavor};
-use rustc_target::spec::{PanicStrategy, RelocModel, RelroLevel};
+use rustc_target::spec::{PanicStrategy, RelocModel, RelroLevel, SanitizerSet, SplitDebuginfo};
 
 use super::archive::ArchiveBuilder;
 use super::command::Command;
@@ -107,11 +107,20 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
     // Remove the temporary object file and metadata if we aren't saving temps
     sess.time("link_binary_remove_temps", || {
         if !sess.opts.cg.save_temps {
-            if sess.opts.output_types.should_codegen()
-                && !preserve_objects_for_their_debuginfo(sess)
-            {
-                for obj in codegen_results.modules.iter().filter_map(|m| m.object.as_ref()) {
-                    remove(sess, obj);
+            let (preserve_objects, preserve_dwarf_objects) =
+                preserve_objects_for_their_debuginfo(sess);
+            if sess.opts.output_types.should_codegen() {
+                if !preserve_objects {
+                    for obj in codegen_results.modules.iter().filter_map(|m| m.object.as_ref()) {
+                        remove(sess, obj);
+                    }
+                }
+                if !preserve_dwarf_objects {
+                    for dwo in
+                        codegen_results.modules.iter().filter_map(|m| m.dwarf_object.as_ref())
+                    {
+                        remove(sess, dwo);
+                    }
                 }
             }
             if let Some(ref metadata_module) = codegen_results.metadata_module {
@@ -128,6 +137,14 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
     });
 }
 
+fn escape_string(s: &[u8]) -> String {
+    str::from_utf8(s).map(|s| s.to_owned()).unwrap_or_else(|_| {
+        let mut x = "Non-UTF-8 output: ".to_string();
+        x.extend(s.iter().flat_map(|&b| ascii::escape_default(b)).map(char::from));
+        x
+    })
+}
+
 // The third parameter is for env vars, used on windows to set up the
 // path for MSVC to find its DLLs, and gcc to find its bundled
 // toolchain
@@ -664,13 +681,6 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(
 
     match prog {
         Ok(prog) => {
-            fn escape_string(s: &[u8]) -> String {
-                str::from_utf8(s).map(|s| s.to_owned()).unwrap_or_else(|_| {
-                    let mut x = "Non-UTF-8 output: ".to_string();
-                    x.extend(s.iter().flat_map(|&b| ascii::escape_default(b)).map(char::from));
-                    x
-                })
-            }
             if !prog.status.success() {
                 let mut output = prog.stderr.clone();
                 output.extend_from_slice(&prog.stdout);
@@ -772,15 +782,74 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(
         }
     }
 
-    // On macOS, debuggers need this utility to get run to do some munging of
-    // the symbols. Note, though, that if the object files are being preserved
-    // for their debug information there's no need for us to run dsymutil.
-    if sess.target.target.options.is_like_osx
-        && sess.opts.debuginfo != DebugInfo::None
-        && !preserve_objects_for_their_debuginfo(sess)
-    {
-        if let Err(e) = Command::new("dsymutil").arg(out_filename).output() {
-            sess.fatal(&format!("failed to run dsymutil: {}", e))
+    // Packed split debuginfo has to be collected from wherever codegen left it
+    // once the final artifact exists. Unpacked debuginfo stays where it is and
+    // the artifact points at it.
+    if sess.opts.debuginfo != DebugInfo::None && sess.split_debuginfo() == SplitDebuginfo::Packed {
+        // On macOS, debuggers need this utility to get run to do some munging
+        // of the symbols, producing a `.dSYM` bundle next to the artifact.
+        if sess.target.target.options.is_like_osx {
+            if let Err(e) = Command::new("dsymutil").arg(out_filename).output() {
+                sess.fatal(&format!("failed to run dsymutil: {}", e))
+            }
+        } else if sess.target_can_use_split_dwarf() {
+            link_dwarf_object(sess, out_filename);
+        }
+    }
+}
+
+/// The name of the tool which packs DWARF objects into a DWARF package. It is
+/// shipped alongside `rust-lld` in the sysroot, so that it matches the LLVM
+/// which produced the `.dwo` files.
+const LLVM_DWP_EXECUTABLE: &str = "rust-llvm-dwp";
+
+/// Packs the DWARF objects (`.dwo` files) referenced by the skeleton units in
+/// `executable_out_filename` into a DWARF package next to it, with the `.dwp`
+/// extension.
+fn link_dwarf_object(sess: &Session, executable_out_filename: &Path) {
+    let dwp_out_filename = executable_out_filename.with_extension("dwp");
+    info!("preparing dwp to {}", dwp_out_filename.display());
+
+    let mut cmd = Command::new(LLVM_DWP_EXECUTABLE);
+    cmd.arg("-e");
+    cmd.arg(executable_out_filename);
+    cmd.arg("-o");
+    cmd.arg(&dwp_out_filename);
+
+    // `rust-llvm-dwp` is found in the same place as the bundled linker tools.
+    let mut new_path = sess.host_filesearch(PathKind::All).get_tools_search_paths(false);
+    if let Some(path) = env::var_os("PATH") {
+        new_path.extend(env::split_paths(&path));
+    }
+    let new_path = env::join_paths(new_path).unwrap();
+    cmd.env("PATH", new_path);
+
+    info!("{:?}", &cmd);
+    match sess.time("run_dwp", || cmd.output()) {
+        Ok(prog) if !prog.status.success() => {
+            let mut output = prog.stderr.clone();
+            output.extend_from_slice(&prog.stdout);
+            sess.struct_err(&format!(
+                "linking dwarf objects with `{}` failed: {}",
+                LLVM_DWP_EXECUTABLE, prog.status
+            ))
+            .note(&format!("{:?}", &cmd))
+            .note(&escape_string(&output))
+            .emit();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let dwp_not_found = e.kind() == io::ErrorKind::NotFound;
+            let mut err = if dwp_not_found {
+                sess.struct_err(&format!("`{}` not found", LLVM_DWP_EXECUTABLE))
+            } else {
+                sess.struct_err(&format!("could not exec `{}`", LLVM_DWP_EXECUTABLE))
+            };
+            err.note(&e.to_string());
+            if !dwp_not_found {
+                err.note(&format!("{:?}", &cmd));
+            }
+            err.emit();
         }
     }
 }
@@ -815,6 +884,9 @@ fn link_sanitizers(sess: &Session, crate_type: CrateType, linker: &mut dyn Linke
     if sanitizer.contains(SanitizerSet::THREAD) {
         link_sanitizer_runtime(sess, linker, "tsan");
     }
+    if sanitizer.contains(SanitizerSet::HWADDRESS) {
+        link_sanitizer_runtime(sess, linker, "hwasan");
+    }
 }
 
 fn link_sanitizer_runtime(sess: &Session, linker: &mut dyn Linker, name: &str) {
@@ -952,38 +1024,52 @@ fn linker_and_flavor(sess: &Session) -> (PathBuf, LinkerFlavor) {
     bug!("Not enough information provided to determine how to invoke the linker");
 }
 
-/// Returns a boolean indicating whether we should preserve the object files on
-/// the filesystem for their debug information. This is often useful with
-/// split-dwarf like schemes.
-fn preserve_objects_for_their_debuginfo(sess: &Session) -> bool {
+/// Returns a pair of booleans indicating whether we should preserve the object
+/// files and the DWARF objects (`.dwo` files) respectively on the filesystem
+/// for their debug information. This is often useful with split-dwarf like
+/// schemes.
+fn preserve_objects_for_their_debuginfo(sess: &Session) -> (bool, bool) {
     // If the objects don't have debuginfo there's nothing to preserve.
     if sess.opts.debuginfo == config::DebugInfo::None {
-        return false;
+        return (false, false);
     }
 
     // If we're only producing artifacts that are archives, no need to preserve
-    // the objects as they're losslessly contained inside the archives.
+    // the objects as they're losslessly contained inside the archives. The
+    // DWARF objects they point at aren't, though, so those have to stay around
+    // for whoever links the archive to pack or reference them.
     let output_linked =
         sess.crate_types().iter().any(|&x| x != CrateType::Rlib && x != CrateType::Staticlib);
-    if !output_linked {
-        return false;
-    }
 
-    // If we're on OSX then the equivalent of split dwarf is turned on by
-    // default. The final executable won't actually have any debug information
-    // except it'll have pointers to elsewhere. Historically we've always run
-    // `dsymutil` to "link all the dwarf together" but this is actually sort of
-    // a bummer for incremental compilation! (the whole point of split dwarf is
-    // that you don't do this sort of dwarf link).
-    //
-    // Basically as a result this just means that if we're on OSX and we're
-    // *not* running dsymutil then the object files are the only source of truth
-    // for debug information, so we must preserve them.
-    if sess.target.target.options.is_like_osx {
-        return !sess.opts.debugging_opts.run_dsymutil;
+    let split_dwarf = sess.target_can_use_split_dwarf();
+    match (sess.split_debuginfo(), sess.opts.debugging_opts.split_dwarf) {
+        // The debuginfo is in the objects themselves, which end up in the
+        // final artifact.
+        (SplitDebuginfo::Off, _) => (false, false),
+
+        // Archives keep whatever their skeleton units point at: the objects
+        // with `-Z split-dwarf=single`, the `.dwo` files otherwise.
+        (_, SplitDwarfKind::Single) if !output_linked => (split_dwarf, false),
+        (_, SplitDwarfKind::Split) if !output_linked => (false, split_dwarf),
+
+        // The debuginfo has been collected into a `.dwp` file or `.dSYM`
+        // bundle by now, so nothing needs to be kept around.
+        (SplitDebuginfo::Packed, _) => (false, false),
+
+        // If we're on OSX then the equivalent of split dwarf is turned on by
+        // default. The final executable won't actually have any debug
+        // information except it'll have pointers to elsewhere. Without
+        // `dsymutil` to "link all the dwarf together" (the whole point of split
+        // dwarf is that you don't do this sort of dwarf link), the object
+        // files are the only source of truth for debug information, so we
+        // must preserve them. The same goes for ELF with
+        // `-Z split-dwarf=single`.
+        (SplitDebuginfo::Unpacked, _) if !split_dwarf => {
+            (sess.target.target.options.is_like_osx, false)
+        }
+        (SplitDebuginfo::Unpacked, SplitDwarfKind::Single) => (true, false),
+        (SplitDebuginfo::Unpacked, SplitDwarfKind::Split) => (false, true),
     }
-
-    false
 }
 
 pub fn archive_search_paths(sess: &Session) -> Vec<PathBuf> {
@@ -28,12 +28,12 @@ use rustc_middle::middle::exported_symbols::SymbolExportLevel;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::cgu_reuse_tracker::CguReuseTracker;
 use rustc_session::config::{self, CrateType, Lto, OutputFilenames, OutputType};
-use rustc_session::config::{Passes, SanitizerSet, SwitchWithOptPath};
+use rustc_session::config::{Passes, SwitchWithOptPath};
 use rustc_session::Session;
 use rustc_span::source_map::SourceMap;
 use rustc_span::symbol::{sym, Symbol};
 use rustc_span::{BytePos, FileName, InnerSpan, Pos, Span};
-use rustc_target::spec::{MergeFunctions, PanicStrategy};
+use rustc_target::spec::{MergeFunctions, PanicStrategy, SanitizerSet, SplitDebuginfo};
 
 use std::any::Any;
 use std::fs;
@@ -278,10 +278,40 @@ impl ModuleConfig {
     }
 }
 
+/// Configuration passed to the function returned by the `target_machine_factory`.
+pub struct TargetMachineFactoryConfig {
+    /// Split DWARF is enabled in LLVM by checking that `TM.MCOptions.SplitDwarfFile` isn't empty,
+    /// so the path to the dwarf object has to be provided when we create the target machine.
+    /// This can be ignored by backends which do not need it for their Split DWARF support.
+    pub split_dwarf_file: Option<PathBuf>,
+}
+
+impl TargetMachineFactoryConfig {
+    pub fn new(
+        cgcx: &CodegenContext<impl WriteBackendMethods>,
+        module_name: &str,
+    ) -> TargetMachineFactoryConfig {
+        let split_dwarf_file = if cgcx.target_can_use_split_dwarf {
+            cgcx.output_filenames.split_dwarf_path(
+                cgcx.split_debuginfo,
+                cgcx.split_dwarf_kind,
+                Some(module_name),
+            )
+        } else {
+            None
+        };
+        TargetMachineFactoryConfig { split_dwarf_file }
+    }
+}
+
+pub type TargetMachineFactoryFn<B> = Arc<
+    dyn Fn(TargetMachineFactoryConfig) -> Result<<B as WriteBackendMethods>::TargetMachine, String>
+        + Send
+        + Sync,
+>;
+
 // HACK(eddyb) work around `#[derive]` producing wrong bounds for `Clone`.
-pub struct TargetMachineFactory<B: WriteBackendMethods>(
-    pub Arc<dyn Fn() -> Result<B::TargetMachine, String> + Send + Sync>,
-);
+pub struct TargetMachineFactory<B: WriteBackendMethods>(pub TargetMachineFactoryFn<B>);
 
 impl<B: WriteBackendMethods> Clone for TargetMachineFactory<B> {
     fn clone(&self) -> Self {
@@ -314,6 +344,9 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub target_pointer_width: String,
     pub target_arch: String,
     pub debuginfo: config::DebugInfo,
+    pub split_debuginfo: SplitDebuginfo,
+    pub split_dwarf_kind: config::SplitDwarfKind,
+    pub target_can_use_split_dwarf: bool,
 
     // Number of cgus excluding the allocator/metadata modules
     pub total_cgus: usize,
@@ -321,6 +354,8 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub diag_emitter: SharedEmitter,
     // LLVM optimizations for which we want to print remarks.
     pub remark: Passes,
+    // Whether to print the functions that stack protectors were inserted into.
+    pub print_stack_protectors: bool,
     // Worker thread number
     pub worker: usize,
     // The incremental compilation session directory, or None if we are not
@@ -493,10 +528,11 @@ fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
     let _timer = sess.timer("copy_all_cgu_workproducts_to_incr_comp_cache_dir");
 
     for module in compiled_modules.modules.iter().filter(|m| m.kind == ModuleKind::Regular) {
-        let path = module.object.as_ref().cloned();
+        let object = module.object.as_ref().cloned();
+        let dwarf_object = module.dwarf_object.as_ref().cloned();
 
         if let Some((id, product)) =
-            copy_cgu_workproduct_to_incr_comp_cache_dir(sess, &module.name, &path)
+            copy_cgu_workproduct_to_incr_comp_cache_dir(sess, &module.name, &object, &dwarf_object)
         {
             work_products.insert(id, product);
         }
@@ -835,27 +871,40 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
     module_config: &ModuleConfig,
 ) -> Result<WorkItemResult<B>, FatalError> {
     let incr_comp_session_dir = cgcx.incr_comp_session_dir.as_ref().unwrap();
-    let mut object = None;
-    if let Some(saved_file) = module.source.saved_file {
-        let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, Some(&module.name));
-        object = Some(obj_out.clone());
-        let source_file = in_incr_comp_dir(&incr_comp_session_dir, &saved_file);
+
+    let load_from_incr_comp_dir = |output_path: PathBuf, saved_path: &str| {
+        let source_file = in_incr_comp_dir(&incr_comp_session_dir, saved_path);
         debug!(
             "copying pre-existing module `{}` from {:?} to {}",
             module.name,
             source_file,
-            obj_out.display()
+            output_path.display()
         );
-        if let Err(err) = link_or_copy(&source_file, &obj_out) {
+        if let Err(err) = link_or_copy(&source_file, &output_path) {
             let diag_handler = cgcx.create_diag_handler();
             diag_handler.err(&format!(
                 "unable to copy {} to {}: {}",
                 source_file.display(),
-                obj_out.display(),
+                output_path.display(),
                 err
             ));
         }
-    }
+        output_path
+    };
+
+    let object = module.source.saved_file.as_ref().map(|saved_file| {
+        let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, Some(&module.name));
+        load_from_incr_comp_dir(obj_out, saved_file)
+    });
+    let dwarf_object = module.source.saved_dwarf_object.as_ref().map(|saved_dwarf_object| {
+        let dwarf_obj_out = cgcx
+            .output_filenames
+            .split_dwarf_path(cgcx.split_debuginfo, cgcx.split_dwarf_kind, Some(&module.name))
+            .expect(
+                "saved dwarf object in work product but `split_dwarf_path` returned `None`",
+            );
+        load_from_incr_comp_dir(dwarf_obj_out, saved_dwarf_object)
+    });
 
     assert_eq!(object.is_some(), module_config.emit_obj != EmitObj::None);
 
@@ -863,6 +912,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
         name: module.name,
         kind: ModuleKind::Regular,
         object,
+        dwarf_object,
         bytecode: None,
     }))
 }
@@ -1025,6 +1075,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         prof: sess.prof.clone(),
         exported_symbols,
         remark: sess.opts.cg.remark.clone(),
+        print_stack_protectors: sess.opts.debugging_opts.print_stack_protectors,
         worker: 0,
         incr_comp_session_dir: sess.incr_comp_session_dir_opt().map(|r| r.clone()),
         cgu_reuse_tracker: sess.cgu_reuse_tracker.clone(),
@@ -1040,6 +1091,9 @@ fn start_executing_work<B: ExtraBackendMethods>(
         target_pointer_width: tcx.sess.target.target.target_pointer_width.clone(),
         target_arch: tcx.sess.target.target.arch.clone(),
         debuginfo: tcx.sess.opts.debuginfo,
+        split_debuginfo: tcx.sess.split_debuginfo(),
+        split_dwarf_kind: tcx.sess.opts.debugging_opts.split_dwarf,
+        target_can_use_split_dwarf: tcx.sess.target_can_use_split_dwarf(),
     };
 
     // This is the "main loop" of parallel work happening for parallel codegen.
@@ -13,6 +13,14 @@ pub struct ExpressionRegion {
     lhs: ExpressionOperandId,
     op: Op,
     rhs: ExpressionOperandId,
+    /// `None` if the expression is only used as an operand of other expressions or branches.
+    region: Option<CodeRegion>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BranchRegion {
+    true_id: ExpressionOperandId,
+    false_id: ExpressionOperandId,
     region: CodeRegion,
 }
 
@@ -33,6 +41,7 @@ pub struct FunctionCoverage {
     counters: IndexVec<CounterValueReference, Option<CodeRegion>>,
     expressions: IndexVec<InjectedExpressionIndex, Option<ExpressionRegion>>,
     unreachable_regions: Vec<CodeRegion>,
+    branch_regions: Vec<BranchRegion>,
 }
 
 impl FunctionCoverage {
@@ -43,6 +52,7 @@ impl FunctionCoverage {
             counters: IndexVec::from_elem_n(None, coverageinfo.num_counters as usize),
             expressions: IndexVec::from_elem_n(None, coverageinfo.num_expressions as usize),
             unreachable_regions: Vec::new(),
+            branch_regions: Vec::new(),
         }
     }
 
@@ -78,7 +88,7 @@ impl FunctionCoverage {
         lhs: ExpressionOperandId,
         op: Op,
         rhs: ExpressionOperandId,
-        region: CodeRegion,
+        region: Option<CodeRegion>,
     ) {
         let expression_index = self.expression_index(u32::from(expression_id));
         self.expressions[expression_index]
@@ -91,27 +101,45 @@ impl FunctionCoverage {
         self.unreachable_regions.push(region)
     }
 
+    /// Add a branch region, whose "taken" and "not taken" counts are the counters or expressions
+    /// `true_id` and `false_id`.
+    pub fn add_branch_region(
+        &mut self,
+        true_id: ExpressionOperandId,
+        false_id: ExpressionOperandId,
+        region: CodeRegion,
+    ) {
+        self.branch_regions.push(BranchRegion { true_id, false_id, region })
+    }
+
     /// Return the source hash, generated from the HIR node structure, and used to indicate whether
     /// or not the source code structure changed between different compilations.
     pub fn source_hash(&self) -> u64 {
         self.source_hash
     }
 
-    /// Generate an array of CounterExpressions, and an iterator over all `Counter`s and their
+    /// Generate an array of CounterExpressions, an iterator over all `Counter`s and their
     /// associated `Regions` (from which the LLVM-specific `CoverageMapGenerator` will create
-    /// `CounterMappingRegion`s.
+    /// `CounterMappingRegion`s), and the branch regions with their "taken" and "not taken"
+    /// `Counter`s.
     pub fn get_expressions_and_counter_regions<'a>(
         &'a self,
-    ) -> (Vec<CounterExpression>, impl Iterator<Item = (Counter, &'a CodeRegion)>) {
+    ) -> (
+        Vec<CounterExpression>,
+        impl Iterator<Item = (Counter, &'a CodeRegion)>,
+        Vec<(Counter, Counter, &'a CodeRegion)>,
+    ) {
         assert!(self.source_hash != 0);
 
         let counter_regions = self.counter_regions();
-        let (counter_expressions, expression_regions) = self.expressions_with_regions();
+        let (counter_expressions, expression_regions, new_indexes) =
+            self.expressions_with_regions();
         let unreachable_regions = self.unreachable_regions();
+        let branch_regions = self.branch_regions(&new_indexes);
 
         let counter_regions =
             counter_regions.chain(expression_regions.into_iter().chain(unreachable_regions));
-        (counter_expressions, counter_regions)
+        (counter_expressions, counter_regions, branch_regions)
     }
 
     fn counter_regions<'a>(&'a self) -> impl Iterator<Item = (Counter, &'a CodeRegion)> {
@@ -126,7 +154,11 @@ impl FunctionCoverage {
 
     fn expressions_with_regions(
         &'a self,
-    ) -> (Vec<CounterExpression>, impl Iterator<Item = (Counter, &'a CodeRegion)>) {
+    ) -> (
+        Vec<CounterExpression>,
+        impl Iterator<Item = (Counter, &'a CodeRegion)>,
+        IndexVec<InjectedExpressionIndex, MappedExpressionIndex>,
+    ) {
         let mut counter_expressions = Vec::with_capacity(self.expressions.len());
         let mut expression_regions = Vec::with_capacity(self.expressions.len());
         let mut new_indexes =
@@ -140,26 +172,6 @@ impl FunctionCoverage {
         // `expression_index`s lower than the referencing `ExpressionRegion`. Therefore, it is
         // reasonable to look up the new index of an expression operand while the `new_indexes`
         // vector is only complete up to the current `ExpressionIndex`.
-        let id_to_counter =
-            |new_indexes: &IndexVec<InjectedExpressionIndex, MappedExpressionIndex>,
-             id: ExpressionOperandId| {
-                if id.index() < self.counters.len() {
-                    let index = CounterValueReference::from(id.index());
-                    self.counters
-                        .get(index)
-                        .unwrap() // pre-validated
-                        .as_ref()
-                        .map(|_| Counter::counter_value_reference(index))
-                } else {
-                    let index = self.expression_index(u32::from(id));
-                    self.expressions
-                        .get(index)
-                        .expect("expression id is out of range")
-                        .as_ref()
-                        .map(|_| Counter::expression(new_indexes[index]))
-                }
-            };
-
         for (original_index, expression_region) in
             self.expressions.iter_enumerated().filter_map(|(original_index, entry)| {
                 // Option::map() will return None to filter out missing expressions. This may happen
@@ -167,12 +179,12 @@ impl FunctionCoverage {
                 entry.as_ref().map(|region| (original_index, region))
             })
         {
-            let region = &expression_region.region;
-            let ExpressionRegion { lhs, op, rhs, .. } = *expression_region;
+            let ExpressionRegion { lhs, op, rhs, ref region } = *expression_region;
 
             if let Some(Some((lhs_counter, rhs_counter))) =
-                id_to_counter(&new_indexes, lhs).map(|lhs_counter| {
-                    id_to_counter(&new_indexes, rhs).map(|rhs_counter| (lhs_counter, rhs_counter))
+                self.operand_to_counter(&new_indexes, lhs).map(|lhs_counter| {
+                    self.operand_to_counter(&new_indexes, rhs)
+                        .map(|rhs_counter| (lhs_counter, rhs_counter))
                 })
             {
                 // Both operands exist. `Expression` operands exist in `self.expressions` and have
@@ -188,10 +200,52 @@ impl FunctionCoverage {
                     rhs_counter,
                 ));
                 new_indexes[original_index] = mapped_expression_index;
-                expression_regions.push((Counter::expression(mapped_expression_index), region));
+                if let Some(region) = region {
+                    expression_regions
+                        .push((Counter::expression(mapped_expression_index), region));
+                }
             }
         }
-        (counter_expressions, expression_regions.into_iter())
+        (counter_expressions, expression_regions.into_iter(), new_indexes)
+    }
+
+    /// Converts a counter or expression ID into the `Counter` it is encoded as, or `None` if the
+    /// counter or expression doesn't exist (anymore).
+    fn operand_to_counter(
+        &self,
+        new_indexes: &IndexVec<InjectedExpressionIndex, MappedExpressionIndex>,
+        id: ExpressionOperandId,
+    ) -> Option<Counter> {
+        if id.index() < self.counters.len() {
+            let index = CounterValueReference::from(id.index());
+            self.counters
+                .get(index)
+                .unwrap() // pre-validated
+                .as_ref()
+                .map(|_| Counter::counter_value_reference(index))
+        } else {
+            let index = self.expression_index(u32::from(id));
+            self.expressions
+                .get(index)
+                .expect("expression id is out of range")
+                .as_ref()
+                .map(|_| Counter::expression(new_indexes[index]))
+        }
+    }
+
+    fn branch_regions(
+        &'a self,
+        new_indexes: &IndexVec<InjectedExpressionIndex, MappedExpressionIndex>,
+    ) -> Vec<(Counter, Counter, &'a CodeRegion)> {
+        self.branch_regions
+            .iter()
+            .filter_map(|BranchRegion { true_id, false_id, region }| {
+                // Like expressions, branches are left out if one of their counters was removed.
+                let true_counter = self.operand_to_counter(new_indexes, *true_id)?;
+                let false_counter = self.operand_to_counter(new_indexes, *false_id)?;
+                Some((true_counter, false_counter, region))
+            })
+            .collect()
     }
 
     fn unreachable_regions<'a>(&'a self) -> impl Iterator<Item = (Counter, &'a CodeRegion)> {
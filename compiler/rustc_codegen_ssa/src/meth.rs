@@ -50,11 +50,33 @@ impl<'a, 'tcx> VirtualIndex {
         bx.set_invariant_load(ptr);
         ptr
     }
+
+    pub fn get_vtable_ptr<Bx: BuilderMethods<'a, 'tcx>>(
+        self,
+        bx: &mut Bx,
+        llvtable: Bx::Value,
+        vtable_ptr_ty: Bx::Type,
+    ) -> Bx::Value {
+        // Load the supertrait vtable pointer from the object's vtable.
+        debug!("get_vtable_ptr({:?}, {:?})", llvtable, self);
+
+        let llvtable = bx.pointercast(llvtable, bx.type_ptr_to(vtable_ptr_ty));
+        let ptr_align = bx.tcx().data_layout.pointer_align.abi;
+        let gep = bx.inbounds_gep(llvtable, &[bx.const_usize(self.0)]);
+        let ptr = bx.load(gep, ptr_align);
+        bx.nonnull_metadata(ptr);
+        // Vtable loads are invariant.
+        bx.set_invariant_load(ptr);
+        ptr
+    }
 }
 
 /// Creates a dynamic vtable for the given type and vtable origin.
 /// This is used only for objects.
 ///
+/// Besides the drop glue, size, align and methods, the vtable holds a
+/// pointer to the vtable of each supertrait, for trait upcasting coercions.
+///
 /// The vtables are cached instead of created on every call.
 ///
 /// The `trait_ref` encodes the erased self type. Hence if we are
@@ -77,15 +99,14 @@ pub fn get_vtable<'tcx, Cx: CodegenMethods<'tcx>>(
     // Not in the cache; build it.
     let nullptr = cx.const_null(cx.type_inst_i8p());
 
-    let methods_root;
-    let methods = if let Some(trait_ref) = trait_ref {
-        methods_root = tcx.vtable_methods(trait_ref.with_self_ty(tcx, ty));
-        methods_root.iter()
+    let (methods, supertraits) = if let Some(trait_ref) = trait_ref {
+        let trait_ref = trait_ref.with_self_ty(tcx, ty);
+        (tcx.vtable_methods(trait_ref), tcx.vtable_supertraits(trait_ref))
     } else {
-        (&[]).iter()
+        (&[][..], &[][..])
     };
 
-    let methods = methods.cloned().map(|opt_mth| {
+    let methods = methods.iter().cloned().map(|opt_mth| {
         opt_mth.map_or(nullptr, |(def_id, substs)| {
             cx.get_fn_addr(
                 ty::Instance::resolve_for_vtable(
@@ -100,6 +121,12 @@ pub fn get_vtable<'tcx, Cx: CodegenMethods<'tcx>>(
         })
     });
 
+    let supertrait_vtables = supertraits.iter().map(|&supertrait_ref| {
+        let supertrait_ref = supertrait_ref
+            .map_bound(|trait_ref| ty::ExistentialTraitRef::erase_self_ty(tcx, trait_ref));
+        get_vtable(cx, ty, Some(tcx.erase_regions(&supertrait_ref)))
+    });
+
     let layout = cx.layout_of(ty);
     // /////////////////////////////////////////////////////////////////////////////////////////////
     // If you touch this code, be sure to also make the corresponding changes to
@@ -113,6 +140,7 @@ pub fn get_vtable<'tcx, Cx: CodegenMethods<'tcx>>(
     .iter()
     .cloned()
     .chain(methods)
+    .chain(supertrait_vtables)
     .collect();
 
     let vtable_const = cx.const_struct(&components, false);
@@ -18,11 +18,13 @@ use rustc_middle::mir::AssertKind;
 use rustc_middle::ty::layout::{FnAbiExt, HasTyCtxt};
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, Instance, Ty, TypeFoldable};
-use rustc_span::source_map::Span;
+use rustc_span::source_map::{RemapPathScopes, Span};
 use rustc_span::{sym, Symbol};
+use rustc_symbol_mangling::{typeid_for_fnabi, typeid_for_fnabi_erasing_receiver};
 use rustc_target::abi::call::{ArgAbi, FnAbi, PassMode};
 use rustc_target::abi::{self, LayoutOf};
 use rustc_target::spec::abi::Abi;
+use rustc_target::spec::SanitizerSet;
 
 use std::borrow::Cow;
 
@@ -821,7 +823,35 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         }
 
         let fn_ptr = match (llfn, instance) {
-            (Some(llfn), _) => llfn,
+            (Some(llfn), _) => {
+                // This is an indirect call, through either a fn pointer or a vtable. With CFI
+                // enabled, trap unless the callee is tagged with the type id of `fn_abi`.
+                let tcx = bx.tcx();
+                if tcx.sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::CFI)
+                    && !tcx
+                        .codegen_fn_attrs(self.instance.def_id())
+                        .no_sanitize
+                        .contains(SanitizerSet::CFI)
+                {
+                    // Virtual calls pass a `dyn Trait` receiver, so their receiver type can't
+                    // be checked against that of the method in the vtable.
+                    let typeid = match instance.map(|instance| instance.def) {
+                        Some(ty::InstanceDef::Virtual(..)) => {
+                            typeid_for_fnabi_erasing_receiver(tcx, &fn_abi)
+                        }
+                        _ => typeid_for_fnabi(tcx, &fn_abi),
+                    };
+                    let typeid_metadata = bx.cx().typeid_metadata(typeid);
+                    let cond = bx.type_test(llfn, typeid_metadata);
+                    let bx_pass = bx.build_sibling_block("type_test.pass");
+                    let mut bx_fail = bx.build_sibling_block("type_test.fail");
+                    bx.cond_br(cond, bx_pass.llbb(), bx_fail.llbb());
+                    bx_fail.abort();
+                    bx_fail.unreachable();
+                    bx = bx_pass;
+                }
+                llfn
+            }
             (None, Some(instance)) => bx.get_fn_addr(instance),
             _ => span_bug!(span, "no llfn for call"),
         };
@@ -1183,9 +1213,11 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
     fn get_caller_location(&mut self, bx: &mut Bx, span: Span) -> OperandRef<'tcx, Bx::Value> {
         self.caller_location.unwrap_or_else(|| {
             let topmost = span.ctxt().outer_expn().expansion_cause().unwrap_or(span);
-            let caller = bx.tcx().sess.source_map().lookup_char_pos(topmost.lo());
+            let source_map = bx.tcx().sess.source_map();
+            let caller = source_map.lookup_char_pos(topmost.lo());
+            let file = source_map.filename_for_scope(&caller.file, RemapPathScopes::MACRO);
             let const_loc = bx.tcx().const_caller_location((
-                Symbol::intern(&caller.file.name.to_string()),
+                Symbol::intern(&file.to_string()),
                 caller.line as u32,
                 caller.col_display as u32 + 1,
             ));
@@ -10,6 +10,7 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         let Coverage { kind, code_region } = coverage;
         match kind {
             CoverageKind::Counter { function_source_hash, id } => {
+                let code_region = code_region.expect("counters always have a code region");
                 bx.add_counter_region(self.instance, function_source_hash, id, code_region);
 
                 let coverageinfo = bx.tcx().coverageinfo(self.instance.def_id());
@@ -28,8 +29,13 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                 bx.add_counter_expression_region(self.instance, id, lhs, op, rhs, code_region);
             }
             CoverageKind::Unreachable => {
+                let code_region = code_region.expect("unreachable regions always have a region");
                 bx.add_unreachable_region(self.instance, code_region);
             }
+            CoverageKind::Branch { true_id, false_id } => {
+                let code_region = code_region.expect("branches always have a code region");
+                bx.add_branch_region(self.instance, true_id, false_id, code_region);
+            }
         }
     }
 }
@@ -13,8 +13,8 @@ use super::operand::OperandValue;
 use super::place::PlaceRef;
 use super::{FunctionCx, LocalRef};
 
-pub struct FunctionDebugContext<D> {
-    pub scopes: IndexVec<mir::SourceScope, DebugScope<D>>,
+pub struct FunctionDebugContext<S, L> {
+    pub scopes: IndexVec<mir::SourceScope, DebugScope<S, L>>,
     pub defining_crate: CrateNum,
 }
 
@@ -38,15 +38,19 @@ pub struct PerLocalVarDebugInfo<'tcx, D> {
 }
 
 #[derive(Clone, Copy, Debug)]
-pub struct DebugScope<D> {
-    pub scope_metadata: Option<D>,
+pub struct DebugScope<S, L> {
+    pub scope_metadata: Option<S>,
+
+    /// Call site location, if this scope was inlined from another function.
+    pub inlined_at: Option<L>,
+
     // Start and end offsets of the file to which this DIScope belongs.
     // These are used to quickly determine whether some span refers to the same file.
     pub file_start_pos: BytePos,
     pub file_end_pos: BytePos,
 }
 
-impl<D> DebugScope<D> {
+impl<S, L> DebugScope<S, L> {
     pub fn is_valid(&self) -> bool {
         self.scope_metadata.is_some()
     }
@@ -54,25 +58,29 @@ impl<D> DebugScope<D> {
 
 impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
     pub fn set_debug_loc(&self, bx: &mut Bx, source_info: mir::SourceInfo) {
-        let (scope, span) = self.debug_loc(source_info);
+        let (scope, inlined_at, span) = self.debug_loc(source_info);
         if let Some(scope) = scope {
-            bx.set_source_location(scope, span);
+            bx.set_source_location(scope, inlined_at, span);
         }
     }
 
-    pub fn debug_loc(&self, source_info: mir::SourceInfo) -> (Option<Bx::DIScope>, Span) {
+    pub fn debug_loc(
+        &self,
+        source_info: mir::SourceInfo,
+    ) -> (Option<Bx::DIScope>, Option<Bx::DILocation>, Span) {
         // Bail out if debug info emission is not enabled.
-        match self.debug_context {
-            None => return (None, source_info.span),
-            Some(_) => {}
-        }
+        let debug_context = match &self.debug_context {
+            None => return (None, None, source_info.span),
+            Some(debug_context) => debug_context,
+        };
+        let inlined_at = debug_context.scopes[source_info.scope].inlined_at;
 
         // In order to have a good line stepping behavior in debugger, we overwrite debug
         // locations of macro expansions with that of the outermost expansion site
         // (unless the crate is being compiled with `-Z debug-macros`).
         if !source_info.span.from_expansion() || self.cx.sess().opts.debugging_opts.debug_macros {
             let scope = self.scope_metadata_for_loc(source_info.scope, source_info.span.lo());
-            (scope, source_info.span)
+            (scope, inlined_at, source_info.span)
         } else {
             // Walk up the macro expansion chain until we reach a non-expanded span.
             // We also stop at the function body level because no line stepping can occur
@@ -80,7 +88,7 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
             let span = rustc_span::hygiene::walk_chain(source_info.span, self.mir.span.ctxt());
             let scope = self.scope_metadata_for_loc(source_info.scope, span.lo());
             // Use span of the outermost expansion site, while keeping the original lexical scope.
-            (scope, span)
+            (scope, inlined_at, span)
         }
     }
 
@@ -148,10 +156,10 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
             } else {
                 let name = kw::Invalid;
                 let decl = &self.mir.local_decls[local];
-                let (scope, span) = if full_debug_info {
+                let (scope, _, span) = if full_debug_info {
                     self.debug_loc(decl.source_info)
                 } else {
-                    (None, decl.source_info.span)
+                    (None, None, decl.source_info.span)
                 };
                 let dbg_var = scope.map(|scope| {
                     // FIXME(eddyb) is this `+ 1` needed at all?
@@ -282,12 +290,13 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                 }
             }
 
-            let (scope, span) = self.debug_loc(var.source_info);
+            let (scope, inlined_at, span) = self.debug_loc(var.source_info);
             if let Some(scope) = scope {
                 if let Some(dbg_var) = var.dbg_var {
                     bx.dbg_var_addr(
                         dbg_var,
                         scope,
+                        inlined_at,
                         base.llval,
                         direct_offset,
                         &indirect_offsets,
@@ -318,10 +327,10 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
 
         let mut per_local = IndexVec::from_elem(vec![], &self.mir.local_decls);
         for var in &self.mir.var_debug_info {
-            let (scope, span) = if full_debug_info {
+            let (scope, _, span) = if full_debug_info {
                 self.debug_loc(var.source_info)
             } else {
-                (None, var.source_info.span)
+                (None, None, var.source_info.span)
             };
             let dbg_var = scope.map(|scope| {
                 let place = var.place;
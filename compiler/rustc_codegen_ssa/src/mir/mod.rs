@@ -4,9 +4,11 @@ use rustc_errors::ErrorReported;
 use rustc_middle::mir;
 use rustc_middle::mir::interpret::ErrorHandled;
 use rustc_middle::ty::layout::{FnAbiExt, HasTyCtxt, TyAndLayout};
-use rustc_middle::ty::{self, Instance, Ty, TypeFoldable};
+use rustc_middle::ty::{self, Instance, Ty, TyCtxt, TypeFoldable};
+use rustc_symbol_mangling::{typeid_for_fnabi, typeid_for_fnabi_erasing_receiver};
 use rustc_target::abi::call::{FnAbi, PassMode};
 use rustc_target::abi::HasDataLayout;
+use rustc_target::spec::SanitizerSet;
 
 use std::iter;
 
@@ -26,7 +28,7 @@ pub struct FunctionCx<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> {
 
     mir: &'tcx mir::Body<'tcx>,
 
-    debug_context: Option<FunctionDebugContext<Bx::DIScope>>,
+    debug_context: Option<FunctionDebugContext<Bx::DIScope, Bx::DILocation>>,
 
     llfn: Bx::Function,
 
@@ -145,6 +147,17 @@ pub fn codegen_mir<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
     let fn_abi = FnAbi::of_instance(cx, instance, &[]);
     debug!("fn_abi: {:?}", fn_abi);
 
+    // Tag the function with the type id of its ABI, which indirect calls are checked against
+    // when CFI is enabled.
+    if cx.tcx().sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::CFI) {
+        let typeid = typeid_for_fnabi(cx.tcx(), &fn_abi);
+        cx.add_type_metadata(llfn, typeid);
+        if may_be_called_virtually(cx.tcx(), instance) {
+            let typeid = typeid_for_fnabi_erasing_receiver(cx.tcx(), &fn_abi);
+            cx.add_type_metadata(llfn, typeid);
+        }
+    }
+
     let debug_context = cx.create_function_debug_context(instance, &fn_abi, llfn, &mir);
 
     let mut bx = Bx::new_block(cx, llfn, "start");
@@ -271,6 +284,18 @@ pub fn codegen_mir<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
     }
 }
 
+/// Returns whether `instance` may end up in a vtable, i.e. is a trait method (including the
+/// shims for them) or the body of a closure, which `dyn Fn*` vtables point to directly.
+fn may_be_called_virtually(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+    let def_id = instance.def_id();
+    tcx.is_closure(def_id)
+        || tcx.trait_of_item(def_id).is_some()
+        || tcx
+            .impl_of_method(def_id)
+            .and_then(|impl_def_id| tcx.trait_id_of_impl(impl_def_id))
+            .is_some()
+}
+
 fn create_funclets<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
     mir: &'tcx mir::Body<'tcx>,
     bx: &mut Bx,
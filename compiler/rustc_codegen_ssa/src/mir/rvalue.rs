@@ -264,22 +264,23 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                                 // unsize from a fat pointer -- this is a
                                 // "trait-object-to-supertrait" coercion, for
                                 // example, `&'a fmt::Debug + Send => &'a fmt::Debug`.
-
-                                // HACK(eddyb) have to bitcast pointers
-                                // until LLVM removes pointee types.
-                                let lldata = bx.pointercast(
+                                let (lldata, llextra) = base::unsize_ptr(
+                                    &mut bx,
                                     lldata,
-                                    bx.cx().scalar_pair_element_backend_type(cast, 0, true),
+                                    operand.layout.ty,
+                                    cast.ty,
+                                    Some(llextra),
                                 );
                                 OperandValue::Pair(lldata, llextra)
                             }
                             OperandValue::Immediate(lldata) => {
                                 // "standard" unsize
-                                let (lldata, llextra) = base::unsize_thin_ptr(
+                                let (lldata, llextra) = base::unsize_ptr(
                                     &mut bx,
                                     lldata,
                                     operand.layout.ty,
                                     cast.ty,
+                                    None,
                                 );
                                 OperandValue::Pair(lldata, llextra)
                             }
@@ -24,8 +24,18 @@ pub trait CoverageInfoBuilderMethods<'tcx>: BackendTypes {
         lhs: ExpressionOperandId,
         op: Op,
         rhs: ExpressionOperandId,
-        region: CodeRegion,
+        region: Option<CodeRegion>,
     );
 
     fn add_unreachable_region(&mut self, instance: Instance<'tcx>, region: CodeRegion);
+
+    /// Adds a branch region, counting how often the branch was taken with `true_id` and how often
+    /// it wasn't with `false_id`, which both refer to counters or expressions of the function.
+    fn add_branch_region(
+        &mut self,
+        instance: Instance<'tcx>,
+        true_id: ExpressionOperandId,
+        false_id: ExpressionOperandId,
+        region: CodeRegion,
+    );
 }
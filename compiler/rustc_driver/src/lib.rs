@@ -32,7 +32,8 @@ use rustc_save_analysis as save;
 use rustc_save_analysis::DumpHandler;
 use rustc_serialize::json::{self, ToJson};
 use rustc_session::config::nightly_options;
-use rustc_session::config::{ErrorOutputType, Input, OutputType, PrintRequest, TrimmedDefPaths};
+use rustc_session::config::{ErrorOutputType, Input, OutputType};
+use rustc_session::config::{PrintRequest, PrintTypeSizes, TrimmedDefPaths};
 use rustc_session::getopts;
 use rustc_session::lint::{Lint, LintId};
 use rustc_session::{config, DiagnosticOutput, Session};
@@ -398,8 +399,19 @@ pub fn run_compiler(
 
             queries.ongoing_codegen()?;
 
-            if sess.opts.debugging_opts.print_type_sizes {
-                sess.code_stats.print_type_sizes();
+            match sess.opts.debugging_opts.print_type_sizes {
+                Some(PrintTypeSizes::Text) => sess.code_stats.print_type_sizes(),
+                Some(PrintTypeSizes::Json) => {
+                    let path = queries.prepare_outputs()?.peek().with_extension("type-sizes.json");
+                    if let Err(e) = sess.code_stats.write_type_sizes_json(&path) {
+                        sess.err(&format!(
+                            "failed to write type sizes to `{}`: {}",
+                            path.display(),
+                            e
+                        ));
+                    }
+                }
+                None => {}
             }
 
             let linker = queries.linker()?;
@@ -1145,6 +1157,16 @@ pub fn catch_fatal_errors<F: FnOnce() -> R, R>(f: F) -> Result<R, ErrorReported>
 /// Variant of `catch_fatal_errors` for the `interface::Result` return type
 /// that also computes the exit code.
 pub fn catch_with_exit_code(f: impl FnOnce() -> interface::Result<()>) -> i32 {
+    // All the handlers of the process add to the same SARIF log, which is only complete once
+    // `f` is done. It is written even if `f` panics, as the log of an ICE is still useful.
+    struct WriteSarifLog;
+    impl Drop for WriteSarifLog {
+        fn drop(&mut self) {
+            rustc_errors::sarif::write_stderr_log();
+        }
+    }
+    let _write_sarif_log = WriteSarifLog;
+
     let result = catch_fatal_errors(f).and_then(|result| result);
     match result {
         Ok(()) => EXIT_SUCCESS,
@@ -13,6 +13,8 @@ use std::fmt;
 pub struct Diagnostic {
     pub level: Level,
     pub message: Vec<(String, Style)>,
+    /// The id of the message in the message catalogs, if it was translated from them.
+    pub message_id: Option<String>,
     pub code: Option<DiagnosticId>,
     pub span: MultiSpan,
     pub children: Vec<SubDiagnostic>,
@@ -95,6 +97,7 @@ impl Diagnostic {
         Diagnostic {
             level,
             message: vec![(message.to_owned(), Style::NoStyle)],
+            message_id: None,
             code,
             span: MultiSpan::new(),
             children: vec![],
@@ -522,6 +525,12 @@ impl Diagnostic {
         self.code.clone()
     }
 
+    /// Records that the primary message was translated from the message `id` of the catalogs.
+    pub fn set_message_id(&mut self, id: &str) -> &mut Self {
+        self.message_id = Some(id.to_owned());
+        self
+    }
+
     pub fn set_primary_message<M: Into<String>>(&mut self, msg: M) -> &mut Self {
         self.message[0] = (msg.into(), Style::NoStyle);
         self
@@ -150,6 +150,8 @@ struct Diagnostic {
     /// The primary error message.
     message: String,
     code: Option<DiagnosticCode>,
+    /// The id of the message in the message catalogs, if it was translated from them.
+    message_id: Option<String>,
     /// "error: internal compiler error", "error", "warning", "note", "help".
     level: &'static str,
     spans: Vec<DiagnosticSpan>,
@@ -231,6 +233,7 @@ impl Diagnostic {
         let sugg = diag.suggestions.iter().map(|sugg| Diagnostic {
             message: sugg.msg.clone(),
             code: None,
+            message_id: None,
             level: "help",
             spans: DiagnosticSpan::from_suggestion(sugg, je),
             children: vec![],
@@ -269,6 +272,7 @@ impl Diagnostic {
         Diagnostic {
             message: diag.message(),
             code: DiagnosticCode::map_opt_string(diag.code.clone(), je),
+            message_id: diag.message_id.clone(),
             level: diag.level.to_str(),
             spans: DiagnosticSpan::from_multispan(&diag.span, je),
             children: diag
@@ -285,6 +289,7 @@ impl Diagnostic {
         Diagnostic {
             message: diag.message(),
             code: None,
+            message_id: None,
             level: diag.level.to_str(),
             spans: diag
                 .render_span
@@ -344,7 +349,7 @@ impl DiagnosticSpan {
         });
 
         DiagnosticSpan {
-            file_name: start.file.name.to_string(),
+            file_name: je.sm.filename_for_diagnostics(&start.file).to_string(),
             byte_start: start.file.original_relative_byte_pos(span.lo()).0,
             byte_end: start.file.original_relative_byte_pos(span.hi()).0,
             line_start: start.line,
@@ -6,6 +6,7 @@
 #![feature(crate_visibility_modifier)]
 #![feature(backtrace)]
 #![feature(nll)]
+#![feature(once_cell)]
 
 #[macro_use]
 extern crate rustc_macros;
@@ -17,6 +18,7 @@ use Level::*;
 
 use emitter::{is_case_difference, Emitter, EmitterWriter};
 use registry::Registry;
+use translation::{FluentArgs, FluentBundle};
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{self, Lock, Lrc};
@@ -38,8 +40,10 @@ pub mod emitter;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
+pub mod translation;
 pub use snippet::Style;
 
 pub type PResult<'a, T> = Result<T, DiagnosticBuilder<'a>>;
@@ -285,6 +289,9 @@ pub use diagnostic_builder::DiagnosticBuilder;
 pub struct Handler {
     flags: HandlerFlags,
     inner: Lock<HandlerInner>,
+    /// The catalogs of the language selected with `-Z translate-lang`, if any. Messages they
+    /// lack are looked up in `translation::fallback_fluent_bundle`.
+    fluent_bundle: Option<Lrc<FluentBundle>>,
 }
 
 /// This inner struct exists to keep it all behind a single lock;
@@ -438,9 +445,27 @@ impl Handler {
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
             }),
+            fluent_bundle: None,
         }
     }
 
+    pub fn with_fluent_bundle(self, fluent_bundle: Option<Lrc<FluentBundle>>) -> Self {
+        Self { fluent_bundle, ..self }
+    }
+
+    /// Formats the message `id` of the catalogs, or its attribute `attr`, with `args`, in the
+    /// language of the handler.
+    pub fn translate_message(&self, id: &str, attr: Option<&str>, args: &FluentArgs<'_>) -> String {
+        self.fluent_bundle
+            .as_ref()
+            .and_then(|bundle| bundle.format(id, attr, args))
+            .or_else(|| translation::fallback_fluent_bundle().format(id, attr, args))
+            .unwrap_or_else(|| match attr {
+                Some(attr) => panic!("missing attribute `{}` of message `{}`", attr, id),
+                None => panic!("missing message `{}`", id),
+            })
+    }
+
     // This is here to not allow mutation of flags;
     // as of this writing it's only used in tests in librustc_middle.
     pub fn can_emit_warnings(&self) -> bool {
@@ -0,0 +1,551 @@
+//! A [SARIF] emitter for errors.
+//!
+//! Unlike the JSON emitter, which prints one object per diagnostic, SARIF describes a whole
+//! analysis run in a single document. The emitter therefore buffers the diagnostics it is given
+//! in a log, which is only written once the diagnostics are complete.
+//!
+//! A process may create several `Handler`s: rustc reports errors in its command line before the
+//! session exists, and rustdoc uses a handler of its own besides the one of the compiler. So
+//! that tools reading stderr get a single document, all the emitters created by
+//! `SarifEmitter::stderr` share one log, which `write_stderr_log` prints once the process is
+//! done. `rustc_driver::catch_with_exit_code` takes care of that, including when compilation is
+//! aborted by a fatal error or a panic. An emitter given its own destination writes its own log
+//! when it is dropped.
+//!
+//! The mapping from diagnostics is as follows:
+//!
+//! - Each diagnostic, except failure notes, is a `result` of the run. Its children are folded
+//!   into the message of the result.
+//! - Error codes and lint names are the `rules` of the tool, results refer to them by `ruleId`.
+//! - Primary spans are the `locations` of a result, secondary labeled spans and the spans of
+//!   children are its `relatedLocations`.
+//! - Each substitution of a suggestion is one of the `fixes` of the result.
+//! - The id of a message translated from the message catalogs is the `messageId` property of
+//!   the result.
+//! - Paths remapped with `--remap-path-prefix` are made relative to the `REMAP_<n>` base, the
+//!   `n`th remapping, and relative paths to the `%SRCROOT%` base, the working directory. The
+//!   `originalUriBaseIds` of the run resolve these bases to the prefixes the paths were remapped
+//!   to and to the (possibly remapped) working directory, so that the log doesn't reveal the
+//!   original prefixes. Consumers that have the sources can override the bases.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use rustc_span::source_map::{FilePathMapping, RemapPathScopes, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::{CodeSuggestion, Diagnostic, DiagnosticId, Level, SubDiagnostic};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{Json, ToJson};
+use rustc_span::{FileName, SourceFile, Span};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::lazy::SyncLazy;
+use std::path::{Component, Path};
+use std::sync::{Arc, Mutex, PoisonError};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str =
+    "https://schemastore.azurewebsites.net/schemas/json/sarif-2.1.0-rtm.5.json";
+
+/// The base of relative paths, which is the working directory of the compiler.
+const SRCROOT: &str = "%SRCROOT%";
+
+/// The log shared by the emitters created by `SarifEmitter::stderr`.
+static STDERR_LOG: SyncLazy<Arc<Mutex<Log>>> = SyncLazy::new(Default::default);
+
+/// Prints the log of the emitters created by `SarifEmitter::stderr` to stderr, if there were
+/// any, and starts a new one.
+///
+/// This must be called once all the `Handler`s using these emitters have been dropped.
+pub fn write_stderr_log() {
+    // Don't lose the log because of a panic while it was locked.
+    let log = std::mem::take(&mut *STDERR_LOG.lock().unwrap_or_else(PoisonError::into_inner));
+    if log.used {
+        if let Err(e) = log.write(&mut io::BufWriter::new(io::stderr())) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+pub struct SarifEmitter {
+    /// Where to write the log once the emitter is dropped, or `None` if the log is shared.
+    dst: Option<Box<dyn Write + Send>>,
+    sm: Lrc<SourceMap>,
+    ui_testing: bool,
+    log: Arc<Mutex<Log>>,
+}
+
+/// The rules and results of a run.
+#[derive(Default)]
+struct Log {
+    /// Whether an emitter has been created for the log, even if it emitted no results.
+    used: bool,
+    ui_testing: bool,
+    rules: Vec<Rule>,
+    rule_indices: FxHashMap<String, usize>,
+    results: Vec<Json>,
+    original_uri_base_ids: BTreeMap<String, Json>,
+}
+
+impl SarifEmitter {
+    /// Creates an emitter adding to the log shared by the whole process, which is written to
+    /// stderr by `write_stderr_log`.
+    pub fn stderr(source_map: Lrc<SourceMap>) -> SarifEmitter {
+        let log = STDERR_LOG.clone();
+        log.lock().unwrap().used = true;
+        SarifEmitter { dst: None, sm: source_map, ui_testing: false, log }
+    }
+
+    pub fn basic() -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(Lrc::new(SourceMap::new(file_path_mapping)))
+    }
+
+    /// Creates an emitter with a log of its own, which is written to `dst` once the emitter is
+    /// dropped.
+    pub fn new(dst: Box<dyn Write + Send>, source_map: Lrc<SourceMap>) -> SarifEmitter {
+        let log = Log { used: true, ..Log::default() };
+        SarifEmitter {
+            dst: Some(dst),
+            sm: source_map,
+            ui_testing: false,
+            log: Arc::new(Mutex::new(log)),
+        }
+    }
+
+    pub fn ui_testing(self, ui_testing: bool) -> Self {
+        Self { ui_testing, ..self }
+    }
+
+    fn result(&self, log: &mut Log, diag: &Diagnostic) -> Option<Json> {
+        let level = match diag.level {
+            Level::Bug | Level::Fatal | Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note | Level::Help => "note",
+            Level::Cancelled | Level::FailureNote => return None,
+        };
+
+        let mut message = diag.message();
+        for child in &diag.children {
+            message.push_str(&format!("\n{}: {}", child.level, child.message()));
+        }
+
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        for span_label in diag.span.span_labels() {
+            let is_labeled = span_label.label.is_some();
+            let location = self.location(span_label.span, span_label.label);
+            if span_label.is_primary {
+                locations.extend(location);
+            } else if is_labeled {
+                related_locations.extend(location);
+            }
+        }
+        for child in &diag.children {
+            related_locations.extend(self.child_locations(child));
+        }
+
+        let fixes: Vec<Json> = diag
+            .suggestions
+            .iter()
+            .flat_map(|sugg| self.fixes(sugg))
+            .map(|f| f.to_json())
+            .collect();
+
+        let mut result = BTreeMap::new();
+        if let Some(code) = &diag.code {
+            let rule_index = log.rule_index(code);
+            result.insert("ruleId".to_string(), log.rules[rule_index].id.to_json());
+            result.insert("ruleIndex".to_string(), rule_index.to_json());
+        }
+        result.insert("level".to_string(), level.to_json());
+        result.insert("message".to_string(), text(&message));
+        result.insert("locations".to_string(), locations.to_json());
+        if !related_locations.is_empty() {
+            result.insert("relatedLocations".to_string(), related_locations.to_json());
+        }
+        if !fixes.is_empty() {
+            result.insert("fixes".to_string(), Json::Array(fixes));
+        }
+        if let Some(message_id) = &diag.message_id {
+            let mut properties = BTreeMap::new();
+            properties.insert("messageId".to_string(), message_id.to_json());
+            result.insert("properties".to_string(), Json::Object(properties));
+        }
+        Some(Json::Object(result))
+    }
+
+    /// The primary spans of `child`, labeled with its message.
+    fn child_locations(&self, child: &SubDiagnostic) -> Vec<Location> {
+        let message = format!("{}: {}", child.level, child.message());
+        let span = child.render_span.as_ref().unwrap_or(&child.span);
+        span.primary_spans()
+            .iter()
+            .filter_map(|&span| self.location(span, Some(message.clone())))
+            .collect()
+    }
+
+    fn fixes(&self, suggestion: &CodeSuggestion) -> Vec<Fix> {
+        suggestion
+            .substitutions
+            .iter()
+            .filter_map(|substitution| {
+                let mut changes: Vec<ArtifactChange> = vec![];
+                for part in &substitution.parts {
+                    let (file, region) = self.region(part.span)?;
+                    let replacement = Replacement { region, text: part.snippet.clone() };
+                    match changes.iter_mut().find(|change| change.location == file) {
+                        Some(change) => change.replacements.push(replacement),
+                        None => changes.push(ArtifactChange {
+                            location: file,
+                            replacements: vec![replacement],
+                        }),
+                    }
+                }
+                Some(Fix { description: suggestion.msg.clone(), changes })
+            })
+            .collect()
+    }
+
+    fn location(&self, span: Span, message: Option<String>) -> Option<Location> {
+        let (file, region) = self.region(span)?;
+        Some(Location { file, region, message })
+    }
+
+    /// Returns the file and region of `span`, or `None` if `span` is not in a file on disk.
+    fn region(&self, span: Span) -> Option<(ArtifactLocation, Region)> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let file = self.artifact_location(&start.file)?;
+        let region = Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+        };
+        Some((file, region))
+    }
+
+    fn artifact_location(&self, file: &SourceFile) -> Option<ArtifactLocation> {
+        let path = match self.sm.filename_for_diagnostics(file) {
+            FileName::Real(name) => name.local_path(),
+            _ => return None,
+        };
+        let mapping = self.sm.path_mapping();
+        if file.name_was_remapped && mapping.scopes().contains(RemapPathScopes::DIAGNOSTICS) {
+            // Entries later on the command line take precedence, see `FilePathMapping::map_prefix`.
+            for (index, (_, to)) in mapping.entries().iter().enumerate().rev() {
+                if let Ok(rest) = path.strip_prefix(to) {
+                    return Some(ArtifactLocation {
+                        uri: relative_uri(rest),
+                        uri_base_id: Some(format!("REMAP_{}", index)),
+                    });
+                }
+            }
+        }
+        Some(artifact_location_for_path(path))
+    }
+
+    /// The bases of the relative URIs of the run, keyed by their id.
+    fn original_uri_base_ids(&self) -> BTreeMap<String, Json> {
+        let mut base_ids = BTreeMap::new();
+        let mapping = self.sm.path_mapping();
+        // The working directory is left out of UI tests so that their output is the same on
+        // every machine.
+        let working_dir = if self.ui_testing { None } else { std::env::current_dir().ok() };
+        if let Some(working_dir) = working_dir {
+            let working_dir =
+                mapping.map_prefix_for_scope(working_dir, RemapPathScopes::DIAGNOSTICS);
+            if working_dir.is_absolute() {
+                base_ids.insert(SRCROOT.to_string(), base_location(&working_dir).to_json());
+            }
+        }
+        if mapping.scopes().contains(RemapPathScopes::DIAGNOSTICS) {
+            for (index, (_, to)) in mapping.entries().iter().enumerate() {
+                base_ids.insert(format!("REMAP_{}", index), base_location(to).to_json());
+            }
+        }
+        base_ids
+    }
+}
+
+impl Log {
+    /// Returns the index of the rule `id` in `self.rules`, adding the rule if needed.
+    fn rule_index(&mut self, id: &DiagnosticId) -> usize {
+        let (name, help_uri) = match id {
+            DiagnosticId::Error(code) => {
+                (code, Some(format!("https://doc.rust-lang.org/error-index.html#{}", code)))
+            }
+            DiagnosticId::Lint(name) => (name, None),
+        };
+        if let Some(&index) = self.rule_indices.get(name) {
+            return index;
+        }
+        let index = self.rules.len();
+        self.rules.push(Rule { id: name.clone(), help_uri });
+        self.rule_indices.insert(name.clone(), index);
+        index
+    }
+
+    fn write(self, dst: &mut dyn Write) -> io::Result<()> {
+        let tool_name = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "rustc".to_string());
+        let mut driver = BTreeMap::new();
+        driver.insert("name".to_string(), tool_name.to_json());
+        if !self.ui_testing {
+            if let Some(version) = option_env!("CFG_VERSION") {
+                driver.insert("version".to_string(), version.to_json());
+            }
+        }
+        driver.insert("informationUri".to_string(), "https://www.rust-lang.org/".to_json());
+        driver.insert("rules".to_string(), self.rules.to_json());
+
+        let mut tool = BTreeMap::new();
+        tool.insert("driver".to_string(), Json::Object(driver));
+
+        let mut run = BTreeMap::new();
+        run.insert("tool".to_string(), Json::Object(tool));
+        run.insert("columnKind".to_string(), "unicodeCodePoints".to_json());
+        run.insert("originalUriBaseIds".to_string(), Json::Object(self.original_uri_base_ids));
+        run.insert("results".to_string(), Json::Array(self.results));
+
+        let mut log = BTreeMap::new();
+        log.insert("$schema".to_string(), SARIF_SCHEMA.to_json());
+        log.insert("version".to_string(), SARIF_VERSION.to_json());
+        log.insert("runs".to_string(), Json::Array(vec![Json::Object(run)]));
+
+        writeln!(dst, "{}", Json::Object(log).pretty())?;
+        dst.flush()
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        let mut log = self.log.lock().unwrap();
+        if let Some(result) = self.result(&mut log, diag) {
+            log.results.push(result);
+        }
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let base_ids = self.original_uri_base_ids();
+        let mut log = self.log.lock().unwrap_or_else(PoisonError::into_inner);
+        log.ui_testing |= self.ui_testing;
+        log.original_uri_base_ids.extend(base_ids);
+        if let Some(dst) = &mut self.dst {
+            let log = std::mem::take(&mut *log);
+            if let Err(e) = log.write(dst) {
+                panic!("failed to print diagnostics: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Wraps `text` in a `message` object.
+fn text(text: &str) -> Json {
+    let mut message = BTreeMap::new();
+    message.insert("text".to_string(), text.to_json());
+    Json::Object(message)
+}
+
+/// Returns the location of `path`, which is relative to `%SRCROOT%` if `path` is relative.
+fn artifact_location_for_path(path: &Path) -> ArtifactLocation {
+    if path.is_absolute() {
+        ArtifactLocation { uri: file_uri(path), uri_base_id: None }
+    } else {
+        ArtifactLocation { uri: relative_uri(path), uri_base_id: Some(SRCROOT.to_string()) }
+    }
+}
+
+/// Returns the location of the directory `path`, for use as the base of other URIs.
+fn base_location(path: &Path) -> ArtifactLocation {
+    let mut location = artifact_location_for_path(path);
+    if !location.uri.is_empty() && !location.uri.ends_with('/') {
+        location.uri.push('/');
+    }
+    location
+}
+
+/// Returns the `file:` URI of the absolute `path`.
+fn file_uri(path: &Path) -> String {
+    let uri = relative_uri(path);
+    // Windows paths start with a drive letter rather than with a slash.
+    if uri.starts_with('/') { format!("file://{}", uri) } else { format!("file:///{}", uri) }
+}
+
+/// Returns `path` as a relative URI reference, with its components percent-encoded.
+fn relative_uri(path: &Path) -> String {
+    let mut uri = String::new();
+    for component in path.components() {
+        let segment = match component {
+            Component::RootDir => {
+                if !uri.ends_with('/') {
+                    uri.push('/');
+                }
+                continue;
+            }
+            Component::Prefix(prefix) => prefix.as_os_str().to_string_lossy().into_owned(),
+            Component::CurDir => ".".to_string(),
+            Component::ParentDir => "..".to_string(),
+            Component::Normal(name) => name.to_string_lossy().into_owned(),
+        };
+        if !uri.is_empty() && !uri.ends_with('/') {
+            uri.push('/');
+        }
+        percent_encode(&mut uri, &segment);
+    }
+    uri
+}
+
+/// Appends `s` to `uri`, percent-encoding the bytes that can't appear in a path segment.
+fn percent_encode(uri: &mut String, s: &str) {
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => uri.push(byte as char),
+            b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+'
+            | b',' | b';' | b'=' | b':' | b'@' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+struct Rule {
+    id: String,
+    help_uri: Option<String>,
+}
+
+#[derive(PartialEq)]
+struct ArtifactLocation {
+    uri: String,
+    uri_base_id: Option<String>,
+}
+
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    end_line: usize,
+    /// 1-based, character offset. The end is exclusive.
+    start_column: usize,
+    end_column: usize,
+}
+
+struct Location {
+    file: ArtifactLocation,
+    region: Region,
+    message: Option<String>,
+}
+
+struct Replacement {
+    region: Region,
+    text: String,
+}
+
+struct ArtifactChange {
+    location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+struct Fix {
+    description: String,
+    changes: Vec<ArtifactChange>,
+}
+
+impl ToJson for Rule {
+    fn to_json(&self) -> Json {
+        let mut rule = BTreeMap::new();
+        rule.insert("id".to_string(), self.id.to_json());
+        if let Some(help_uri) = &self.help_uri {
+            rule.insert("helpUri".to_string(), help_uri.to_json());
+        }
+        Json::Object(rule)
+    }
+}
+
+impl ToJson for ArtifactLocation {
+    fn to_json(&self) -> Json {
+        let mut location = BTreeMap::new();
+        location.insert("uri".to_string(), self.uri.to_json());
+        if let Some(uri_base_id) = &self.uri_base_id {
+            location.insert("uriBaseId".to_string(), uri_base_id.to_json());
+        }
+        Json::Object(location)
+    }
+}
+
+impl ToJson for Region {
+    fn to_json(&self) -> Json {
+        let mut region = BTreeMap::new();
+        region.insert("startLine".to_string(), self.start_line.to_json());
+        region.insert("startColumn".to_string(), self.start_column.to_json());
+        region.insert("endLine".to_string(), self.end_line.to_json());
+        region.insert("endColumn".to_string(), self.end_column.to_json());
+        Json::Object(region)
+    }
+}
+
+impl ToJson for Location {
+    fn to_json(&self) -> Json {
+        let mut physical_location = BTreeMap::new();
+        physical_location.insert("artifactLocation".to_string(), self.file.to_json());
+        physical_location.insert("region".to_string(), self.region.to_json());
+
+        let mut location = BTreeMap::new();
+        location.insert("physicalLocation".to_string(), Json::Object(physical_location));
+        if let Some(message) = &self.message {
+            location.insert("message".to_string(), text(message));
+        }
+        Json::Object(location)
+    }
+}
+
+impl ToJson for Replacement {
+    fn to_json(&self) -> Json {
+        let mut inserted_content = BTreeMap::new();
+        inserted_content.insert("text".to_string(), self.text.to_json());
+
+        let mut replacement = BTreeMap::new();
+        replacement.insert("deletedRegion".to_string(), self.region.to_json());
+        replacement.insert("insertedContent".to_string(), Json::Object(inserted_content));
+        Json::Object(replacement)
+    }
+}
+
+impl ToJson for ArtifactChange {
+    fn to_json(&self) -> Json {
+        let mut change = BTreeMap::new();
+        change.insert("artifactLocation".to_string(), self.location.to_json());
+        change.insert("replacements".to_string(), self.replacements.to_json());
+        Json::Object(change)
+    }
+}
+
+impl ToJson for Fix {
+    fn to_json(&self) -> Json {
+        let mut fix = BTreeMap::new();
+        fix.insert("description".to_string(), text(&self.description));
+        fix.insert("artifactChanges".to_string(), self.changes.to_json());
+        Json::Object(fix)
+    }
+}
@@ -0,0 +1,165 @@
+use super::*;
+
+use crate::{Applicability, Handler};
+use rustc_serialize::json::from_str;
+use rustc_span::source_map::{FilePathMapping, RemapPathScopes, SourceMap};
+use rustc_span::{BytePos, Span};
+
+use std::path::PathBuf;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+/// Emits diagnostics with `emit` on a handler with a `SarifEmitter` and returns the log that
+/// the emitter writes once the handler is dropped.
+fn test_log(
+    path_mapping: FilePathMapping,
+    file_name: &str,
+    code: &str,
+    emit: impl FnOnce(&Handler),
+) -> Json {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    with_default_session_globals(|| {
+        let sm = Lrc::new(SourceMap::new(path_mapping));
+        sm.new_source_file(PathBuf::from(file_name).into(), code.to_owned());
+
+        let se = SarifEmitter::new(Box::new(Shared { data: output.clone() }), sm).ui_testing(true);
+        let handler = Handler::with_emitter(true, None, Box::new(se));
+        emit(&handler);
+    });
+
+    let bytes = output.lock().unwrap();
+    from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn results() {
+    let log = test_log(FilePathMapping::empty(), "test.rs", "let x = y;\nlet z = x;", |handler| {
+        handler
+            .struct_span_err_with_code(
+                span(19, 20),
+                "use of moved value",
+                DiagnosticId::Error("E0382".to_string()),
+            )
+            .span_label(span(19, 20), "value used here after move")
+            .span_label(span(8, 9), "value moved here")
+            .span_suggestion(
+                span(8, 9),
+                "consider cloning",
+                "y.clone()".to_string(),
+                Applicability::MachineApplicable,
+            )
+            .emit();
+        handler.struct_span_warn(span(4, 5), "unused variable").emit();
+    });
+
+    let run = &log["runs"][0];
+    assert_eq!(log["version"].as_string(), Some("2.1.0"));
+    assert_eq!(run.find_path(&["tool", "driver", "rules"]).unwrap().as_array().unwrap().len(), 1);
+    assert_eq!(
+        run.find_path(&["tool", "driver", "rules"]).unwrap()[0]["helpUri"].as_string(),
+        Some("https://doc.rust-lang.org/error-index.html#E0382")
+    );
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+
+    let error = &results[0];
+    assert_eq!(error["ruleId"].as_string(), Some("E0382"));
+    assert_eq!(error["ruleIndex"].as_u64(), Some(0));
+    assert_eq!(error["level"].as_string(), Some("error"));
+
+    let location = &error["locations"][0];
+    assert_eq!(location["message"]["text"].as_string(), Some("value used here after move"));
+    let physical_location = &location["physicalLocation"];
+    assert_eq!(physical_location["artifactLocation"]["uri"].as_string(), Some("test.rs"));
+    assert_eq!(physical_location["artifactLocation"]["uriBaseId"].as_string(), Some("%SRCROOT%"));
+    assert_eq!(physical_location["region"]["startLine"].as_u64(), Some(2));
+    assert_eq!(physical_location["region"]["startColumn"].as_u64(), Some(9));
+    assert_eq!(physical_location["region"]["endColumn"].as_u64(), Some(10));
+
+    let related_location = &error["relatedLocations"][0];
+    assert_eq!(related_location["message"]["text"].as_string(), Some("value moved here"));
+
+    let fix = &error["fixes"][0];
+    assert_eq!(fix["description"]["text"].as_string(), Some("consider cloning"));
+    let replacement = &fix["artifactChanges"][0]["replacements"][0];
+    assert_eq!(replacement["insertedContent"]["text"].as_string(), Some("y.clone()"));
+    assert_eq!(replacement["deletedRegion"]["startColumn"].as_u64(), Some(9));
+
+    let warning = &results[1];
+    assert_eq!(warning["level"].as_string(), Some("warning"));
+    assert!(warning.find("ruleId").is_none());
+}
+
+#[test]
+fn remapped_paths() {
+    let mapping = vec![(PathBuf::from("/home/user/project"), PathBuf::from("/remapped"))];
+    let path_mapping = FilePathMapping::new(mapping, RemapPathScopes::ALL);
+    let log = test_log(path_mapping, "/home/user/project/src/my lib.rs", "fn f() {}", |handler| {
+        handler.span_err(span(3, 4), "foo");
+    });
+
+    let run = &log["runs"][0];
+    assert_eq!(
+        run.find_path(&["originalUriBaseIds", "REMAP_0", "uri"]).unwrap().as_string(),
+        Some("file:///remapped/")
+    );
+    let artifact_location =
+        &run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"];
+    assert_eq!(artifact_location["uri"].as_string(), Some("src/my%20lib.rs"));
+    assert_eq!(artifact_location["uriBaseId"].as_string(), Some("REMAP_0"));
+}
+
+#[test]
+fn unmapped_paths() {
+    let mapping = vec![(PathBuf::from("/home/user/project"), PathBuf::from("/remapped"))];
+    let path_mapping = FilePathMapping::new(mapping, RemapPathScopes::DEBUGINFO);
+    let log = test_log(path_mapping, "/home/user/project/lib.rs", "fn f() {}", |handler| {
+        handler.span_err(span(3, 4), "foo");
+    });
+
+    let run = &log["runs"][0];
+    assert!(run.find_path(&["originalUriBaseIds", "REMAP_0"]).is_none());
+    let artifact_location =
+        &run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"];
+    assert_eq!(artifact_location["uri"].as_string(), Some("file:///home/user/project/lib.rs"));
+    assert!(artifact_location.find("uriBaseId").is_none());
+}
+
+#[test]
+fn message_ids() {
+    let log = test_log(FilePathMapping::empty(), "test.rs", "fn f() {}", |handler| {
+        handler.struct_span_err(span(3, 4), "translated").set_message_id("test-message").emit();
+        handler.span_err(span(3, 4), "not translated");
+    });
+
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(
+        results[0].find_path(&["properties", "messageId"]).unwrap().as_string(),
+        Some("test-message")
+    );
+    assert!(results[1].find("properties").is_none());
+}
@@ -0,0 +1,386 @@
+//! Message catalogs for translatable diagnostics.
+//!
+//! Translatable diagnostics refer to their messages by an identifier, the slug, rather than by
+//! their English text. The messages themselves live in catalogs, `.ftl` files written in a subset
+//! of the [Fluent] syntax:
+//!
+//! ```text
+//! # Comments start with a `#`.
+//! typeck-field-multiply-specified-in-initializer =
+//!     field `{$ident}` specified more than once
+//!     .label = used more than once
+//!     .previous-use-label = first use of `{$ident}`
+//! ```
+//!
+//! Each message has a value and any number of attributes, which are used for the labels, notes
+//! and suggestions of the diagnostic. Both can span several lines, and refer to the arguments of
+//! the diagnostic with `{$name}` placeables. A literal brace is written `{"{"}`. Terms,
+//! selectors and functions are not supported.
+//!
+//! The English catalogs are built into the compiler and are the fallback for every message that
+//! the catalogs of the language selected with `-Z translate-lang` lack.
+//!
+//! [Fluent]: https://projectfluent.org/fluent/guide/
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::lazy::SyncLazy;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// The English catalogs, as `(file name, source)` pairs.
+pub const DEFAULT_LOCALE_RESOURCES: &[(&str, &str)] =
+    &[("typeck.ftl", include_str!("../locales/en-US/typeck.ftl"))];
+
+/// The language of `DEFAULT_LOCALE_RESOURCES`.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// The arguments of a translatable diagnostic, keyed by the names the catalogs refer to them by.
+pub type FluentArgs<'a> = FxHashMap<&'a str, String>;
+
+/// The messages of one language.
+#[derive(Debug)]
+pub struct FluentBundle {
+    locale: String,
+    messages: FxHashMap<String, Message>,
+}
+
+#[derive(Debug, Default)]
+struct Message {
+    value: Option<Pattern>,
+    attributes: FxHashMap<String, Pattern>,
+}
+
+type Pattern = Vec<PatternElement>;
+
+#[derive(Debug, PartialEq)]
+enum PatternElement {
+    Text(String),
+    Variable(String),
+}
+
+#[derive(Debug)]
+pub enum TranslationBundleError {
+    /// A catalog couldn't be read.
+    ReadFtl(PathBuf, io::Error),
+    /// The directory of the catalogs of a language doesn't exist.
+    MissingLocale(PathBuf),
+    /// A catalog isn't valid, at the given 1-based line.
+    ParseFtl { file: String, line: usize, msg: String },
+}
+
+impl fmt::Display for TranslationBundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationBundleError::ReadFtl(path, e) => {
+                write!(f, "could not read `{}`: {}", path.display(), e)
+            }
+            TranslationBundleError::MissingLocale(path) => {
+                write!(f, "no catalogs for this language in `{}`", path.display())
+            }
+            TranslationBundleError::ParseFtl { file, line, msg } => {
+                write!(f, "invalid catalog `{}`, line {}: {}", file, line, msg)
+            }
+        }
+    }
+}
+
+impl FluentBundle {
+    pub fn new(locale: impl Into<String>) -> FluentBundle {
+        FluentBundle { locale: locale.into(), messages: Default::default() }
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Adds the messages of the catalog `source`, named `name` in errors. A message that is
+    /// already in the bundle is an error, unless `overriding` is set, in which case the message
+    /// of `source` replaces it.
+    pub fn add_resource(
+        &mut self,
+        name: &str,
+        source: &str,
+        overriding: bool,
+    ) -> Result<(), TranslationBundleError> {
+        for (line, id, message) in parse_resource(name, source)? {
+            if !overriding && self.messages.contains_key(&id) {
+                return Err(TranslationBundleError::ParseFtl {
+                    file: name.to_string(),
+                    line,
+                    msg: format!("message `{}` is already defined", id),
+                });
+            }
+            self.messages.insert(id, message);
+        }
+        Ok(())
+    }
+
+    pub fn has_message(&self, id: &str) -> bool {
+        self.messages.contains_key(id)
+    }
+
+    /// Formats the value of the message `id`, or its attribute `attr`, with `args`. Placeables
+    /// referring to missing arguments are kept as they are written in the catalog. Returns
+    /// `None` if the bundle doesn't have the message or attribute.
+    pub fn format(&self, id: &str, attr: Option<&str>, args: &FluentArgs<'_>) -> Option<String> {
+        let message = self.messages.get(id)?;
+        let pattern = match attr {
+            Some(attr) => message.attributes.get(attr)?,
+            None => message.value.as_ref()?,
+        };
+        let mut formatted = String::new();
+        for element in pattern {
+            match element {
+                PatternElement::Text(text) => formatted.push_str(text),
+                PatternElement::Variable(name) => match args.get(name.as_str()) {
+                    Some(arg) => formatted.push_str(arg),
+                    None => formatted.push_str(&format!("{{${}}}", name)),
+                },
+            }
+        }
+        Some(formatted)
+    }
+}
+
+/// Returns the bundle of the English catalogs.
+pub fn fallback_fluent_bundle() -> &'static FluentBundle {
+    static FALLBACK_BUNDLE: SyncLazy<FluentBundle> = SyncLazy::new(|| {
+        let mut bundle = FluentBundle::new(DEFAULT_LOCALE);
+        for (name, source) in DEFAULT_LOCALE_RESOURCES {
+            if let Err(e) = bundle.add_resource(name, source, false) {
+                panic!("{}", e);
+            }
+        }
+        bundle
+    });
+    &FALLBACK_BUNDLE
+}
+
+/// Returns the bundle of the catalogs of `lang`, which are the `.ftl` files in the
+/// `share/locale/<lang>` directory of `sysroot`, and of `additional_ftl`, whose messages take
+/// precedence. Returns `None` if neither is given.
+pub fn fluent_bundle(
+    sysroot: &Path,
+    lang: Option<&str>,
+    additional_ftl: Option<&Path>,
+) -> Result<Option<Lrc<FluentBundle>>, TranslationBundleError> {
+    if lang.is_none() && additional_ftl.is_none() {
+        return Ok(None);
+    }
+
+    let mut bundle = FluentBundle::new(lang.unwrap_or(DEFAULT_LOCALE));
+    if let Some(lang) = lang {
+        let dir = sysroot.join("share").join("locale").join(lang);
+        if !dir.is_dir() {
+            return Err(TranslationBundleError::MissingLocale(dir));
+        }
+        let entries = fs::read_dir(&dir).map_err(|e| TranslationBundleError::ReadFtl(dir, e))?;
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "ftl"))
+            .collect();
+        // Read the catalogs in a deterministic order so that errors are reproducible.
+        paths.sort();
+        for path in paths {
+            add_ftl_file(&mut bundle, &path, false)?;
+        }
+    }
+    if let Some(path) = additional_ftl {
+        add_ftl_file(&mut bundle, path, true)?;
+    }
+    Ok(Some(Lrc::new(bundle)))
+}
+
+fn add_ftl_file(
+    bundle: &mut FluentBundle,
+    path: &Path,
+    overriding: bool,
+) -> Result<(), TranslationBundleError> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| TranslationBundleError::ReadFtl(path.to_path_buf(), e))?;
+    bundle.add_resource(&path.display().to_string(), &source, overriding)
+}
+
+/// The value or attribute of the last message, whose pattern may continue on the following
+/// indented lines.
+struct PendingPattern<'a> {
+    attr: Option<String>,
+    lines: Vec<&'a str>,
+}
+
+/// Parses the messages of the catalog `source`, along with the 1-based line they start at.
+fn parse_resource(
+    name: &str,
+    source: &str,
+) -> Result<Vec<(usize, String, Message)>, TranslationBundleError> {
+    let err = |index: usize, msg: String| TranslationBundleError::ParseFtl {
+        file: name.to_string(),
+        line: index + 1,
+        msg,
+    };
+
+    let mut messages: Vec<(usize, String, Message)> = vec![];
+    let mut pending: Option<PendingPattern<'_>> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indented = line.starts_with(|c: char| c == ' ' || c == '\t');
+        if !indented && trimmed.starts_with('#') {
+            continue;
+        }
+
+        if !indented {
+            if let Some((start, _, message)) = messages.last_mut() {
+                finish_pattern(message, pending.take()).map_err(|msg| err(*start, msg))?;
+            }
+            let (id, value) = split_entry(trimmed)
+                .ok_or_else(|| err(index, "expected `message-id = value`".to_string()))?;
+            if id.starts_with('-') {
+                return Err(err(index, format!("term `{}` is not supported", id)));
+            }
+            check_identifier(id).map_err(|msg| err(index, msg))?;
+            if messages.iter().any(|(_, other, _)| other == id) {
+                return Err(err(index, format!("message `{}` is already defined", id)));
+            }
+            messages.push((index, id.to_string(), Message::default()));
+            pending = Some(PendingPattern { attr: None, lines: vec![value] });
+        } else if let Some(attribute) = trimmed.strip_prefix('.') {
+            let (start, _, message) = messages
+                .last_mut()
+                .ok_or_else(|| err(index, "attribute outside of a message".to_string()))?;
+            finish_pattern(message, pending.take()).map_err(|msg| err(*start, msg))?;
+            let (attr, value) = split_entry(attribute)
+                .ok_or_else(|| err(index, "expected `.attribute = value`".to_string()))?;
+            check_identifier(attr).map_err(|msg| err(index, msg))?;
+            if message.attributes.contains_key(attr) {
+                return Err(err(index, format!("attribute `{}` is already defined", attr)));
+            }
+            pending = Some(PendingPattern { attr: Some(attr.to_string()), lines: vec![value] });
+        } else {
+            match &mut pending {
+                Some(pending) => pending.lines.push(trimmed),
+                None => return Err(err(index, "indented line outside of a message".to_string())),
+            }
+        }
+    }
+    if let Some((start, _, message)) = messages.last_mut() {
+        finish_pattern(message, pending.take()).map_err(|msg| err(*start, msg))?;
+    }
+
+    for (start, id, message) in &mut messages {
+        if message.value.is_none() && message.attributes.is_empty() {
+            return Err(err(
+                *start,
+                format!("message `{}` has neither a value nor attributes", id),
+            ));
+        }
+        *start += 1;
+    }
+    Ok(messages)
+}
+
+/// Parses the lines of `pending`, if any, and stores the pattern in `message`. The lines of a
+/// multiline pattern are joined with newlines.
+fn finish_pattern(
+    message: &mut Message,
+    pending: Option<PendingPattern<'_>>,
+) -> Result<(), String> {
+    let PendingPattern { attr, lines } = match pending {
+        Some(pending) => pending,
+        None => return Ok(()),
+    };
+    let lines: Vec<&str> = lines.into_iter().filter(|line| !line.is_empty()).collect();
+    let pattern = parse_pattern(&lines.join("\n"))?;
+    match attr {
+        Some(attr) if pattern.is_empty() => Err(format!("attribute `{}` has no value", attr)),
+        Some(attr) => {
+            message.attributes.insert(attr, pattern);
+            Ok(())
+        }
+        None => {
+            if !pattern.is_empty() {
+                message.value = Some(pattern);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Splits `id = value` into its trimmed identifier and value.
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let eq = line.find('=')?;
+    Some((line[..eq].trim(), line[eq + 1..].trim()))
+}
+
+fn check_identifier(id: &str) -> Result<(), String> {
+    let mut chars = id.chars();
+    let valid = chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid { Ok(()) } else { Err(format!("invalid identifier `{}`", id)) }
+}
+
+/// Parses the text and placeables of a pattern.
+fn parse_pattern(source: &str) -> Result<Pattern, String> {
+    let mut pattern = vec![];
+    let mut text = String::new();
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let mut placeable = String::new();
+                let mut in_string = false;
+                loop {
+                    let c = chars.next().ok_or_else(|| "unterminated placeable".to_string())?;
+                    match c {
+                        '}' if !in_string => break,
+                        '"' => in_string = !in_string,
+                        '\\' if in_string => {
+                            placeable.push(c);
+                            match chars.next() {
+                                Some(escaped @ '"') | Some(escaped @ '\\') => {
+                                    placeable.push(escaped)
+                                }
+                                _ => return Err("invalid escape in string literal".to_string()),
+                            }
+                            continue;
+                        }
+                        _ => {}
+                    }
+                    placeable.push(c);
+                }
+                let placeable = placeable.trim();
+                if let Some(name) = placeable.strip_prefix('$') {
+                    check_identifier(name)?;
+                    if !text.is_empty() {
+                        pattern.push(PatternElement::Text(std::mem::take(&mut text)));
+                    }
+                    pattern.push(PatternElement::Variable(name.to_string()));
+                } else if placeable.len() >= 2
+                    && placeable.starts_with('"')
+                    && placeable.ends_with('"')
+                {
+                    let literal = &placeable[1..placeable.len() - 1];
+                    text.push_str(&literal.replace("\\\"", "\"").replace("\\\\", "\\"));
+                } else {
+                    return Err(format!("unsupported placeable `{{{}}}`", placeable));
+                }
+            }
+            '}' => return Err("unmatched `}`, write a literal brace as `{\"}\"}`".to_string()),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        pattern.push(PatternElement::Text(text));
+    }
+    Ok(pattern)
+}
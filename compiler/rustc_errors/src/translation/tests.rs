@@ -0,0 +1,86 @@
+use super::*;
+
+fn bundle(source: &str) -> FluentBundle {
+    let mut bundle = FluentBundle::new("en-US");
+    bundle.add_resource("test.ftl", source, false).unwrap();
+    bundle
+}
+
+fn parse_error(source: &str) -> (usize, String) {
+    match FluentBundle::new("en-US").add_resource("test.ftl", source, false) {
+        Err(TranslationBundleError::ParseFtl { line, msg, .. }) => (line, msg),
+        result => panic!("expected a parse error, got {:?}", result),
+    }
+}
+
+#[test]
+fn values_and_attributes() {
+    let bundle = bundle(
+        "# A comment.
+simple = a simple message
+
+multiline =
+    first line
+    second line
+    .label = a label
+    .other-label = another
+        label
+",
+    );
+    let args = FluentArgs::default();
+    assert_eq!(bundle.format("simple", None, &args).as_deref(), Some("a simple message"));
+    assert_eq!(bundle.format("multiline", None, &args).as_deref(), Some("first line\nsecond line"));
+    assert_eq!(bundle.format("multiline", Some("label"), &args).as_deref(), Some("a label"));
+    assert_eq!(
+        bundle.format("multiline", Some("other-label"), &args).as_deref(),
+        Some("another\nlabel")
+    );
+    assert_eq!(bundle.format("simple", Some("label"), &args), None);
+    assert_eq!(bundle.format("missing", None, &args), None);
+}
+
+#[test]
+fn placeables() {
+    let bundle = bundle(
+        r#"with-args = field `{$ident}` of `{ $ty }`, {$missing}
+literals = {"{"}braces{"}"} and {"\"quotes\""}
+"#,
+    );
+    let mut args = FluentArgs::default();
+    args.insert("ident", "x".to_string());
+    args.insert("ty", "Foo".to_string());
+    assert_eq!(
+        bundle.format("with-args", None, &args).as_deref(),
+        Some("field `x` of `Foo`, {$missing}")
+    );
+    assert_eq!(bundle.format("literals", None, &args).as_deref(), Some("{braces} and \"quotes\""));
+}
+
+#[test]
+fn overriding() {
+    let mut bundle = bundle("message = original\n");
+    assert!(bundle.add_resource("other.ftl", "message = duplicate\n", false).is_err());
+    bundle.add_resource("other.ftl", "message = replaced\n", true).unwrap();
+    let args = FluentArgs::default();
+    assert_eq!(bundle.format("message", None, &args).as_deref(), Some("replaced"));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(parse_error("a = b\n\nno value\n"), (3, "expected `message-id = value`".into()));
+    assert_eq!(parse_error("a = b\na = c\n"), (2, "message `a` is already defined".into()));
+    assert_eq!(parse_error("-term = b\n"), (1, "term `-term` is not supported".into()));
+    assert_eq!(parse_error("1a = b\n"), (1, "invalid identifier `1a`".into()));
+    assert_eq!(parse_error("a =\n"), (1, "message `a` has neither a value nor attributes".into()));
+    assert_eq!(parse_error("a = b\n    .c =\n"), (1, "attribute `c` has no value".into()));
+    assert_eq!(parse_error("a = {$b\n"), (1, "unterminated placeable".into()));
+    assert_eq!(parse_error("a = {b}\n"), (1, "unsupported placeable `{b}`".into()));
+    assert_eq!(parse_error("    indented\n"), (1, "indented line outside of a message".into()));
+}
+
+/// Checks that the catalogs built into the compiler are valid.
+#[test]
+fn fallback_bundle() {
+    assert_eq!(fallback_fluent_bundle().locale(), DEFAULT_LOCALE);
+    assert!(fallback_fluent_bundle().has_message("typeck-field-multiply-specified-in-initializer"));
+}
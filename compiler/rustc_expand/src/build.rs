@@ -176,7 +176,7 @@ impl<'a> ExtCtxt<'a> {
         let local = P(ast::Local {
             pat,
             ty: None,
-            init: Some(ex),
+            kind: ast::LocalKind::Init(ex),
             id: ast::DUMMY_NODE_ID,
             span: sp,
             attrs: AttrVec::new(),
@@ -194,7 +194,7 @@ impl<'a> ExtCtxt<'a> {
         let local = P(ast::Local {
             pat: self.pat_wild(span),
             ty: Some(ty),
-            init: None,
+            kind: ast::LocalKind::Decl,
             id: ast::DUMMY_NODE_ID,
             span,
             attrs: AttrVec::new(),
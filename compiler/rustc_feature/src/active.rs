@@ -595,6 +595,18 @@ declare_features! (
     /// Allows to use the `#[cmse_nonsecure_entry]` attribute.
     (active, cmse_nonsecure_entry, "1.48.0", Some(75835), None),
 
+    /// Allows `let PAT = EXPR else { DIVERGING_BLOCK };` statements.
+    (active, let_else, "1.49.0", None, None),
+
+    /// Allows closures to capture disjoint fields of a variable, rather than the whole variable.
+    (active, capture_disjoint_fields, "1.49.0", None, None),
+
+    /// Allows coercing trait objects to trait objects of their supertraits.
+    (active, trait_upcasting, "1.49.0", None, None),
+
+    /// Allows `const { ... }` blocks in expressions and patterns.
+    (active, inline_const, "1.49.0", None, None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
@@ -615,6 +627,9 @@ pub const INCOMPLETE_FEATURES: &[Symbol] = &[
     sym::const_trait_bound_opt_out,
     sym::lazy_normalization_consts,
     sym::specialization,
+    sym::capture_disjoint_fields,
+    sym::trait_upcasting,
+    sym::inline_const,
 ];
 
 /// Some features are not allowed to be used together at the same time, if
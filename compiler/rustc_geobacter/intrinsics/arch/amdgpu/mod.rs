@@ -4,6 +4,7 @@ use crate::intrinsics::suicide::Suicide;
 
 pub mod dpp;
 pub mod grid;
+pub mod wavefront;
 
 pub type AmdGpuSuicide = Suicide<SuicideDetail>;
 
@@ -23,6 +24,7 @@ def_id_intrinsic! {
 }
 def_id_intrinsic!(fn amdgcn_sendmsg(arg0: i32, arg1: u32) => "llvm.amdgcn.s.sendmsg");
 def_id_intrinsic!(fn amdgcn_readfirstlane(arg1: u32) -> u32 => "llvm.amdgcn.readfirstlane");
+def_id_intrinsic!(fn amdgcn_readlane(arg1: u32, lane: u32) -> u32 => "llvm.amdgcn.readlane");
 
 /// This one is an actual Rust intrinsic; the LLVM intrinsic returns
 /// a pointer in the constant address space, which we can't correctly
@@ -44,6 +46,8 @@ pub fn insert_all_intrinsics<F>(mut map: F)
     WaveBarrier::insert_into_map(&mut map);
     SendMsg::insert_into_map(&mut map);
     ReadFirstLane::insert_into_map(&mut map);
+    ReadLane::insert_into_map(&mut map);
+    wavefront::WavefrontSize::insert_into_map(&mut map);
     dpp::UpdateDpp::insert_into_map(&mut map);
     dpp::UpdateDppWorkaround::insert_into_map(&mut map);
     grid::insert_all_intrinsics(&mut map);
@@ -64,6 +68,8 @@ pub fn find_intrinsic(tcx: TyCtxt<'_>, name: &str)
     WaveBarrier::check(name)?;
     SendMsg::check(name)?;
     ReadFirstLane::check(name)?;
+    ReadLane::check(name)?;
+    wavefront::WavefrontSize::check(name)?;
     dpp::UpdateDpp::check(name)?;
     dpp::UpdateDppWorkaround::check(name)?;
     grid::find_intrinsic(tcx, name)?;
@@ -321,3 +327,51 @@ impl fmt::Display for ReadFirstLane {
         write!(f, "{}", Self::NAME)
     }
 }
+/// Same as `ReadFirstLane`; otherwise we'll get:
+/// "LLVM ERROR: Cannot select: intrinsic %llvm.amdgcn.readlane".
+#[derive(Default)]
+pub struct ReadLane;
+impl ReadLane {
+    fn kernel_instance(&self) -> KernelInstanceRef<'static> {
+        amdgcn_readlane.kernel_instance()
+    }
+}
+impl CustomIntrinsicMirGen for ReadLane {
+    fn mirgen_simple_intrinsic<'tcx>(&self,
+                                     tcx: TyCtxt<'tcx>,
+                                     _instance: Instance<'tcx>,
+                                     mir: &mut mir::Body<'tcx>)
+    {
+        debug!("mirgen intrinsic {}", self);
+        let args = mir.args_iter()
+            .map(mir::Place::from)
+            .map(Operand::Move)
+            .collect();
+        tcx.call_device_inst_args(mir, move || {
+            target_check(tcx)?;
+            Some((self.kernel_instance(), args))
+        });
+    }
+
+    fn generic_parameter_count(&self, _tcx: TyCtxt<'_>) -> usize {
+        0
+    }
+    /// The types of the input args.
+    fn inputs<'tcx>(&self, tcx: TyCtxt<'tcx>)
+                    -> &'tcx ty::List<Ty<'tcx>>
+    {
+        tcx.intern_type_list(&[tcx.types.u32, tcx.types.u32])
+    }
+    /// The return type.
+    fn output<'tcx>(&self, tcx: TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.types.u32
+    }
+}
+impl IntrinsicName for ReadLane {
+    const NAME: &'static str = "geobacter_amdgpu_readlane";
+}
+impl fmt::Display for ReadLane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::NAME)
+    }
+}
@@ -0,0 +1,100 @@
+use super::*;
+
+/// Processors which support wave32 (GFX10 and later) also default to it.
+fn cpu_defaults_to_wave32(cpu: &str) -> bool {
+    // Numeric processor names are `gfx`, the major version, then one digit each for the
+    // minor version and the stepping, ie `gfx90a` or `gfx1030`. Processors with other
+    // names (`fiji`, etc) are all GFX8.
+    let ver = match cpu.strip_prefix("gfx") {
+        Some(ver) => ver,
+        None => { return false; },
+    };
+    let major = ver.get(..ver.len().saturating_sub(2))
+        .and_then(|major| major.parse::<u32>().ok());
+    match major {
+        Some(major) => major >= 10,
+        None => false,
+    }
+}
+
+/// The wavefront size of the code being compiled. Explicit `wavefrontsize{32,64}`
+/// target features win over the processor default; the last one mentioned wins.
+/// This is 1 for anything which isn't AMDGPU.
+pub fn wavefront_size(tcx: TyCtxt<'_>) -> u32 {
+    if target_check(tcx).is_none() {
+        return 1;
+    }
+
+    let opts = &tcx.sess.target.target.options;
+    let cpu = tcx.sess.opts.cg.target_cpu.as_deref()
+        .unwrap_or(&opts.cpu);
+    let mut size = if cpu_defaults_to_wave32(cpu) { 32 } else { 64 };
+
+    let features = opts.features.split(',')
+        .chain(tcx.sess.opts.cg.target_feature.split(','));
+    for feature in features {
+        size = match feature.trim() {
+            "+wavefrontsize32" | "-wavefrontsize64" => 32,
+            "+wavefrontsize64" | "-wavefrontsize32" => 64,
+            _ => { continue; },
+        };
+    }
+
+    size
+}
+
+/// fn geobacter_amdgpu_wavefront_size() -> u32;
+#[derive(Default)]
+pub struct WavefrontSize;
+impl CustomIntrinsicMirGen for WavefrontSize {
+    fn mirgen_simple_intrinsic<'tcx>(&self,
+                                     tcx: TyCtxt<'tcx>,
+                                     _instance: Instance<'tcx>,
+                                     mir: &mut mir::Body<'tcx>)
+    {
+        debug!("mirgen intrinsic {}", self);
+
+        let source_info = dummy_source_info();
+        let size = wavefront_size(tcx);
+        let size = tcx.const_value_rvalue(&source_info, tcx.mk_u32_cv(size),
+                                          self.output(tcx));
+
+        let ret = Place::return_place();
+        let stmt = Statement {
+            source_info,
+            kind: StatementKind::Assign(Box::new((ret, size))),
+        };
+        let bb = BasicBlockData {
+            statements: vec![stmt],
+            terminator: Some(mir::Terminator {
+                source_info,
+                kind: TerminatorKind::Return,
+            }),
+
+            is_cleanup: false,
+        };
+        mir.basic_blocks_mut().push(bb);
+    }
+
+    fn generic_parameter_count(&self, _tcx: TyCtxt<'_>) -> usize {
+        0
+    }
+    /// The types of the input args.
+    fn inputs<'tcx>(&self, tcx: TyCtxt<'tcx>)
+                    -> &'tcx ty::List<Ty<'tcx>>
+    {
+        tcx.intern_type_list(&[])
+    }
+    /// The return type.
+    fn output<'tcx>(&self, tcx: TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.types.u32
+    }
+}
+impl IntrinsicName for WavefrontSize {
+    const NAME: &'static str = "geobacter_amdgpu_wavefront_size";
+}
+impl fmt::Display for WavefrontSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::NAME)
+    }
+}
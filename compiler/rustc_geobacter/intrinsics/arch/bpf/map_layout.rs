@@ -0,0 +1,186 @@
+use std::convert::TryInto;
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::LangItem;
+use rustc_middle::mir::interpret::read_target_uint;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::*;
+
+use super::*;
+use crate::collector::collect_items_rec;
+
+/// `fn geobacter_bpf_map_layout_desc<Entries>() -> CompilerMapLayoutDesc`, where
+/// `Entries` is a tuple of program function types (or references to them) of any
+/// arity. `()` elements are ignored. Every map static used by any of the programs is
+/// described once.
+#[derive(Clone, Copy, Default)]
+pub struct MapLayoutDesc;
+
+impl IntrinsicName for MapLayoutDesc {
+    const NAME: &'static str = "geobacter_bpf_map_layout_desc";
+}
+impl fmt::Display for MapLayoutDesc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::NAME)
+    }
+}
+
+/// The fields of `MapDef`, in order.
+const MAP_DEF_FIELDS: usize = 5;
+
+#[derive(Debug)]
+struct MapDesc<'tcx> {
+    instance: ty::Instance<'tcx>,
+    def: [u32; MAP_DEF_FIELDS],
+}
+
+impl MapLayoutDesc {
+    fn entry_points<'tcx>(tcx: TyCtxt<'tcx>, instance: ty::Instance<'tcx>)
+        -> Vec<ty::Instance<'tcx>>
+    {
+        let entries = instance.substs.type_at(0);
+        let entries = tcx.normalize_erasing_regions(ParamEnv::reveal_all(), entries);
+        match entries.kind() {
+            Tuple(_) => { },
+            _ => {
+                let msg = format!("expected a tuple of programs, found `{}`", entries);
+                tcx.sess.span_err(tcx.def_span(instance.def_id()), &msg);
+                return vec![];
+            },
+        }
+
+        entries.tuple_fields()
+            .filter_map(|entry| tcx.extract_opt_fn_instance(instance, entry) )
+            .collect()
+    }
+
+    fn is_map<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+        let lang_items = tcx.lang_items();
+        match *ty.kind() {
+            Adt(def, _) => {
+                Some(def.did) == lang_items.bpf_map_ty() ||
+                    Some(def.did) == lang_items.bpf_ring_buf_ty()
+            },
+            _ => false,
+        }
+    }
+
+    /// Read the `MapDef` out of the initializer of the static, so that anything the
+    /// `const fn` constructors compute (ie the flags) is picked up too.
+    fn extract_map<'tcx>(tcx: TyCtxt<'tcx>, static_did: DefId) -> Option<MapDesc<'tcx>> {
+        let instance = Instance::mono(tcx, static_did);
+        let ty = instance.ty(tcx, ParamEnv::reveal_all());
+        if !Self::is_map(tcx, ty) {
+            return None;
+        }
+
+        let alloc = match tcx.eval_static_initializer(static_did) {
+            Ok(alloc) => alloc,
+            Err(_) => {
+                // Already reported.
+                return None;
+            },
+        };
+
+        let endian = tcx.data_layout.endian;
+        let mut def = [0u32; MAP_DEF_FIELDS];
+        for (idx, field) in def.iter_mut().enumerate() {
+            let start = idx * 4;
+            let bytes = alloc.inspect_with_uninit_and_ptr_outside_interpreter(start..start + 4);
+            *field = read_target_uint(endian, bytes)
+                .ok()
+                .and_then(|v| v.try_into().ok() )
+                .unwrap_or_else(|| {
+                    bug!("malformed eBPF map definition in `{:?}`", static_did);
+                });
+        }
+
+        Some(MapDesc {
+            instance,
+            def,
+        })
+    }
+}
+
+impl CustomIntrinsicMirGen for MapLayoutDesc {
+    fn mirgen_simple_intrinsic<'tcx>(&self, tcx: TyCtxt<'tcx>,
+                                     instance: ty::Instance<'tcx>,
+                                     mir: &mut mir::Body<'tcx>)
+    {
+        debug!("mirgen intrinsic {}", self);
+
+        let source_info = dummy_source_info();
+
+        let mut visited: FxHashSet<_> = Default::default();
+        for entry in Self::entry_points(tcx, instance) {
+            collect_items_rec(tcx, MonoItem::Fn(entry), &mut visited);
+        }
+
+        let mut maps: Vec<_> = visited.into_iter()
+            .filter_map(|mono| match mono {
+                MonoItem::Static(did) => Self::extract_map(tcx, did),
+                _ => None,
+            })
+            .collect();
+        // Keep the output stable across compilations.
+        maps.sort_by_key(|map| tcx.def_path_str(map.instance.def_id()) );
+
+        info!("eBPF maps: {:#?}", maps);
+
+        let map_ty = map_desc_ty(tcx);
+        let maps_ty = tcx.mk_array(map_ty, maps.len() as _);
+        let len = maps.len();
+        let mut values = Vec::with_capacity(len * (MAP_DEF_FIELDS + 1));
+        for map in maps.iter() {
+            let symbol = tcx.symbol_name(map.instance);
+            values.push(tcx.mk_static_str_cv(symbol.name));
+            values.extend(map.def.iter().map(|&v| tcx.mk_u32_cv(v) ));
+        }
+
+        let slice = tcx.mk_static_slice_cv("eBPF maps", values.into_iter(),
+                                           maps_ty, len);
+        let slice = tcx.const_value_rvalue(&source_info, slice, self.output(tcx));
+
+        let ret = Place::return_place();
+        let stmt = Statement {
+            source_info,
+            kind: StatementKind::Assign(Box::new((ret, slice))),
+        };
+        let bb = BasicBlockData {
+            statements: vec![stmt],
+            terminator: Some(mir::Terminator {
+                source_info,
+                kind: TerminatorKind::Return,
+            }),
+
+            is_cleanup: false,
+        };
+        mir.basic_blocks_mut().push(bb);
+    }
+
+    fn generic_parameter_count<'tcx>(&self, _tcx: TyCtxt<'tcx>) -> usize {
+        1
+    }
+    /// The types of the input args.
+    fn inputs<'tcx>(&self, tcx: TyCtxt<'tcx>)
+                    -> &'tcx ty::List<Ty<'tcx>>
+    {
+        tcx.intern_type_list(&[])
+    }
+    /// The return type.
+    fn output<'tcx>(&self, tcx: TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.mk_static_slice(map_desc_ty(tcx))
+    }
+}
+
+fn map_desc_ty<'tcx>(tcx: TyCtxt<'tcx>) -> Ty<'tcx> {
+    let tup = [
+        tcx.mk_static_str(),
+        tcx.types.u32,
+        tcx.types.u32,
+        tcx.types.u32,
+        tcx.types.u32,
+        tcx.types.u32,
+    ];
+    tcx.mk_tup(tup.iter())
+}
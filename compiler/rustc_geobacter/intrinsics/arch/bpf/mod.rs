@@ -0,0 +1,52 @@
+use super::*;
+use crate::intrinsics::suicide::Suicide;
+
+use tracing::info;
+
+pub use map_layout::MapLayoutDesc;
+
+pub mod map_layout;
+
+#[inline(always)]
+pub fn insert_all_intrinsics<F>(mut map: F)
+    where F: for<'a> FnMut(&'a str, Lrc<dyn CustomIntrinsicMirGen>),
+{
+    MapLayoutDesc::insert_into_map(&mut map);
+}
+
+pub fn find_intrinsic(tcx: TyCtxt<'_>, name: &str)
+                      -> Result<(), Lrc<dyn CustomIntrinsicMirGen>>
+{
+    match &tcx.sess.target.target.arch[..] {
+        "bpf" => {
+            BpfSuicide::check(name)?;
+        },
+        _ => { },
+    };
+
+    MapLayoutDesc::check(name)?;
+
+    Ok(())
+}
+
+/// There's no trapping on eBPF; instead, the program returns the drop verdict of
+/// its program type.
+pub struct SuicideDetail;
+impl PlatformImplDetail for SuicideDetail {
+    fn platform() -> &'static str { "bpf" }
+    fn kernel_instance() -> Option<KernelInstanceRef<'static>> {
+        #[inline(always)]
+        fn exit_drop() -> ! {
+            // This only ends the program if it is reached from the entry point. The
+            // intrinsic shim is always inlined into its caller on eBPF, and the
+            // legality check rejects functions which can get here without being
+            // `#[inline(always)]`.
+            unsafe { std::geobacter::bpf::exit_drop() }
+        }
+        Some(exit_drop.kernel_instance())
+    }
+}
+impl IntrinsicName for BpfSuicide {
+    const NAME: &'static str = "geobacter_suicide";
+}
+pub type BpfSuicide = Suicide<SuicideDetail>;
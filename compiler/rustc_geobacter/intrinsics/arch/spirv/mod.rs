@@ -3,17 +3,20 @@ use crate::intrinsics::suicide::Suicide;
 
 use tracing::info;
 
+pub use opencl::KernelArgs;
 pub use pipeline_layout::PipelineLayoutDesc;
 
+pub mod opencl;
 pub mod pipeline_layout;
 // WIP
 //pub mod shader_interface;
 
 #[inline(always)]
-pub fn insert_all_intrinsics<F>(map: F)
+pub fn insert_all_intrinsics<F>(mut map: F)
     where F: for<'a> FnMut(&'a str, Lrc<dyn CustomIntrinsicMirGen>),
 {
-    PipelineLayoutDesc::insert_into_map(map);
+    PipelineLayoutDesc::insert_into_map(&mut map);
+    KernelArgs::insert_into_map(&mut map);
 }
 
 pub fn find_intrinsic(tcx: TyCtxt<'_>, name: &str)
@@ -27,6 +30,7 @@ pub fn find_intrinsic(tcx: TyCtxt<'_>, name: &str)
     };
 
     PipelineLayoutDesc::check(name)?;
+    KernelArgs::check(name)?;
 
     Ok(())
 }
@@ -0,0 +1,182 @@
+use std::geobacter::spirv::opencl::CompilerKernelArgKind;
+
+use smallvec::SmallVec;
+
+use rustc_middle::ty::*;
+use rustc_target::abi::{self, call::{spirv, ArgAbi, PassMode}};
+
+use super::*;
+
+/// `fn geobacter_spirv_kernel_args<F>() -> &'static [CompilerKernelArg]`, where `F`
+/// is the function type of an OpenCL kernel. Describes the arguments the host must
+/// set, in the same order and with the same splitting as the `spir-kernel` ABI.
+#[derive(Clone, Copy, Default)]
+pub struct KernelArgs;
+
+impl IntrinsicName for KernelArgs {
+    const NAME: &'static str = "geobacter_spirv_kernel_args";
+}
+impl fmt::Display for KernelArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::NAME)
+    }
+}
+
+impl KernelArgs {
+    fn pointer_kind<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>)
+        -> Option<(CompilerKernelArgKind, Ty<'tcx>)>
+    {
+        let (pointee, mutbl) = match *ty.kind() {
+            Ref(_, pointee, mutbl) => (pointee, mutbl),
+            RawPtr(TypeAndMut { ty: pointee, mutbl, }) => (pointee, mutbl),
+            Adt(..) if ty.is_box() => (ty.boxed_ty(), Mutability::Mut),
+            _ => { return None; },
+        };
+
+        // Shared references to things with interior mutability can still be written
+        // through, so those can't go into `__constant`.
+        let kind = if mutbl == Mutability::Not &&
+            pointee.is_freeze(tcx.at(DUMMY_SP), ParamEnv::reveal_all())
+        {
+            CompilerKernelArgKind::ConstantPtr
+        } else {
+            CompilerKernelArgKind::GlobalPtr
+        };
+        Some((kind, pointee))
+    }
+
+    /// Describes one scalar of an argument whose type doesn't say what it points to, ie a
+    /// field of a struct. Pointers are assumed to point into a `__global` buffer, with an
+    /// unknown pointee.
+    fn scalar(tcx: TyCtxt<'_>, scalar: &abi::Scalar) -> (CompilerKernelArgKind, u64, u64) {
+        match scalar.value {
+            abi::Pointer => (CompilerKernelArgKind::GlobalPtr, 0, 0),
+            value => (CompilerKernelArgKind::ByValue, value.size(&tcx).bytes(),
+                      value.align(&tcx).abi.bytes()),
+        }
+    }
+
+    /// Splits `ty` into the arguments the `spir-kernel` ABI passes it as, using the same
+    /// classification as the ABI itself.
+    fn classify<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>,
+                      out: &mut SmallVec<[(CompilerKernelArgKind, u64, u64); 8]>)
+    {
+        let layout = tcx.layout_of(ParamEnv::reveal_all().and(ty))
+            .expect("layout failure");
+        // Zero sized args are ignored by the ABI.
+        if layout.is_zst() {
+            return;
+        }
+
+        let mut arg = ArgAbi::new(layout);
+        spirv::classify_kernel_arg(&mut arg);
+        match arg.mode {
+            PassMode::Ignore => { },
+            PassMode::Direct(_) | PassMode::Cast(_) => {
+                match Self::pointer_kind(tcx, ty) {
+                    Some((kind, pointee)) => {
+                        let pointee = tcx.layout_of(ParamEnv::reveal_all().and(pointee))
+                            .expect("layout failure");
+                        out.push((kind, pointee.size.bytes(), pointee.align.abi.bytes()));
+                    },
+                    None => match &layout.abi {
+                        abi::Abi::Scalar(scalar) => out.push(Self::scalar(tcx, scalar)),
+                        _ => {
+                            out.push((CompilerKernelArgKind::ByValue, layout.size.bytes(),
+                                      layout.align.abi.bytes()));
+                        },
+                    },
+                }
+            },
+            // Passed `byval`: the host provides the bytes, the pointer is implicit.
+            PassMode::Indirect(..) => {
+                out.push((CompilerKernelArgKind::ByValue, layout.size.bytes(),
+                          layout.align.abi.bytes()));
+            },
+            PassMode::Pair(..) => {
+                let (a, b) = match &layout.abi {
+                    abi::Abi::ScalarPair(a, b) => (a, b),
+                    _ => bug!("pair argument without a scalar pair ABI: {:?}", layout),
+                };
+                match Self::pointer_kind(tcx, ty) {
+                    // Fat pointer: the data pointer, followed by the metadata.
+                    Some((kind, _)) => out.push((kind, 0, 0)),
+                    None => out.push(Self::scalar(tcx, a)),
+                }
+                out.push(Self::scalar(tcx, b));
+            },
+        }
+    }
+}
+
+impl CustomIntrinsicMirGen for KernelArgs {
+    fn mirgen_simple_intrinsic<'tcx>(&self, tcx: TyCtxt<'tcx>,
+                                     instance: ty::Instance<'tcx>,
+                                     mir: &mut mir::Body<'tcx>)
+    {
+        debug!("mirgen intrinsic {}", self);
+
+        let source_info = dummy_source_info();
+
+        let mut args = SmallVec::new();
+        let kernel = instance.substs.type_at(0);
+        if let Some(kernel) = tcx.extract_opt_fn_instance(instance, kernel) {
+            let sig = kernel.ty(tcx, ParamEnv::reveal_all()).fn_sig(tcx);
+            let sig = tcx.normalize_erasing_late_bound_regions(ParamEnv::reveal_all(),
+                                                               &sig);
+            for &input in sig.inputs().iter() {
+                Self::classify(tcx, input, &mut args);
+            }
+        }
+
+        info!("kernel args for {}: {:?}", instance, args);
+
+        let arg_ty = kernel_arg_ty(tcx);
+        let args_ty = tcx.mk_array(arg_ty, args.len() as _);
+        let len = args.len();
+        let values = args.into_iter()
+            .flat_map(|(kind, size, align)| {
+                let kind: u32 = kind.into();
+                vec![tcx.mk_u32_cv(kind), tcx.mk_u64_cv(size), tcx.mk_u64_cv(align)]
+            })
+            .collect::<Vec<_>>();
+        let slice = tcx.mk_static_slice_cv("kernel args", values.into_iter(),
+                                           args_ty, len);
+        let slice = tcx.const_value_rvalue(&source_info, slice, self.output(tcx));
+
+        let ret = Place::return_place();
+        let stmt = Statement {
+            source_info,
+            kind: StatementKind::Assign(Box::new((ret, slice))),
+        };
+        let bb = BasicBlockData {
+            statements: vec![stmt],
+            terminator: Some(mir::Terminator {
+                source_info,
+                kind: TerminatorKind::Return,
+            }),
+
+            is_cleanup: false,
+        };
+        mir.basic_blocks_mut().push(bb);
+    }
+
+    fn generic_parameter_count<'tcx>(&self, _tcx: TyCtxt<'tcx>) -> usize {
+        1
+    }
+    /// The types of the input args.
+    fn inputs<'tcx>(&self, tcx: TyCtxt<'tcx>)
+                    -> &'tcx ty::List<Ty<'tcx>>
+    {
+        tcx.intern_type_list(&[])
+    }
+    /// The return type.
+    fn output<'tcx>(&self, tcx: TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.mk_static_slice(kernel_arg_ty(tcx))
+    }
+}
+
+fn kernel_arg_ty<'tcx>(tcx: TyCtxt<'tcx>) -> Ty<'tcx> {
+    let tup = [tcx.types.u32, tcx.types.u64, tcx.types.u64];
+    tcx.mk_tup(tup.iter())
+}
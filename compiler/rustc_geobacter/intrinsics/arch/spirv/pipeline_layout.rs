@@ -2,13 +2,13 @@
 use std::collections::BTreeMap;
 use std::collections::btree_map::Entry;
 use std::convert::TryInto;
+use std::geobacter::platform::spirv::ExeModel;
 use std::geobacter::spirv::pipeline_layout::*;
 use std::str::FromStr;
 
 use smallvec::SmallVec;
 
 use rustc_data_structures::fx::FxHashSet;
-use rustc_errors::DiagnosticBuilder;
 use rustc_hir::LangItem;
 use rustc_middle::mir::mono::MonoItem;
 use rustc_middle::ty::*;
@@ -16,32 +16,79 @@ use rustc_middle::ty::*;
 use super::*;
 use crate::collector::collect_items_rec;
 
-/// `geobacter_spirv_pipeline_layout_desc{}`, where `{}` is the number of
-/// entry point type params.
-#[derive(Clone, Copy)]
-pub struct PipelineLayoutDesc(u32);
+/// `fn geobacter_spirv_pipeline_layout_desc<Entries>() -> CompilerDescriptorSetBindingsDesc`,
+/// where `Entries` is a tuple of entry point function types (or references to them) of
+/// any arity. `()` elements are ignored.
+#[derive(Clone, Copy, Default)]
+pub struct PipelineLayoutDesc;
 
-const PREFIX: &'static str = "geobacter_spirv_pipeline_layout_desc";
+impl IntrinsicName for PipelineLayoutDesc {
+    const NAME: &'static str = "geobacter_spirv_pipeline_layout_desc";
+}
+impl fmt::Display for PipelineLayoutDesc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::NAME)
+    }
+}
 
 impl PipelineLayoutDesc {
-    pub fn insert_into_map<F>(mut map: F)
-        where Self: Sized,
-              F: for<'a> FnMut(&'a str, Lrc<dyn CustomIntrinsicMirGen>),
+    fn entry_points<'tcx>(tcx: TyCtxt<'tcx>, instance: ty::Instance<'tcx>)
+        -> Vec<ty::Instance<'tcx>>
     {
-        for suffix in 0u32..8 {
-            let name = format!("{}{}", PREFIX, suffix);
-            map(&name, Lrc::new(PipelineLayoutDesc(suffix)))
+        let entries = instance.substs.type_at(0);
+        let entries = tcx.normalize_erasing_regions(ParamEnv::reveal_all(), entries);
+        match entries.kind() {
+            Tuple(_) => { },
+            _ => {
+                let msg = format!("expected a tuple of entry points, found `{}`",
+                                  entries);
+                tcx.sess.span_err(tcx.def_span(instance.def_id()), &msg);
+                return vec![];
+            },
         }
+
+        entries.tuple_fields()
+            .filter_map(|entry| tcx.extract_opt_fn_instance(instance, entry) )
+            .collect()
     }
-    pub fn check(name: &str) -> Result<(), Lrc<dyn CustomIntrinsicMirGen>> {
-        if !name.starts_with(PREFIX) {
-            return Ok(());
-        }
 
-        let suffix = &name[PREFIX.len()..];
-        match u32::from_str(suffix) {
-            Ok(c) => Err(Lrc::new(PipelineLayoutDesc(c))),
-            Err(_) => Ok(()),
+    /// The shader stages `entry` runs in, according to its execution model. If the
+    /// entry doesn't declare one, assume it could be any of the usual suspects.
+    fn entry_stages<'tcx>(tcx: TyCtxt<'tcx>, entry: ty::Instance<'tcx>) -> ShaderStages {
+        let attrs = tcx.codegen_fn_attrs(entry.def_id());
+        let exe_model = attrs.spirv.as_ref()
+            .and_then(|spirv| spirv.exe_model.as_ref() );
+        let exe_model = match exe_model {
+            Some(exe_model) => exe_model,
+            None => {
+                return ShaderStages {
+                    vertex: true,
+                    fragment: true,
+                    compute: true,
+                    ..ShaderStages::NONE
+                };
+            },
+        };
+
+        match ExeModel::from_str(exe_model) {
+            Ok(ExeModel::Vertex) => ShaderStages { vertex: true, ..ShaderStages::NONE },
+            Ok(ExeModel::TessellationControl) => ShaderStages {
+                tessellation_control: true,
+                ..ShaderStages::NONE
+            },
+            Ok(ExeModel::TessellationEval) => ShaderStages {
+                tessellation_evaluation: true,
+                ..ShaderStages::NONE
+            },
+            Ok(ExeModel::Geometry) => ShaderStages { geometry: true, ..ShaderStages::NONE },
+            Ok(ExeModel::Fragment) => ShaderStages { fragment: true, ..ShaderStages::NONE },
+            Ok(ExeModel::GLCompute) |
+            Ok(ExeModel::Kernel) => ShaderStages { compute: true, ..ShaderStages::NONE },
+            Err(()) => {
+                let msg = format!("unknown SPIR-V execution model `{}`", exe_model);
+                tcx.sess.span_err(tcx.def_span(entry.def_id()), &msg);
+                ShaderStages::NONE
+            },
         }
     }
 }
@@ -63,7 +110,8 @@ impl LangItems {
         did == self.buffer || did == self.uniform
     }
 
-    fn extract_descriptor<'tcx>(&self, tcx: TyCtxt<'tcx>, from: ty::Instance<'tcx>)
+    fn extract_descriptor<'tcx>(&self, tcx: TyCtxt<'tcx>, from: ty::Instance<'tcx>,
+                                stages: ShaderStages)
         -> Option<(DescriptorDesc<'tcx>, (u32, u32))>
     {
         let ty = from.ty(tcx, ParamEnv::reveal_all());
@@ -123,16 +171,7 @@ impl LangItems {
             insts: SmallVec::new(),
             ty,
             array_count,
-            stages: ShaderStages {
-                // TO DO
-                vertex: true,
-                fragment: true,
-                compute: true,
-
-                tessellation_control: false,
-                tessellation_evaluation: false,
-                geometry: false,
-            },
+            stages,
             // TO DO
             readonly: false,
         };
@@ -186,11 +225,33 @@ struct ShaderStages {
     fragment: bool,
     compute: bool,
 }
+impl ShaderStages {
+    const NONE: ShaderStages = ShaderStages {
+        vertex: false,
+        tessellation_control: false,
+        tessellation_evaluation: false,
+        geometry: false,
+        fragment: false,
+        compute: false,
+    };
+
+    fn union(self, rhs: Self) -> Self {
+        ShaderStages {
+            vertex: self.vertex || rhs.vertex,
+            tessellation_control: self.tessellation_control || rhs.tessellation_control,
+            tessellation_evaluation: self.tessellation_evaluation || rhs.tessellation_evaluation,
+            geometry: self.geometry || rhs.geometry,
+            fragment: self.fragment || rhs.fragment,
+            compute: self.compute || rhs.compute,
+        }
+    }
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct DescriptorDesc<'tcx> {
     /// If `len()` > 1, then this spot has multiple assignments; these instances
     /// will be used for reporting an error to the user.
+    /// Every instance is only present once, even if it is used by many entry points.
     insts: SmallVec<[ty::Instance<'tcx>; 1]>,
     ty: DescriptorDescTy,
     array_count: u32,
@@ -220,50 +281,55 @@ impl CustomIntrinsicMirGen for PipelineLayoutDesc {
         };
 
 
-        let mut visited: FxHashSet<_> = Default::default();
-        let entries = instance.substs.types()
-            .filter_map(|root| tcx.extract_opt_fn_instance(instance, root) );
-        for entry in entries {
-            // collect all referenced mono items upfront:
-            let mono_root = MonoItem::Fn(entry);
-            collect_items_rec(tcx, mono_root, &mut visited);
-        }
-
         let lang_items = LangItems::new(tcx);
 
         let mut sets: BTreeMap<u32, BTreeMap<u32, DescriptorDesc<'_>>> = Default::default();
 
-        for mono in visited.into_iter() {
-            let instance = match mono {
-                MonoItem::Fn(_) => { continue; },
-                MonoItem::Static(mono_did) => Instance::mono(tcx, mono_did),
-                MonoItem::GlobalAsm(..) => {
-                    bug!("unexpected mono item `{:?}`", mono);
-                },
-            };
-
-            let desc = lang_items.extract_descriptor(tcx, instance);
-            if desc.is_none() { continue; }
-            let (desc, slot) = desc.unwrap();
-
-            let set = match sets.entry(slot.0) {
-                Entry::Occupied(o) => o.into_mut(),
-                Entry::Vacant(v) => {
-                    v.insert(BTreeMap::default())
-                },
-            };
-
-            let desc = match set.entry(slot.1) {
-                Entry::Vacant(v) => {
-                    // the happy path
-                    v.insert(desc)
-                },
-                Entry::Occupied(o) => {
-                    // There are two or more statics which are assigned to the same set+binding.
-                    o.into_mut()
-                },
-            };
-            desc.insts.push(instance);
+        for entry in Self::entry_points(tcx, instance) {
+            let stages = Self::entry_stages(tcx, entry);
+
+            // collect all referenced mono items upfront. This is done per entry point
+            // so we know which stages use which descriptors.
+            let mut visited: FxHashSet<_> = Default::default();
+            collect_items_rec(tcx, MonoItem::Fn(entry), &mut visited);
+
+            for mono in visited.into_iter() {
+                let instance = match mono {
+                    MonoItem::Fn(_) => { continue; },
+                    MonoItem::Static(mono_did) => Instance::mono(tcx, mono_did),
+                    MonoItem::GlobalAsm(..) => {
+                        bug!("unexpected mono item `{:?}`", mono);
+                    },
+                };
+
+                let desc = lang_items.extract_descriptor(tcx, instance, stages);
+                if desc.is_none() { continue; }
+                let (desc, slot) = desc.unwrap();
+
+                let set = match sets.entry(slot.0) {
+                    Entry::Occupied(o) => o.into_mut(),
+                    Entry::Vacant(v) => {
+                        v.insert(BTreeMap::default())
+                    },
+                };
+
+                let desc = match set.entry(slot.1) {
+                    Entry::Vacant(v) => {
+                        // the happy path
+                        v.insert(desc)
+                    },
+                    Entry::Occupied(o) => {
+                        // Either another entry point uses this static too, or there are
+                        // two or more statics which are assigned to the same set+binding.
+                        let o = o.into_mut();
+                        o.stages = o.stages.union(stages);
+                        o
+                    },
+                };
+                if !desc.insts.contains(&instance) {
+                    desc.insts.push(instance);
+                }
+            }
         }
 
         info!("desc set bindings: {:#?}", sets);
@@ -284,26 +350,16 @@ impl CustomIntrinsicMirGen for PipelineLayoutDesc {
             for (&binding_id, desc) in set.iter() {
                 // check that all the spots have only one instance, otherwise report an error.
                 if desc.insts.len() > 1 {
-                    let hir = tcx.hir();
-
-                    let msg = format!("duplicate descriptor: set = {}, binding = {}",
+                    let msg = format!("conflicting descriptor declarations: set = {}, binding = {}",
                                       set_id, binding_id);
 
-                    let mut diag: Option<DiagnosticBuilder<'_>> = None;
-                    for inst in desc.insts.iter() {
-                        let local_did = inst.def_id().as_local()
-                            .expect("TODO: non-local DefIds");
-                        let hir_id = hir.local_def_id_to_hir_id(local_did);
-                        let span = hir.span(hir_id);
-                        match diag {
-                            Some(ref mut diag) => {
-                                diag.span_label(span, &msg);
-                            },
-                            None => {
-                                diag = Some(tcx.sess.struct_span_err(span, &msg));
-                            },
-                        }
+                    let first = tcx.def_span(desc.insts[0].def_id());
+                    let mut diag = tcx.sess.struct_span_err(first, &msg);
+                    diag.span_label(first, "first declared here");
+                    for inst in desc.insts[1..].iter() {
+                        diag.span_label(tcx.def_span(inst.def_id()), "also declared here");
                     }
+                    diag.emit();
                 }
 
                 c_set.extend(build_compiler_descriptor_desc(tcx, binding_id, desc));
@@ -335,7 +391,7 @@ impl CustomIntrinsicMirGen for PipelineLayoutDesc {
     }
 
     fn generic_parameter_count<'tcx>(&self, _tcx: TyCtxt<'tcx>) -> usize {
-        self.0 as _
+        1
     }
     /// The types of the input args.
     fn inputs<'tcx>(&self, tcx: TyCtxt<'tcx>)
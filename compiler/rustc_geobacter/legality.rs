@@ -0,0 +1,579 @@
+//! Device legality checks. Some devices can only run a subset of what Rust can
+//! express, and the errors their toolchains produce when given something outside
+//! that subset are unhelpful at best (eg a kernel verifier log which refers to
+//! instruction offsets). So we check the MIR of every mono item before codegen,
+//! and report errors with spans instead.
+//!
+//! Which checks are run is decided by the target arch; for most targets nothing is
+//! checked. Only the program entry points and the functions they reach are checked,
+//! so libraries built for the target, eg `core`, aren't held to these rules.
+
+use tracing::debug;
+
+use rustc_attr::InlineAttr;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_index::bit_set::BitSet;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::{self, BasicBlock, BinOp, Local, Operand, Rvalue};
+use rustc_middle::mir::{StatementKind, TerminatorKind, traversal};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::{self, Instance, InstanceDef, ParamEnv, TyCtxt, TypeFoldable};
+use rustc_span::Span;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LegalityMode {
+    /// eBPF: every loop needs an exit which compares a counter against a constant,
+    /// or has to be a `for` loop over a range of integers. The only calls allowed are
+    /// to functions in the same program, LLVM intrinsics, and kernel helpers (which
+    /// are called through integer function pointers). Functions which can panic or
+    /// exit have to be inlined into the entry point, as `exit` only ends the program
+    /// from there.
+    Bpf,
+}
+
+impl LegalityMode {
+    pub fn for_target(tcx: TyCtxt<'_>) -> Option<Self> {
+        match &tcx.sess.target.target.arch[..] {
+            "bpf" => Some(LegalityMode::Bpf),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            LegalityMode::Bpf => "eBPF",
+        }
+    }
+}
+
+/// Check the program entry points of the local crate, and everything they reach, if
+/// the target has a legality mode.
+pub fn check_crate(tcx: TyCtxt<'_>) {
+    let mode = match LegalityMode::for_target(tcx) {
+        Some(mode) => mode,
+        None => { return; },
+    };
+
+    // `compiler_builtins` has what LLVM lowers operations to, not programs.
+    if tcx.is_compiler_builtins(LOCAL_CRATE) {
+        return;
+    }
+
+    let (_, cgus) = tcx.collect_and_partition_mono_items(LOCAL_CRATE);
+    let entries = cgus.iter()
+        .flat_map(|cgu| cgu.items().keys() )
+        .filter_map(|item| match *item {
+            MonoItem::Fn(instance) if is_entry_point(tcx, instance) => Some(instance),
+            _ => None,
+        });
+    check_programs(tcx, mode, entries);
+}
+
+/// Programs are found by the loader through their symbol, so every function of the
+/// local crate which is exported by name is an entry point.
+fn is_entry_point<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+    let def_id = match instance.def {
+        InstanceDef::Item(def) if def.did.is_local() => def.did,
+        _ => { return false; },
+    };
+    let attrs = tcx.codegen_fn_attrs(def_id);
+    attrs.flags.contains(CodegenFnAttrFlags::NO_MANGLE) || attrs.export_name.is_some() ||
+        attrs.link_section.is_some()
+}
+
+/// Check the programs starting at `entries`, and every function they call,
+/// according to `mode`. Errors are reported in a stable order, and only once per
+/// span.
+pub fn check_programs<'tcx, I>(tcx: TyCtxt<'tcx>, mode: LegalityMode, entries: I)
+    where I: IntoIterator<Item = Instance<'tcx>>,
+{
+    let mut entries: Vec<_> = entries.into_iter().collect();
+    entries.sort_by_key(|&instance| tcx.symbol_name(instance).name );
+    entries.dedup();
+
+    let mut checker = Checker {
+        tcx,
+        mode,
+        reported: Default::default(),
+        calls: Default::default(),
+        order: Vec::new(),
+    };
+    for entry in entries {
+        checker.check_reachable(entry);
+    }
+    match mode {
+        LegalityMode::Bpf => {
+            checker.check_exits_inlined();
+        },
+    }
+}
+
+/// The calls made by a checked function.
+#[derive(Default)]
+struct Calls<'tcx> {
+    /// The functions of the program which are called, or dropped through, with the
+    /// span of the call.
+    callees: Vec<(Span, Instance<'tcx>)>,
+    /// Whether the function can end the program itself, ie it can panic or calls
+    /// something outside of the program which never returns.
+    ends_program: bool,
+}
+
+struct Checker<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    mode: LegalityMode,
+    reported: FxHashSet<Span>,
+    calls: FxHashMap<Instance<'tcx>, Calls<'tcx>>,
+    /// The checked functions, in the order they were first reached.
+    order: Vec<Instance<'tcx>>,
+}
+
+impl<'tcx> Checker<'tcx> {
+    /// Check `root`, and every function of the program it calls.
+    fn check_reachable(&mut self, root: Instance<'tcx>) {
+        let mut stack = vec![root];
+        while let Some(instance) = stack.pop() {
+            if self.calls.contains_key(&instance) {
+                continue;
+            }
+            let calls = self.check_instance(instance);
+            stack.extend(calls.callees.iter().rev().map(|&(_, callee)| callee ));
+            self.calls.insert(instance, calls);
+            self.order.push(instance);
+        }
+    }
+
+    fn check_instance(&mut self, instance: Instance<'tcx>) -> Calls<'tcx> {
+        match instance.def {
+            InstanceDef::Intrinsic(..) | InstanceDef::Virtual(..) => {
+                return Calls::default();
+            },
+            _ => { },
+        }
+
+        debug!("checking {} legality of {}", self.mode.name(), instance);
+
+        let body = self.tcx.instance_mir(instance.def);
+        match self.mode {
+            LegalityMode::Bpf => {
+                self.check_loops(instance, body);
+                self.check_calls(instance, body)
+            },
+        }
+    }
+
+    fn error(&mut self, span: Span, msg: &str, note: &str) {
+        if !self.reported.insert(span) {
+            return;
+        }
+        self.tcx.sess.struct_span_err(span, msg)
+            .note(note)
+            .emit();
+    }
+
+    /// Find the natural loops of `body`, and reject those which can't be shown to
+    /// be bounded by `loop_is_bounded`.
+    fn check_loops(&mut self, instance: Instance<'tcx>, body: &mir::Body<'tcx>) {
+        let dominators = body.dominators();
+        let predecessors = body.predecessors();
+        // Unreachable blocks have no dominators, and can't loop anyway.
+        let reachable = traversal::reachable_as_bitset(body);
+
+        for (latch, data) in body.basic_blocks().iter_enumerated() {
+            if data.is_cleanup || !reachable.contains(latch) {
+                continue;
+            }
+            for &header in data.terminator().successors() {
+                if !dominators.is_dominated_by(latch, header) {
+                    continue;
+                }
+
+                // `latch -> header` is a back edge. Collect the loop body by walking
+                // backwards from the latch until we hit the header.
+                let mut blocks = BitSet::new_empty(body.basic_blocks().len());
+                blocks.insert(header);
+                let mut stack = vec![latch];
+                while let Some(bb) = stack.pop() {
+                    if reachable.contains(bb) && blocks.insert(bb) {
+                        stack.extend(predecessors[bb].iter().cloned());
+                    }
+                }
+
+                if !self.loop_is_bounded(instance, body, &blocks) {
+                    let span = body[header].terminator().source_info.span;
+                    let msg = format!("unbounded loop in {} program", self.mode.name());
+                    self.error(span, &msg,
+                               "the eBPF verifier rejects loops which can't be shown \
+                                to terminate; exit the loop by comparing a counter, \
+                                which is only ever incremented or decremented by a \
+                                constant, against a constant, or loop over a range");
+                }
+            }
+        }
+    }
+
+    /// A loop is considered bounded if one of its exits is taken depending on the
+    /// comparison of an induction variable against a constant, or on a range running
+    /// out. This doesn't check that the variable actually moves towards the constant,
+    /// or that the range ends at a constant; the verifier still has the final say,
+    /// but this catches the common mistakes with a span.
+    fn loop_is_bounded(&self, instance: Instance<'tcx>, body: &mir::Body<'tcx>,
+                       blocks: &BitSet<BasicBlock>) -> bool
+    {
+        blocks.iter().any(|bb| {
+            let data = &body[bb];
+            let terminator = data.terminator();
+            let discr = match terminator.kind {
+                TerminatorKind::SwitchInt { ref discr, .. } => discr,
+                _ => { return false; },
+            };
+            // Unwinding doesn't count as a way out.
+            let exits = terminator.successors()
+                .any(|&succ| !body[succ].is_cleanup && !blocks.contains(succ) );
+            if !exits {
+                return false;
+            }
+
+            let cond = match discr.place().and_then(|place| place.as_local() ) {
+                Some(cond) => cond,
+                None => { return false; },
+            };
+            // `for` loops exit when `next` returns `None`.
+            if let Some(&Rvalue::Discriminant(place)) = find_assignment(data, cond) {
+                return place.as_local()
+                    .map_or(false, |next| self.is_range_next(instance, body, blocks, next) );
+            }
+            let counter = match find_assignment(data, cond) {
+                Some(Rvalue::BinaryOp(op, lhs, rhs)) if is_comparison(*op) => {
+                    match (lhs, rhs) {
+                        (Operand::Constant(_), counter) | (counter, Operand::Constant(_)) => {
+                            counter
+                        },
+                        _ => { return false; },
+                    }
+                },
+                // Comparisons against constants may have been turned into a switch on
+                // the compared value itself.
+                _ => discr,
+            };
+            match operand_root_local(data, counter) {
+                Some(counter) => self.is_induction_variable(body, blocks, counter),
+                None => false,
+            }
+        })
+    }
+
+    /// Is `local` the result of `Iterator::next` on a range of integers, which isn't
+    /// changed in the loop `blocks` other than by `next`?
+    fn is_range_next(&self, instance: Instance<'tcx>, body: &mir::Body<'tcx>,
+                     blocks: &BitSet<BasicBlock>, local: Local) -> bool
+    {
+        let tcx = self.tcx;
+        let call = blocks.iter().find_map(|bb| match body[bb].terminator().kind {
+            TerminatorKind::Call { ref func, ref args, destination: Some((place, _)), .. }
+                if place.as_local() == Some(local) => Some((func, args)),
+            _ => None,
+        });
+        let (func, args) = match call {
+            Some(call) => call,
+            None => { return false; },
+        };
+
+        let fn_ty = monomorphize(tcx, instance, func.ty(body, tcx));
+        let iter_ty = match *fn_ty.kind() {
+            ty::FnDef(def_id, substs) if Some(def_id) == tcx.lang_items().next_fn() => {
+                substs.type_at(0)
+            },
+            _ => { return false; },
+        };
+        match *iter_ty.kind() {
+            ty::Adt(def, substs) if Some(def.did) == tcx.lang_items().range_struct() &&
+                substs.type_at(0).is_integral() => { },
+            _ => { return false; },
+        }
+
+        let iter = match args.first().and_then(|arg| borrowed_local(body, blocks, arg) ) {
+            Some(iter) => iter,
+            None => { return false; },
+        };
+        !blocks.iter().any(|bb| {
+            let data = &body[bb];
+            let assigned = data.statements.iter().any(|stmt| match stmt.kind {
+                StatementKind::Assign(ref assign) => assign.0.local == iter,
+                _ => false,
+            });
+            let called = match data.terminator().kind {
+                TerminatorKind::Call { destination: Some((place, _)), .. } => {
+                    place.local == iter
+                },
+                _ => false,
+            };
+            assigned || called
+        })
+    }
+
+    /// Is `local` only ever modified in the loop `blocks` by adding or subtracting a
+    /// non-zero constant, and never borrowed?
+    fn is_induction_variable(&self, body: &mir::Body<'tcx>, blocks: &BitSet<BasicBlock>,
+                             local: Local) -> bool
+    {
+        let borrowed = body.basic_blocks().iter()
+            .flat_map(|data| data.statements.iter() )
+            .any(|stmt| match stmt.kind {
+                StatementKind::Assign(ref assign) => match assign.1 {
+                    Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) => {
+                        place.local == local
+                    },
+                    _ => false,
+                },
+                _ => false,
+            });
+        if borrowed {
+            return false;
+        }
+
+        let mut steps = 0;
+        for bb in blocks.iter() {
+            let data = &body[bb];
+            if let TerminatorKind::Call { destination: Some((place, _)), .. } =
+                data.terminator().kind
+            {
+                if place.local == local {
+                    return false;
+                }
+            }
+            for stmt in data.statements.iter() {
+                let (place, rvalue) = match stmt.kind {
+                    StatementKind::Assign(ref assign) => (&assign.0, &assign.1),
+                    _ => { continue; },
+                };
+                if place.local != local {
+                    continue;
+                }
+                if place.as_local().is_none() || !self.is_step(body, blocks, local, rvalue) {
+                    return false;
+                }
+                steps += 1;
+            }
+        }
+        steps > 0
+    }
+
+    /// Is `rvalue` `local` plus or minus a non-zero constant? With overflow checks,
+    /// the sum is computed into a temporary first, so this looks through that.
+    fn is_step(&self, body: &mir::Body<'tcx>, blocks: &BitSet<BasicBlock>, local: Local,
+               rvalue: &Rvalue<'tcx>) -> bool
+    {
+        let (op, lhs, rhs) = match *rvalue {
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => (op, lhs, rhs),
+            Rvalue::Use(Operand::Copy(place)) | Rvalue::Use(Operand::Move(place)) => {
+                // `local = move (checked_sum.0)`
+                match place.projection[..] {
+                    [mir::ProjectionElem::Field(field, _)] if field.index() == 0 => { },
+                    _ => { return false; },
+                }
+                let checked = blocks.iter()
+                    .find_map(|bb| find_assignment(&body[bb], place.local) );
+                match checked {
+                    Some(&Rvalue::CheckedBinaryOp(op, ref lhs, ref rhs)) => (op, lhs, rhs),
+                    _ => { return false; },
+                }
+            },
+            _ => { return false; },
+        };
+        if op != BinOp::Add && op != BinOp::Sub {
+            return false;
+        }
+        let is_counter = |operand: &Operand<'tcx>| {
+            operand.place().and_then(|place| place.as_local() ) == Some(local)
+        };
+        let is_non_zero = |operand: &Operand<'tcx>| match operand.constant() {
+            Some(constant) => {
+                let bits = constant.literal
+                    .try_eval_bits(self.tcx, ParamEnv::reveal_all(), constant.literal.ty);
+                bits.map_or(false, |bits| bits != 0 )
+            },
+            None => false,
+        };
+        (is_counter(lhs) && is_non_zero(rhs)) ||
+            (op == BinOp::Add && is_non_zero(lhs) && is_counter(rhs))
+    }
+
+    /// Reject the calls in `body` which eBPF can't make, and collect the functions of
+    /// the program which it calls.
+    fn check_calls(&mut self, instance: Instance<'tcx>, body: &mir::Body<'tcx>) -> Calls<'tcx> {
+        let tcx = self.tcx;
+        let mut calls = Calls::default();
+        for data in body.basic_blocks().iter() {
+            let terminator = data.terminator();
+            let span = terminator.source_info.span;
+            let func = match terminator.kind {
+                TerminatorKind::Call { ref func, .. } => func,
+                TerminatorKind::Assert { .. } => {
+                    calls.ends_program = true;
+                    continue;
+                },
+                TerminatorKind::Drop { place, .. } |
+                TerminatorKind::DropAndReplace { place, .. } => {
+                    let ty = monomorphize(tcx, instance, place.ty(body, tcx).ty);
+                    let glue = Instance::resolve_drop_in_place(tcx, ty);
+                    if let InstanceDef::DropGlue(_, Some(_)) = glue.def {
+                        calls.callees.push((span, glue));
+                    }
+                    continue;
+                },
+                _ => { continue; },
+            };
+
+            let fn_ty = monomorphize(tcx, instance, func.ty(body, tcx));
+            let (def_id, substs) = match *fn_ty.kind() {
+                ty::FnDef(def_id, substs) => (def_id, substs),
+                // Calls through function pointers are how kernel helpers are
+                // called; the verifier checks the helper id.
+                _ => { continue; },
+            };
+
+            let callee = match Instance::resolve(tcx, ParamEnv::reveal_all(), def_id, substs) {
+                Ok(Some(callee)) => callee,
+                _ => { continue; },
+            };
+            let diverges = fn_ty.fn_sig(tcx).output().skip_binder().is_never();
+            match callee.def {
+                InstanceDef::Virtual(..) => {
+                    let msg = format!("dynamic dispatch is not supported in {} programs",
+                                      self.mode.name());
+                    self.error(span, &msg,
+                               "eBPF has no indirect calls, other than to kernel helpers");
+                },
+                InstanceDef::Item(def) if tcx.is_foreign_item(def.did) => {
+                    let name = tcx.codegen_fn_attrs(def.did).link_name
+                        .unwrap_or_else(|| tcx.item_name(def.did) );
+                    if name.as_str().starts_with("llvm.") {
+                        continue;
+                    }
+
+                    let msg = format!("call to foreign function `{}` is not supported in {} \
+                                       programs", name, self.mode.name());
+                    self.error(span, &msg,
+                               "eBPF programs can only call kernel helpers and functions in \
+                                the same object");
+                },
+                // Upstream functions which never return are the ways out of the
+                // program, ie the panic entry points, the Geobacter suicide and
+                // `bpf::exit`.
+                _ if diverges && !callee.def_id().is_local() => {
+                    calls.ends_program = true;
+                },
+                InstanceDef::Intrinsic(..) => { },
+                _ => {
+                    calls.callees.push((span, callee));
+                },
+            }
+        }
+        calls
+    }
+
+    /// eBPF's `exit` returns from the current function, so it only ends the program
+    /// in the entry point. Reject calls to functions which can panic or exit, and
+    /// which therefore have to be inlined into the entry point, unless they are
+    /// `#[inline(always)]`.
+    fn check_exits_inlined(&mut self) {
+        let tcx = self.tcx;
+
+        let mut ends_program: FxHashSet<_> = self.calls.iter()
+            .filter(|(_, calls)| calls.ends_program )
+            .map(|(&instance, _)| instance )
+            .collect();
+        loop {
+            let count = ends_program.len();
+            for (&instance, calls) in self.calls.iter() {
+                if calls.callees.iter().any(|(_, callee)| ends_program.contains(callee) ) {
+                    ends_program.insert(instance);
+                }
+            }
+            if ends_program.len() == count {
+                break;
+            }
+        }
+
+        let mut not_inlined = Vec::new();
+        for instance in self.order.iter() {
+            for &(span, callee) in self.calls[instance].callees.iter() {
+                if !ends_program.contains(&callee) {
+                    continue;
+                }
+                match tcx.codegen_fn_attrs(callee.def_id()).inline {
+                    InlineAttr::Always => { },
+                    _ => not_inlined.push((span, callee)),
+                }
+            }
+        }
+
+        for (span, callee) in not_inlined {
+            let msg = format!("`{}` can end the {} program, but is not `#[inline(always)]`",
+                              tcx.def_path_str(callee.def_id()), self.mode.name());
+            self.error(span, &msg,
+                       "a panic or `exit` only ends an eBPF program in its entry point; \
+                        anywhere else, `exit` returns to the caller, which then runs on \
+                        after a call which never returns");
+        }
+    }
+}
+
+fn monomorphize<'tcx, T>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>, value: T) -> T
+    where T: TypeFoldable<'tcx>,
+{
+    if let Some(substs) = instance.substs_for_mir_body() {
+        tcx.subst_and_normalize_erasing_regions(substs, ParamEnv::reveal_all(), &value)
+    } else {
+        tcx.normalize_erasing_regions(ParamEnv::reveal_all(), value)
+    }
+}
+
+fn is_comparison(op: BinOp) -> bool {
+    match op {
+        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => true,
+        _ => false,
+    }
+}
+
+/// Find the last assignment to `local` in `data`.
+fn find_assignment<'a, 'tcx>(data: &'a mir::BasicBlockData<'tcx>, local: Local)
+                             -> Option<&'a Rvalue<'tcx>>
+{
+    data.statements.iter().rev().find_map(|stmt| match stmt.kind {
+        StatementKind::Assign(ref assign) if assign.0.as_local() == Some(local) => {
+            Some(&assign.1)
+        },
+        _ => None,
+    })
+}
+
+/// `next` is passed `&mut iter`, maybe reborrowed through temporaries; find `iter`.
+fn borrowed_local(body: &mir::Body<'_>, blocks: &BitSet<BasicBlock>, operand: &Operand<'_>)
+                  -> Option<Local>
+{
+    let mut local = operand.place()?.as_local()?;
+    loop {
+        let place = blocks.iter().find_map(|bb| match find_assignment(&body[bb], local) {
+            Some(&Rvalue::Ref(_, _, place)) => Some(place),
+            _ => None,
+        })?;
+        match place.projection[..] {
+            [] => { return Some(place.local); },
+            [mir::ProjectionElem::Deref] => { local = place.local; },
+            _ => { return None; },
+        }
+    }
+}
+
+/// Operands are copied into temporaries before they are compared; find the local
+/// the value was copied from.
+fn operand_root_local(data: &mir::BasicBlockData<'_>, operand: &Operand<'_>) -> Option<Local> {
+    let mut local = operand.place()?.as_local()?;
+    while let Some(Rvalue::Use(Operand::Copy(place))) = find_assignment(data, local) {
+        local = place.as_local()?;
+    }
+    Some(local)
+}
@@ -28,6 +28,7 @@ pub mod codec;
 pub mod collector;
 pub mod intrinsics;
 pub mod const_builder;
+pub mod legality;
 pub mod mir_builder;
 
 pub trait TyCtxtKernelInstance<'tcx>: HasTyCtxt<'tcx> {
@@ -1384,6 +1384,7 @@ impl Expr<'_> {
         match self.kind {
             ExprKind::Box(_) => ExprPrecedence::Box,
             ExprKind::Array(_) => ExprPrecedence::Array,
+            ExprKind::ConstBlock(_) => ExprPrecedence::ConstBlock,
             ExprKind::Call(..) => ExprPrecedence::Call,
             ExprKind::MethodCall(..) => ExprPrecedence::MethodCall,
             ExprKind::Tup(_) => ExprPrecedence::Tup,
@@ -1459,6 +1460,7 @@ impl Expr<'_> {
             | ExprKind::Block(..)
             | ExprKind::Repeat(..)
             | ExprKind::Array(..)
+            | ExprKind::ConstBlock(..)
             | ExprKind::Break(..)
             | ExprKind::Continue(..)
             | ExprKind::Ret(..)
@@ -1525,6 +1527,8 @@ pub enum ExprKind<'hir> {
     Box(&'hir Expr<'hir>),
     /// An array (e.g., `[a, b, c, d]`).
     Array(&'hir [Expr<'hir>]),
+    /// Allow anonymous constants from an inline `const` block
+    ConstBlock(AnonConst),
     /// A function call.
     ///
     /// The first field resolves to the function itself (usually an `ExprKind::Path`),
@@ -1709,6 +1713,9 @@ pub enum LocalSource {
     AsyncFn,
     /// A desugared `<expr>.await`.
     AwaitDesugar,
+    /// The `let _: ! = ELSE_BLOCK;` introduced when desugaring `let PAT = EXPR else { .. };`,
+    /// checking that the `else` block diverges.
+    LetElseDesugar,
 }
 
 /// Hints at the original code for a `match _ { .. }`.
@@ -1732,6 +1739,8 @@ pub enum MatchSource {
     TryDesugar,
     /// A desugared `<expr>.await`.
     AwaitDesugar,
+    /// A desugared `let PAT = EXPR else { .. };`.
+    LetElseDesugar,
 }
 
 impl MatchSource {
@@ -1744,6 +1753,7 @@ impl MatchSource {
             ForLoopDesugar => "for",
             TryDesugar => "?",
             AwaitDesugar => ".await",
+            LetElseDesugar => "let...else",
         }
     }
 }
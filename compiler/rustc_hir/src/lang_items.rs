@@ -340,4 +340,6 @@ language_item_table! {
     SpirvUniformObject,      sym::spirv_uniform_object,spirv_uniform_object_ty,    Target::Struct;
     SpirvTexture,            sym::spirv_texture,       spirv_texture_ty,           Target::Struct;
     SpirvSampler,            sym::spirv_sampler,       spirv_sampler_ty,           Target::Struct;
+    BpfMap,                  sym::bpf_map,             bpf_map_ty,                 Target::Struct;
+    BpfRingBuf,              sym::bpf_ring_buf,        bpf_ring_buf_ty,            Target::Struct;
 }
@@ -994,6 +994,11 @@ pub fn start_codegen<'tcx>(
 ) -> Box<dyn Any> {
     info!("Pre-codegen\n{:?}", tcx.debug_stats());
 
+    tcx.sess.time("geobacter_device_legality", || {
+        rustc_geobacter::legality::check_crate(tcx);
+    });
+    tcx.sess.abort_if_errors();
+
     let (metadata, need_metadata_module) = encode_and_write_metadata(tcx, outputs);
 
     let codegen = tcx.sess.time("codegen_crate", move || {
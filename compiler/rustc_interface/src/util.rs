@@ -241,7 +241,9 @@ pub fn get_codegen_backend(sopts: &config::Options) -> Box<dyn CodegenBackend> {
         let codegen_name = sopts.debugging_opts.codegen_backend.as_deref().unwrap_or("llvm");
         let backend = match codegen_name {
             filename if filename.contains('.') => load_backend_from_dylib(filename.as_ref()),
-            codegen_name => get_builtin_codegen_backend(codegen_name),
+            #[cfg(feature = "llvm")]
+            "llvm" => get_builtin_codegen_backend("llvm"),
+            codegen_name => get_codegen_sysroot(codegen_name),
         };
 
         unsafe {
@@ -366,6 +368,60 @@ fn sysroot_candidates() -> Vec<PathBuf> {
     }
 }
 
+/// Loads a codegen backend that isn't linked into the compiler, like `cranelift`, from the
+/// `codegen-backends` directory next to the target libdir of the sysroot, where bootstrap
+/// places `{DLL_PREFIX}rustc_codegen_{name}-{release}{DLL_SUFFIX}`.
+fn get_codegen_sysroot(backend_name: &str) -> fn() -> Box<dyn CodegenBackend> {
+    let target = session::config::host_triple();
+    let sysroot_candidates = sysroot_candidates();
+
+    let sysroot = sysroot_candidates
+        .iter()
+        .map(|sysroot| {
+            let libdir = filesearch::relative_target_lib_path(&sysroot, &target);
+            sysroot.join(libdir).with_file_name("codegen-backends")
+        })
+        .find(|f| {
+            info!("codegen backend candidate: {}", f.display());
+            f.exists()
+        });
+    let sysroot = match sysroot {
+        Some(sysroot) => sysroot,
+        None => {
+            let candidates = sysroot_candidates
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n* ");
+            let err = format!(
+                "unsupported builtin codegen backend `{}`; failed to find a \
+                 `codegen-backends` folder in the sysroot candidates:\n* {}",
+                backend_name, candidates
+            );
+            early_error(ErrorOutputType::default(), &err);
+        }
+    };
+    info!("probing {} for a codegen backend", sysroot.display());
+
+    let expected_name = format!(
+        "{}rustc_codegen_{}-{}{}",
+        env::consts::DLL_PREFIX,
+        backend_name,
+        option_env!("CFG_RELEASE").unwrap_or("unknown"),
+        env::consts::DLL_SUFFIX
+    );
+    let path = sysroot.join(&expected_name);
+    if !path.exists() {
+        let err = format!(
+            "unsupported builtin codegen backend `{}`; `{}` does not exist",
+            backend_name,
+            path.display()
+        );
+        early_error(ErrorOutputType::default(), &err);
+    }
+    load_backend_from_dylib(&path)
+}
+
 pub fn get_builtin_codegen_backend(backend_name: &str) -> fn() -> Box<dyn CodegenBackend> {
     #[cfg(feature = "llvm")]
     {
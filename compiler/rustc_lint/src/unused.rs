@@ -1,7 +1,7 @@
 use crate::Lint;
 use crate::{EarlyContext, EarlyLintPass, LateContext, LateLintPass, LintContext};
 use rustc_ast as ast;
-use rustc_ast::util::parser;
+use rustc_ast::util::{classify, parser};
 use rustc_ast::{ExprKind, StmtKind};
 use rustc_ast_pretty::pprust;
 use rustc_data_structures::fx::FxHashMap;
@@ -664,10 +664,19 @@ trait UnusedDelimLint {
     fn check_stmt(&mut self, cx: &EarlyContext<'_>, s: &ast::Stmt) {
         match s.kind {
             StmtKind::Local(ref local) if Self::LINT_EXPR_IN_PATTERN_MATCHING_CTX => {
-                if let Some(ref value) = local.init {
+                if let Some((value, els)) = local.kind.init_else_opt() {
+                    // Parentheses around an initializer ending in a `}` are required
+                    // in a `let...else` statement.
+                    if els.is_some() {
+                        if let ExprKind::Paren(ref inner) = value.kind {
+                            if !classify::expr_requires_semi_to_be_stmt(inner) {
+                                return;
+                            }
+                        }
+                    }
                     self.check_unused_delims_expr(
                         cx,
-                        &value,
+                        value,
                         UnusedDelimsCtx::AssignedValue,
                         false,
                         None,
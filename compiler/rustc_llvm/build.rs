@@ -76,6 +76,7 @@ fn main() {
         "aarch64",
         "amdgpu",
         "avr",
+        "bpf",
         "mips",
         "powerpc",
         "systemz",
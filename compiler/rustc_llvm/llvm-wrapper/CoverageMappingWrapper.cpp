@@ -6,6 +6,7 @@
 #include "llvm/Support/LEB128.h"
 
 #include <iostream>
+#include <vector>
 
 using namespace llvm;
 
@@ -24,18 +25,58 @@ extern "C" void LLVMRustCoverageWriteFilenamesSectionToBuffer(
   }
 }
 
+enum class LLVMRustCounterMappingRegionKind {
+  CodeRegion = 0,
+  ExpansionRegion = 1,
+  SkippedRegion = 2,
+  GapRegion = 3,
+  BranchRegion = 4,
+};
+
+// Mirrors `coverage::CounterMappingRegion` of LLVM 12, which added `FalseCount` for branch
+// regions, so that the Rust side doesn't depend on the LLVM version.
+struct LLVMRustCounterMappingRegion {
+  coverage::Counter Count;
+  coverage::Counter FalseCount;
+  unsigned FileID;
+  unsigned ExpandedFileID;
+  unsigned LineStart;
+  unsigned ColumnStart;
+  unsigned LineEnd;
+  unsigned ColumnEnd;
+  LLVMRustCounterMappingRegionKind Kind;
+};
+
 extern "C" void LLVMRustCoverageWriteMappingToBuffer(
     const unsigned *VirtualFileMappingIDs,
     unsigned NumVirtualFileMappingIDs,
     const coverage::CounterExpression *Expressions,
     unsigned NumExpressions,
-    coverage::CounterMappingRegion *MappingRegions,
+    LLVMRustCounterMappingRegion *RustMappingRegions,
     unsigned NumMappingRegions,
     RustStringRef BufferOut) {
+  std::vector<coverage::CounterMappingRegion> MappingRegions;
+  MappingRegions.reserve(NumMappingRegions);
+  for (const auto &Region : makeArrayRef(RustMappingRegions, NumMappingRegions)) {
+#if LLVM_VERSION_GE(12, 0)
+    MappingRegions.emplace_back(
+        Region.Count, Region.FalseCount, Region.FileID, Region.ExpandedFileID,
+        Region.LineStart, Region.ColumnStart, Region.LineEnd, Region.ColumnEnd,
+        static_cast<coverage::CounterMappingRegion::RegionKind>(Region.Kind));
+#else
+    // Branch regions can't be encoded before LLVM 12, so they are left out.
+    if (Region.Kind == LLVMRustCounterMappingRegionKind::BranchRegion)
+      continue;
+    MappingRegions.emplace_back(
+        Region.Count, Region.FileID, Region.ExpandedFileID,
+        Region.LineStart, Region.ColumnStart, Region.LineEnd, Region.ColumnEnd,
+        static_cast<coverage::CounterMappingRegion::RegionKind>(Region.Kind));
+#endif
+  }
   auto CoverageMappingWriter = coverage::CoverageMappingWriter(
       makeArrayRef(VirtualFileMappingIDs, NumVirtualFileMappingIDs),
       makeArrayRef(Expressions, NumExpressions),
-      makeMutableArrayRef(MappingRegions, NumMappingRegions));
+      MappingRegions);
   RawRustStringOstream OS(BufferOut);
   CoverageMappingWriter.write(OS);
 }
@@ -85,6 +85,10 @@ enum LLVMRustAttribute {
   ReturnsTwice = 25,
   ReadNone = 26,
   InaccessibleMemOnly = 27,
+  SanitizeHWAddress = 28,
+  StackProtectReq = 29,
+  StackProtectStrong = 30,
+  StackProtect = 31,
 };
 
 typedef struct OpaqueRustString *RustStringRef;
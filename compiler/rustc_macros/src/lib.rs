@@ -43,6 +43,7 @@ decl_derive!(
         message,
         lint,
         error,
+        slug,
         label,
         suggestion,
         suggestion_short,
@@ -39,6 +39,35 @@ use std::collections::{BTreeSet, HashMap};
 ///     opt_sugg: Some(suggestion, Applicability::MachineApplicable),
 /// });
 /// ```
+///
+/// Translatable diagnostics name their message in the catalogs of `rustc_errors::translation`
+/// with `#[slug = "..."]` instead of spelling it out. The primary message is the value of the
+/// message, `#[message]` marks its span, and `#[label]` and `#[label = "attribute"]` use the
+/// `label` attribute of the message or the given one. Fields without attributes are the
+/// arguments of the message, formatted with `Display`:
+/// ```ignore (pseudo-rust)
+/// #[derive(SessionDiagnostic)]
+/// #[error = "E0505"]
+/// #[slug = "borrowck-move-out-of-borrow"]
+/// pub struct MoveOutOfBorrowError<'tcx> {
+///     pub name: Ident,
+///     pub ty: Ty<'tcx>,
+///     #[message]
+///     #[label]
+///     pub span: Span,
+///     #[label = "first-borrow-label"]
+///     pub other_span: Span,
+///     #[suggestion(message = "suggestion", code = "{name}.clone()")]
+///     pub opt_sugg: Option<(Span, Applicability)>
+/// }
+/// ```
+/// with the message in a catalog:
+/// ```text
+/// borrowck-move-out-of-borrow = cannot move out of {$name} because it is borrowed
+///     .label = cannot move out of borrow
+///     .first-borrow-label = `{$ty}` first borrowed here
+///     .suggestion = consider cloning here
+/// ```
 pub fn session_diagnostic_derive(s: synstructure::Structure<'_>) -> proc_macro2::TokenStream {
     // Names for the diagnostic we build and the session we build it from.
     let diag = format_ident!("diag");
@@ -149,7 +178,13 @@ impl<'a> SessionDiagnosticDerive<'a> {
         }
 
         Self {
-            builder: SessionDiagnosticDeriveBuilder { diag, sess, fields: fields_map, kind: None },
+            builder: SessionDiagnosticDeriveBuilder {
+                diag,
+                sess,
+                fields: fields_map,
+                kind: None,
+                slug: None,
+            },
             structure,
         }
     }
@@ -203,8 +238,24 @@ impl<'a> SessionDiagnosticDerive<'a> {
                         DiagnosticId::Lint(_lint) => todo!(),
                         DiagnosticId::Error(code) => {
                             let (diag, sess) = (&builder.diag, &builder.sess);
+                            let init = match &builder.slug {
+                                Some((slug, _)) => {
+                                    let args = builder.fluent_args(&structure);
+                                    quote! {
+                                        #args
+                                        let mut #diag = #sess.struct_err_with_code(
+                                            &#sess.diagnostic().translate_message(#slug, None, &__fluent_args),
+                                            rustc_errors::DiagnosticId::Error(#code),
+                                        );
+                                        #diag.set_message_id(#slug);
+                                    }
+                                }
+                                None => quote! {
+                                    let mut #diag = #sess.struct_err_with_code("", rustc_errors::DiagnosticId::Error(#code));
+                                },
+                            };
                             quote! {
-                                let mut #diag = #sess.struct_err_with_code("", rustc_errors::DiagnosticId::Error(#code));
+                                #init
                                 #preamble
                                 match self {
                                     #body
@@ -268,6 +319,10 @@ struct SessionDiagnosticDeriveBuilder<'a> {
     /// stores at what Span the kind was first set at (for error reporting purposes, if the kind
     /// was multiply specified).
     kind: Option<(DiagnosticId, proc_macro2::Span)>,
+
+    /// The identifier of the message in the catalogs, if this is a translatable diagnostic.
+    /// Span stores where the slug was set, like for `kind`.
+    slug: Option<(String, proc_macro2::Span)>,
 }
 
 impl<'a> SessionDiagnosticDeriveBuilder<'a> {
@@ -277,10 +332,23 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
     ) -> Result<proc_macro2::TokenStream, SessionDiagnosticDeriveError> {
         Ok(match attr.parse_meta()? {
             syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(s), .. }) => {
-                let formatted_str = self.build_format(&s.value(), attr.span());
                 let name = attr.path.segments.last().unwrap().ident.to_string();
                 let name = name.as_str();
+                if name == "slug" {
+                    if self.slug.is_some() {
+                        throw_span_err!(s.span().unwrap(), "`slug` specified multiple times");
+                    }
+                    self.slug = Some((s.value(), s.span()));
+                    // The slug is used in the initialisation code.
+                    return Ok(quote! {});
+                }
+                let formatted_str = self.build_format(&s.value(), attr.span());
                 match name {
+                    "message" if self.slug.is_some() => throw_span_err!(
+                        attr.span().unwrap(),
+                        "`#[message = ...]` can't be used with `#[slug = ...]`, the message is \
+                         looked up in the catalogs"
+                    ),
                     "message" => {
                         let diag = &self.diag;
                         quote! {
@@ -384,6 +452,79 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
         // type.
         let meta = attr.parse_meta()?;
         Ok(match meta {
+            syn::Meta::Path(_) => {
+                let slug = match &self.slug {
+                    Some((slug, _)) => slug,
+                    None => throw_span_err!(
+                        attr.span().unwrap(),
+                        &format!("`#[{}]` can only be used with `#[slug = ...]`", name),
+                        |diag| diag.help(&format!(
+                            "use `#[{} = \"...\"]` to spell out the message instead",
+                            name
+                        ))
+                    ),
+                };
+                if !type_matches_path(&info.ty, &["rustc_span", "Span"]) {
+                    throw_span_err!(
+                        attr.span().unwrap(),
+                        &format!(
+                            "the `#[{}]` attribute can only be applied to fields of type Span",
+                            name
+                        )
+                    );
+                }
+                let sess = &self.sess;
+                match name {
+                    // The primary message is set from the slug in the initialisation code.
+                    "message" => quote! {
+                        #diag.set_span(*#field_binding);
+                    },
+                    "label" => quote! {
+                        #diag.span_label(
+                            *#field_binding,
+                            #sess.diagnostic().translate_message(#slug, Some("label"), &__fluent_args),
+                        );
+                    },
+                    other => throw_span_err!(
+                        attr.span().unwrap(),
+                        &format!("`#[{}]` is not a valid SessionDiagnostic field attribute", other)
+                    ),
+                }
+            }
+            syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(s), .. })
+                if self.slug.is_some() =>
+            {
+                let (slug, sess) = (&self.slug.as_ref().unwrap().0, &self.sess);
+                let fluent_attr = s.value();
+                match name {
+                    "label" => {
+                        if type_matches_path(&info.ty, &["rustc_span", "Span"]) {
+                            quote! {
+                                #diag.span_label(
+                                    *#field_binding,
+                                    #sess.diagnostic().translate_message(#slug, Some(#fluent_attr), &__fluent_args),
+                                );
+                            }
+                        } else {
+                            throw_span_err!(
+                                attr.span().unwrap(),
+                                "The `#[label = ...]` attribute can only be applied to fields of type Span"
+                            );
+                        }
+                    }
+                    "message" => throw_span_err!(
+                        attr.span().unwrap(),
+                        "`#[message = ...]` can't be used with `#[slug = ...]`, use `#[message]`"
+                    ),
+                    other => throw_span_err!(
+                        attr.span().unwrap(),
+                        &format!(
+                            "`#[{} = ...]` is not a valid SessionDiagnostic field attribute",
+                            other
+                        )
+                    ),
+                }
+            }
             syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(s), .. }) => {
                 let formatted_str = self.build_format(&s.value(), attr.span());
                 match name {
@@ -512,13 +653,26 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
                                         .ident
                                         .to_string();
                                     let name = name.as_str();
-                                    let formatted_str = self.build_format(&s.value(), arg.span());
                                     match name {
                                         "message" => {
-                                            msg = Some(formatted_str);
+                                            msg = Some(match &self.slug {
+                                                // The message is an attribute of the slug.
+                                                Some((slug, _)) => {
+                                                    let (sess, fluent_attr) =
+                                                        (&self.sess, s.value());
+                                                    quote! {
+                                                        #sess.diagnostic().translate_message(
+                                                            #slug,
+                                                            Some(#fluent_attr),
+                                                            &__fluent_args,
+                                                        )
+                                                    }
+                                                }
+                                                None => self.build_format(&s.value(), arg.span()),
+                                            });
                                         }
                                         "code" => {
-                                            code = Some(formatted_str);
+                                            code = Some(self.build_format(&s.value(), arg.span()));
                                         }
                                         other => throw_span_err!(
                                             arg.span().unwrap(),
@@ -559,6 +713,27 @@ impl<'a> SessionDiagnosticDeriveBuilder<'a> {
         })
     }
 
+    /// For translatable diagnostics, builds the `FluentArgs` the messages are formatted with from
+    /// the fields without attributes, which are bound to `__fluent_args`.
+    fn fluent_args(&self, structure: &synstructure::Structure<'_>) -> proc_macro2::TokenStream {
+        let mut args = vec![];
+        if let syn::Data::Struct(syn::DataStruct { fields, .. }) = &structure.ast().data {
+            for field in fields.iter().filter(|field| field.attrs.is_empty()) {
+                if let Some(ident) = &field.ident {
+                    let name = ident.to_string();
+                    args.push(quote! {
+                        __fluent_args.insert(#name, self.#ident.to_string());
+                    });
+                }
+            }
+        }
+        quote! {
+            #[allow(unused_mut)]
+            let mut __fluent_args = rustc_errors::translation::FluentArgs::default();
+            #(#args)*
+        }
+    }
+
     /// In the strings in the attributes supplied to this macro, we want callers to be able to
     /// reference fields in the format string. Take this, for example:
     /// ```ignore (not-usage-example)
@@ -1,8 +1,7 @@
 use crate::mir::mono::Linkage;
 use rustc_attr::{InlineAttr, OptimizeAttr};
-use rustc_session::config::SanitizerSet;
 use rustc_span::symbol::Symbol;
-use rustc_target::spec::AddrSpaceIdx;
+use rustc_target::spec::{AddrSpaceIdx, SanitizerSet};
 
 #[derive(Clone, TyEncodable, TyDecodable, HashStable)]
 pub struct CodegenFnAttrs {
@@ -65,6 +65,13 @@ pub enum CoverageKind {
         rhs: ExpressionOperandId,
     },
     Unreachable,
+    /// A branch region, which reports how often a condition (an `if` condition, a `&&` or `||`
+    /// operand, or a `match` arm) was taken, and how often it wasn't. Both operands refer to
+    /// counters or expressions that are injected separately.
+    Branch {
+        true_id: ExpressionOperandId,
+        false_id: ExpressionOperandId,
+    },
 }
 
 impl CoverageKind {
@@ -75,6 +82,7 @@ impl CoverageKind {
             CoverageKind::Unreachable => {
                 bug!("Unreachable coverage cannot be part of an expression")
             }
+            CoverageKind::Branch { .. } => bug!("Branch coverage cannot be part of an expression"),
         }
     }
 }
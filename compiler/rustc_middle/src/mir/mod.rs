@@ -128,7 +128,7 @@ pub struct Body<'tcx> {
 
     /// A list of source scopes; these are referenced by statements
     /// and used for debuginfo. Indexed by a `SourceScope`.
-    pub source_scopes: IndexVec<SourceScope, SourceScopeData>,
+    pub source_scopes: IndexVec<SourceScope, SourceScopeData<'tcx>>,
 
     /// The yield type of the function, if it is a generator.
     pub yield_ty: Option<Ty<'tcx>>,
@@ -210,7 +210,7 @@ pub struct Body<'tcx> {
 impl<'tcx> Body<'tcx> {
     pub fn new(
         basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>,
-        source_scopes: IndexVec<SourceScope, SourceScopeData>,
+        source_scopes: IndexVec<SourceScope, SourceScopeData<'tcx>>,
         local_decls: LocalDecls<'tcx>,
         user_type_annotations: CanonicalUserTypeAnnotations<'tcx>,
         arg_count: usize,
@@ -1581,22 +1581,30 @@ impl Debug for Statement<'_> {
                 write!(fmt, "AscribeUserType({:?}, {:?}, {:?})", place, variance, c_ty)
             }
             Coverage(box ref coverage) => {
-                let rgn = &coverage.code_region;
                 match coverage.kind {
                     CoverageKind::Counter { id, .. } => {
-                        write!(fmt, "Coverage::Counter({:?}) for {:?}", id.index(), rgn)
+                        write!(fmt, "Coverage::Counter({:?})", id.index())?
                     }
                     CoverageKind::Expression { id, lhs, op, rhs } => write!(
                         fmt,
-                        "Coverage::Expression({:?}) = {} {} {} for {:?}",
+                        "Coverage::Expression({:?}) = {} {} {}",
                         id.index(),
                         lhs.index(),
                         if op == coverage::Op::Add { "+" } else { "-" },
                         rhs.index(),
-                        rgn
-                    ),
-                    CoverageKind::Unreachable => write!(fmt, "Coverage::Unreachable for {:?}", rgn),
+                    )?,
+                    CoverageKind::Unreachable => write!(fmt, "Coverage::Unreachable")?,
+                    CoverageKind::Branch { true_id, false_id } => write!(
+                        fmt,
+                        "Coverage::Branch(true: {}, false: {})",
+                        true_id.index(),
+                        false_id.index()
+                    )?,
+                }
+                if let Some(rgn) = &coverage.code_region {
+                    write!(fmt, " for {:?}", rgn)?;
                 }
+                Ok(())
             }
             Nop => write!(fmt, "nop"),
         }
@@ -1606,7 +1614,9 @@ impl Debug for Statement<'_> {
 #[derive(Clone, Debug, PartialEq, TyEncodable, TyDecodable, HashStable, TypeFoldable)]
 pub struct Coverage {
     pub kind: CoverageKind,
-    pub code_region: CodeRegion,
+    /// The source code counted by `kind`. Expressions that are only used as operands of other
+    /// expressions or of branch regions have no region of their own.
+    pub code_region: Option<CodeRegion>,
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -1849,10 +1859,20 @@ rustc_index::newtype_index! {
 }
 
 #[derive(Clone, Debug, TyEncodable, TyDecodable, HashStable)]
-pub struct SourceScopeData {
+pub struct SourceScopeData<'tcx> {
     pub span: Span,
     pub parent_scope: Option<SourceScope>,
 
+    /// Whether this scope is the root of a scope tree of another body,
+    /// inlined into this body by the MIR inliner.
+    /// `ty::Instance` is the callee, and the `Span` is the call site.
+    pub inlined: Option<(ty::Instance<'tcx>, Span)>,
+
+    /// Nearest (transitive) parent scope (if any) which is inlined.
+    /// This is an optimization over walking up `parent_scope`
+    /// until a scope with `inlined: Some(...)` is found.
+    pub inlined_parent_scope: Option<SourceScope>,
+
     /// Crate-local information for this source scope, that can't (and
     /// needn't) be tracked across crates.
     pub local_data: ClearCrossCrate<SourceScopeLocalData>,
@@ -2269,11 +2289,11 @@ impl<'tcx> Debug for Rvalue<'tcx> {
                             };
                             let mut struct_fmt = fmt.debug_struct(&name);
 
-                            if let Some(upvars) = tcx.upvars_mentioned(def_id) {
-                                for (&var_id, place) in upvars.keys().zip(places) {
-                                    let var_name = tcx.hir().name(var_id);
-                                    struct_fmt.field(&var_name.as_str(), place);
-                                }
+                            let typeck_results = tcx.typeck(def_id);
+                            let captured_places =
+                                typeck_results.closure_min_captures_flattened(def_id.to_def_id());
+                            for (captured_place, place) in captured_places.zip(places) {
+                                struct_fmt.field(&captured_place.to_string(tcx), place);
                             }
 
                             struct_fmt.finish()
@@ -2288,11 +2308,11 @@ impl<'tcx> Debug for Rvalue<'tcx> {
                             let name = format!("[generator@{:?}]", tcx.hir().span(hir_id));
                             let mut struct_fmt = fmt.debug_struct(&name);
 
-                            if let Some(upvars) = tcx.upvars_mentioned(def_id) {
-                                for (&var_id, place) in upvars.keys().zip(places) {
-                                    let var_name = tcx.hir().name(var_id);
-                                    struct_fmt.field(&var_name.as_str(), place);
-                                }
+                            let typeck_results = tcx.typeck(def_id);
+                            let captured_places =
+                                typeck_results.closure_min_captures_flattened(def_id.to_def_id());
+                            for (captured_place, place) in captured_places.zip(places) {
+                                struct_fmt.field(&captured_place.to_string(tcx), place);
                             }
 
                             struct_fmt.finish()
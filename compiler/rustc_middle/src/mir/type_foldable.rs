@@ -10,11 +10,26 @@ CloneTypeFoldableAndLiftImpls! {
     FakeReadCause,
     RetagKind,
     SourceScope,
-    SourceScopeData,
     SourceScopeLocalData,
     UserTypeAnnotationIndex,
 }
 
+impl<'tcx> TypeFoldable<'tcx> for SourceScopeData<'tcx> {
+    fn super_fold_with<F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Self {
+        SourceScopeData {
+            span: self.span,
+            parent_scope: self.parent_scope,
+            inlined: self.inlined.fold_with(folder),
+            inlined_parent_scope: self.inlined_parent_scope,
+            local_data: self.local_data.clone(),
+        }
+    }
+
+    fn super_visit_with<V: TypeVisitor<'tcx>>(&self, visitor: &mut V) -> bool {
+        self.inlined.visit_with(visitor)
+    }
+}
+
 impl<'tcx> TypeFoldable<'tcx> for Terminator<'tcx> {
     fn super_fold_with<F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Self {
         use crate::mir::TerminatorKind::*;
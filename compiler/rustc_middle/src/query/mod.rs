@@ -306,6 +306,28 @@ rustc_queries! {
             }
         }
 
+        /// Checks whether `key.1` can be reached from the MIR of `key.0` by following calls.
+        /// The MIR inliner uses this to avoid requesting the optimized MIR of a callee whose
+        /// own optimization would in turn inline the caller, which would be a query cycle.
+        query mir_callgraph_reachable(key: (ty::Instance<'tcx>, LocalDefId)) -> bool {
+            fatal_cycle
+            desc { |tcx|
+                "computing if `{}` (transitively) calls `{}`",
+                key.0,
+                tcx.def_path_str(key.1.to_def_id()),
+            }
+        }
+
+        /// Obtains all the calls a function makes, for the call graph walk of
+        /// `mir_callgraph_reachable`.
+        query mir_inliner_callees(key: ty::InstanceDef<'tcx>) -> &'tcx [(DefId, SubstsRef<'tcx>)] {
+            fatal_cycle
+            desc { |tcx|
+                "computing all function calls in `{}`",
+                tcx.def_path_str(key.def_id()),
+            }
+        }
+
         /// If defined by the driver, returns the extra mir statements to codegen,
         /// else returns `None`.
         query custom_intrinsic_mirgen(key: DefId) -> Option<Lrc<dyn mir::CustomIntrinsicMirGen>> {
@@ -886,6 +908,21 @@ rustc_queries! {
                             -> &'tcx [Option<(DefId, SubstsRef<'tcx>)>] {
             desc { |tcx| "finding all methods for trait {}", tcx.def_path_str(key.def_id()) }
         }
+
+        /// Returns the strict supertraits of `key` whose vtable pointers are stored
+        /// in its vtable, right after its methods.
+        query vtable_supertraits(key: ty::PolyTraitRef<'tcx>) -> &'tcx [ty::PolyTraitRef<'tcx>] {
+            desc { |tcx| "finding the supertrait vtables of {}", tcx.def_path_str(key.def_id()) }
+        }
+
+        /// Given the source and target trait object types of a trait upcasting coercion,
+        /// returns the index (after the drop, size and align entries) of the vtable entry
+        /// holding the target's vtable pointer, or `None` if the source vtable can be reused.
+        query vtable_trait_upcasting_coercion_new_vptr_slot(
+            key: (Ty<'tcx>, Ty<'tcx>)
+        ) -> Option<usize> {
+            desc { "finding the vtable slot for upcasting `{}` to `{}`", key.0, key.1 }
+        }
     }
 
     Codegen {
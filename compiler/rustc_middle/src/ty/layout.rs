@@ -13,7 +13,7 @@ use rustc_hir as hir;
 use rustc_hir::lang_items::LangItem;
 use rustc_index::bit_set::BitSet;
 use rustc_index::vec::{Idx, IndexVec};
-use rustc_session::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+use rustc_session::{DataTypeKind, FieldInfo, NicheInfo, SizeKind, VariantInfo};
 use rustc_span::symbol::{Ident, Symbol};
 use rustc_span::DUMMY_SP;
 use rustc_target::abi::call::{
@@ -22,6 +22,9 @@ use rustc_target::abi::call::{
 use rustc_target::abi::*;
 use rustc_target::spec::{abi::Abi as SpecAbi, HasTargetSpec, PanicStrategy};
 
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_xoshiro::Xoshiro128StarStar;
+
 use std::cmp;
 use std::fmt;
 use std::iter;
@@ -386,19 +389,27 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
             let field_align = |f: &TyAndLayout<'_>| {
                 if let Some(pack) = pack { f.align.abi.min(pack) } else { f.align.abi }
             };
-            match kind {
-                StructKind::AlwaysSized | StructKind::MaybeUnsized => {
-                    optimizing.sort_by_key(|&x| {
-                        // Place ZSTs first to avoid "interesting offsets",
-                        // especially with only one or two non-ZST fields.
-                        let f = &fields[x as usize];
-                        (!f.is_zst(), cmp::Reverse(field_align(f)))
-                    });
-                }
-                StructKind::Prefixed(..) => {
-                    // Sort in ascending alignment so that the layout stay optimal
-                    // regardless of the prefix
-                    optimizing.sort_by_key(|&x| field_align(&fields[x as usize]));
+
+            // With `-Z randomize-layout`, the fields are shuffled instead of sorted, using the
+            // same seed for the type every time, so that every crate agrees on its layout.
+            if repr.can_randomize_type_layout() {
+                let mut rng = Xoshiro128StarStar::seed_from_u64(repr.field_shuffle_seed);
+                optimizing.shuffle(&mut rng);
+            } else {
+                match kind {
+                    StructKind::AlwaysSized | StructKind::MaybeUnsized => {
+                        optimizing.sort_by_key(|&x| {
+                            // Place ZSTs first to avoid "interesting offsets",
+                            // especially with only one or two non-ZST fields.
+                            let f = &fields[x as usize];
+                            (!f.is_zst(), cmp::Reverse(field_align(f)))
+                        });
+                    }
+                    StructKind::Prefixed(..) => {
+                        // Sort in ascending alignment so that the layout stay optimal
+                        // regardless of the prefix
+                        optimizing.sort_by_key(|&x| field_align(&fields[x as usize]));
+                    }
                 }
             }
         }
@@ -1765,7 +1776,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
     fn record_layout_for_printing(&self, layout: TyAndLayout<'tcx>) {
         // If we are running with `-Zprint-type-sizes`, maybe record layouts
         // for dumping later.
-        if self.tcx.sess.opts.debugging_opts.print_type_sizes {
+        if self.tcx.sess.opts.debugging_opts.print_type_sizes.is_some() {
             self.record_layout_for_printing_outlined(layout)
         }
     }
@@ -1779,8 +1790,18 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
         }
 
         // (delay format until we actually need it)
-        let record = |kind, packed, opt_discr_size, variants| {
+        let record = |kind, def_id, repr: Option<&ReprOptions>, opt_discr_size, variants| {
+            let packed = repr.map_or(false, |repr| repr.packed());
+            let field_shuffle_seed = repr
+                .filter(|repr| repr.can_randomize_type_layout())
+                .map(|repr| repr.field_shuffle_seed);
             let type_desc = format!("{:?}", layout.ty);
+            let span = self.tcx.sess.source_map().span_to_string(self.tcx.def_span(def_id));
+            let niche = layout.largest_niche.as_ref().map(|niche| NicheInfo {
+                offset: niche.offset.bytes(),
+                size: niche.scalar.value.size(self).bytes(),
+                available: niche.available(self),
+            });
             self.tcx.sess.code_stats.record_type_size(
                 kind,
                 type_desc,
@@ -1788,7 +1809,10 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                 layout.size,
                 packed,
                 opt_discr_size,
+                niche,
                 variants,
+                Some(span),
+                field_shuffle_seed,
             );
         };
 
@@ -1798,9 +1822,9 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                 adt_def
             }
 
-            ty::Closure(..) => {
+            ty::Closure(def_id, _) => {
                 debug!("print-type-size t: `{:?}` record closure", layout.ty);
-                record(DataTypeKind::Closure, false, None, vec![]);
+                record(DataTypeKind::Closure, def_id, None, None, vec![]);
                 return;
             }
 
@@ -1811,7 +1835,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
         };
 
         let adt_kind = adt_def.adt_kind();
-        let adt_packed = adt_def.repr.pack.is_some();
+        let adt_repr = Some(&adt_def.repr);
 
         let build_variant_info = |n: Option<Ident>, flds: &[Symbol], layout: TyAndLayout<'tcx>| {
             let mut min_size = Size::ZERO;
@@ -1855,14 +1879,15 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                     let fields: Vec<_> = variant_def.fields.iter().map(|f| f.ident.name).collect();
                     record(
                         adt_kind.into(),
-                        adt_packed,
+                        adt_def.did,
+                        adt_repr,
                         None,
                         vec![build_variant_info(Some(variant_def.ident), &fields, layout)],
                     );
                 } else {
                     // (This case arises for *empty* enums; so give it
                     // zero variants.)
-                    record(adt_kind.into(), adt_packed, None, vec![]);
+                    record(adt_kind.into(), adt_def.did, adt_repr, None, vec![]);
                 }
             }
 
@@ -1887,7 +1912,8 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                     .collect();
                 record(
                     adt_kind.into(),
-                    adt_packed,
+                    adt_def.did,
+                    adt_repr,
                     match tag_encoding {
                         TagEncoding::Direct => Some(tag.value.size(self)),
                         _ => None,
@@ -6,6 +6,9 @@ pub use self::IntVarValue::*;
 pub use self::Variance::*;
 
 use crate::hir::exports::ExportMap;
+use crate::hir::place::{
+    Place as HirPlace, PlaceBase as HirPlaceBase, ProjectionKind as HirProjectionKind,
+};
 use crate::ich::StableHashingContext;
 use crate::infer::canonical::Canonical;
 use crate::middle::cstore::CrateStoreDyn;
@@ -757,6 +760,82 @@ pub struct UpvarBorrow<'tcx> {
 pub type UpvarListMap = FxHashMap<DefId, FxIndexMap<hir::HirId, UpvarId>>;
 pub type UpvarCaptureMap<'tcx> = FxHashMap<UpvarId, UpvarCapture<'tcx>>;
 
+/// Given the closure `DefId`, this map provides a map of root variables to the minimum
+/// set of `CapturedPlace`s that need to be tracked to support all captures of that closure.
+pub type MinCaptureInformationMap<'tcx> = FxHashMap<DefId, RootVariableMinCaptureList<'tcx>>;
+
+/// Part of `MinCaptureInformationMap`; maps a root variable to the list of `CapturedPlace`s
+/// starting at that variable that are captured by the closure.
+pub type RootVariableMinCaptureList<'tcx> = FxIndexMap<hir::HirId, MinCaptureList<'tcx>>;
+
+/// Part of `MinCaptureInformationMap`; none of the places in the list is an ancestor
+/// of another one.
+pub type MinCaptureList<'tcx> = Vec<CapturedPlace<'tcx>>;
+
+/// A `Place` captured by a closure, and how it is captured.
+#[derive(PartialEq, Clone, Debug, TyEncodable, TyDecodable, HashStable)]
+pub struct CapturedPlace<'tcx> {
+    pub place: HirPlace<'tcx>,
+    pub info: CaptureInfo<'tcx>,
+    /// Whether the place can be mutated through the capture, which depends on the binding
+    /// of the root variable and on the references it is accessed through.
+    pub mutability: hir::Mutability,
+}
+
+impl<'tcx> CapturedPlace<'tcx> {
+    /// Returns the `HirId` of the variable this place is based on.
+    pub fn get_root_variable(&self) -> hir::HirId {
+        match self.place.base {
+            HirPlaceBase::Upvar(upvar_id) => upvar_id.var_path.hir_id,
+            base => bug!("expected an upvar, found {:?}", base),
+        }
+    }
+
+    /// Returns the captured place as written in the source, e.g. `p.x`, for diagnostics.
+    /// Derefs are left out, as they mostly come from autoderef.
+    pub fn to_string(&self, tcx: TyCtxt<'tcx>) -> String {
+        self.path_with_separator(tcx, ".")
+    }
+
+    /// Returns a name for the captured place, for debuginfo. Fields are appended to the
+    /// name of the root variable, so `p.x` becomes `p__x`.
+    pub fn to_symbol(&self, tcx: TyCtxt<'tcx>) -> Symbol {
+        Symbol::intern(&self.path_with_separator(tcx, "__"))
+    }
+
+    fn path_with_separator(&self, tcx: TyCtxt<'tcx>, separator: &str) -> String {
+        let mut path = tcx.hir().name(self.get_root_variable()).to_string();
+        let mut ty = self.place.base_ty;
+        for projection in &self.place.projections {
+            match projection.kind {
+                HirProjectionKind::Field(index, variant) => match ty.kind() {
+                    ty::Tuple(_) => path.push_str(&format!("{}{}", separator, index)),
+                    ty::Adt(def, _) => {
+                        let field = &def.variants[variant].fields[index as usize];
+                        path.push_str(&format!("{}{}", separator, field.ident));
+                    }
+                    ty => bug!("unexpected type {:?} for a field projection", ty),
+                },
+                HirProjectionKind::Deref => {}
+                kind => bug!("unexpected projection {:?} in a captured place", kind),
+            }
+            ty = projection.ty;
+        }
+        path
+    }
+}
+
+/// Describes the capture kind (`&`, `&mut`, move) of a captured place, along with the
+/// use of that place which required that capture kind.
+#[derive(PartialEq, Clone, Debug, Copy, TyEncodable, TyDecodable, HashStable)]
+pub struct CaptureInfo<'tcx> {
+    /// The expression or pattern which resulted in selecting the current capture kind,
+    /// if any. Places which are captured because of the capture clause of the closure
+    /// don't have one.
+    pub expr_id: Option<hir::HirId>,
+    pub capture_kind: UpvarCapture<'tcx>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum IntVarValue {
     IntType(ast::IntTy),
@@ -2203,6 +2282,8 @@ bitflags! {
         const IS_LINEAR          = 1 << 3;
         // If true, don't expose any niche to type's context.
         const HIDE_NICHE         = 1 << 4;
+        // If true, the fields are shuffled rather than sorted (`-Z randomize-layout`).
+        const RANDOMIZE_LAYOUT   = 1 << 5;
         // Any of these flags being set prevent field reordering optimisation.
         const IS_UNOPTIMISABLE   = ReprFlags::IS_C.bits |
                                    ReprFlags::IS_SIMD.bits |
@@ -2217,6 +2298,9 @@ pub struct ReprOptions {
     pub align: Option<Align>,
     pub pack: Option<Align>,
     pub flags: ReprFlags,
+    /// The seed that fields are shuffled with if the layout is randomized. It is derived from
+    /// the `DefPathHash` of the type, so it is stable across compilations.
+    pub field_shuffle_seed: u64,
 }
 
 impl ReprOptions {
@@ -2257,7 +2341,17 @@ impl ReprOptions {
         if !tcx.consider_optimizing(|| format!("Reorder fields of {:?}", tcx.def_path_str(did))) {
             flags.insert(ReprFlags::IS_LINEAR);
         }
-        ReprOptions { int: size, align: max_align, pack: min_pack, flags }
+
+        // Same here: the crate defining the type decides whether its layout is randomized, so
+        // that every crate using it agrees on the layout.
+        let mut field_shuffle_seed = 0;
+        if tcx.sess.opts.debugging_opts.randomize_layout {
+            flags.insert(ReprFlags::RANDOMIZE_LAYOUT);
+            let salt = tcx.sess.opts.debugging_opts.layout_seed.unwrap_or(0) as u64;
+            field_shuffle_seed = tcx.def_path_hash(did).0.to_smaller_hash() ^ salt;
+        }
+
+        ReprOptions { int: size, align: max_align, pack: min_pack, flags, field_shuffle_seed }
     }
 
     #[inline]
@@ -2284,6 +2378,10 @@ impl ReprOptions {
     pub fn hide_niche(&self) -> bool {
         self.flags.contains(ReprFlags::HIDE_NICHE)
     }
+    #[inline]
+    pub fn randomize_layout(&self) -> bool {
+        self.flags.contains(ReprFlags::RANDOMIZE_LAYOUT)
+    }
 
     /// Returns the discriminant type, given these `repr` options.
     /// This must only be called on enums!
@@ -2309,6 +2407,13 @@ impl ReprOptions {
         self.flags.intersects(ReprFlags::IS_UNOPTIMISABLE) || self.int.is_some()
     }
 
+    /// Returns `true` if the fields should be shuffled instead of sorted by the layout
+    /// optimizations, which only happens with `-Z randomize-layout` for types that may be
+    /// reordered at all.
+    pub fn can_randomize_type_layout(&self) -> bool {
+        !self.inhibit_struct_field_reordering_opt() && self.randomize_layout()
+    }
+
     /// Returns `true` if this `#[repr()]` should inhibit union ABI optimisations.
     pub fn inhibit_union_abi_opt(&self) -> bool {
         self.c()
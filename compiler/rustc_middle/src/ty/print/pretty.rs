@@ -653,7 +653,8 @@ pub trait PrettyPrinter<'tcx>:
                     if let Some(did) = did.as_local() {
                         let hir_id = self.tcx().hir().local_def_id_to_hir_id(did);
                         let span = self.tcx().hir().span(hir_id);
-                        p!(write("@{}", self.tcx().sess.source_map().span_to_string(span)));
+                        let sm = self.tcx().sess.source_map();
+                        p!(write("@{}", sm.span_to_embeddable_string(span)));
                     } else {
                         p!(write("@{}", self.tcx().def_path_str(did)));
                     }
@@ -693,7 +694,8 @@ pub trait PrettyPrinter<'tcx>:
                             p!(write("@"), print_def_path(did.to_def_id(), substs));
                         } else {
                             let span = self.tcx().hir().span(hir_id);
-                            p!(write("@{}", self.tcx().sess.source_map().span_to_string(span)));
+                            let sm = self.tcx().sess.source_map();
+                            p!(write("@{}", sm.span_to_embeddable_string(span)));
                         }
                     } else {
                         p!(write("@{}", self.tcx().def_path_str(did)));
@@ -1381,7 +1383,8 @@ impl<F: fmt::Write> Printer<'tcx> for FmtPrinter<'_, 'tcx, F> {
                 if !self.empty_path {
                     write!(self, "::")?;
                 }
-                write!(self, "<impl at {}>", self.tcx.sess.source_map().span_to_string(span))?;
+                let sm = self.tcx.sess.source_map();
+                write!(self, "<impl at {}>", sm.span_to_embeddable_string(span))?;
                 self.empty_path = false;
 
                 return Ok(self);
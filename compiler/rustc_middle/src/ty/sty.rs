@@ -378,7 +378,7 @@ impl<'tcx> ClosureSubsts<'tcx> {
 
     #[inline]
     pub fn upvar_tys(self) -> impl Iterator<Item = Ty<'tcx>> + 'tcx {
-        self.tupled_upvars_ty().tuple_fields()
+        upvar_tys_of_tuple(self.tupled_upvars_ty())
     }
 
     /// Returns the tuple type representing the upvars for this closure.
@@ -505,7 +505,7 @@ impl<'tcx> GeneratorSubsts<'tcx> {
 
     #[inline]
     pub fn upvar_tys(self) -> impl Iterator<Item = Ty<'tcx>> + 'tcx {
-        self.tupled_upvars_ty().tuple_fields()
+        upvar_tys_of_tuple(self.tupled_upvars_ty())
     }
 
     /// Returns the tuple type representing the upvars for this generator.
@@ -654,8 +654,31 @@ impl<'tcx> UpvarSubsts<'tcx> {
             UpvarSubsts::Closure(substs) => substs.as_closure().split().tupled_upvars_ty,
             UpvarSubsts::Generator(substs) => substs.as_generator().split().tupled_upvars_ty,
         };
-        tupled_upvars_ty.expect_ty().tuple_fields()
+        upvar_tys_of_tuple(tupled_upvars_ty.expect_ty())
     }
+
+    #[inline]
+    pub fn tupled_upvars_ty(self) -> Ty<'tcx> {
+        match self {
+            UpvarSubsts::Closure(substs) => substs.as_closure().tupled_upvars_ty(),
+            UpvarSubsts::Generator(substs) => substs.as_generator().tupled_upvars_ty(),
+        }
+    }
+}
+
+/// Returns the fields of the tuple of upvar types of a closure or generator. With
+/// `capture_disjoint_fields`, that tuple is a single type variable until upvar analysis
+/// is done, so callers which may run during typeck must use the tuple type itself.
+fn upvar_tys_of_tuple<'tcx>(tupled_upvars_ty: Ty<'tcx>) -> impl Iterator<Item = Ty<'tcx>> + 'tcx {
+    match tupled_upvars_ty.kind() {
+        // Capture analysis did not complete because of an earlier error.
+        Error(_) => None,
+        Tuple(..) => Some(tupled_upvars_ty.tuple_fields()),
+        Infer(_) => bug!("upvar types requested before capture analysis"),
+        _ => bug!("unexpected tupled upvar types: {:?}", tupled_upvars_ty),
+    }
+    .into_iter()
+    .flatten()
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Ord, Eq, Hash, TyEncodable, TyDecodable)]
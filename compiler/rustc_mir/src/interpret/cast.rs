@@ -276,11 +276,24 @@ impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
                 self.write_immediate(val, dest)
             }
             (&ty::Dynamic(..), &ty::Dynamic(..)) => {
-                // For now, upcasts are limited to changes in marker
-                // traits, and hence never actually require an actual
-                // change to the vtable.
                 let val = self.read_immediate(src)?;
-                self.write_immediate(*val, dest)
+                // Changes in marker traits or lifetime bounds keep the vtable
+                // as is; upcasting to a supertrait reads its vtable pointer
+                // from the old vtable.
+                match self.tcx.vtable_trait_upcasting_coercion_new_vptr_slot((
+                    src_pointee_ty,
+                    dest_pointee_ty,
+                )) {
+                    Some(idx) => {
+                        let (ptr, old_vtable) = val.to_scalar_pair()?;
+                        let vtable = self.read_supertrait_vtable_from_vtable(
+                            old_vtable,
+                            u64::try_from(idx).unwrap(),
+                        )?;
+                        self.write_immediate(Immediate::new_dyn_trait(ptr, vtable), dest)
+                    }
+                    None => self.write_immediate(*val, dest),
+                }
             }
             (_, &ty::Dynamic(ref data, _)) => {
                 // Initial cast from sized to dyn trait
@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 use rustc_hir::lang_items::LangItem;
 use rustc_middle::mir::TerminatorKind;
 use rustc_middle::ty::subst::Subst;
+use rustc_span::source_map::RemapPathScopes;
 use rustc_span::{Span, Symbol};
 use rustc_target::abi::LayoutOf;
 
@@ -81,9 +82,12 @@ impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
 
     crate fn location_triple_for_span(&self, span: Span) -> (Symbol, u32, u32) {
         let topmost = span.ctxt().outer_expn().expansion_cause().unwrap_or(span);
-        let caller = self.tcx.sess.source_map().lookup_char_pos(topmost.lo());
+        let source_map = self.tcx.sess.source_map();
+        let caller = source_map.lookup_char_pos(topmost.lo());
         (
-            Symbol::intern(&caller.file.name.to_string()),
+            Symbol::intern(
+                &source_map.filename_for_scope(&caller.file, RemapPathScopes::MACRO).to_string(),
+            ),
             u32::try_from(caller.line).unwrap(),
             u32::try_from(caller.col_display).unwrap().checked_add(1).unwrap(),
         )
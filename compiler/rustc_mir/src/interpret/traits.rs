@@ -35,13 +35,13 @@ impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
             return Ok(vtable);
         }
 
-        let methods = if let Some(poly_trait_ref) = poly_trait_ref {
+        let (methods, supertraits) = if let Some(poly_trait_ref) = poly_trait_ref {
             let trait_ref = poly_trait_ref.with_self_ty(*self.tcx, ty);
             let trait_ref = self.tcx.erase_regions(&trait_ref);
 
-            self.tcx.vtable_methods(trait_ref)
+            (self.tcx.vtable_methods(trait_ref), self.tcx.vtable_supertraits(trait_ref))
         } else {
-            &[]
+            (&[][..], &[][..])
         };
 
         let layout = self.layout_of(ty)?;
@@ -57,7 +57,8 @@ impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
         // `get_vtable` in `rust_codegen_llvm/meth.rs`.
         // /////////////////////////////////////////////////////////////////////////////////////////
         let vtable = self.memory.allocate(
-            ptr_size * u64::try_from(methods.len()).unwrap().checked_add(3).unwrap(),
+            ptr_size
+                * u64::try_from(methods.len() + supertraits.len()).unwrap().checked_add(3).unwrap(),
             ptr_align,
             MemoryKind::Vtable,
         );
@@ -93,6 +94,19 @@ impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
             }
         }
 
+        // The supertrait vtables come last, for trait upcasting coercions.
+        for (i, supertrait_ref) in supertraits.iter().enumerate() {
+            let supertrait_ref = supertrait_ref
+                .map_bound(|trait_ref| ty::ExistentialTraitRef::erase_self_ty(tcx, trait_ref));
+            let supertrait_vtable = self.get_vtable(ty, Some(supertrait_ref))?;
+            let vtable_ptr = vtable.offset(ptr_size * (3 + (methods.len() + i) as u64), &tcx)?;
+            self.memory.get_raw_mut(vtable.alloc_id)?.write_ptr_sized(
+                &tcx,
+                vtable_ptr,
+                supertrait_vtable.into(),
+            )?;
+        }
+
         self.memory.mark_immutable(vtable.alloc_id)?;
         assert!(self.vtables.insert((ty, poly_trait_ref), vtable).is_none());
 
@@ -122,6 +136,29 @@ impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
         Ok(self.memory.get_fn(fn_ptr)?)
     }
 
+    /// Reads the vtable pointer of a supertrait stored at the specified slot
+    /// in the provided vtable, as computed by
+    /// `vtable_trait_upcasting_coercion_new_vptr_slot`.
+    pub fn read_supertrait_vtable_from_vtable(
+        &self,
+        vtable: Scalar<M::PointerTag>,
+        idx: u64,
+    ) -> InterpResult<'tcx, Pointer<M::PointerTag>> {
+        let ptr_size = self.pointer_size();
+        // Skip over the 'drop_ptr', 'size', and 'align' fields.
+        let vtable_slot = vtable.ptr_offset(ptr_size * idx.checked_add(3).unwrap(), self)?;
+        let vtable_slot = self
+            .memory
+            .check_ptr_access(vtable_slot, ptr_size, self.tcx.data_layout.pointer_align.abi)?
+            .expect("cannot be a ZST");
+        let new_vtable = self
+            .memory
+            .get_raw(vtable_slot.alloc_id)?
+            .read_ptr_sized(self, vtable_slot)?
+            .check_init()?;
+        self.memory.force_ptr(new_vtable)
+    }
+
     /// Returns the drop fn instance as well as the actual dynamic type.
     pub fn read_drop_type_from_vtable(
         &self,
@@ -231,16 +231,15 @@ impl<'rt, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> ValidityVisitor<'rt, 'mir, '
                 let mut name = None;
                 if let Some(def_id) = def_id.as_local() {
                     let tables = self.ecx.tcx.typeck(def_id);
-                    if let Some(upvars) = tables.closure_captures.get(&def_id.to_def_id()) {
-                        // Sometimes the index is beyond the number of upvars (seen
-                        // for a generator).
-                        if let Some((&var_hir_id, _)) = upvars.get_index(field) {
-                            let node = self.ecx.tcx.hir().get(var_hir_id);
-                            if let hir::Node::Binding(pat) = node {
-                                if let hir::PatKind::Binding(_, _, ident, _) = pat.kind {
-                                    name = Some(ident.name);
-                                }
-                            }
+                    // Sometimes the index is beyond the number of upvars (seen
+                    // for a generator).
+                    if let Some(captured_place) =
+                        tables.closure_min_captures_flattened(def_id.to_def_id()).nth(field)
+                    {
+                        let node = self.ecx.tcx.hir().get(captured_place.get_root_variable());
+                        if let hir::Node::Binding(_) = node {
+                            let tcx = *self.ecx.tcx;
+                            name = Some(Symbol::intern(&captured_place.to_string(tcx)));
                         }
                     }
                 }
@@ -956,7 +956,9 @@ fn create_mono_items_for_vtable_methods<'tcx>(
             let poly_trait_ref = principal.with_self_ty(tcx, impl_ty);
             assert!(!poly_trait_ref.has_escaping_bound_vars());
 
-            // Walk all methods of the trait, including those of its supertraits
+            // Walk all methods of the trait, including those of its supertraits.
+            // This also covers the supertrait vtables embedded in this vtable,
+            // whose methods are a subset of these.
             let methods = tcx.vtable_methods(poly_trait_ref);
             let methods = methods
                 .iter()
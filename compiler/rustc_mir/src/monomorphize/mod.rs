@@ -36,6 +36,8 @@ fn custom_intrinsic_mir<'tcx>(tcx: TyCtxt<'tcx>,
     let source_scope = mir::SourceScopeData {
         span: DUMMY_SP,
         parent_scope: None,
+        inlined: None,
+        inlined_parent_scope: None,
         local_data: source_scope_local_data,
     };
     let source_info = mir::SourceInfo {
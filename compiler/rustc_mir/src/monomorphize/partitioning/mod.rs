@@ -97,14 +97,21 @@ mod merging;
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync;
-use rustc_hir::def_id::{CrateNum, DefIdSet, LOCAL_CRATE};
+use rustc_hir::def_id::{CrateNum, DefId, DefIdSet, LOCAL_CRATE};
+use rustc_macros::Encodable;
 use rustc_middle::mir::mono::MonoItem;
 use rustc_middle::mir::mono::{CodegenUnit, Linkage};
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::query::Providers;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{Instance, TyCtxt};
+use rustc_serialize::json::as_pretty_json;
+use rustc_session::config::MonoStatsFormat;
 use rustc_span::symbol::Symbol;
 
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
 use crate::monomorphize::collector::InliningMap;
 use crate::monomorphize::collector::{self, MonoItemCollectionMode};
 
@@ -413,9 +420,175 @@ fn collect_and_partition_mono_items<'tcx>(
         }
     }
 
+    if let Some(format) = tcx.sess.opts.debugging_opts.dump_mono_stats {
+        let path = tcx.output_filenames(LOCAL_CRATE).with_extension(format.extension());
+        let result = tcx.sess.time("dump_mono_items_stats", || {
+            dump_mono_items_stats(tcx, codegen_units, &items, &inlining_map, format, &path)
+        });
+        if let Err(e) = result {
+            tcx.sess.err(&format!(
+                "failed to write mono item stats to `{}`: {}",
+                path.display(),
+                e
+            ));
+        }
+    }
+
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
+#[derive(Encodable)]
+struct MonoStats {
+    crate_name: String,
+    items: Vec<MonoItemStats>,
+}
+
+/// The instances of a generic function, closure or shim.
+#[derive(Encodable)]
+struct MonoItemStats {
+    name: String,
+    /// The crate that defines the item.
+    defined_in: String,
+    instantiation_count: usize,
+    /// The sum of the size estimates of all instances.
+    total_estimate: usize,
+    instances: Vec<InstanceStats>,
+}
+
+#[derive(Encodable)]
+struct InstanceStats {
+    instance: String,
+    /// The number of MIR statements of the instance, see `MonoItem::size_estimate`.
+    size_estimate: usize,
+    /// The codegen units the instance was placed in, there is more than one if the instance
+    /// is inlined into each CGU that uses it.
+    cgus: Vec<String>,
+    /// The crates whose code references the instance, i.e. caused it to be instantiated in
+    /// this crate.
+    triggered_by: Vec<String>,
+}
+
+/// Writes the report of `-Z dump-mono-stats` to `path`: how many instances of each generic
+/// item were collected, how large they are, where they were placed and who needed them.
+/// Items are sorted by their total size, so the ones most worth outlining come first.
+fn dump_mono_items_stats<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    codegen_units: &[CodegenUnit<'tcx>],
+    items: &FxHashSet<MonoItem<'tcx>>,
+    inlining_map: &InliningMap<'tcx>,
+    format: MonoStatsFormat,
+    path: &Path,
+) -> io::Result<()> {
+    let mut item_to_cgus: FxHashMap<_, Vec<_>> = Default::default();
+    for cgu in codegen_units {
+        for &mono_item in cgu.items().keys() {
+            item_to_cgus.entry(mono_item).or_default().push(cgu.name());
+        }
+    }
+
+    let mut item_to_users: FxHashMap<_, FxHashSet<_>> = Default::default();
+    inlining_map.iter_accesses(|accessor, accessees| {
+        let krate = match accessor {
+            MonoItem::Fn(instance) => instance.def_id().krate,
+            MonoItem::Static(def_id) => def_id.krate,
+            MonoItem::GlobalAsm(_) => LOCAL_CRATE,
+        };
+        for &accessee in accessees {
+            item_to_users.entry(accessee).or_default().insert(krate);
+        }
+    });
+
+    let mut instances_of: FxHashMap<DefId, Vec<Instance<'tcx>>> = Default::default();
+    for &mono_item in items {
+        if let MonoItem::Fn(instance) = mono_item {
+            if mono_item.is_generic_fn() {
+                instances_of.entry(instance.def_id()).or_default().push(instance);
+            }
+        }
+    }
+
+    let crate_name = |cnum: CrateNum| tcx.crate_name(cnum).to_string();
+    let mut stats: Vec<_> = instances_of
+        .into_iter()
+        .map(|(def_id, instances)| {
+            let mut instances: Vec<_> = instances
+                .into_iter()
+                .map(|instance| {
+                    let mono_item = MonoItem::Fn(instance);
+                    let mut cgus: Vec<_> = item_to_cgus
+                        .get(&mono_item)
+                        .map_or(&[][..], |cgus| &cgus[..])
+                        .iter()
+                        .map(|name| name.to_string())
+                        .collect();
+                    cgus.sort();
+                    cgus.dedup();
+                    let mut triggered_by: Vec<_> = match item_to_users.get(&mono_item) {
+                        Some(crates) => crates.iter().map(|&cnum| crate_name(cnum)).collect(),
+                        // Only the roots of the collection aren't referenced by another item.
+                        None => vec![crate_name(LOCAL_CRATE)],
+                    };
+                    triggered_by.sort();
+                    InstanceStats {
+                        instance: with_no_trimmed_paths(|| instance.to_string()),
+                        size_estimate: mono_item.size_estimate(tcx),
+                        cgus,
+                        triggered_by,
+                    }
+                })
+                .collect();
+            instances.sort_by(|a, b| a.instance.cmp(&b.instance));
+            MonoItemStats {
+                name: with_no_trimmed_paths(|| tcx.def_path_str(def_id)),
+                defined_in: crate_name(def_id.krate),
+                instantiation_count: instances.len(),
+                total_estimate: instances.iter().map(|i| i.size_estimate).sum(),
+                instances,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.total_estimate.cmp(&a.total_estimate).then_with(|| a.name.cmp(&b.name)));
+
+    let crate_name = crate_name(LOCAL_CRATE);
+    let mut file = BufWriter::new(File::create(path)?);
+    match format {
+        MonoStatsFormat::Json => {
+            writeln!(file, "{}", as_pretty_json(&MonoStats { crate_name, items: stats }))?;
+        }
+        MonoStatsFormat::Markdown => {
+            writeln!(file, "# Mono item statistics for `{}`", crate_name)?;
+            writeln!(file)?;
+            writeln!(file, "| Item | Defined in | Instantiations | Total size estimate |")?;
+            writeln!(file, "| --- | --- | ---: | ---: |")?;
+            for item in &stats {
+                writeln!(
+                    file,
+                    "| `{}` | `{}` | {} | {} |",
+                    item.name, item.defined_in, item.instantiation_count, item.total_estimate
+                )?;
+            }
+            for item in &stats {
+                writeln!(file)?;
+                writeln!(file, "## `{}`", item.name)?;
+                writeln!(file)?;
+                writeln!(file, "| Instance | Size estimate | CGUs | Triggered by |")?;
+                writeln!(file, "| --- | ---: | --- | --- |")?;
+                for instance in &item.instances {
+                    writeln!(
+                        file,
+                        "| `{}` | {} | {} | {} |",
+                        instance.instance,
+                        instance.size_estimate,
+                        instance.cgus.join(", "),
+                        instance.triggered_by.join(", ")
+                    )?;
+                }
+            }
+        }
+    }
+    file.flush()
+}
+
 pub fn provide(providers: &mut Providers) {
     providers.collect_and_partition_mono_items = collect_and_partition_mono_items;
 
@@ -1,13 +1,13 @@
 //! Inlining pass for MIR functions
 
 use rustc_attr as attr;
-use rustc_hir::def_id::DefId;
 use rustc_index::bit_set::BitSet;
 use rustc_index::vec::{Idx, IndexVec};
 use rustc_middle::middle::codegen_fn_attrs::{CodegenFnAttrFlags, CodegenFnAttrs};
 use rustc_middle::mir::visit::*;
 use rustc_middle::mir::*;
-use rustc_middle::ty::subst::{Subst, SubstsRef};
+use rustc_middle::ty::query::Providers;
+use rustc_middle::ty::subst::Subst;
 use rustc_middle::ty::{self, ConstKind, Instance, InstanceDef, ParamEnv, Ty, TyCtxt};
 use rustc_target::spec::abi::Abi;
 
@@ -16,8 +16,7 @@ use crate::transform::{MirPass, MirSource};
 use std::collections::VecDeque;
 use std::iter;
 
-const DEFAULT_THRESHOLD: usize = 50;
-const HINT_THRESHOLD: usize = 100;
+mod cycle;
 
 const INSTR_COST: usize = 5;
 const CALL_PENALTY: usize = 25;
@@ -30,15 +29,32 @@ pub struct Inline;
 
 #[derive(Copy, Clone, Debug)]
 struct CallSite<'tcx> {
-    callee: DefId,
-    substs: SubstsRef<'tcx>,
+    callee: Instance<'tcx>,
     bb: BasicBlock,
     location: SourceInfo,
+    /// The entry of the inlining history for the call that brought this call site into the
+    /// caller, if it wasn't there to begin with.
+    inlined_from: Option<usize>,
+}
+
+crate fn provide(providers: &mut Providers) {
+    providers.mir_callgraph_reachable = cycle::mir_callgraph_reachable;
+    providers.mir_inliner_callees = cycle::mir_inliner_callees;
+}
+
+/// Whether the MIR inliner runs, either because it was requested with `-Z inline-mir`, or
+/// because the MIR optimization level is high enough.
+crate fn is_enabled(tcx: TyCtxt<'_>) -> bool {
+    if let Some(enabled) = tcx.sess.opts.debugging_opts.inline_mir {
+        return enabled;
+    }
+
+    tcx.sess.opts.debugging_opts.mir_opt_level >= 2
 }
 
 impl<'tcx> MirPass<'tcx> for Inline {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
+        if is_enabled(tcx) {
             if tcx.sess.opts.debugging_opts.instrument_coverage {
                 // The current implementation of source code coverage injects code region counters
                 // into the MIR, and assumes a 1-to-1 correspondence between MIR and source-code-
@@ -74,6 +90,12 @@ impl Inliner<'tcx> {
 
         let mut callsites = VecDeque::new();
 
+        // The callees inlined so far, each with the history entry of the call that brought its
+        // call site into the caller. Following these links from a call site gives the chain of
+        // inlined calls it came from, which is used to stop inlining recursive calls, even when
+        // the recursion goes through other crates.
+        let mut history: Vec<(Instance<'tcx>, Option<usize>)> = Vec::new();
+
         let param_env = self.tcx.param_env_reveal_all_normalized(self.source.def_id());
 
         // Only do inlining into fn bodies.
@@ -81,7 +103,7 @@ impl Inliner<'tcx> {
         if self.tcx.hir().body_owner_kind(id).is_fn_or_closure() && self.source.promoted.is_none() {
             for (bb, bb_data) in caller_body.basic_blocks().iter_enumerated() {
                 if let Some(callsite) =
-                    self.get_valid_function_call(bb, bb_data, caller_body, param_env)
+                    self.get_valid_function_call(bb, bb_data, caller_body, param_env, None)
                 {
                     callsites.push_back(callsite);
                 }
@@ -97,39 +119,50 @@ impl Inliner<'tcx> {
             local_change = false;
             while let Some(callsite) = callsites.pop_front() {
                 debug!("checking whether to inline callsite {:?}", callsite);
-                if !self.tcx.is_mir_available(callsite.callee) {
+                let callee_def_id = callsite.callee.def_id();
+                if !self.tcx.is_mir_available(callee_def_id) {
                     debug!("checking whether to inline callsite {:?} - MIR unavailable", callsite);
                     continue;
                 }
 
-                let callee_body = if let Some(callee_def_id) = callsite.callee.as_local() {
+                let callee_body = if let Some(callee_def_id) = callee_def_id.as_local() {
                     let callee_hir_id = self.tcx.hir().local_def_id_to_hir_id(callee_def_id);
-                    let self_hir_id =
-                        self.tcx.hir().local_def_id_to_hir_id(self.source.def_id().expect_local());
-                    // Avoid a cycle here by only using `optimized_mir` only if we have
-                    // a lower `HirId` than the callee. This ensures that the callee will
-                    // not inline us. This trick only works without incremental compilation.
-                    // So don't do it if that is enabled. Also avoid inlining into generators,
-                    // since their `optimized_mir` is used for layout computation, which can
-                    // create a cycle, even when no attempt is made to inline the function
-                    // in the other direction.
-                    if !self.tcx.dep_graph.is_fully_enabled()
-                        && self_hir_id < callee_hir_id
-                        && caller_body.generator_kind.is_none()
+                    let self_def_id = self.source.def_id().expect_local();
+                    let self_hir_id = self.tcx.hir().local_def_id_to_hir_id(self_def_id);
+                    // Avoid inlining into generators, since their `optimized_mir` is used for
+                    // layout computation, which can create a cycle, even when no attempt is made
+                    // to inline the function in the other direction.
+                    if caller_body.generator_kind.is_some() {
+                        continue;
+                    }
+                    // Avoid a cycle here by only using `optimized_mir` if we have a lower
+                    // `HirId` than the callee, which ensures that the callee will not inline
+                    // us, or if the callee can't call back into us at all. The former only
+                    // works without incremental compilation, as `HirId`s aren't stable
+                    // across sessions.
+                    let may_cycle = if callee_def_id == self_def_id {
+                        true
+                    } else if !self.tcx.dep_graph.is_fully_enabled() && self_hir_id < callee_hir_id
                     {
-                        self.tcx.optimized_mir(callsite.callee)
+                        false
                     } else {
+                        self.tcx.mir_callgraph_reachable((callsite.callee, self_def_id))
+                    };
+                    if may_cycle {
+                        debug!("checking whether to inline callsite {:?} - may cycle", callsite);
                         continue;
                     }
+                    self.tcx.optimized_mir(callee_def_id)
                 } else {
                     // This cannot result in a cycle since the callee MIR is from another crate
-                    // and is already optimized.
-                    self.tcx.optimized_mir(callsite.callee)
+                    // and is already optimized. Recursion through other crates is caught by
+                    // the inlining history instead.
+                    self.tcx.optimized_mir(callee_def_id)
                 };
 
                 let callee_body = if self.consider_optimizing(callsite, callee_body) {
                     self.tcx.subst_and_normalize_erasing_regions(
-                        &callsite.substs,
+                        &callsite.callee.substs,
                         param_env,
                         callee_body,
                     )
@@ -154,14 +187,31 @@ impl Inliner<'tcx> {
                     continue;
                 }
                 debug!("attempting to inline callsite {:?} - success", callsite);
+                history.push((callsite.callee, callsite.inlined_from));
+                let inlined_from = Some(history.len() - 1);
 
                 // Add callsites from inlined function
                 for (bb, bb_data) in caller_body.basic_blocks().iter_enumerated().skip(start) {
-                    if let Some(new_callsite) =
-                        self.get_valid_function_call(bb, bb_data, caller_body, param_env)
-                    {
-                        // Don't inline the same function multiple times.
-                        if callsite.callee != new_callsite.callee {
+                    if let Some(new_callsite) = self.get_valid_function_call(
+                        bb,
+                        bb_data,
+                        caller_body,
+                        param_env,
+                        inlined_from,
+                    ) {
+                        // Don't inline the same function multiple times along a chain of
+                        // inlined calls, that would never stop for recursive functions.
+                        let mut entry = inlined_from;
+                        let mut recursive = false;
+                        while let Some(index) = entry {
+                            let (callee, parent) = history[index];
+                            if callee.def_id() == new_callsite.callee.def_id() {
+                                recursive = true;
+                                break;
+                            }
+                            entry = parent;
+                        }
+                        if !recursive {
                             callsites.push_back(new_callsite);
                         }
                     }
@@ -190,6 +240,7 @@ impl Inliner<'tcx> {
         bb_data: &BasicBlockData<'tcx>,
         caller_body: &Body<'tcx>,
         param_env: ParamEnv<'tcx>,
+        inlined_from: Option<usize>,
     ) -> Option<CallSite<'tcx>> {
         // Don't inline calls that are in cleanup blocks.
         if bb_data.is_cleanup {
@@ -203,16 +254,17 @@ impl Inliner<'tcx> {
                 let instance =
                     Instance::resolve(self.tcx, param_env, callee_def_id, substs).ok().flatten()?;
 
-                if let InstanceDef::Virtual(..) = instance.def {
-                    return None;
+                // Only items have MIR of their own, which is what gets inlined. Shims and
+                // intrinsics are generated or handled by codegen, and virtual calls are
+                // not known until runtime.
+                if let InstanceDef::Item(_) = instance.def {
+                    return Some(CallSite {
+                        callee: instance,
+                        bb,
+                        location: terminator.source_info,
+                        inlined_from,
+                    });
                 }
-
-                return Some(CallSite {
-                    callee: instance.def_id(),
-                    substs: instance.substs,
-                    bb,
-                    location: terminator.source_info,
-                });
             }
         }
 
@@ -237,7 +289,7 @@ impl Inliner<'tcx> {
             return false;
         }
 
-        let codegen_fn_attrs = tcx.codegen_fn_attrs(callsite.callee);
+        let codegen_fn_attrs = tcx.codegen_fn_attrs(callsite.callee.def_id());
 
         if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::TRACK_CALLER) {
             debug!("`#[track_caller]` present - not inlining");
@@ -276,14 +328,18 @@ impl Inliner<'tcx> {
         // Only inline local functions if they would be eligible for cross-crate
         // inlining. This is to ensure that the final crate doesn't have MIR that
         // reference unexported symbols
-        if callsite.callee.is_local() {
-            if callsite.substs.non_erasable_generics().count() == 0 && !hinted {
+        if callsite.callee.def_id().is_local() {
+            if callsite.callee.substs.non_erasable_generics().count() == 0 && !hinted {
                 debug!("    callee is an exported function - not inlining");
                 return false;
             }
         }
 
-        let mut threshold = if hinted { HINT_THRESHOLD } else { DEFAULT_THRESHOLD };
+        let mut threshold = if hinted {
+            self.tcx.sess.opts.debugging_opts.inline_mir_hint_threshold
+        } else {
+            self.tcx.sess.opts.debugging_opts.inline_mir_threshold
+        };
 
         // Significantly lower the threshold for inlining cold functions
         if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
@@ -333,7 +389,7 @@ impl Inliner<'tcx> {
                     work_list.push(target);
                     // If the place doesn't actually need dropping, treat it like
                     // a regular goto.
-                    let ty = place.ty(callee_body, tcx).subst(tcx, callsite.substs).ty;
+                    let ty = place.ty(callee_body, tcx).subst(tcx, callsite.callee.substs).ty;
                     if ty.needs_drop(tcx, param_env) {
                         cost += CALL_PENALTY;
                         if let Some(unwind) = unwind {
@@ -396,7 +452,7 @@ impl Inliner<'tcx> {
 
         for v in callee_body.vars_and_temps_iter() {
             let v = &callee_body.local_decls[v];
-            let ty = v.ty.subst(tcx, callsite.substs);
+            let ty = v.ty.subst(tcx, callsite.callee.substs);
             // Cost of the var is the size in machine-words, if we know
             // it.
             if let Some(size) = type_size_of(tcx, param_env, ty) {
@@ -436,18 +492,28 @@ impl Inliner<'tcx> {
                 let mut scope_map = IndexVec::with_capacity(callee_body.source_scopes.len());
 
                 for mut scope in callee_body.source_scopes.iter().cloned() {
-                    if scope.parent_scope.is_none() {
+                    // Scopes are always created after their parents, so the parents of
+                    // callee scopes have already been mapped into the caller.
+                    if let Some(parent_scope) = scope.parent_scope {
+                        scope.parent_scope = Some(scope_map[parent_scope]);
+                        // Scopes without an inlined parent in the callee are now nested in
+                        // the inlined call itself, i.e. in the callee's root scope.
+                        scope.inlined_parent_scope = Some(
+                            scope_map[scope.inlined_parent_scope.unwrap_or(OUTERMOST_SOURCE_SCOPE)],
+                        );
+                    } else {
+                        // The callee's root scope becomes the scope of the inlined call,
+                        // nested in the scope of the call site.
                         scope.parent_scope = Some(callsite.location.scope);
-                        // FIXME(eddyb) is this really needed?
-                        // (also note that it's always overwritten below)
-                        scope.span = callee_body.span;
+                        scope.inlined = Some((callsite.callee, callsite.location.span));
+                        let callsite_scope = &caller_body.source_scopes[callsite.location.scope];
+                        scope.inlined_parent_scope = if callsite_scope.inlined.is_some() {
+                            Some(callsite.location.scope)
+                        } else {
+                            callsite_scope.inlined_parent_scope
+                        };
                     }
 
-                    // FIXME(eddyb) this doesn't seem right at all.
-                    // The inlined source scopes should probably be annotated as
-                    // such, but also contain all of the original information.
-                    scope.span = callsite.location.span;
-
                     let idx = caller_body.source_scopes.push(scope);
                     scope_map.push(idx);
                 }
@@ -580,7 +646,7 @@ impl Inliner<'tcx> {
         //     tmp2 = tuple_tmp.2
         //
         // and the vector is `[closure_ref, tmp0, tmp1, tmp2]`.
-        if tcx.is_closure(callsite.callee) {
+        if tcx.is_closure(callsite.callee.def_id()) {
             let mut args = args.into_iter();
             let self_ = self.create_temp_if_necessary(
                 args.next().unwrap(),
@@ -0,0 +1,119 @@
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::mir::TerminatorKind;
+use rustc_middle::ty::subst::SubstsRef;
+use rustc_middle::ty::{self, InstanceDef, TyCtxt};
+
+/// Checks whether `root` can (transitively) call back into `target`, in which case inlining
+/// `root` into `target` could cause a query cycle, as the optimized MIR of `target` would
+/// depend on the optimized MIR of `root`, and the other way around.
+///
+/// This is conservative: calls that can't be resolved are assumed not to reach `target`,
+/// but running into the recursion limit is assumed to reach it.
+crate fn mir_callgraph_reachable<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    (root, target): (ty::Instance<'tcx>, LocalDefId),
+) -> bool {
+    debug!("mir_callgraph_reachable({:?}, {:?})", root, target);
+    assert_ne!(
+        root.def_id(),
+        target.to_def_id(),
+        "`mir_callgraph_reachable` should not be called on immediate self recursion"
+    );
+    assert!(
+        matches!(root.def, InstanceDef::Item(_)),
+        "`mir_callgraph_reachable` should only be called on items, not {:?}",
+        root
+    );
+
+    fn process<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        caller: ty::Instance<'tcx>,
+        target: LocalDefId,
+        seen: &mut FxHashSet<ty::Instance<'tcx>>,
+        recursion_limiter: &mut FxHashMap<DefId, usize>,
+    ) -> bool {
+        for &(callee, substs) in tcx.mir_inliner_callees(caller.def) {
+            let substs = tcx.subst_and_normalize_erasing_regions(caller.substs, param_env, &substs);
+            let callee = match ty::Instance::resolve(tcx, param_env, callee, substs) {
+                Ok(Some(callee)) => callee,
+                _ => continue,
+            };
+
+            // Found a path.
+            if callee.def_id() == target.to_def_id() {
+                return true;
+            }
+
+            if tcx.is_constructor(callee.def_id()) {
+                // Constructors have no MIR of their own to walk.
+                continue;
+            }
+
+            // Only items get inlined, shims and intrinsics can't call back into `target`.
+            if !matches!(callee.def, InstanceDef::Item(_)) || !tcx.is_mir_available(callee.def_id())
+            {
+                continue;
+            }
+
+            if seen.insert(callee) {
+                let recursion = recursion_limiter.entry(callee.def_id()).or_default();
+                *recursion += 1;
+                if !tcx.sess.recursion_limit().value_within_limit(*recursion) {
+                    // We can't tell, so assume the worst.
+                    return true;
+                }
+                let found = ensure_sufficient_stack(|| {
+                    process(tcx, param_env, callee, target, seen, recursion_limiter)
+                });
+                if found {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    let param_env = tcx.param_env_reveal_all_normalized(target);
+    process(tcx, param_env, root, target, &mut FxHashSet::default(), &mut FxHashMap::default())
+}
+
+/// Collects the functions called by the MIR of `instance`, with their generic arguments
+/// in terms of the generics of `instance`.
+///
+/// For local items this reads the MIR from before optimizations, so that the cycle check
+/// in `mir_callgraph_reachable` doesn't itself need optimized MIR.
+crate fn mir_inliner_callees<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: ty::InstanceDef<'tcx>,
+) -> &'tcx [(DefId, SubstsRef<'tcx>)] {
+    let steal;
+    let guard;
+    let body = match instance {
+        InstanceDef::Item(def) => match def.as_local() {
+            Some(def) => {
+                steal = tcx.mir_promoted(def).0;
+                guard = steal.borrow();
+                &*guard
+            }
+            None => tcx.optimized_mir(def.did),
+        },
+        _ => tcx.mir_shims(instance),
+    };
+
+    let mut calls = Vec::new();
+    for bb_data in body.basic_blocks() {
+        let terminator = bb_data.terminator();
+        if let TerminatorKind::Call { func, .. } = &terminator.kind {
+            if let ty::FnDef(def_id, substs) = *func.ty(body, tcx).kind() {
+                let call = (def_id, substs);
+                if !calls.contains(&call) {
+                    calls.push(call);
+                }
+            }
+        }
+    }
+    tcx.arena.alloc_slice(&calls)
+}
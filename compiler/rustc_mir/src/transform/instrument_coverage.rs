@@ -6,6 +6,7 @@ use crate::util::spanview::{
 };
 
 use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_index::bit_set::BitSet;
 use rustc_middle::hir;
@@ -94,6 +95,25 @@ struct CoverageRegion {
     pub blocks: Vec<BasicBlock>,
 }
 
+/// Where the "not taken" count of a branch region comes from.
+enum FalseCount {
+    /// The counter of the block that the branch leads to when the condition is `false`.
+    Counter(ExpressionOperandId),
+    /// The difference between the counter of the `SwitchInt` block, and the counter of the
+    /// block the branch leads to (used for `match` arms, which have no single "else" block).
+    Remainder(ExpressionOperandId),
+    /// The sum of the counters of the other arms of a `match` whose `SwitchInt` block has no
+    /// counter of its own.
+    Others(Vec<ExpressionOperandId>),
+}
+
+struct BranchRegion {
+    span: Span,
+    switch_block: BasicBlock,
+    true_id: ExpressionOperandId,
+    false_count: FalseCount,
+}
+
 struct Instrumentor<'a, 'tcx> {
     pass_name: &'a str,
     tcx: TyCtxt<'tcx>,
@@ -228,6 +248,7 @@ impl<'a, 'tcx> Instrumentor<'a, 'tcx> {
         };
 
         // Inject counters for the selected spans
+        let mut block_counters = FxHashMap::default();
         for CoverageRegion { span, blocks } in coverage_regions {
             debug!(
                 "Injecting counter at: {:?}:\n{}\n==========",
@@ -235,9 +256,16 @@ impl<'a, 'tcx> Instrumentor<'a, 'tcx> {
                 tcx.sess.source_map().span_to_snippet(span).expect("Error getting source for span"),
             );
             let counter = self.make_counter();
+            block_counters.insert(blocks[0], counter.as_operand_id());
             self.inject_statement(counter, span, blocks[0]);
         }
 
+        if tcx.sess.opts.debugging_opts.coverage_branches {
+            for branch in self.branch_regions(&block_counters) {
+                self.inject_branch(branch);
+            }
+        }
+
         if let Some(span_viewables) = span_viewables {
             let mut file =
                 pretty::create_dump_file(tcx, "html", None, self.pass_name, &0, self.mir_source)
@@ -274,6 +302,115 @@ impl<'a, 'tcx> Instrumentor<'a, 'tcx> {
         }
     }
 
+    /// Finds the branches of every `SwitchInt` in the function body: one for each `bool` switch
+    /// (`if` conditions and the left operands of `&&` and `||`), and one for each reachable arm of
+    /// any other switch (`match`), `otherwise` included. The counts of a branch come from the
+    /// counters of the blocks it leads to, so it is left out unless those blocks have a counter
+    /// and no other predecessor.
+    fn branch_regions(
+        &self,
+        block_counters: &FxHashMap<BasicBlock, ExpressionOperandId>,
+    ) -> Vec<BranchRegion> {
+        let mir_body = &*self.mir_body;
+        let body_span = self.hir_body.value.span;
+        let predecessors = mir_body.predecessors();
+        let counter_of = |bb: BasicBlock| {
+            if predecessors[bb].len() == 1 { block_counters.get(&bb).copied() } else { None }
+        };
+
+        let mut branches = Vec::new();
+        for (bb, data) in mir_body.basic_blocks().iter_enumerated() {
+            let terminator = data.terminator();
+            let span = terminator.source_info.span;
+            let (switch_ty, targets) = match terminator.kind {
+                TerminatorKind::SwitchInt { switch_ty, ref targets, .. } => (switch_ty, targets),
+                _ => continue,
+            };
+            if !body_span.contains(span) {
+                continue;
+            }
+
+            if switch_ty.is_bool() && targets.len() == 2 {
+                // A `bool` switch branches to its first target if the value is `false`.
+                let (false_target, true_target) = (targets[0], targets[1]);
+                if let (Some(false_id), Some(true_id)) =
+                    (counter_of(false_target), counter_of(true_target))
+                {
+                    let false_count = FalseCount::Counter(false_id);
+                    branches.push(BranchRegion { span, switch_block: bb, true_id, false_count });
+                }
+            } else {
+                // Every target is an arm, including the last (`otherwise`) one, which is the
+                // `_` arm, unless it is unreachable (as it is for an exhaustive `match` on an
+                // enum).
+                let arms: Vec<_> = targets
+                    .iter()
+                    .copied()
+                    .filter(|&target| {
+                        !matches!(mir_body[target].terminator().kind, TerminatorKind::Unreachable)
+                    })
+                    .collect();
+                let arm_ids: Vec<_> = arms.iter().map(|&target| counter_of(target)).collect();
+                let switch_id = block_counters.get(&bb).copied();
+                for (i, (&target, &arm_id)) in arms.iter().zip(&arm_ids).enumerate() {
+                    let true_id = match arm_id {
+                        Some(true_id) => true_id,
+                        None => continue,
+                    };
+                    // An arm isn't taken if the switch is, but the arm isn't; or, if the switch
+                    // block has no counter of its own, if any of the other arms is taken.
+                    let false_count = match switch_id {
+                        Some(switch_id) => FalseCount::Remainder(switch_id),
+                        None => {
+                            let others = arm_ids
+                                .iter()
+                                .enumerate()
+                                .filter(|&(j, _)| j != i)
+                                .map(|(_, &id)| id)
+                                .collect::<Option<Vec<_>>>();
+                            match others {
+                                Some(others) if !others.is_empty() => FalseCount::Others(others),
+                                _ => continue,
+                            }
+                        }
+                    };
+                    branches.push(BranchRegion {
+                        span: mir_body[target].terminator().source_info.span,
+                        switch_block: bb,
+                        true_id,
+                        false_count,
+                    });
+                }
+            }
+        }
+        branches
+    }
+
+    fn inject_branch(&mut self, branch: BranchRegion) {
+        let BranchRegion { span, switch_block, true_id, false_count } = branch;
+        let false_id = match false_count {
+            FalseCount::Counter(false_id) => false_id,
+            FalseCount::Remainder(switch_id) => {
+                let expression = self.make_expression(switch_id, Op::Subtract, true_id);
+                let false_id = expression.as_operand_id();
+                self.inject_statement(expression, None, switch_block);
+                false_id
+            }
+            FalseCount::Others(others) => {
+                let mut others = others.into_iter();
+                let mut false_id = others.next().expect("a branch with no other arms");
+                for other in others {
+                    let expression = self.make_expression(false_id, Op::Add, other);
+                    false_id = expression.as_operand_id();
+                    self.inject_statement(expression, None, switch_block);
+                }
+                false_id
+            }
+        };
+        debug!("Injecting branch at {:?}: true: {:?}, false: {:?}", span, true_id, false_id);
+        self.inject_statement(CoverageKind::Branch { true_id, false_id }, span, switch_block);
+    }
+
     fn make_counter(&mut self) -> CoverageKind {
         CoverageKind::Counter {
             function_source_hash: self.function_source_hash(),
@@ -290,8 +427,13 @@ impl<'a, 'tcx> Instrumentor<'a, 'tcx> {
         CoverageKind::Expression { id: self.next_expression(), lhs, op, rhs }
     }
 
-    fn inject_statement(&mut self, coverage_kind: CoverageKind, span: Span, block: BasicBlock) {
-        let code_region = make_code_region(self.tcx, &span);
+    fn inject_statement(
+        &mut self,
+        coverage_kind: CoverageKind,
+        span: impl Into<Option<Span>>,
+        block: BasicBlock,
+    ) {
+        let code_region = span.into().map(|span| make_code_region(self.tcx, &span));
         debug!("  injecting statement {:?} covering {:?}", coverage_kind, code_region);
 
         let data = &mut self.mir_body[block];
@@ -355,6 +497,12 @@ impl<'a, 'tcx> Instrumentor<'a, 'tcx> {
                             }
                             String::from("unreachable")
                         }
+                        CoverageKind::Branch { true_id, false_id } => {
+                            if !INCLUDE_COVERAGE_STATEMENTS {
+                                continue;
+                            }
+                            format!("branch true: {} false: {}", true_id.index(), false_id.index())
+                        }
                     },
                     _ => format!("{:?}", statement),
                 };
@@ -0,0 +1,278 @@
+//! Jump threading: redirects jumps to a `SwitchInt` whose outcome is already known on the
+//! incoming edge.
+//!
+//! Code like `?` chains or a `match` on an enum that was just built in a predecessor block often
+//! ends up as:
+//!
+//! ```text
+//! bb1: {
+//!     discriminant(_1) = 1;
+//!     goto -> bb3;
+//! }
+//!
+//! bb3: {
+//!     _2 = discriminant(_1);
+//!     switchInt(move _2) -> [0_isize: bb4, 1_isize: bb5, otherwise: bb6];
+//! }
+//! ```
+//!
+//! Coming from `bb1`, the switch always goes to `bb5`. This pass gives `bb1` its own copy of the
+//! statements of `bb3`, followed by a `goto -> bb5`. The original switch is left alone for the
+//! other predecessors, and becomes dead once all of them have been threaded.
+//!
+//! A value is only considered known if it is set in the predecessor, or in a chain of blocks that
+//! unconditionally jump into each other, without any other write to the switched-on local in
+//! between. Locals that are ever borrowed are ignored, as they may be written through a pointer.
+//! Since every threaded edge duplicates a block, the total number of copied statements is bounded.
+//!
+//! The pass changes the shape of most bodies, so for now it only runs with `-Z mir-opt-level=4`.
+
+use crate::transform::dest_prop::ever_borrowed_locals;
+use crate::transform::{MirPass, MirSource};
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{ParamEnv, Ty, TyCtxt};
+
+pub struct JumpThreading;
+
+/// The maximum number of statements that are copied into new blocks in a single body.
+const MAX_COPIED_STATEMENTS: usize = 100;
+
+/// How many blocks are walked backwards from a predecessor to find the value of a local.
+const MAX_BACKTRACK_DEPTH: usize = 8;
+
+impl<'tcx> MirPass<'tcx> for JumpThreading {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 4 {
+            return;
+        }
+        trace!("Running JumpThreading on {:?}", source);
+
+        let param_env = tcx.param_env(source.def_id());
+        let finder =
+            ThreadingFinder { tcx, param_env, body, borrowed_locals: ever_borrowed_locals(body) };
+        let opportunities = finder.find_opportunities();
+
+        let mut budget = MAX_COPIED_STATEMENTS;
+        for ThreadingOpportunity { predecessor, switch_block, target } in opportunities {
+            let switch_data = &body.basic_blocks()[switch_block];
+            let cost = switch_data.statements.len();
+            if cost > budget {
+                debug!("not threading {:?} -> {:?}: over budget", predecessor, switch_block);
+                continue;
+            }
+            if !tcx.consider_optimizing(|| {
+                format!("JumpThreading {:?} {:?} -> {:?}", source.def_id(), predecessor, target)
+            }) {
+                break;
+            }
+            budget -= cost;
+
+            let new_block = BasicBlockData {
+                statements: switch_data.statements.clone(),
+                terminator: Some(Terminator {
+                    source_info: switch_data.terminator().source_info,
+                    kind: TerminatorKind::Goto { target },
+                }),
+                is_cleanup: switch_data.is_cleanup,
+            };
+            let new_block = body.basic_blocks_mut().push(new_block);
+            debug!("threading {:?} -> {:?} through {:?}", predecessor, target, new_block);
+            match &mut body.basic_blocks_mut()[predecessor].terminator_mut().kind {
+                TerminatorKind::Goto { target } => *target = new_block,
+                kind => bug!("threaded a predecessor ending in {:?}", kind),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ThreadingOpportunity {
+    /// A block ending in a `goto` to `switch_block`.
+    predecessor: BasicBlock,
+    switch_block: BasicBlock,
+    /// The target `switch_block` always jumps to when entered from `predecessor`.
+    target: BasicBlock,
+}
+
+/// What a `SwitchInt` tests, as seen at the start of its block.
+#[derive(Copy, Clone, Debug)]
+enum Condition<'tcx> {
+    /// The discriminant of a place, read into the switched-on local within the block.
+    Discriminant(Place<'tcx>),
+    /// The value of a local that is not written within the block.
+    Value(Local),
+}
+
+impl Condition<'tcx> {
+    fn local(self) -> Local {
+        match self {
+            Condition::Discriminant(place) => place.local,
+            Condition::Value(local) => local,
+        }
+    }
+}
+
+/// The effect of a statement on the value tested by a `Condition`.
+enum Effect {
+    /// The statement sets the tested value to these bits.
+    Known(u128),
+    /// The statement may change the tested value in some other way.
+    Clobbered,
+    /// The statement does not touch the tested value.
+    Unchanged,
+}
+
+struct ThreadingFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    borrowed_locals: BitSet<Local>,
+}
+
+impl<'a, 'tcx> ThreadingFinder<'a, 'tcx> {
+    fn find_opportunities(&self) -> Vec<ThreadingOpportunity> {
+        let mut opportunities = Vec::new();
+        for (switch_block, data) in self.body.basic_blocks().iter_enumerated() {
+            let (switch_ty, values, targets) = match &data.terminator().kind {
+                TerminatorKind::SwitchInt { switch_ty, values, targets, .. } => {
+                    (*switch_ty, values, targets)
+                }
+                _ => continue,
+            };
+            let condition = match self.switch_condition(data) {
+                Some(condition) => condition,
+                None => continue,
+            };
+            if self.borrowed_locals.contains(condition.local()) {
+                continue;
+            }
+
+            let predecessors = &self.body.predecessors()[switch_block];
+            for &predecessor in predecessors {
+                // Other edges may carry unwinding or call returns, which we can't redirect.
+                match self.body[predecessor].terminator().kind {
+                    TerminatorKind::Goto { .. } => {}
+                    _ => continue,
+                }
+                let bits = match self.known_value(predecessor, condition, switch_ty) {
+                    Some(bits) => bits,
+                    None => continue,
+                };
+                let target = match values.iter().position(|&value| value == bits) {
+                    Some(index) => targets[index],
+                    None => *targets.last().unwrap(),
+                };
+                opportunities.push(ThreadingOpportunity { predecessor, switch_block, target });
+            }
+        }
+        opportunities
+    }
+
+    /// Returns what the switch terminating `data` tests, if it is something we can track.
+    fn switch_condition(&self, data: &BasicBlockData<'tcx>) -> Option<Condition<'tcx>> {
+        let discr = match &data.terminator().kind {
+            TerminatorKind::SwitchInt { discr, .. } => discr.place()?.as_local()?,
+            _ => return None,
+        };
+        for (index, statement) in data.statements.iter().enumerate().rev() {
+            if !writes_to(statement, discr) {
+                continue;
+            }
+            return match &statement.kind {
+                StatementKind::Assign(box (lhs, Rvalue::Discriminant(place)))
+                    if lhs.as_local() == Some(discr) && !place.is_indirect() =>
+                {
+                    let clobbered = data.statements[..index]
+                        .iter()
+                        .any(|statement| writes_to(statement, place.local));
+                    if clobbered { None } else { Some(Condition::Discriminant(*place)) }
+                }
+                _ => None,
+            };
+        }
+        Some(Condition::Value(discr))
+    }
+
+    /// Walks backwards from the end of `block` to find the value tested by `condition`.
+    fn known_value(
+        &self,
+        mut block: BasicBlock,
+        condition: Condition<'tcx>,
+        switch_ty: Ty<'tcx>,
+    ) -> Option<u128> {
+        for _ in 0..MAX_BACKTRACK_DEPTH {
+            for statement in self.body[block].statements.iter().rev() {
+                match self.statement_effect(statement, condition, switch_ty) {
+                    Effect::Known(bits) => return Some(bits),
+                    Effect::Clobbered => return None,
+                    Effect::Unchanged => {}
+                }
+            }
+
+            // Keep going if there is only one way into this block.
+            let predecessors = &self.body.predecessors()[block];
+            if predecessors.len() != 1 {
+                return None;
+            }
+            block = predecessors[0];
+            match self.body[block].terminator().kind {
+                TerminatorKind::Goto { .. } => {}
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    fn statement_effect(
+        &self,
+        statement: &Statement<'tcx>,
+        condition: Condition<'tcx>,
+        switch_ty: Ty<'tcx>,
+    ) -> Effect {
+        match (condition, &statement.kind) {
+            (
+                Condition::Discriminant(tested),
+                StatementKind::SetDiscriminant { place, variant_index },
+            ) if **place == tested => {
+                let ty = tested.ty(self.body, self.tcx).ty;
+                match ty.discriminant_for_variant(self.tcx, *variant_index) {
+                    Some(discr) => Effect::Known(discr.val),
+                    None => Effect::Clobbered,
+                }
+            }
+            (
+                Condition::Value(tested),
+                StatementKind::Assign(box (lhs, Rvalue::Use(Operand::Constant(constant)))),
+            ) if lhs.as_local() == Some(tested) => {
+                match constant.literal.try_eval_bits(self.tcx, self.param_env, switch_ty) {
+                    Some(bits) => Effect::Known(bits),
+                    None => Effect::Clobbered,
+                }
+            }
+            _ if writes_to(statement, condition.local()) => Effect::Clobbered,
+            _ => Effect::Unchanged,
+        }
+    }
+}
+
+/// Returns whether `statement` may change the value of `local`, or end its storage.
+fn writes_to(statement: &Statement<'_>, local: Local) -> bool {
+    let mut finder = WriteFinder { local, found: false };
+    finder.visit_statement(statement, Location::START);
+    finder.found
+}
+
+struct WriteFinder {
+    local: Local,
+    found: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for WriteFinder {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if local == self.local && (context.is_mutating_use() || context.is_storage_marker()) {
+            self.found = true;
+        }
+    }
+}
@@ -32,6 +32,7 @@ pub mod generator;
 pub mod inline;
 pub mod instcombine;
 pub mod instrument_coverage;
+pub mod jump_threading;
 pub mod match_branches;
 pub mod multiple_return_terminators;
 pub mod no_landing_pads;
@@ -45,6 +46,7 @@ pub mod simplify;
 pub mod simplify_branches;
 pub mod simplify_comparison_integral;
 pub mod simplify_try;
+pub mod sroa;
 pub mod uninhabited_enum_branching;
 pub mod unreachable_prop;
 pub mod validate;
@@ -84,6 +86,7 @@ pub(crate) fn provide(providers: &mut Providers) {
         ..*providers
     };
     instrument_coverage::provide(providers);
+    inline::provide(providers);
 }
 
 fn is_mir_available(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
@@ -388,6 +391,17 @@ fn mir_drops_elaborated_and_const_checked<'tcx>(
         tcx.ensure().mir_borrowck(def.did);
     }
 
+    // The inliner's cycle check reads the callees of fn bodies from `mir_promoted`,
+    // so collect them before it gets stolen. This can't use `tcx.ensure()`, which
+    // leaves a green result on disk, to be recomputed from the stolen MIR when it's
+    // read later on.
+    if inline::is_enabled(tcx) {
+        let hir_id = tcx.hir().local_def_id_to_hir_id(def.did);
+        if tcx.hir().body_owner_kind(hir_id).is_fn_or_closure() {
+            let _ = tcx.mir_inliner_callees(InstanceDef::Item(def.to_global()));
+        }
+    }
+
     let (body, _) = tcx.mir_promoted(def);
     let mut body = body.steal();
 
@@ -467,6 +481,8 @@ fn run_optimization_passes<'tcx>(
         // inst combine is after MatchBranchSimplification to clean up Ne(_1, false)
         &multiple_return_terminators::MultipleReturnTerminators,
         &instcombine::InstCombine,
+        // Splitting aggregates into scalars gives const propagation more locals to work with.
+        &sroa::ScalarReplacementOfAggregates,
         &const_prop::ConstProp,
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
         &early_otherwise_branch::EarlyOtherwiseBranch,
@@ -476,6 +492,8 @@ fn run_optimization_passes<'tcx>(
         &dest_prop::DestinationPropagation,
         &copy_prop::CopyPropagation,
         &simplify_branches::SimplifyBranches::new("after-copy-prop"),
+        // Jump threading leaves the original switches behind, `SimplifyCfg` removes the dead ones.
+        &jump_threading::JumpThreading,
         &remove_noop_landing_pads::RemoveNoopLandingPads,
         &simplify::SimplifyCfg::new("final"),
         &nrvo::RenameReturnPlace,
@@ -0,0 +1,337 @@
+//! Scalar replacement of aggregates: this pass splits locals of struct, tuple and closure type
+//! into one local per field.
+//!
+//! A local is only split if it is exclusively accessed through its fields, as is commonly the case
+//! after the `Deaggregator` has run:
+//!
+//! ```text
+//! (_1.0: u32) = const 1_u32;
+//! (_1.1: bool) = const true;
+//! _0 = (_1.0: u32);
+//! ```
+//!
+//! Here `_1` is replaced by two new locals `_2: u32` and `_3: bool`. Copying or moving the whole
+//! local into another local, or the other way around, is done field by field instead. Any other
+//! use of the whole local, like a drop or a read of its discriminant, as well as borrowing any of
+//! its fields, keeps the local in one piece. Enums with a single variant are split like structs,
+//! since setting their discriminant has no effect.
+//!
+//! The new locals can be aggregates themselves, so the replacement is repeated until no more
+//! locals can be split.
+//!
+//! Until its effect on compile times and on the generated code has been measured, the pass only
+//! runs with `-Z mir-opt-level=4`.
+
+use crate::transform::{MirPass, MirSource};
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_middle::mir::visit::{
+    MutVisitor, MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor,
+};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_session::config::DebugInfo;
+use rustc_span::DUMMY_SP;
+use rustc_target::abi::VariantIdx;
+
+pub struct ScalarReplacementOfAggregates;
+
+/// For each local that is split, the new local of each of its fields that is actually used.
+type Fragments = IndexVec<Local, Option<IndexVec<Field, Option<Local>>>>;
+
+impl<'tcx> MirPass<'tcx> for ScalarReplacementOfAggregates {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 4 {
+            return;
+        }
+        trace!("Running ScalarReplacementOfAggregates on {:?}", source);
+        let param_env = tcx.param_env(source.def_id());
+        loop {
+            let fragments = compute_fragments(tcx, param_env, body);
+            if fragments.iter().all(Option::is_none) {
+                break;
+            }
+            replace_flattened_locals(tcx, body, &fragments);
+        }
+    }
+}
+
+/// Returns whether a local of type `ty` can be split into its fields.
+fn is_splittable<'tcx>(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, ty: Ty<'tcx>) -> bool {
+    let splittable_kind = match ty.kind() {
+        ty::Tuple(..) | ty::Closure(..) => true,
+        ty::Adt(def, _) if def.is_box() || def.repr.packed() || def.repr.simd() => false,
+        ty::Adt(def, _) if def.is_enum() => {
+            // The discriminant of enums with an explicit representation is stored even if there
+            // is only one variant.
+            def.variants.len() == 1 && !def.repr.inhibit_enum_layout_opt()
+        }
+        ty::Adt(def, _) => def.is_struct(),
+        _ => false,
+    };
+    // The last field of an unsized aggregate can't be moved into a local of its own.
+    splittable_kind && ty.is_sized(tcx.at(DUMMY_SP), param_env)
+}
+
+/// Returns the types of the fields of a type that can be split.
+fn field_tys<'tcx>(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, ty: Ty<'tcx>) -> Vec<Ty<'tcx>> {
+    match ty.kind() {
+        ty::Tuple(..) => ty.tuple_fields().collect(),
+        ty::Closure(_, substs) => substs.as_closure().upvar_tys().collect(),
+        ty::Adt(def, substs) => def.variants[VariantIdx::new(0)]
+            .fields
+            .iter()
+            .map(|field| tcx.normalize_erasing_regions(param_env, field.ty(tcx, substs)))
+            .collect(),
+        _ => bug!("splitting a local of type {:?}", ty),
+    }
+}
+
+/// If `projection` starts by accessing a field, returns that field, its type and the rest of the
+/// projection. Fields of single-variant enums are accessed through a downcast first.
+fn field_projection<'a, 'tcx>(
+    projection: &'a [PlaceElem<'tcx>],
+) -> Option<(Field, Ty<'tcx>, &'a [PlaceElem<'tcx>])> {
+    match projection {
+        [ProjectionElem::Field(field, ty), rest @ ..]
+        | [ProjectionElem::Downcast(..), ProjectionElem::Field(field, ty), rest @ ..] => {
+            Some((*field, *ty, rest))
+        }
+        _ => None,
+    }
+}
+
+/// If `statement` copies or moves a whole local into another local, returns both locals.
+fn whole_local_copy(statement: &Statement<'_>) -> Option<(Local, Local)> {
+    match &statement.kind {
+        StatementKind::Assign(box (lhs, Rvalue::Use(Operand::Copy(rhs) | Operand::Move(rhs)))) => {
+            Some((lhs.as_local()?, rhs.as_local()?))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the locals that can be split and creates a new local for each of their used fields.
+fn compute_fragments<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &mut Body<'tcx>,
+) -> Fragments {
+    // Arguments and the return place are part of the function's ABI, so they are never split.
+    let mut field_uses = IndexVec::from_elem(None, &body.local_decls);
+    for local in body.vars_and_temps_iter() {
+        if is_splittable(tcx, param_env, body.local_decls[local].ty) {
+            field_uses[local] = Some(IndexVec::new());
+        }
+    }
+
+    let mut finder = FieldUseFinder {
+        tcx,
+        param_env,
+        local_decls: &body.local_decls,
+        keep_debuginfo: tcx.sess.opts.debuginfo != DebugInfo::None,
+        field_uses,
+    };
+    finder.visit_body(body);
+
+    let mut fragments = IndexVec::from_elem(None, &body.local_decls);
+    for (local, field_tys) in finder.field_uses.into_iter_enumerated() {
+        let field_tys = match field_tys {
+            Some(field_tys) if field_tys.iter().any(Option::is_some) => field_tys,
+            _ => continue,
+        };
+        let source_info = body.local_decls[local].source_info;
+        let local_fragments: IndexVec<_, _> = field_tys
+            .into_iter()
+            .map(|ty| {
+                ty.map(|ty| body.local_decls.push(LocalDecl::with_source_info(ty, source_info)))
+            })
+            .collect();
+        debug!("splitting {:?} into {:?}", local, local_fragments);
+        fragments[local] = Some(local_fragments);
+    }
+    fragments
+}
+
+struct FieldUseFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    local_decls: &'a LocalDecls<'tcx>,
+    keep_debuginfo: bool,
+    /// The types of the fields used for each local that can still be split, or `None` for locals
+    /// that have to be kept whole.
+    field_uses: IndexVec<Local, Option<IndexVec<Field, Option<Ty<'tcx>>>>>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for FieldUseFinder<'a, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        // Copying a whole local uses all of its fields.
+        if let Some((lhs, rhs)) = whole_local_copy(statement) {
+            if self.field_uses[lhs].is_some() || self.field_uses[rhs].is_some() {
+                let tys = field_tys(self.tcx, self.param_env, self.local_decls[lhs].ty);
+                for &local in &[lhs, rhs] {
+                    if let Some(field_tys) = &mut self.field_uses[local] {
+                        for (index, &ty) in tys.iter().enumerate() {
+                            let field = Field::new(index);
+                            field_tys.ensure_contains_elem(field, || None);
+                            field_tys[field] = Some(ty);
+                        }
+                    }
+                }
+            }
+            return;
+        }
+        // Only single-variant enums are split, so their discriminant doesn't need to be stored.
+        if let StatementKind::SetDiscriminant { place, .. } = &statement.kind {
+            if place.as_local().is_some() {
+                return;
+            }
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        let (field, ty, _) = match field_projection(&place.projection) {
+            Some(field_projection) => field_projection,
+            None => return self.super_place(place, context, location),
+        };
+        let field_tys = match &mut self.field_uses[place.local] {
+            Some(field_tys) => field_tys,
+            None => return,
+        };
+
+        let address_taken = context.is_borrow()
+            || matches!(
+                context,
+                PlaceContext::MutatingUse(MutatingUseContext::AddressOf)
+                    | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf)
+            );
+        if address_taken {
+            self.field_uses[place.local] = None;
+        } else {
+            field_tys.ensure_contains_elem(field, || None);
+            field_tys[field] = Some(ty);
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if context.is_use() {
+            self.field_uses[local] = None;
+        }
+    }
+
+    fn visit_var_debug_info(&mut self, var_debug_info: &VarDebugInfo<'tcx>) {
+        // There is no way to describe a user variable spread over several locals, so it keeps the
+        // local whole. Without debuginfo, the variable is dropped instead.
+        let place = var_debug_info.place;
+        if self.keep_debuginfo && field_projection(&place.projection).is_none() {
+            self.field_uses[place.local] = None;
+        }
+        self.super_var_debug_info(var_debug_info);
+    }
+}
+
+fn replace_flattened_locals<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>, fragments: &Fragments) {
+    body.var_debug_info.retain(|var_debug_info| {
+        let place = var_debug_info.place;
+        fragments[place.local].is_none() || field_projection(&place.projection).is_some()
+    });
+
+    let (basic_blocks, local_decls) = body.basic_blocks_and_local_decls_mut();
+    let local_decls = &*local_decls;
+    for bb in basic_blocks {
+        bb.expand_statements(|stmt| {
+            let (local, storage_marker): (_, fn(Local) -> StatementKind<'tcx>) = match stmt.kind {
+                StatementKind::StorageLive(local) => (local, StatementKind::StorageLive),
+                StatementKind::StorageDead(local) => (local, StatementKind::StorageDead),
+                StatementKind::SetDiscriminant { ref place, .. } => {
+                    let local = place.as_local()?;
+                    return if fragments[local].is_some() {
+                        Some(Vec::new().into_iter())
+                    } else {
+                        None
+                    };
+                }
+                StatementKind::Assign(_) => {
+                    return split_whole_local_copy(tcx, local_decls, fragments, stmt)
+                        .map(Vec::into_iter);
+                }
+                _ => return None,
+            };
+            let source_info = stmt.source_info;
+            let new_stmts: Vec<_> = fragments[local]
+                .as_ref()?
+                .iter()
+                .flatten()
+                .map(|&fragment| Statement { source_info, kind: storage_marker(fragment) })
+                .collect();
+            Some(new_stmts.into_iter())
+        });
+    }
+
+    ReplacementVisitor { tcx, fragments }.visit_body(body);
+}
+
+/// Turns a copy between two locals, at least one of which is split, into a copy of each field.
+fn split_whole_local_copy<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    local_decls: &LocalDecls<'tcx>,
+    fragments: &Fragments,
+    statement: &Statement<'tcx>,
+) -> Option<Vec<Statement<'tcx>>> {
+    let (lhs, rhs) = whole_local_copy(statement)?;
+    let split_fields = fragments[lhs].as_ref().or(fragments[rhs].as_ref())?;
+    let is_move = matches!(
+        statement.kind,
+        StatementKind::Assign(box (_, Rvalue::Use(Operand::Move(_))))
+    );
+
+    let field_place = |local: Local, field: Field, ty: Ty<'tcx>| match &fragments[local] {
+        Some(fragments) => Place::from(fragments[field].unwrap()),
+        None => {
+            let mut place = Place::from(local);
+            if let ty::Adt(def, _) = local_decls[local].ty.kind() {
+                if def.is_enum() {
+                    place = tcx.mk_place_downcast(place, def, VariantIdx::new(0));
+                }
+            }
+            tcx.mk_place_field(place, field, ty)
+        }
+    };
+    let statements = split_fields
+        .iter_enumerated()
+        .map(|(field, fragment)| {
+            let ty = local_decls[fragment.unwrap()].ty;
+            let lhs = field_place(lhs, field, ty);
+            let rhs = field_place(rhs, field, ty);
+            let operand = if is_move { Operand::Move(rhs) } else { Operand::Copy(rhs) };
+            Statement {
+                source_info: statement.source_info,
+                kind: StatementKind::Assign(box (lhs, Rvalue::Use(operand))),
+            }
+        })
+        .collect();
+    Some(statements)
+}
+
+struct ReplacementVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    fragments: &'a Fragments,
+}
+
+/// Replaces the fields of split locals by their new locals.
+impl<'a, 'tcx> MutVisitor<'tcx> for ReplacementVisitor<'a, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, location: Location) {
+        if let Some(fragments) = &self.fragments[place.local] {
+            let projection: &'tcx [PlaceElem<'tcx>] = place.projection;
+            if let Some((field, _, rest)) = field_projection(projection) {
+                let local = fragments[field].unwrap();
+                *place = Place { local, projection: self.tcx.intern_place_elems(rest) };
+            }
+        }
+        self.super_place(place, context, location);
+    }
+}
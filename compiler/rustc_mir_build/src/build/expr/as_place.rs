@@ -4,14 +4,42 @@ use crate::build::expr::category::Category;
 use crate::build::ForGuard::{OutsideGuard, RefWithinGuard};
 use crate::build::{BlockAnd, BlockAndExtension, Builder};
 use crate::thir::*;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_middle::hir::place::ProjectionKind as HirProjectionKind;
 use rustc_middle::middle::region;
 use rustc_middle::mir::AssertKind::BoundsCheck;
 use rustc_middle::mir::*;
 use rustc_middle::ty::{self, CanonicalUserTypeAnnotation, Ty, TyCtxt, Variance};
 use rustc_span::Span;
+use rustc_target::abi::VariantIdx;
 
 use rustc_index::vec::Idx;
 
+/// The "outermost" place that holds this value.
+#[derive(Copy, Clone)]
+enum PlaceBase {
+    /// Denotes the start of a `Place`.
+    Local(Local),
+
+    /// A place starting at an upvar of the closure or generator being built.
+    ///
+    /// With `capture_disjoint_fields`, closures may capture a path such as `t.0.1` rather than
+    /// the whole variable `t`, so which field of the closure environment holds the place is
+    /// only known once its projections have been applied. The place is resolved to start at
+    /// the closure environment in `PlaceBuilder::into_place`, by looking for the captured path
+    /// which is a prefix of the projections.
+    Upvar {
+        /// `HirId` of the root variable.
+        var_hir_id: hir::HirId,
+        /// `DefId` of the closure or generator.
+        closure_def_id: DefId,
+        /// The trait implemented by the closure, which determines whether the environment is
+        /// accessed by reference. Generators are treated as `FnOnce`.
+        closure_kind: ty::ClosureKind,
+    },
+}
+
 /// `PlaceBuilder` is used to create places during MIR construction. It allows you to "build up" a
 /// place by pushing more and more projections onto the end, and then convert the final set into a
 /// place using the `into_place` method.
@@ -20,13 +48,137 @@ use rustc_index::vec::Idx;
 /// and `c` can be progressively pushed onto the place builder that is created when converting `a`.
 #[derive(Clone)]
 struct PlaceBuilder<'tcx> {
-    local: Local,
+    base: PlaceBase,
     projection: Vec<PlaceElem<'tcx>>,
 }
 
+/// Converts the MIR projections of a place starting at an upvar to the projections of a captured
+/// place, as found in `closure_min_captures`. Only the projections which can be part of a captured
+/// place are converted, everything starting at the first other projection is left out.
+fn convert_to_hir_projections_and_truncate_for_capture<'tcx>(
+    mir_projections: &[PlaceElem<'tcx>],
+) -> Vec<HirProjectionKind> {
+    let mut hir_projections = Vec::new();
+
+    for mir_projection in mir_projections {
+        let hir_projection = match mir_projection {
+            ProjectionElem::Deref => HirProjectionKind::Deref,
+            // Enum variants aren't captured on their own, so neither is anything behind a downcast.
+            ProjectionElem::Field(field, _) => {
+                HirProjectionKind::Field(field.index() as u32, VariantIdx::new(0))
+            }
+            ProjectionElem::Index(..)
+            | ProjectionElem::ConstantIndex { .. }
+            | ProjectionElem::Subslice { .. }
+            | ProjectionElem::Downcast(..) => break,
+        };
+        hir_projections.push(hir_projection);
+    }
+
+    hir_projections
+}
+
+/// Returns `true` if the captured place with the projections `proj_possible_ancestor` is the
+/// place with the projections `proj_capture`, or one of its ancestors.
+fn is_ancestor_or_same_capture(
+    proj_possible_ancestor: &[HirProjectionKind],
+    proj_capture: &[HirProjectionKind],
+) -> bool {
+    // Zipping alone would also consider `x.0.0` to be an ancestor of `x.0`.
+    proj_possible_ancestor.len() <= proj_capture.len()
+        && proj_possible_ancestor.iter().zip(proj_capture).all(|(a, b)| a == b)
+}
+
+/// Finds the capture of the root variable `var_hir_id` by the closure `closure_def_id` which the
+/// place with the MIR projections `projections` is based on, and returns its index within the
+/// closure environment along with the captured place.
+fn find_capture_matching_projections<'a, 'tcx>(
+    typeck_results: &'a ty::TypeckResults<'tcx>,
+    var_hir_id: hir::HirId,
+    closure_def_id: DefId,
+    projections: &[PlaceElem<'tcx>],
+) -> Option<(usize, &'a ty::CapturedPlace<'tcx>)> {
+    let hir_projections = convert_to_hir_projections_and_truncate_for_capture(projections);
+
+    typeck_results.closure_min_captures_flattened(closure_def_id).enumerate().find(
+        |(_, captured_place)| {
+            let possible_ancestor_proj_kinds: Vec<_> =
+                captured_place.place.projections.iter().map(|proj| proj.kind).collect();
+            captured_place.get_root_variable() == var_hir_id
+                && is_ancestor_or_same_capture(&possible_ancestor_proj_kinds, &hir_projections)
+        },
+    )
+}
+
 impl<'tcx> PlaceBuilder<'tcx> {
-    fn into_place(self, tcx: TyCtxt<'tcx>) -> Place<'tcx> {
-        Place { local: self.local, projection: tcx.intern_place_elems(&self.projection) }
+    fn into_place(
+        self,
+        tcx: TyCtxt<'tcx>,
+        typeck_results: &ty::TypeckResults<'tcx>,
+    ) -> Place<'tcx> {
+        let place_builder = self.resolve_upvar(tcx, typeck_results);
+        match place_builder.base {
+            PlaceBase::Local(local) => {
+                Place { local, projection: tcx.intern_place_elems(&place_builder.projection) }
+            }
+            PlaceBase::Upvar { .. } => unreachable!(),
+        }
+    }
+
+    /// If the place starts at an upvar, turns it into a place starting at the closure
+    /// environment. The projections which are part of the captured place get replaced by
+    /// the field of the environment holding the capture, followed by a deref if it is
+    /// captured by reference.
+    fn resolve_upvar(
+        self,
+        tcx: TyCtxt<'tcx>,
+        typeck_results: &ty::TypeckResults<'tcx>,
+    ) -> PlaceBuilder<'tcx> {
+        let (var_hir_id, closure_def_id, closure_kind) = match self.base {
+            PlaceBase::Local(_) => return self,
+            PlaceBase::Upvar { var_hir_id, closure_def_id, closure_kind } => {
+                (var_hir_id, closure_def_id, closure_kind)
+            }
+        };
+
+        let (capture_index, capture) = find_capture_matching_projections(
+            typeck_results,
+            var_hir_id,
+            closure_def_id,
+            &self.projection,
+        )
+        .unwrap_or_else(|| {
+            bug!(
+                "no capture of {:?} in {:?} matches the projections {:?}",
+                var_hir_id,
+                closure_def_id,
+                self.projection
+            )
+        });
+
+        let closure_ty = typeck_results
+            .node_type(tcx.hir().local_def_id_to_hir_id(closure_def_id.expect_local()));
+        let substs = match closure_ty.kind() {
+            ty::Closure(_, substs) => ty::UpvarSubsts::Closure(substs),
+            ty::Generator(_, substs, _) => ty::UpvarSubsts::Generator(substs),
+            _ => bug!("upvar of non-closure type {:?}", closure_ty),
+        };
+        let var_ty = substs.upvar_tys().nth(capture_index).unwrap();
+
+        let mut upvar_resolved_place_builder = PlaceBuilder::from(Local::new(1));
+        if closure_kind != ty::ClosureKind::FnOnce {
+            upvar_resolved_place_builder = upvar_resolved_place_builder.deref();
+        }
+        upvar_resolved_place_builder =
+            upvar_resolved_place_builder.field(Field::new(capture_index), var_ty);
+        if let ty::UpvarCapture::ByRef(_) = capture.info.capture_kind {
+            upvar_resolved_place_builder = upvar_resolved_place_builder.deref();
+        }
+
+        // The projections which aren't part of the captured place get applied on top of it.
+        let next_projection = capture.place.projections.len();
+        upvar_resolved_place_builder.projection.extend(&self.projection[next_projection..]);
+        upvar_resolved_place_builder
     }
 
     fn field(self, f: Field, ty: Ty<'tcx>) -> Self {
@@ -49,7 +201,13 @@ impl<'tcx> PlaceBuilder<'tcx> {
 
 impl<'tcx> From<Local> for PlaceBuilder<'tcx> {
     fn from(local: Local) -> Self {
-        Self { local, projection: Vec::new() }
+        Self { base: PlaceBase::Local(local), projection: Vec::new() }
+    }
+}
+
+impl<'tcx> From<PlaceBase> for PlaceBuilder<'tcx> {
+    fn from(base: PlaceBase) -> Self {
+        Self { base, projection: Vec::new() }
     }
 }
 
@@ -71,7 +229,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         M: Mirror<'tcx, Output = Expr<'tcx>>,
     {
         let place_builder = unpack!(block = self.as_place_builder(block, expr));
-        block.and(place_builder.into_place(self.hir.tcx()))
+        block.and(place_builder.into_place(self.hir.tcx(), self.hir.typeck_results()))
     }
 
     /// This is used when constructing a compound `Place`, so that we can avoid creating
@@ -98,7 +256,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         M: Mirror<'tcx, Output = Expr<'tcx>>,
     {
         let place_builder = unpack!(block = self.as_read_only_place_builder(block, expr));
-        block.and(place_builder.into_place(self.hir.tcx()))
+        block.and(place_builder.into_place(self.hir.tcx(), self.hir.typeck_results()))
     }
 
     /// This is used when constructing a compound `Place`, so that we can avoid creating
@@ -160,7 +318,9 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 expr_span,
                 source_info,
             ),
-            ExprKind::SelfRef => block.and(PlaceBuilder::from(Local::new(1))),
+            ExprKind::UpvarRef { closure_def_id, var_hir_id } => {
+                block.and(this.lower_captured_upvar(closure_def_id, var_hir_id))
+            }
             ExprKind::VarRef { id } => {
                 let place_builder = if this.is_bound_var_in_guard(id) {
                     let index = this.var_local_id(id, RefWithinGuard);
@@ -185,7 +345,9 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                             inferred_ty: expr.ty,
                         });
 
-                    let place = place_builder.clone().into_place(this.hir.tcx());
+                    let place = place_builder
+                        .clone()
+                        .into_place(this.hir.tcx(), this.hir.typeck_results());
                     this.cfg.push(
                         block,
                         Statement {
@@ -254,6 +416,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             | ExprKind::Continue { .. }
             | ExprKind::Return { .. }
             | ExprKind::Literal { .. }
+            | ExprKind::ConstBlock { .. }
             | ExprKind::StaticRef { .. }
             | ExprKind::InlineAsm { .. }
             | ExprKind::LlvmInlineAsm { .. }
@@ -272,6 +435,25 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         }
     }
 
+    /// Lower a reference to an upvar. The capture it goes through depends on the projections
+    /// applied to it later on, so this returns a `PlaceBase::Upvar`, which is resolved to the
+    /// closure environment in `PlaceBuilder::into_place`.
+    fn lower_captured_upvar(
+        &mut self,
+        closure_def_id: DefId,
+        var_hir_id: hir::HirId,
+    ) -> PlaceBuilder<'tcx> {
+        let closure_ty = self.hir.typeck_results().node_type(
+            self.hir.tcx().hir().local_def_id_to_hir_id(closure_def_id.expect_local()),
+        );
+        let closure_kind = match closure_ty.kind() {
+            ty::Closure(_, substs) => substs.as_closure().kind(),
+            // Generators take their environment by value.
+            _ => ty::ClosureKind::FnOnce,
+        };
+        PlaceBuilder::from(PlaceBase::Upvar { var_hir_id, closure_def_id, closure_kind })
+    }
+
     /// Lower an index expression
     ///
     /// This has two complications;
@@ -307,7 +489,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
 
         block = self.bounds_check(
             block,
-            base_place.clone().into_place(self.hir.tcx()),
+            base_place.clone().into_place(self.hir.tcx(), self.hir.typeck_results()),
             idx,
             expr_span,
             source_info,
@@ -365,6 +547,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         source_info: SourceInfo,
     ) {
         let tcx = self.hir.tcx();
+        let base_place = base_place.clone().into_place(tcx, self.hir.typeck_results());
         let place_ty =
             Place::ty_from(base_place.local, &base_place.projection, &self.local_decls, tcx);
         if let ty::Slice(_) = place_ty.ty.kind() {
@@ -234,6 +234,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             }
             ExprKind::Yield { .. }
             | ExprKind::Literal { .. }
+            | ExprKind::ConstBlock { .. }
             | ExprKind::StaticRef { .. }
             | ExprKind::Block { .. }
             | ExprKind::Match { .. }
@@ -249,7 +250,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             | ExprKind::Deref { .. }
             | ExprKind::Index { .. }
             | ExprKind::VarRef { .. }
-            | ExprKind::SelfRef
+            | ExprKind::UpvarRef { .. }
             | ExprKind::Break { .. }
             | ExprKind::Continue { .. }
             | ExprKind::Return { .. }
@@ -394,33 +395,22 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 );
                 this.local_decls[local].mutability
             }
-            PlaceRef {
-                local,
-                projection: &[ref proj_base @ .., ProjectionElem::Field(upvar_index, _)],
-            }
+            // A place captured by the closure being built: its mutability is the one of the
+            // capture, which may be followed by the projections of a more precise place.
+            PlaceRef { local, projection: &[ProjectionElem::Field(upvar_index, _), ..] }
             | PlaceRef {
                 local,
-                projection:
-                    &[ref proj_base @ .., ProjectionElem::Field(upvar_index, _), ProjectionElem::Deref],
-            } => {
-                let place = PlaceRef { local, projection: proj_base };
-
-                // Not projected from the implicit `self` in a closure.
-                debug_assert!(
-                    match place.local_or_deref_local() {
-                        Some(local) => local == Local::new(1),
-                        None => false,
-                    },
-                    "Unexpected capture place"
-                );
-                // Not in a closure
+                projection: &[ProjectionElem::Deref, ProjectionElem::Field(upvar_index, _), ..],
+            } if local == Local::new(1) && !this.upvar_mutbls.is_empty() => {
                 debug_assert!(
                     this.upvar_mutbls.len() > upvar_index.index(),
                     "Unexpected capture place"
                 );
                 this.upvar_mutbls[upvar_index.index()]
             }
-            _ => bug!("Unexpected capture place"),
+            // A place starting at a variable of the closure's parent, which is captured more
+            // precisely than the variable itself with `capture_disjoint_fields`.
+            PlaceRef { local, projection: _ } => this.local_decls[local].mutability,
         };
 
         let borrow_kind = match mutability {
@@ -208,11 +208,17 @@ crate enum ExprKind<'tcx> {
         lhs: ExprRef<'tcx>,
         index: ExprRef<'tcx>,
     },
+    /// A local variable.
     VarRef {
         id: hir::HirId,
     },
-    /// first argument, used for self in a closure
-    SelfRef,
+    /// A variable of the parent, used within a closure or generator.
+    UpvarRef {
+        /// The closure or generator capturing the variable.
+        closure_def_id: DefId,
+        /// The captured variable.
+        var_hir_id: hir::HirId,
+    },
     Borrow {
         borrow_kind: BorrowKind,
         arg: ExprRef<'tcx>,
@@ -278,6 +284,10 @@ crate enum ExprKind<'tcx> {
         /// literal value.
         const_id: Option<DefId>,
     },
+    /// An inline `const { ... }` block.
+    ConstBlock {
+        value: &'tcx Const<'tcx>,
+    },
     /// A literal containing the address of a `static`.
     ///
     /// This is only distinguished from `Literal` so that we can register some
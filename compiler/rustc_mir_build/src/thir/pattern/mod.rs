@@ -847,6 +847,35 @@ impl<'a, 'tcx> PatCtxt<'a, 'tcx> {
         }
     }
 
+    /// Converts inline const patterns.
+    fn lower_inline_const(
+        &mut self,
+        anon_const: &'tcx hir::AnonConst,
+        id: hir::HirId,
+        span: Span,
+    ) -> PatKind<'tcx> {
+        let anon_const_def_id = self.tcx.hir().local_def_id(anon_const.hir_id);
+        let value = ty::Const::from_anon_const(self.tcx, anon_const_def_id);
+
+        // Evaluate early like we do in `lower_path`.
+        let value = value.eval(self.tcx, self.param_env);
+
+        match value.val {
+            ty::ConstKind::Param(_) => {
+                self.errors.push(PatternError::ConstParamInPattern(span));
+                PatKind::Wild
+            }
+            ty::ConstKind::Unevaluated(..) => {
+                // If we land here it means the const can't be evaluated because it's `TooGeneric`.
+                self.tcx.sess.span_err(span, "constant pattern depends on a generic parameter");
+                PatKind::Wild
+            }
+            // Evaluation errors have already been reported.
+            ty::ConstKind::Error(_) => PatKind::Wild,
+            _ => *self.const_to_pat(value, id, span, false).kind,
+        }
+    }
+
     /// Converts literals, paths and negation of literals to patterns.
     /// The special case for negation exists to allow things like `-128_i8`
     /// which would overflow if we tried to evaluate `128_i8` and then negate
@@ -854,6 +883,8 @@ impl<'a, 'tcx> PatCtxt<'a, 'tcx> {
     fn lower_lit(&mut self, expr: &'tcx hir::Expr<'tcx>) -> PatKind<'tcx> {
         if let hir::ExprKind::Path(ref qpath) = expr.kind {
             *self.lower_path(qpath, expr.hir_id, expr.span).kind
+        } else if let hir::ExprKind::ConstBlock(ref anon_const) = expr.kind {
+            self.lower_inline_const(anon_const, expr.hir_id, expr.span)
         } else {
             let (lit, neg) = match expr.kind {
                 hir::ExprKind::Lit(ref lit) => (lit, false),
@@ -11,7 +11,9 @@ use rustc_ast::ptr::P;
 use rustc_ast::token::{self, TokenKind};
 use rustc_ast::util::classify;
 use rustc_ast::{AttrStyle, AttrVec, Attribute, MacCall, MacCallStmt, MacStmtStyle};
-use rustc_ast::{Block, BlockCheckMode, Expr, ExprKind, Local, Stmt, StmtKind, DUMMY_NODE_ID};
+use rustc_ast::{
+    Block, BlockCheckMode, Expr, ExprKind, Local, LocalKind, Stmt, StmtKind, DUMMY_NODE_ID,
+};
 use rustc_errors::{Applicability, PResult};
 use rustc_span::source_map::{BytePos, Span};
 use rustc_span::symbol::{kw, sym};
@@ -218,8 +220,41 @@ impl<'a> Parser<'a> {
                 return Err(err);
             }
         };
+        let kind = match init {
+            None => LocalKind::Decl,
+            Some(init) if self.eat_keyword(kw::Else) => {
+                let els = self.parse_let_else(lo, &init)?;
+                LocalKind::InitElse(init, els)
+            }
+            Some(init) => LocalKind::Init(init),
+        };
         let hi = if self.token == token::Semi { self.token.span } else { self.prev_token.span };
-        Ok(P(ast::Local { ty, pat, init, id: DUMMY_NODE_ID, span: lo.to(hi), attrs }))
+        Ok(P(ast::Local { ty, pat, kind, id: DUMMY_NODE_ID, span: lo.to(hi), attrs }))
+    }
+
+    /// Parses the diverging block of a `let PAT = EXPR else { BLOCK };` statement,
+    /// after the `else` keyword.
+    fn parse_let_else(&mut self, lo: Span, init: &Expr) -> PResult<'a, P<Block>> {
+        if !classify::expr_requires_semi_to_be_stmt(init) {
+            // `let x = if a { b } else { c } else { return };` is too confusing to read.
+            let brace_span = init.span.with_lo(init.span.hi() - BytePos(1));
+            self.struct_span_err(
+                brace_span,
+                "right curly brace `}` before `else` in a `let...else` statement not allowed",
+            )
+            .multipart_suggestion(
+                "try wrapping the expression in parentheses",
+                vec![
+                    (init.span.shrink_to_lo(), "(".to_string()),
+                    (init.span.shrink_to_hi(), ")".to_string()),
+                ],
+                Applicability::MachineApplicable,
+            )
+            .emit();
+        }
+        let els = self.parse_block()?;
+        self.sess.gated_spans.gate(sym::let_else, lo.to(els.span));
+        Ok(els)
     }
 
     /// Parses the RHS of a local variable declaration (e.g., '= 14;').
@@ -402,13 +437,13 @@ impl<'a> Parser<'a> {
             StmtKind::Local(ref mut local) => {
                 if let Err(e) = self.expect_semi() {
                     // We might be at the `,` in `let x = foo<bar, baz>;`. Try to recover.
-                    match &mut local.init {
-                        Some(ref mut expr) => {
+                    match &mut local.kind {
+                        LocalKind::Init(expr) | LocalKind::InitElse(expr, _) => {
                             self.check_mistyped_turbofish_with_multiple_type_params(e, expr)?;
                             // We found `foo<bar, baz>`, have we fully recovered?
                             self.expect_semi()?;
                         }
-                        None => return Err(e),
+                        LocalKind::Decl => return Err(e),
                     }
                 }
                 eat_semi = false;
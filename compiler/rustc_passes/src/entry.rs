@@ -8,6 +8,7 @@ use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::{CrateType, EntryFnType};
 use rustc_session::Session;
+use rustc_span::source_map::RemapPathScopes;
 use rustc_span::symbol::sym;
 use rustc_span::{Span, DUMMY_SP};
 
@@ -174,7 +175,7 @@ fn no_main_err(tcx: TyCtxt<'_>, visitor: &EntryContext<'_, '_>) {
         "`main` function not found in crate `{}`",
         tcx.crate_name(LOCAL_CRATE)
     );
-    let filename = &tcx.sess.local_crate_source_file;
+    let filename = tcx.sess.local_crate_source_file_for_scope(RemapPathScopes::DIAGNOSTICS);
     let note = if !visitor.non_main_fns.is_empty() {
         for &(_, span) in &visitor.non_main_fns {
             err.span_note(span, "here is a function named `main`");
@@ -187,7 +188,7 @@ fn no_main_err(tcx: TyCtxt<'_>, visitor: &EntryContext<'_, '_>) {
         // There were some functions named `main` though. Try to give the user a hint.
         format!(
             "the main function must be defined at the crate level{}",
-            filename.as_ref().map(|f| format!(" (in `{}`)", f.display())).unwrap_or_default()
+            filename.map(|f| format!(" (in `{}`)", f.display())).unwrap_or_default()
         )
     } else if let Some(filename) = filename {
         format!("consider adding a `main` function to `{}`", filename.display())
@@ -421,6 +421,7 @@ impl<'tcx> Visitor<'tcx> for IrMaps<'tcx> {
             hir::ExprKind::Index(..)
             | hir::ExprKind::Field(..)
             | hir::ExprKind::Array(..)
+            | hir::ExprKind::ConstBlock(..)
             | hir::ExprKind::Call(..)
             | hir::ExprKind::MethodCall(..)
             | hir::ExprKind::Tup(..)
@@ -1232,6 +1233,7 @@ impl<'a, 'tcx> Liveness<'a, 'tcx> {
             }
 
             hir::ExprKind::Lit(..)
+            | hir::ExprKind::ConstBlock(..)
             | hir::ExprKind::Err
             | hir::ExprKind::Path(hir::QPath::TypeRelative(..))
             | hir::ExprKind::Path(hir::QPath::LangItem(..)) => succ,
@@ -1469,6 +1471,7 @@ fn check_expr<'tcx>(this: &mut Liveness<'_, 'tcx>, expr: &'tcx Expr<'tcx>) {
         | hir::ExprKind::Index(..)
         | hir::ExprKind::Field(..)
         | hir::ExprKind::Array(..)
+        | hir::ExprKind::ConstBlock(..)
         | hir::ExprKind::Tup(..)
         | hir::ExprKind::Binary(..)
         | hir::ExprKind::Cast(..)
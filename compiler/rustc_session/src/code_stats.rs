@@ -1,7 +1,11 @@
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::sync::Lock;
+use rustc_serialize::json::as_pretty_json;
 use rustc_target::abi::{Align, Size};
 use std::cmp::{self, Ordering};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -26,6 +30,16 @@ pub struct FieldInfo {
     pub align: u64,
 }
 
+/// The largest niche of a type, i.e. the range of invalid values that an enum containing the
+/// type can use to store its discriminant.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Encodable)]
+pub struct NicheInfo {
+    pub offset: u64,
+    pub size: u64,
+    /// How many invalid values are still free.
+    pub available: u128,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum DataTypeKind {
     Struct,
@@ -34,6 +48,17 @@ pub enum DataTypeKind {
     Closure,
 }
 
+impl DataTypeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            DataTypeKind::Struct => "struct",
+            DataTypeKind::Union => "union",
+            DataTypeKind::Enum => "enum",
+            DataTypeKind::Closure => "closure",
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct TypeSizeInfo {
     pub kind: DataTypeKind,
@@ -42,7 +67,68 @@ pub struct TypeSizeInfo {
     pub overall_size: u64,
     pub packed: bool,
     pub opt_discr_size: Option<u64>,
+    pub niche: Option<NicheInfo>,
     pub variants: Vec<VariantInfo>,
+    /// Where the type is defined, as printed by `SourceMap::span_to_string`.
+    pub span: Option<String>,
+    /// The seed the fields were shuffled with, if the layout was randomized.
+    pub field_shuffle_seed: Option<u64>,
+}
+
+/// The JSON form of a `TypeSizeInfo`, with the padding that the text report prints between
+/// fields worked out.
+#[derive(Encodable)]
+struct JsonTypeSize<'a> {
+    type_description: &'a str,
+    kind: &'static str,
+    span: Option<&'a str>,
+    size: u64,
+    align: u64,
+    packed: bool,
+    discriminant_size: Option<u64>,
+    niche: Option<&'a NicheInfo>,
+    field_shuffle_seed: Option<u64>,
+    variants: Vec<JsonVariant<'a>>,
+    end_padding: u64,
+}
+
+#[derive(Encodable)]
+struct JsonVariant<'a> {
+    name: Option<&'a str>,
+    /// The size of the variant, including the discriminant if there is one.
+    size: u64,
+    align: u64,
+    /// `false` if `size` is only a lower bound, because the variant is unsized.
+    exact: bool,
+    fields: Vec<JsonField<'a>>,
+}
+
+#[derive(Encodable)]
+struct JsonField<'a> {
+    name: &'a str,
+    offset: u64,
+    size: u64,
+    align: u64,
+    /// The padding between the end of the previous field (or the discriminant) and this one.
+    padding_before: u64,
+}
+
+#[derive(Encodable)]
+struct JsonTypeSizes<'a> {
+    types: Vec<JsonTypeSize<'a>>,
+}
+
+/// Sorts the recorded types large-to-small, and by description for types of the same size.
+fn sorted_type_sizes(type_sizes: &FxHashSet<TypeSizeInfo>) -> Vec<&TypeSizeInfo> {
+    let mut sorted: Vec<_> = type_sizes.iter().collect();
+    sorted.sort_by(|info1, info2| {
+        // (reversing cmp order to get large-to-small ordering)
+        match info2.overall_size.cmp(&info1.overall_size) {
+            Ordering::Equal => info1.type_description.cmp(&info2.type_description),
+            other => other,
+        }
+    });
+    sorted
 }
 
 #[derive(Default)]
@@ -59,7 +145,10 @@ impl CodeStats {
         overall_size: Size,
         packed: bool,
         opt_discr_size: Option<Size>,
+        niche: Option<NicheInfo>,
         mut variants: Vec<VariantInfo>,
+        span: Option<String>,
+        field_shuffle_seed: Option<u64>,
     ) {
         // Sort variants so the largest ones are shown first. A stable sort is
         // used here so that source code order is preserved for all variants
@@ -72,30 +161,29 @@ impl CodeStats {
             overall_size: overall_size.bytes(),
             packed,
             opt_discr_size: opt_discr_size.map(|s| s.bytes()),
+            niche,
             variants,
+            span,
+            field_shuffle_seed,
         };
         self.type_sizes.borrow_mut().insert(info);
     }
 
     pub fn print_type_sizes(&self) {
         let type_sizes = self.type_sizes.borrow();
-        let mut sorted: Vec<_> = type_sizes.iter().collect();
-
-        // Primary sort: large-to-small.
-        // Secondary sort: description (dictionary order)
-        sorted.sort_by(|info1, info2| {
-            // (reversing cmp order to get large-to-small ordering)
-            match info2.overall_size.cmp(&info1.overall_size) {
-                Ordering::Equal => info1.type_description.cmp(&info2.type_description),
-                other => other,
+        for info in sorted_type_sizes(&type_sizes) {
+            if let Some(seed) = info.field_shuffle_seed {
+                println!(
+                    "print-type-size type: `{}`: {} bytes, alignment: {} bytes, \
+                     field shuffle seed: {}",
+                    info.type_description, info.overall_size, info.align, seed
+                );
+            } else {
+                println!(
+                    "print-type-size type: `{}`: {} bytes, alignment: {} bytes",
+                    info.type_description, info.overall_size, info.align
+                );
             }
-        });
-
-        for info in &sorted {
-            println!(
-                "print-type-size type: `{}`: {} bytes, alignment: {} bytes",
-                info.type_description, info.overall_size, info.align
-            );
             let indent = "    ";
 
             let discr_size = if let Some(discr_size) = info.opt_discr_size {
@@ -190,4 +278,66 @@ impl CodeStats {
             }
         }
     }
+
+    /// Writes the same information as `print_type_sizes` to `path` as JSON, along with the
+    /// definition site and largest niche of each type.
+    pub fn write_type_sizes_json(&self, path: &Path) -> io::Result<()> {
+        let type_sizes = self.type_sizes.borrow();
+        let types = sorted_type_sizes(&type_sizes)
+            .into_iter()
+            .map(|info| {
+                let discr_size = info.opt_discr_size.unwrap_or(0);
+                let mut max_variant_size = discr_size;
+                let variants = info
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        max_variant_size = cmp::max(max_variant_size, variant.size);
+                        // Same order as in the text report, see `print_type_sizes`.
+                        let mut fields: Vec<_> = variant.fields.iter().collect();
+                        fields.sort_by_key(|f| (f.offset, f.size));
+                        let mut min_offset = discr_size;
+                        let fields = fields
+                            .into_iter()
+                            .map(|field| {
+                                let padding_before = field.offset.saturating_sub(min_offset);
+                                min_offset = field.offset + field.size;
+                                JsonField {
+                                    name: &field.name,
+                                    offset: field.offset,
+                                    size: field.size,
+                                    align: field.align,
+                                    padding_before,
+                                }
+                            })
+                            .collect();
+                        JsonVariant {
+                            name: variant.name.as_deref(),
+                            size: variant.size,
+                            align: variant.align,
+                            exact: variant.kind == SizeKind::Exact,
+                            fields,
+                        }
+                    })
+                    .collect();
+                JsonTypeSize {
+                    type_description: &info.type_description,
+                    kind: info.kind.as_str(),
+                    span: info.span.as_deref(),
+                    size: info.overall_size,
+                    align: info.align,
+                    packed: info.packed,
+                    discriminant_size: info.opt_discr_size,
+                    niche: info.niche.as_ref(),
+                    field_shuffle_seed: info.field_shuffle_seed,
+                    variants,
+                    end_padding: info.overall_size.saturating_sub(max_variant_size),
+                }
+            })
+            .collect();
+
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "{}", as_pretty_json(&JsonTypeSizes { types }))?;
+        file.flush()
+    }
 }
@@ -10,15 +10,14 @@ use crate::{early_error, early_warn, Session};
 
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::impl_stable_hash_via_hash;
-use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 
 use rustc_target::abi::{Align, TargetDataLayout};
-use rustc_target::spec::{Target, TargetTriple};
+use rustc_target::spec::{SanitizerSet, SplitDebuginfo, Target, TargetTriple};
 
 use crate::parse::CrateConfig;
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::{Edition, DEFAULT_EDITION, EDITION_NAME_LIST};
-use rustc_span::source_map::{FileName, FilePathMapping};
+use rustc_span::source_map::{FileName, FilePathMapping, RemapPathScopes};
 use rustc_span::symbol::{sym, Symbol};
 use rustc_span::SourceFileHashAlgorithm;
 
@@ -39,58 +38,6 @@ pub struct Config {
     pub ptr_width: u32,
 }
 
-bitflags! {
-    #[derive(Default, Encodable, Decodable)]
-    pub struct SanitizerSet: u8 {
-        const ADDRESS = 1 << 0;
-        const LEAK    = 1 << 1;
-        const MEMORY  = 1 << 2;
-        const THREAD  = 1 << 3;
-    }
-}
-
-/// Formats a sanitizer set as a comma separated list of sanitizers' names.
-impl fmt::Display for SanitizerSet {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut first = true;
-        for s in *self {
-            let name = match s {
-                SanitizerSet::ADDRESS => "address",
-                SanitizerSet::LEAK => "leak",
-                SanitizerSet::MEMORY => "memory",
-                SanitizerSet::THREAD => "thread",
-                _ => panic!("unrecognized sanitizer {:?}", s),
-            };
-            if !first {
-                f.write_str(",")?;
-            }
-            f.write_str(name)?;
-            first = false;
-        }
-        Ok(())
-    }
-}
-
-impl IntoIterator for SanitizerSet {
-    type Item = SanitizerSet;
-    type IntoIter = std::vec::IntoIter<SanitizerSet>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        [SanitizerSet::ADDRESS, SanitizerSet::LEAK, SanitizerSet::MEMORY, SanitizerSet::THREAD]
-            .iter()
-            .copied()
-            .filter(|&s| self.contains(s))
-            .collect::<Vec<_>>()
-            .into_iter()
-    }
-}
-
-impl<CTX> HashStable<CTX> for SanitizerSet {
-    fn hash_stable(&self, ctx: &mut CTX, hasher: &mut StableHasher) {
-        self.bits().hash_stable(ctx, hasher);
-    }
-}
-
 /// The different settings that the `-Z strip` flag can have.
 #[derive(Clone, Copy, PartialEq, Hash, Debug)]
 pub enum Strip {
@@ -117,6 +64,19 @@ pub enum CFGuard {
     Checks,
 }
 
+/// The different settings that the `-Z split-dwarf` flag can have, which controls where the
+/// DWARF sections which don't need to be linked end up when `-C split-debuginfo` is enabled.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SplitDwarfKind {
+    /// Sections which do not require relocation are written into the object file but ignored by
+    /// the linker.
+    Single,
+
+    /// Sections which do not require relocation are written into a DWARF object (`.dwo`) file
+    /// which is ignored by the linker.
+    Split,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum OptLevel {
     No,         // -O0
@@ -219,6 +179,33 @@ pub enum SymbolManglingVersion {
 
 impl_stable_hash_via_hash!(SymbolManglingVersion);
 
+/// The format of the report produced by `-Z print-type-sizes`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PrintTypeSizes {
+    /// `print-type-size` lines on stdout.
+    Text,
+    /// A `<crate>.type-sizes.json` file in the output directory.
+    Json,
+}
+
+/// The format of the report written by `-Z dump-mono-stats`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MonoStatsFormat {
+    /// A `<crate>.mono-stats.md` file in the output directory.
+    Markdown,
+    /// A `<crate>.mono-stats.json` file in the output directory.
+    Json,
+}
+
+impl MonoStatsFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            MonoStatsFormat::Markdown => "mono-stats.md",
+            MonoStatsFormat::Json => "mono-stats.json",
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum DebugInfo {
     None,
@@ -321,6 +308,9 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF log, written once compilation ends, that is consumed by static analysis
+    /// tools and code scanning services.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -538,6 +528,7 @@ impl_stable_hash_via_hash!(OutputFilenames);
 
 pub const RLINK_EXT: &str = "rlink";
 pub const RUST_CGU_EXT: &str = "rcgu";
+pub const DWARF_OBJECT_EXT: &str = "dwo";
 
 impl OutputFilenames {
     pub fn new(
@@ -593,11 +584,34 @@ impl OutputFilenames {
         self.with_extension(&extension)
     }
 
+    /// Like `temp_path`, but specifically for dwarf objects.
+    pub fn temp_path_dwo(&self, codegen_unit_name: Option<&str>) -> PathBuf {
+        self.temp_path_ext(DWARF_OBJECT_EXT, codegen_unit_name)
+    }
+
     pub fn with_extension(&self, extension: &str) -> PathBuf {
         let mut path = self.out_directory.join(&self.filestem);
         path.set_extension(extension);
         path
     }
+
+    /// Returns the path which the Split DWARF attributes of the codegen unit's compile unit
+    /// point at, or `None` if debuginfo isn't being split. With `-Z split-dwarf=single` that is
+    /// the object file itself, otherwise it is the `.dwo` file LLVM writes next to it.
+    pub fn split_dwarf_path(
+        &self,
+        split_debuginfo: SplitDebuginfo,
+        split_dwarf_kind: SplitDwarfKind,
+        codegen_unit_name: Option<&str>,
+    ) -> Option<PathBuf> {
+        match (split_debuginfo, split_dwarf_kind) {
+            (SplitDebuginfo::Off, _) => None,
+            (_, SplitDwarfKind::Single) => {
+                Some(self.temp_path(OutputType::Object, codegen_unit_name))
+            }
+            (_, SplitDwarfKind::Split) => Some(self.temp_path_dwo(codegen_unit_name)),
+        }
+    }
 }
 
 pub fn host_triple() -> &'static str {
@@ -665,7 +679,15 @@ impl Options {
     }
 
     pub fn file_path_mapping(&self) -> FilePathMapping {
-        FilePathMapping::new(self.remap_path_prefix.clone())
+        let mut mapping = self.remap_path_prefix.clone();
+        // Later mappings take precedence, so `-Z remap-cwd-prefix` wins over a
+        // `--remap-path-prefix` for a parent of the working directory.
+        if let Some(to) = &self.debugging_opts.remap_cwd_prefix {
+            if let Ok(cwd) = std::env::current_dir() {
+                mapping.push((cwd, to.clone()));
+            }
+        }
+        FilePathMapping::new(mapping, self.debugging_opts.remap_path_scope)
     }
 
     /// Returns `true` if there will be an output file generated.
@@ -1061,7 +1083,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|sarif|short",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1206,12 +1228,13 @@ pub fn parse_error_format(
             }
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
+                    "argument for `--error-format` must be `human`, `json`, `sarif` or \
                      `short` (instead was `{}`)",
                     arg
                 ),
@@ -1289,6 +1312,12 @@ fn check_debug_option_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
     }
 }
 
@@ -1756,6 +1785,12 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         );
     }
 
+    // Branch regions refer to the counters of the blocks that each branch leads to, which are
+    // only injected with `-Z experimental-coverage`.
+    if debugging_opts.coverage_branches {
+        debugging_opts.experimental_coverage = true;
+    }
+
     if debugging_opts.experimental_coverage {
         debugging_opts.instrument_coverage = true;
     }
@@ -1796,6 +1831,16 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let sysroot_opt = matches.opt_str("sysroot").map(|m| PathBuf::from(&m));
     let target_triple = parse_target_triple(matches, error_format);
+
+    // `-C split-debuginfo` only changes whether `dsymutil` runs on macOS, which has been
+    // supported for a long time; everywhere else it is still experimental.
+    if !debugging_opts.unstable_options
+        && cg.split_debuginfo.is_some()
+        && !target_triple.triple().contains("apple")
+    {
+        early_error(error_format, "`-C split-debuginfo` is unstable on this platform");
+    }
+
     let opt_level = parse_opt_level(matches, &cg, error_format);
     // The `-g` and `-C debuginfo` flags specify the same setting, so we want to be able
     // to use them interchangeably. See the note above (regarding `-O` and `-C opt-level`)
@@ -2097,15 +2142,15 @@ impl PpMode {
 crate mod dep_tracking {
     use super::{
         CFGuard, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli, OptLevel,
-        OutputTypes, Passes, SanitizerSet, SourceFileHashAlgorithm, SwitchWithOptPath,
-        SymbolManglingVersion, TrimmedDefPaths,
+        OutputTypes, Passes, RemapPathScopes, SanitizerSet, SourceFileHashAlgorithm,
+        SwitchWithOptPath, SplitDwarfKind, SymbolManglingVersion, TrimmedDefPaths,
     };
     use crate::lint;
     use crate::utils::NativeLibKind;
     use rustc_feature::UnstableFeatures;
     use rustc_span::edition::Edition;
     use rustc_target::spec::{CodeModel, MergeFunctions, PanicStrategy, RelocModel};
-    use rustc_target::spec::{RelroLevel, TargetTriple, TlsModel};
+    use rustc_target::spec::{RelroLevel, SplitDebuginfo, StackProtector, TargetTriple, TlsModel};
     use std::collections::hash_map::DefaultHasher;
     use std::collections::BTreeMap;
     use std::hash::Hash;
@@ -2158,6 +2203,7 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(Option<TlsModel>);
     impl_dep_tracking_hash_via_hash!(Option<PanicStrategy>);
     impl_dep_tracking_hash_via_hash!(Option<RelroLevel>);
+    impl_dep_tracking_hash_via_hash!(Option<SplitDebuginfo>);
     impl_dep_tracking_hash_via_hash!(Option<lint::Level>);
     impl_dep_tracking_hash_via_hash!(Option<PathBuf>);
     impl_dep_tracking_hash_via_hash!(CrateType);
@@ -2173,6 +2219,8 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(NativeLibKind);
     impl_dep_tracking_hash_via_hash!(SanitizerSet);
     impl_dep_tracking_hash_via_hash!(CFGuard);
+    impl_dep_tracking_hash_via_hash!(SplitDwarfKind);
+    impl_dep_tracking_hash_via_hash!(StackProtector);
     impl_dep_tracking_hash_via_hash!(TargetTriple);
     impl_dep_tracking_hash_via_hash!(Edition);
     impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
@@ -2180,6 +2228,7 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(SymbolManglingVersion);
     impl_dep_tracking_hash_via_hash!(Option<SourceFileHashAlgorithm>);
     impl_dep_tracking_hash_via_hash!(TrimmedDefPaths);
+    impl_dep_tracking_hash_via_hash!(RemapPathScopes);
 
     impl_dep_tracking_hash_for_sortable_vec_of!(String);
     impl_dep_tracking_hash_for_sortable_vec_of!(PathBuf);
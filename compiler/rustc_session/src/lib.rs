@@ -2,8 +2,6 @@
 #![feature(once_cell)]
 #![feature(or_patterns)]
 
-#[macro_use]
-extern crate bitflags;
 #[macro_use]
 extern crate rustc_macros;
 
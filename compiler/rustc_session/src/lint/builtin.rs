@@ -2637,6 +2637,51 @@ declare_lint! {
     };
 }
 
+declare_lint! {
+    /// The `disjoint_capture_drop_reorder` lint detects variables that aren't completely
+    /// captured when the feature `capture_disjoint_fields` is enabled and it affects the Drop
+    /// order of at least one path starting at this variable.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(disjoint_capture_drop_reorder)]
+    /// # #![allow(unused)]
+    /// struct FancyInteger(i32);
+    ///
+    /// impl Drop for FancyInteger {
+    ///     fn drop(&mut self) {
+    ///         println!("Just dropped {}", self.0);
+    ///     }
+    /// }
+    ///
+    /// struct Point { x: FancyInteger, y: FancyInteger }
+    ///
+    /// fn main() {
+    ///     let p = Point { x: FancyInteger(10), y: FancyInteger(20) };
+    ///
+    ///     let c = || {
+    ///         let x = p.x;
+    ///     };
+    ///
+    ///     c();
+    ///
+    ///     // ... More code ...
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// In the above example `p.y` will be dropped at the end of `main` instead of
+    /// with `c` if the feature `capture_disjoint_fields` is enabled, as only `p.x`
+    /// would then be captured by the closure.
+    pub DISJOINT_CAPTURE_DROP_REORDER,
+    Allow,
+    "detects closures whose drop order would change with disjoint field captures"
+}
+
 declare_tool_lint! {
     pub rustc::INEFFECTIVE_UNSTABLE_TRAIT_IMPL,
     Deny,
@@ -2721,6 +2766,7 @@ declare_lint_pass! {
         CENUM_IMPL_DROP_CAST,
         CONST_EVALUATABLE_UNCHECKED,
         INEFFECTIVE_UNSTABLE_TRAIT_IMPL,
+        DISJOINT_CAPTURE_DROP_REORDER,
     ]
 }
 
@@ -6,10 +6,12 @@ use crate::search_paths::SearchPath;
 use crate::utils::NativeLibKind;
 
 use rustc_target::spec::{CodeModel, LinkerFlavor, MergeFunctions, PanicStrategy};
-use rustc_target::spec::{RelocModel, RelroLevel, TargetTriple, TlsModel};
+use rustc_target::spec::{RelocModel, RelroLevel, SanitizerSet, SplitDebuginfo};
+use rustc_target::spec::{StackProtector, TargetTriple, TlsModel};
 
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::Edition;
+use rustc_span::source_map::RemapPathScopes;
 use rustc_span::SourceFileHashAlgorithm;
 
 use std::collections::BTreeMap;
@@ -251,11 +253,20 @@ macro_rules! options {
         pub const parse_passes: &str = "a space-separated list of passes, or `all`";
         pub const parse_panic_strategy: &str = "either `unwind` or `abort`";
         pub const parse_relro_level: &str = "one of: `full`, `partial`, or `off`";
-        pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `leak`, `memory` or `thread`";
+        pub const parse_sanitizers: &str =
+            "comma separated list of sanitizers: `address`, `cfi`, `hwaddress`, `leak`, `memory` or `thread`";
         pub const parse_sanitizer_memory_track_origins: &str = "0, 1, or 2";
         pub const parse_cfguard: &str =
             "either a boolean (`yes`, `no`, `on`, `off`, etc), `checks`, or `nochecks`";
         pub const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
+        pub const parse_split_debuginfo: &str = "one of: `off`, `packed`, or `unpacked`";
+        pub const parse_split_dwarf_kind: &str = "one of: `single` or `split`";
+        pub const parse_print_type_sizes: &str =
+            "either a boolean (`yes`, `no`, `on`, `off`, etc), `text`, or `json`";
+        pub const parse_remap_path_scope: &str =
+            "comma separated list of scopes: `diagnostics`, `macro`, `debuginfo`, `object`, or `all`";
+        pub const parse_mono_stats_format: &str = "either `markdown` or `json`";
+        pub const parse_stack_protector: &str = "one of: `none`, `basic`, `strong`, or `all`";
         pub const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavor::one_of();
         pub const parse_optimization_fuel: &str = "crate=integer";
         pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
@@ -468,6 +479,8 @@ macro_rules! options {
                 for s in v.split(',') {
                     *slot |= match s {
                         "address" => SanitizerSet::ADDRESS,
+                        "cfi" => SanitizerSet::CFI,
+                        "hwaddress" => SanitizerSet::HWADDRESS,
                         "leak" => SanitizerSet::LEAK,
                         "memory" => SanitizerSet::MEMORY,
                         "thread" => SanitizerSet::THREAD,
@@ -499,6 +512,74 @@ macro_rules! options {
             true
         }
 
+        fn parse_split_debuginfo(slot: &mut Option<SplitDebuginfo>, v: Option<&str>) -> bool {
+            match v.and_then(|s| SplitDebuginfo::from_str(s).ok()) {
+                Some(e) => *slot = Some(e),
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_split_dwarf_kind(slot: &mut SplitDwarfKind, v: Option<&str>) -> bool {
+            match v {
+                Some("single") => *slot = SplitDwarfKind::Single,
+                Some("split") => *slot = SplitDwarfKind::Split,
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_print_type_sizes(slot: &mut Option<PrintTypeSizes>, v: Option<&str>) -> bool {
+            match v {
+                Some("text") => *slot = Some(PrintTypeSizes::Text),
+                Some("json") => *slot = Some(PrintTypeSizes::Json),
+                _ => {
+                    let mut bool_arg = false;
+                    if !parse_bool(&mut bool_arg, v) {
+                        return false;
+                    }
+                    *slot = if bool_arg { Some(PrintTypeSizes::Text) } else { None };
+                }
+            }
+            true
+        }
+
+        fn parse_remap_path_scope(slot: &mut RemapPathScopes, v: Option<&str>) -> bool {
+            if let Some(v) = v {
+                *slot = RemapPathScopes::EMPTY;
+                for s in v.split(',') {
+                    *slot |= match s {
+                        "diagnostics" => RemapPathScopes::DIAGNOSTICS,
+                        "macro" => RemapPathScopes::MACRO,
+                        "debuginfo" => RemapPathScopes::DEBUGINFO,
+                        "object" => RemapPathScopes::OBJECT,
+                        "all" => RemapPathScopes::ALL,
+                        _ => return false,
+                    }
+                }
+                true
+            } else {
+                false
+            }
+        }
+
+        fn parse_mono_stats_format(slot: &mut Option<MonoStatsFormat>, v: Option<&str>) -> bool {
+            match v {
+                Some("markdown") => *slot = Some(MonoStatsFormat::Markdown),
+                Some("json") => *slot = Some(MonoStatsFormat::Json),
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_stack_protector(slot: &mut StackProtector, v: Option<&str>) -> bool {
+            match v.and_then(|s| StackProtector::from_str(s).ok()) {
+                Some(ssp) => *slot = ssp,
+                _ => return false,
+            }
+            true
+        }
+
         fn parse_cfguard(slot: &mut CFGuard, v: Option<&str>) -> bool {
             if v.is_some() {
                 let mut bool_arg = None;
@@ -805,6 +886,8 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
         "save all temporary output files during compilation (default: no)"),
     soft_float: bool = (false, parse_bool, [TRACKED],
         "use soft float ABI (*eabihf targets only) (default: no)"),
+    split_debuginfo: Option<SplitDebuginfo> = (None, parse_split_debuginfo, [TRACKED],
+        "how to handle split-debuginfo, a platform-specific option"),
     target_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
         "select target processor (`rustc --print target-cpus` for details)"),
     target_feature: String = (String::new(), parse_target_feature, [TRACKED],
@@ -854,6 +937,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    coverage_branches: bool = (false, parse_bool, [TRACKED],
+        "add branch regions for each `if` condition, left `&&` and `||` operand and `match` arm to \
+        the `-Z instrument-coverage` coverage map; implies `-Z experimental-coverage` \
+        (default: no)"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
         "inject the given attribute in the crate"),
     debug_macros: bool = (false, parse_bool, [TRACKED],
@@ -895,6 +982,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         all `statement`s (including terminators), only `terminator` spans, or \
         computed `block` spans (one span encompassing a block's terminator and \
         all statements)."),
+    dump_mono_stats: Option<MonoStatsFormat> = (None, parse_mono_stats_format, [UNTRACKED],
+        "write statistics about the monomorphized instances of each generic item into the \
+        output directory, as `markdown` or `json` (default: no)"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emit a section containing stack size metadata (default: no)"),
     experimental_coverage: bool = (false, parse_bool, [TRACKED],
@@ -931,6 +1021,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "verify incr. comp. hashes of green query instances (default: no)"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "control whether `#[inline]` functions are in all CGUs"),
+    inline_mir: Option<bool> = (None, parse_opt_bool, [TRACKED],
+        "enable MIR inlining; by default it only runs with `-Z mir-opt-level=2` or higher"),
+    inline_mir_hint_threshold: usize = (100, parse_uint, [TRACKED],
+        "MIR inlining threshold for functions with an inline hint (default: 100)"),
+    inline_mir_threshold: usize = (50, parse_uint, [TRACKED],
+        "default MIR inlining threshold (default: 50)"),
     input_stats: bool = (false, parse_bool, [UNTRACKED],
         "gather statistics about the input (default: no)"),
     insert_sideeffect: bool = (false, parse_bool, [TRACKED],
@@ -948,6 +1044,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "insert function instrument code for mcount-based tracing (default: no)"),
     keep_hygiene_data: bool = (false, parse_bool, [UNTRACKED],
         "keep hygiene data after analysis (default: no)"),
+    layout_seed: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "salt the seed that `-Z randomize-layout` shuffles fields with (default: 0)"),
     link_native_libraries: bool = (true, parse_bool, [UNTRACKED],
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
@@ -967,7 +1065,7 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "emit Retagging MIR statements, interpreted e.g., by miri; implies -Zmir-opt-level=0 \
         (default: no)"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
-        "MIR optimization level (0-3; default: 1)"),
+        "MIR optimization level (0-4, where 4 adds experimental passes; default: 1)"),
     mutable_noalias: bool = (false, parse_bool, [TRACKED],
         "emit noalias metadata for mutable references (default: no)"),
     new_llvm_pass_manager: bool = (false, parse_bool, [TRACKED],
@@ -1024,8 +1122,11 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print the LLVM optimization passes being run (default: no)"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "print the result of the monomorphization collection pass"),
-    print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
-        "print layout information for each type encountered (default: no)"),
+    print_stack_protectors: bool = (false, parse_bool, [UNTRACKED],
+        "print the functions that stack protectors were inserted into (default: no)"),
+    print_type_sizes: Option<PrintTypeSizes> = (None, parse_print_type_sizes, [UNTRACKED],
+        "print layout information for each type encountered, either as text on stdout or as \
+        JSON in the output directory (default: no)"),
     proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
          "show backtraces for panics during proc-macro execution (default: no)"),
     profile: bool = (false, parse_bool, [TRACKED],
@@ -1037,8 +1138,18 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "enable queries of the dependency graph for regression testing (default: no)"),
     query_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about the query system (default: no)"),
+    randomize_layout: bool = (false, parse_bool, [TRACKED],
+        "randomize the field order of `repr(Rust)` types, seeded by their def path hash \
+        (default: no)"),
     relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
         "choose which RELRO level to use"),
+    remap_cwd_prefix: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "remap the current working directory to the given path, like \
+        `--remap-path-prefix=$(pwd)=<path>` but without having to know the directory"),
+    remap_path_scope: RemapPathScopes = (RemapPathScopes::ALL, parse_remap_path_scope, [TRACKED],
+        "comma separated list of the kinds of output that `--remap-path-prefix` applies to: \
+        `diagnostics`, `macro` (`file!()` and panic locations), `debuginfo`, `object` (paths \
+        to other output files, like the split DWARF file) or `all` (default: all)"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
         "immediately print bugs registered with `delay_span_bug` (default: no)"),
     // The default historical behavior was to always run dsymutil, so we're
@@ -1076,8 +1187,21 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     // o/w tests have closure@path
     span_free_formats: bool = (false, parse_bool, [UNTRACKED],
         "exclude spans when debug-printing compiler state (default: no)"),
+    split_dwarf: SplitDwarfKind = (SplitDwarfKind::Split, parse_split_dwarf_kind, [TRACKED],
+        "split dwarf variant (only if -Csplit-debuginfo is enabled and on relevant platform)
+        (default: `split`)
+
+        `split`: sections which do not require relocation are written into a DWARF object (`.dwo`)
+                 file which is ignored by the linker
+        `single`: sections which do not require relocation are written into object file but ignored
+                  by the linker"),
+    split_dwarf_inlining: bool = (true, parse_bool, [TRACKED],
+        "provide minimal debug info in the object/executable to facilitate online \
+         symbolication/stack traces in the absence of .dwo/.dwp files when using Split DWARF"),
     src_hash_algorithm: Option<SourceFileHashAlgorithm> = (None, parse_src_file_hash, [TRACKED],
         "hash algorithm of source files in debug info (`md5`, or `sha1`)"),
+    stack_protector: StackProtector = (StackProtector::None, parse_stack_protector, [TRACKED],
+        "control stack smash protection strategy (`none` (default), `basic`, `strong`, or `all`)"),
     strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
         "tell the linker which information to strip (`none` (default), `debuginfo` or `symbols`)"),
     symbol_mangling_version: SymbolManglingVersion = (SymbolManglingVersion::Legacy,
@@ -1105,6 +1229,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
     trace_macros: bool = (false, parse_bool, [UNTRACKED],
         "for every macro invocation, print its name and arguments (default: no)"),
+    translate_additional_ftl: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "additional message catalog to translate diagnostics with, taking precedence over \
+        the catalogs of `-Z translate-lang`"),
+    translate_lang: Option<String> = (None, parse_opt_string, [TRACKED],
+        "language to translate diagnostics to, using the catalogs in \
+        `$sysroot/share/locale/<lang>` (default: en-US)"),
     treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
         "treat error number `val` that occurs as bug"),
     trim_diagnostic_paths: bool = (true, parse_bool, [UNTRACKED],
@@ -1,7 +1,7 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
-use crate::config::{self, CrateType, OutputType, PrintRequest, SanitizerSet, SwitchWithOptPath};
+pub use crate::code_stats::{DataTypeKind, FieldInfo, NicheInfo, SizeKind, VariantInfo};
+use crate::config::{self, CrateType, OutputType, PrintRequest, SwitchWithOptPath};
 use crate::filesearch;
 use crate::lint;
 use crate::parse::ParseSess;
@@ -20,14 +20,16 @@ use rustc_data_structures::sync::{
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::registry::Registry;
 use rustc_errors::{Applicability, DiagnosticBuilder, DiagnosticId, ErrorReported};
 use rustc_span::edition::Edition;
-use rustc_span::source_map::{FileLoader, MultiSpan, RealFileLoader, SourceMap, Span};
+use rustc_span::source_map::{FileLoader, MultiSpan, RealFileLoader, RemapPathScopes};
+use rustc_span::source_map::{SourceMap, Span};
 use rustc_span::{sym, SourceFileHashAlgorithm, Symbol};
 use rustc_target::asm::InlineAsmArch;
-use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel};
-use rustc_target::spec::{Target, TargetTriple, TlsModel};
+use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel, SplitDebuginfo};
+use rustc_target::spec::{SanitizerSet, StackProtector, Target, TargetTriple, TlsModel};
 
 use std::cell::{self, RefCell};
 use std::env;
@@ -35,7 +37,7 @@ use std::fmt;
 use std::io::Write;
 use std::num::NonZeroU32;
 use std::ops::{Div, Mul};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -113,9 +115,13 @@ pub struct Session {
     /// The name of the root source file of the crate, in the local file system.
     /// `None` means that there is no source file.
     pub local_crate_source_file: Option<PathBuf>,
+    /// `local_crate_source_file` before path remapping.
+    pub unmapped_local_crate_source_file: Option<PathBuf>,
     /// The directory the compiler has been executed in plus a flag indicating
     /// if the value stored here has been affected by path remapping.
     pub working_dir: (PathBuf, bool),
+    /// `working_dir` before path remapping.
+    pub unmapped_working_dir: PathBuf,
 
     /// Set of `(DiagnosticId, Option<Span>, message)` tuples tracking
     /// (sub)diagnostics that have been set once, but should not be set again,
@@ -458,7 +464,7 @@ impl Session {
     /// warnings or errors are emitted. If no messages are emitted ("good path"), then
     /// it's likely a bug.
     pub fn delay_good_path_bug(&self, msg: &str) {
-        if self.opts.debugging_opts.print_type_sizes
+        if self.opts.debugging_opts.print_type_sizes.is_some()
             || self.opts.debugging_opts.query_dep_graph
             || self.opts.debugging_opts.dump_mir.is_some()
             || self.opts.debugging_opts.unpretty.is_some()
@@ -565,6 +571,22 @@ impl Session {
     pub fn source_map(&self) -> &SourceMap {
         self.parse_sess.source_map()
     }
+    /// The working directory as it should appear in `scope`, see `-Z remap-path-scope`.
+    pub fn working_dir_for_scope(&self, scope: RemapPathScopes) -> &Path {
+        if self.opts.debugging_opts.remap_path_scope.contains(scope) {
+            &self.working_dir.0
+        } else {
+            &self.unmapped_working_dir
+        }
+    }
+    /// The root source file of the crate as it should appear in `scope`.
+    pub fn local_crate_source_file_for_scope(&self, scope: RemapPathScopes) -> Option<&Path> {
+        if self.opts.debugging_opts.remap_path_scope.contains(scope) {
+            self.local_crate_source_file.as_deref()
+        } else {
+            self.unmapped_local_crate_source_file.as_deref()
+        }
+    }
     pub fn verbose(&self) -> bool {
         self.opts.debugging_opts.verbose
     }
@@ -1045,6 +1067,36 @@ impl Session {
         dbg_opts.plt.unwrap_or(needs_plt || !full_relro)
     }
 
+    /// Returns how debuginfo should be split out of the final artifact: the `-C split-debuginfo`
+    /// value if one was given, otherwise the target's default.
+    pub fn split_debuginfo(&self) -> SplitDebuginfo {
+        if let Some(split_debuginfo) = self.opts.cg.split_debuginfo {
+            return split_debuginfo;
+        }
+        // `-Z run-dsymutil=no` predates `-C split-debuginfo` and keeps the object files around
+        // instead of packing their debuginfo into a `.dSYM`, which is what `unpacked` means.
+        if self.target.target.options.is_like_osx && !self.opts.debugging_opts.run_dsymutil {
+            return SplitDebuginfo::Unpacked;
+        }
+        self.target.target.options.split_debuginfo
+    }
+
+    /// Returns `true` if the target's debuginfo is DWARF which LLVM can split into `.dwo` files,
+    /// as opposed to CodeView (Windows) or DWARF left in the object files (macOS).
+    pub fn target_can_use_split_dwarf(&self) -> bool {
+        !self.target.target.options.is_like_windows && !self.target.target.options.is_like_osx
+    }
+
+    /// Returns the stack protection strategy to use, which is always `none` on targets that
+    /// cannot emit stack protectors.
+    pub fn stack_protector(&self) -> StackProtector {
+        if self.target.target.options.supports_stack_protector {
+            self.opts.debugging_opts.stack_protector
+        } else {
+            StackProtector::None
+        }
+    }
+
     /// Checks if LLVM lifetime markers should be emitted.
     pub fn emit_lifetime_markers(&self) -> bool {
         self.opts.optimize != config::OptLevel::No
@@ -1216,6 +1268,12 @@ fn default_emitter(
             )
             .ui_testing(sopts.debugging_opts.ui_testing),
         ),
+        (config::ErrorOutputType::Sarif, None) => {
+            Box::new(SarifEmitter::stderr(source_map).ui_testing(sopts.debugging_opts.ui_testing))
+        }
+        (config::ErrorOutputType::Sarif, Some(dst)) => {
+            Box::new(SarifEmitter::new(dst, source_map).ui_testing(sopts.debugging_opts.ui_testing))
+        }
     }
 }
 
@@ -1270,12 +1328,26 @@ pub fn build_session(
         sopts.file_path_mapping(),
         hash_kind,
     ));
+    let sysroot = match &sopts.maybe_sysroot {
+        Some(sysroot) => sysroot.clone(),
+        None => filesearch::get_or_default_sysroot(),
+    };
+
+    let fluent_bundle = rustc_errors::translation::fluent_bundle(
+        &sysroot,
+        sopts.debugging_opts.translate_lang.as_deref(),
+        sopts.debugging_opts.translate_additional_ftl.as_deref(),
+    )
+    .unwrap_or_else(|e| {
+        early_error(sopts.error_format, &format!("failed to load message catalogs: {}", e))
+    });
     let emitter = default_emitter(&sopts, registry, source_map.clone(), write_dest);
 
     let span_diagnostic = rustc_errors::Handler::with_emitter_and_flags(
         emitter,
         sopts.debugging_opts.diagnostic_handler_flags(can_emit_warnings),
-    );
+    )
+    .with_fluent_bundle(fluent_bundle);
 
     let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.debugging_opts.self_profile
     {
@@ -1299,10 +1371,6 @@ pub fn build_session(
     };
 
     let parse_sess = ParseSess::with_span_handler(span_diagnostic, source_map);
-    let sysroot = match &sopts.maybe_sysroot {
-        Some(sysroot) => sysroot.clone(),
-        None => filesearch::get_or_default_sysroot(),
-    };
 
     let host_triple = config::host_triple();
     let target_triple = sopts.target_triple.triple();
@@ -1315,6 +1383,7 @@ pub fn build_session(
 
     let file_path_mapping = sopts.file_path_mapping();
 
+    let unmapped_local_crate_source_file = local_crate_source_file.clone();
     let local_crate_source_file =
         local_crate_source_file.map(|path| file_path_mapping.map_prefix(path).0);
 
@@ -1329,6 +1398,7 @@ pub fn build_session(
     let working_dir = env::current_dir().unwrap_or_else(|e| {
         parse_sess.span_diagnostic.fatal(&format!("Current directory is invalid: {}", e)).raise()
     });
+    let unmapped_working_dir = working_dir.clone();
     let working_dir = file_path_mapping.map_prefix(working_dir);
 
     let cgu_reuse_tracker = if sopts.debugging_opts.query_dep_graph {
@@ -1384,7 +1454,9 @@ pub fn build_session(
         parse_sess,
         sysroot,
         local_crate_source_file,
+        unmapped_local_crate_source_file,
         working_dir,
+        unmapped_working_dir,
         one_time_diagnostics: Default::default(),
         crate_types: OnceCell::new(),
         crate_disambiguator: OnceCell::new(),
@@ -1457,6 +1529,16 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         }
     }
 
+    // Windows only ever produces `.pdb` files, which are what `packed` means there.
+    if sess.target.target.options.is_like_windows
+        && sess.split_debuginfo() != sess.target.target.options.split_debuginfo
+    {
+        sess.err(&format!(
+            "`-C split-debuginfo={}` is not supported on this target",
+            sess.split_debuginfo()
+        ));
+    }
+
     // Unwind tables cannot be disabled if the target requires them.
     if let Some(include_uwtables) = sess.opts.cg.force_unwind_tables {
         if sess.panic_strategy() == PanicStrategy::Unwind && !include_uwtables {
@@ -1493,39 +1575,14 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         );
     }
 
-    const ASAN_SUPPORTED_TARGETS: &[&str] = &[
-        "aarch64-fuchsia",
-        "aarch64-unknown-linux-gnu",
-        "x86_64-apple-darwin",
-        "x86_64-fuchsia",
-        "x86_64-unknown-freebsd",
-        "x86_64-unknown-linux-gnu",
-    ];
-    const LSAN_SUPPORTED_TARGETS: &[&str] =
-        &["aarch64-unknown-linux-gnu", "x86_64-apple-darwin", "x86_64-unknown-linux-gnu"];
-    const MSAN_SUPPORTED_TARGETS: &[&str] =
-        &["aarch64-unknown-linux-gnu", "x86_64-unknown-freebsd", "x86_64-unknown-linux-gnu"];
-    const TSAN_SUPPORTED_TARGETS: &[&str] = &[
-        "aarch64-unknown-linux-gnu",
-        "x86_64-apple-darwin",
-        "x86_64-unknown-freebsd",
-        "x86_64-unknown-linux-gnu",
-    ];
-
-    // Sanitizers can only be used on some tested platforms.
+    // Sanitizers can only be used on platforms that we know have working sanitizer codegen.
+    let supported_sanitizers = sess.target.target.options.supported_sanitizers;
     for s in sess.opts.debugging_opts.sanitizer {
-        let supported_targets = match s {
-            SanitizerSet::ADDRESS => ASAN_SUPPORTED_TARGETS,
-            SanitizerSet::LEAK => LSAN_SUPPORTED_TARGETS,
-            SanitizerSet::MEMORY => MSAN_SUPPORTED_TARGETS,
-            SanitizerSet::THREAD => TSAN_SUPPORTED_TARGETS,
-            _ => panic!("unrecognized sanitizer {}", s),
-        };
-        if !supported_targets.contains(&&*sess.opts.target_triple.triple()) {
+        if !supported_sanitizers.contains(s) {
             sess.err(&format!(
-                "`-Zsanitizer={}` only works with targets: {}",
+                "`-Zsanitizer={}` is not supported for target `{}`",
                 s,
-                supported_targets.join(", ")
+                sess.opts.target_triple.triple()
             ));
         }
         let conflicting = sess.opts.debugging_opts.sanitizer - s;
@@ -1538,6 +1595,24 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
             break;
         }
     }
+
+    // LLVM CFI relies on the whole-program view provided by LTO to compute the set of valid targets
+    // of each indirect call.
+    if sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::CFI)
+        && sess.lto() != config::Lto::Fat
+    {
+        sess.err("`-Zsanitizer=cfi` requires `-Clto`");
+    }
+
+    if sess.opts.debugging_opts.stack_protector != StackProtector::None
+        && !sess.target.target.options.supports_stack_protector
+    {
+        sess.warn(&format!(
+            "`-Z stack-protector={}` is not supported for target {} and will be ignored",
+            sess.opts.debugging_opts.stack_protector,
+            sess.opts.target_triple.triple()
+        ));
+    }
 }
 
 /// Holds data on the current incremental compilation session, if there is one.
@@ -1567,6 +1642,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_fatal(msg).emit();
@@ -1582,6 +1658,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_warn(msg).emit();
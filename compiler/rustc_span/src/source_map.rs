@@ -16,6 +16,7 @@ use rustc_data_structures::sync::{AtomicU32, Lock, LockGuard, Lrc, MappedLockGua
 use std::cmp;
 use std::convert::TryFrom;
 use std::hash::Hash;
+use std::ops::{BitOr, BitOrAssign};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 
@@ -531,7 +532,37 @@ impl SourceMap {
         }
     }
 
+    /// The name of `file` as it should appear in `scope`: the remapped name if `scope` is
+    /// remapped, the original one otherwise. Files imported from other crates only have the
+    /// name their crate was compiled with.
+    pub fn filename_for_scope<'a>(
+        &self,
+        file: &'a SourceFile,
+        scope: RemapPathScopes,
+    ) -> &'a FileName {
+        if self.path_mapping.scopes.contains(scope) {
+            &file.name
+        } else {
+            file.unmapped_path.as_ref().unwrap_or(&file.name)
+        }
+    }
+
+    /// The name of `file` as it should be shown in diagnostics.
+    pub fn filename_for_diagnostics<'a>(&self, file: &'a SourceFile) -> &'a FileName {
+        self.filename_for_scope(file, RemapPathScopes::DIAGNOSTICS)
+    }
+
     pub fn span_to_string(&self, sp: Span) -> String {
+        self.span_to_string_for_scope(sp, RemapPathScopes::DIAGNOSTICS)
+    }
+
+    /// Formats `sp` for output that can end up in the compiled code, such as the type names
+    /// returned by `type_name`, with the file name remapped if the `object` scope is.
+    pub fn span_to_embeddable_string(&self, sp: Span) -> String {
+        self.span_to_string_for_scope(sp, RemapPathScopes::OBJECT)
+    }
+
+    fn span_to_string_for_scope(&self, sp: Span, scope: RemapPathScopes) -> String {
         if self.files.borrow().source_files.is_empty() && sp.is_dummy() {
             return "no-location".to_string();
         }
@@ -540,7 +571,7 @@ impl SourceMap {
         let hi = self.lookup_char_pos(sp.hi());
         format!(
             "{}:{}:{}: {}:{}",
-            lo.file.name,
+            self.filename_for_scope(&lo.file, scope),
             lo.line,
             lo.col.to_usize() + 1,
             hi.line,
@@ -1074,18 +1105,68 @@ impl SourceMap {
     }
 }
 
+/// The kinds of compiler output that the path remapping of `--remap-path-prefix` applies to,
+/// as selected with `-Z remap-path-scope`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RemapPathScopes(u8);
+
+impl RemapPathScopes {
+    pub const EMPTY: RemapPathScopes = RemapPathScopes(0);
+    /// The paths shown in diagnostics.
+    pub const DIAGNOSTICS: RemapPathScopes = RemapPathScopes(1 << 0);
+    /// The paths that `file!()` expands to, which includes the locations of panics.
+    pub const MACRO: RemapPathScopes = RemapPathScopes(1 << 1);
+    /// The source file names and the compilation directory recorded in debuginfo.
+    pub const DEBUGINFO: RemapPathScopes = RemapPathScopes(1 << 2);
+    /// The paths recorded in object files: the name of the split DWARF file, and the locations
+    /// of closures, generators and impls in type names, as returned by `type_name`.
+    pub const OBJECT: RemapPathScopes = RemapPathScopes(1 << 3);
+    pub const ALL: RemapPathScopes = RemapPathScopes(0b1111);
+
+    pub fn contains(self, other: RemapPathScopes) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for RemapPathScopes {
+    type Output = RemapPathScopes;
+
+    fn bitor(self, rhs: RemapPathScopes) -> RemapPathScopes {
+        RemapPathScopes(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for RemapPathScopes {
+    fn bitor_assign(&mut self, rhs: RemapPathScopes) {
+        self.0 |= rhs.0;
+    }
+}
+
 #[derive(Clone)]
 pub struct FilePathMapping {
     mapping: Vec<(PathBuf, PathBuf)>,
+    scopes: RemapPathScopes,
 }
 
 impl FilePathMapping {
     pub fn empty() -> FilePathMapping {
-        FilePathMapping { mapping: vec![] }
+        FilePathMapping { mapping: vec![], scopes: RemapPathScopes::ALL }
     }
 
-    pub fn new(mapping: Vec<(PathBuf, PathBuf)>) -> FilePathMapping {
-        FilePathMapping { mapping }
+    pub fn new(mapping: Vec<(PathBuf, PathBuf)>, scopes: RemapPathScopes) -> FilePathMapping {
+        FilePathMapping { mapping, scopes }
+    }
+
+    /// The kinds of output that the mapping applies to. `SourceFile::name` and crate metadata
+    /// always use the remapped paths, the other kinds of output use `SourceFile::unmapped_path`
+    /// instead if they are not in `scopes`.
+    pub fn scopes(&self) -> RemapPathScopes {
+        self.scopes
+    }
+
+    /// The `(from, to)` prefix pairs of the mapping, in command line order.
+    pub fn entries(&self) -> &[(PathBuf, PathBuf)] {
+        &self.mapping
     }
 
     /// Applies any path prefix substitution as defined by the mapping.
@@ -1103,4 +1184,9 @@ impl FilePathMapping {
 
         (path, false)
     }
+
+    /// Like `map_prefix`, but leaves `path` alone if `scope` is not remapped.
+    pub fn map_prefix_for_scope(&self, path: PathBuf, scope: RemapPathScopes) -> PathBuf {
+        if self.scopes.contains(scope) { self.map_prefix(path).0 } else { path }
+    }
 }
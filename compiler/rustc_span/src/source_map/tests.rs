@@ -224,6 +224,23 @@ fn t10() {
     );
 }
 
+/// Tests that `filename_for_scope` only remaps the scopes of the `FilePathMapping`.
+#[test]
+fn remap_path_scopes() {
+    let mapping = vec![(PathBuf::from("/home/user/project"), PathBuf::from("/remapped"))];
+    let scopes = RemapPathScopes::MACRO | RemapPathScopes::DEBUGINFO;
+    let sm = SourceMap::new(FilePathMapping::new(mapping, scopes));
+    let file = sm.new_source_file(PathBuf::from("/home/user/project/lib.rs").into(), String::new());
+
+    let remapped: FileName = PathBuf::from("/remapped/lib.rs").into();
+    let unmapped: FileName = PathBuf::from("/home/user/project/lib.rs").into();
+    assert_eq!(file.name, remapped);
+    assert_eq!(*sm.filename_for_scope(&file, RemapPathScopes::MACRO), remapped);
+    assert_eq!(*sm.filename_for_scope(&file, RemapPathScopes::DEBUGINFO), remapped);
+    assert_eq!(*sm.filename_for_diagnostics(&file), unmapped);
+    assert_eq!(*sm.filename_for_scope(&file, RemapPathScopes::OBJECT), unmapped);
+}
+
 /// Returns the span corresponding to the `n`th occurrence of `substring` in `source_text`.
 trait SourceMapExtension {
     fn span_substr(
@@ -207,6 +207,7 @@ symbols! {
         __D,
         __H,
         __S,
+        __let_else_init,
         __next,
         __try_var,
         _d,
@@ -308,6 +309,8 @@ symbols! {
         box_free,
         box_patterns,
         box_syntax,
+        bpf_map,
+        bpf_ring_buf,
         braced_empty_structs,
         breakpoint,
         bridge,
@@ -317,6 +320,7 @@ symbols! {
         call_mut,
         call_once,
         caller_location,
+        capture_disjoint_fields,
         cdylib,
         ceilf32,
         ceilf64,
@@ -331,6 +335,7 @@ symbols! {
         cfg_target_thread_local,
         cfg_target_vendor,
         cfg_version,
+        cfi,
         char,
         client,
         clippy,
@@ -351,8 +356,10 @@ symbols! {
         concat_idents,
         conservative_impl_trait,
         console,
+        const_allocate,
         const_compare_raw_pointers,
         const_constructor,
+        const_deallocate,
         const_eval_limit,
         const_evaluatable_checked,
         const_extern_fn,
@@ -362,6 +369,7 @@ symbols! {
         const_fn_transmute,
         const_fn_union,
         const_generics,
+        const_heap,
         const_if_match,
         const_in_array_repeat_expressions,
         const_indexing,
@@ -565,6 +573,7 @@ symbols! {
         html_no_source,
         html_playground_url,
         html_root_url,
+        hwaddress,
         i,
         i128,
         i128_type,
@@ -593,6 +602,7 @@ symbols! {
         infer_static_outlives_requirements,
         inlateout,
         inline,
+        inline_const,
         inout,
         intel,
         into_iter,
@@ -616,6 +626,7 @@ symbols! {
         lazy_normalization_consts,
         le,
         let_chains,
+        let_else,
         lhs,
         lib,
         libc,
@@ -893,6 +904,7 @@ symbols! {
         rustc_args_required_const,
         rustc_attrs,
         rustc_builtin_macro,
+        rustc_capture_analysis,
         rustc_clean,
         rustc_const_stable,
         rustc_const_unstable,
@@ -1109,6 +1121,7 @@ symbols! {
         trace_macros,
         track_caller,
         trait_alias,
+        trait_upcasting,
         transmute,
         transparent,
         transparent_enums,
@@ -103,12 +103,14 @@ use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc_middle::mir::mono::{InstantiationMode, MonoItem};
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::subst::SubstsRef;
-use rustc_middle::ty::{self, Instance, TyCtxt};
+use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
 use rustc_session::config::SymbolManglingVersion;
+use rustc_target::abi::call::FnAbi;
 
 use tracing::debug;
 
 mod legacy;
+mod typeid;
 mod v0;
 
 pub mod test;
@@ -124,6 +126,23 @@ pub fn symbol_name_for_instance_in_crate(
     compute_symbol_name(tcx, instance, || instantiating_crate)
 }
 
+/// This function computes the type id for the given function ABI, for use by LLVM control-flow
+/// integrity (CFI). Functions are tagged with the type id of their own `FnAbi`, and indirect
+/// calls only proceed if the callee is tagged with the type id of the `FnAbi` at the call site.
+pub fn typeid_for_fnabi(tcx: TyCtxt<'tcx>, fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> String {
+    typeid::encode_fnabi(tcx, fn_abi, false)
+}
+
+/// Like `typeid_for_fnabi`, but with the type of the first argument (the receiver) left out.
+/// Virtual calls pass a `dyn Trait` receiver to methods which expect a concrete `Self`, so
+/// they're checked against this type id instead, which trait methods are also tagged with.
+pub fn typeid_for_fnabi_erasing_receiver(
+    tcx: TyCtxt<'tcx>,
+    fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+) -> String {
+    typeid::encode_fnabi(tcx, fn_abi, true)
+}
+
 pub fn provide(providers: &mut Providers) {
     *providers = Providers { symbol_name: symbol_name_provider, ..*providers };
 }
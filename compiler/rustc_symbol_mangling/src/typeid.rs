@@ -0,0 +1,123 @@
+//! Type ids for LLVM control-flow integrity (CFI).
+//!
+//! A type id is the Itanium C++ ABI mangling of a function type's type info name (e.g.
+//! `_ZTSFiiE` for `fn(i32) -> i32`), which is also what Clang uses, so that Rust and C
+//! functions with compatible signatures share type ids. Types without a C++ counterpart are
+//! encoded as vendor extended types (`u<length><name>`), whose name is the type's v0 mangling.
+
+use rustc_ast::{FloatTy, IntTy, UintTy};
+use rustc_hir as hir;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_target::abi::call::{ArgAbi, FnAbi, PassMode};
+
+use std::fmt::Write;
+
+use crate::v0;
+
+pub(super) fn encode_fnabi(
+    tcx: TyCtxt<'tcx>,
+    fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
+    erase_receiver: bool,
+) -> String {
+    let mut out = String::from("_ZTSF");
+    encode_ret(tcx, &fn_abi.ret, &mut out);
+
+    // Arguments which aren't passed at all (e.g. the environment of a non-capturing closure
+    // called through its `FnOnce` shim) are left out, so the callee and the `fn` pointer it is
+    // called through agree.
+    let mut args = fn_abi.args.iter().filter(|arg| !arg.is_ignore()).peekable();
+    if args.peek().is_none() && !fn_abi.c_variadic {
+        out.push('v');
+    }
+    for (i, arg) in args.enumerate() {
+        if i == 0 && erase_receiver {
+            encode_vendor("self", &mut out);
+        } else {
+            encode_ty(tcx, arg.layout.ty, &mut out);
+        }
+    }
+    if fn_abi.c_variadic {
+        out.push('z');
+    }
+
+    out.push('E');
+    out
+}
+
+fn encode_ret(tcx: TyCtxt<'tcx>, ret: &ArgAbi<'tcx, Ty<'tcx>>, out: &mut String) {
+    match ret.mode {
+        PassMode::Ignore => out.push('v'),
+        _ => encode_ty(tcx, ret.layout.ty, out),
+    }
+}
+
+fn encode_ty(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, out: &mut String) {
+    let ty = tcx.normalize_erasing_regions(ty::ParamEnv::reveal_all(), ty);
+    let builtin = match ty.kind() {
+        ty::Bool => "b",
+        ty::Tuple(_) if ty.is_unit() => "v",
+        ty::Int(IntTy::I8) => "a",
+        ty::Int(IntTy::I16) => "s",
+        ty::Int(IntTy::I32) => "i",
+        ty::Int(IntTy::I64) => "x",
+        ty::Int(IntTy::I128) => "n",
+        ty::Uint(UintTy::U8) => "h",
+        ty::Uint(UintTy::U16) => "t",
+        ty::Uint(UintTy::U32) => "j",
+        ty::Uint(UintTy::U64) => "y",
+        ty::Uint(UintTy::U128) => "o",
+        ty::Float(FloatTy::F32) => "f",
+        ty::Float(FloatTy::F64) => "d",
+        _ => "",
+    };
+    if !builtin.is_empty() {
+        out.push_str(builtin);
+        return;
+    }
+
+    match *ty.kind() {
+        ty::Ref(_, pointee, mutbl) => {
+            out.push('R');
+            if mutbl == hir::Mutability::Not {
+                out.push('K');
+            }
+            encode_ty(tcx, pointee, out);
+        }
+        ty::RawPtr(mt) => {
+            out.push('P');
+            if mt.mutbl == hir::Mutability::Not {
+                out.push('K');
+            }
+            encode_ty(tcx, mt.ty, out);
+        }
+        ty::Array(elem, len) => match len.try_eval_usize(tcx, ty::ParamEnv::reveal_all()) {
+            Some(len) => {
+                write!(out, "A{}_", len).unwrap();
+                encode_ty(tcx, elem, out);
+            }
+            None => encode_vendor(&v0::mangle_type(tcx, ty), out),
+        },
+        ty::FnPtr(sig) => {
+            let sig = tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), &sig);
+            out.push_str("PF");
+            encode_ty(tcx, sig.output(), out);
+            if sig.inputs().is_empty() && !sig.c_variadic {
+                out.push('v');
+            }
+            for &input in sig.inputs() {
+                encode_ty(tcx, input, out);
+            }
+            if sig.c_variadic {
+                out.push('z');
+            }
+            out.push('E');
+        }
+        // Everything else, including `isize`, `usize`, `char`, `str`, `!`, and all nominal
+        // types, has no C++ counterpart, and is distinguished by its v0 mangling instead.
+        _ => encode_vendor(&v0::mangle_type(tcx, ty), out),
+    }
+}
+
+fn encode_vendor(name: &str, out: &mut String) {
+    write!(out, "u{}{}", name.len(), name).unwrap();
+}
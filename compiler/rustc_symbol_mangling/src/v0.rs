@@ -54,6 +54,13 @@ pub(super) fn mangle(
     cx.out
 }
 
+/// Mangles `ty` on its own, without the `_R` prefix or backreference compression, for
+/// embedding in other encodings (e.g. CFI type ids).
+pub(super) fn mangle_type(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> String {
+    let cx = SymbolMangler { tcx, compress: None, binders: vec![], out: String::new() };
+    cx.print_type(ty).unwrap().out
+}
+
 struct CompressionCaches<'tcx> {
     // The length of the prefix in `out` (e.g. 2 for `_R`).
     start_offset: usize,
@@ -1,5 +1,5 @@
-use crate::abi::call::{ArgAbi, FnAbi};
-use crate::abi::{HasDataLayout, LayoutOf, TyAndLayout, TyAndLayoutMethods};
+use crate::abi::call::{ArgAbi, ArgAttributes, Conv, FnAbi, PassMode};
+use crate::abi::{Abi, HasDataLayout, LayoutOf, TyAndLayout, TyAndLayoutMethods};
 
 // X X X: We use globals and no function params, for a variety of reasons, making
 // this module dubious at best.
@@ -20,10 +20,38 @@ fn classify_arg_ty<'a, Ty, C>(_cx: &C, arg: &mut ArgAbi<'a, Ty>)
     arg.extend_integer_width_to(32);
 }
 
+/// OpenCL kernel arguments: aggregates are passed `byval`, which the SPIR-V backend
+/// turns into a pointer with the `ByVal` function parameter attribute. This is what
+/// the OpenCL runtime expects for `clSetKernelArg` with a struct argument. Scalar
+/// pairs (ie slices) are split into two separate arguments.
+///
+/// `geobacter_spirv_kernel_args` describes the arguments to the host using this too,
+/// so the two can't disagree.
+pub fn classify_kernel_arg<Ty>(arg: &mut ArgAbi<'_, Ty>) {
+    match arg.layout.abi {
+        Abi::Aggregate { .. } => arg.make_indirect_byval(),
+        Abi::ScalarPair(..) => {
+            arg.mode = PassMode::Pair(ArgAttributes::new(), ArgAttributes::new());
+        }
+        _ => arg.extend_integer_width_to(32),
+    }
+}
+
 pub fn compute_abi_info<'a, Ty, C>(cx: &C, fty: &mut FnAbi<'a, Ty>)
     where Ty: TyAndLayoutMethods<'a, C> + Copy,
           C: LayoutOf<Ty = Ty, TyAndLayout = TyAndLayout<'a, Ty>> + HasDataLayout
 {
+    if fty.conv == Conv::SpirKernel {
+        // Kernels can't return anything.
+        for arg in &mut fty.args {
+            if arg.is_ignore() {
+                continue;
+            }
+            classify_kernel_arg(arg);
+        }
+        return;
+    }
+
     if !fty.ret.is_ignore() {
         classify_ret_ty(cx, &mut fty.ret);
     }
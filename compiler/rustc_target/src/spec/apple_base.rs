@@ -1,6 +1,6 @@
 use std::env;
 
-use crate::spec::{LinkArgs, TargetOptions};
+use crate::spec::{LinkArgs, SplitDebuginfo, TargetOptions};
 
 pub fn opts() -> TargetOptions {
     // ELF TLS is only available in macOS 10.7+. If you try to compile for 10.6
@@ -32,6 +32,9 @@ pub fn opts() -> TargetOptions {
         abi_return_struct_as_int: true,
         emit_debug_gdb_scripts: false,
         eh_frame_header: false,
+        // macOS keeps debuginfo in the object files and `dsymutil` collects it
+        // into a `.dSYM` bundle, which is what "packed" means there.
+        split_debuginfo: SplitDebuginfo::Packed,
 
         // This environment variable is pretty magical but is intended for
         // producing deterministic builds. This was first discovered to be used
@@ -0,0 +1,80 @@
+use crate::spec::abi::Abi;
+use crate::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelocModel, Target,
+                  TargetOptions, TargetResult};
+
+/// A base target for eBPF programs, ie packet filters and tracing probes.
+///
+/// There is no linker: the object file is handed as is to the kernel loader (eg
+/// `libbpf`), which does the relocation of map references itself.
+pub fn target(endian: &str) -> TargetResult {
+    let (llvm_target, data_layout) = match endian {
+        "little" => ("bpfel", "e-m:e-p:64:64-i64:64-i128:128-n32:64-S128"),
+        "big" => ("bpfeb", "E-m:e-p:64:64-i64:64-i128:128-n32:64-S128"),
+        _ => { return Err(format!("invalid eBPF endianness `{}`", endian)); },
+    };
+
+    Ok(Target {
+        arch: "bpf".to_string(),
+        data_layout: data_layout.to_string(),
+        llvm_target: llvm_target.to_string(),
+
+        target_os: "none".to_string(),
+        target_vendor: "unknown".to_string(),
+        target_env: String::new(),
+
+        linker_flavor: LinkerFlavor::Ld,
+
+        target_endian: endian.to_string(),
+        target_pointer_width: "64".to_string(),
+        target_c_int_width: "32".to_string(),
+
+        options: TargetOptions {
+            linker: None,
+
+            // `BPF_XADD` is the only atomic read-modify-write there is.
+            max_atomic_width: Some(64),
+            atomic_cas: false,
+            singlethread: true,
+
+            // The verifier rejects anything which looks like unwinding.
+            panic_strategy: PanicStrategy::Abort,
+
+            // There is no stack guard for a canary to be checked against.
+            supports_stack_protector: false,
+
+            relocation_model: RelocModel::Static,
+            position_independent_executables: false,
+            dynamic_linking: false,
+            executables: false,
+            only_cdylib: true,
+            obj_is_bitcode: false,
+            no_builtins: true,
+            emit_debug_gdb_scripts: false,
+
+            dll_prefix: "".to_string(),
+            dll_suffix: ".o".to_string(),
+            exe_suffix: ".o".to_string(),
+
+            // Every program must be its own function, so don't let LLVM merge them.
+            merge_functions: MergeFunctions::Disabled,
+
+            unsupported_abis: vec![
+                Abi::Cdecl,
+                Abi::Stdcall,
+                Abi::Fastcall,
+                Abi::Vectorcall,
+                Abi::Thiscall,
+                Abi::Aapcs,
+                Abi::Win64,
+                Abi::SysV64,
+                Abi::PtxKernel,
+                Abi::Msp430Interrupt,
+                Abi::X86Interrupt,
+                Abi::AmdGpuKernel,
+                Abi::SpirKernel,
+            ],
+
+            ..Default::default()
+        },
+    })
+}
@@ -32,6 +32,9 @@ pub fn target() -> TargetResult {
             // Unwinding on CUDA is neither feasible nor useful.
             panic_strategy: PanicStrategy::Abort,
 
+            // The NVPTX backend does not support stack protectors.
+            supports_stack_protector: false,
+
             // Needed to use `dylib` and `bin` crate types and the linker.
             dynamic_linking: true,
             executables: true,
@@ -0,0 +1,112 @@
+use crate::spec::abi::Abi;
+use crate::spec::{AddrSpaceIdx, AddrSpaceKind, AddrSpaceProps, AddrSpaces, LinkerFlavor,
+                  MergeFunctions, PanicStrategy, RelocModel, Target, TargetOptions,
+                  TargetResult};
+
+use std::collections::BTreeMap;
+
+/// OpenCL flavored SPIR-V, using the `Kernel` execution model and the `Physical64`
+/// addressing model.
+pub fn target() -> TargetResult {
+    Ok(Target {
+        arch: "spirv64".to_string(),
+        data_layout: "e-i64:64-v16:16-v24:32-v32:32-v48:64-v96:128-v192:256-v256:256-v512:512-v1024:1024"
+            .to_string(),
+        llvm_target: "spirv64-unknown-unknown".to_string(),
+
+        target_os: "opencl".to_string(),
+        target_vendor: "unknown".to_string(),
+        target_env: String::new(),
+
+        // There is no SPIR-V linker; SPIR-V modules are produced by the LLVM backend
+        // directly.
+        linker_flavor: LinkerFlavor::Ld,
+
+        target_endian: "little".to_string(),
+        target_pointer_width: "64".to_string(),
+        target_c_int_width: "32".to_string(),
+
+        options: TargetOptions {
+            linker: None,
+            max_atomic_width: Some(64),
+
+            // OpenCL has no exception handling.
+            panic_strategy: PanicStrategy::Abort,
+
+            // SPIR-V kernels have no addressable stack to protect.
+            supports_stack_protector: false,
+
+            relocation_model: RelocModel::Static,
+            position_independent_executables: false,
+            dynamic_linking: false,
+            executables: false,
+            only_cdylib: true,
+            obj_is_bitcode: true,
+
+            dll_prefix: "".to_string(),
+            dll_suffix: ".spv".to_string(),
+            exe_suffix: ".spv".to_string(),
+
+            // Kernels calling other kernels is legal in OpenCL C, but not in SPIR-V.
+            merge_functions: MergeFunctions::Disabled,
+
+            addr_spaces: opencl_addr_spaces(),
+
+            unsupported_abis: vec![
+                Abi::Cdecl,
+                Abi::Stdcall,
+                Abi::Fastcall,
+                Abi::Vectorcall,
+                Abi::Thiscall,
+                Abi::Aapcs,
+                Abi::Win64,
+                Abi::SysV64,
+                Abi::PtxKernel,
+                Abi::Msp430Interrupt,
+                Abi::X86Interrupt,
+                Abi::AmdGpuKernel,
+            ],
+
+            ..Default::default()
+        },
+    })
+}
+
+/// The OpenCL address spaces, as the LLVM SPIR-V backend numbers them. `workgroup`
+/// statics are mapped to the `Workgroup` storage class, ie OpenCL `__local`.
+fn opencl_addr_spaces() -> AddrSpaces {
+    let spaces = [
+        // Function
+        (AddrSpaceKind::Alloca, 0),
+        // CrossWorkgroup
+        (AddrSpaceKind::ReadWrite, 1),
+        // UniformConstant
+        (AddrSpaceKind::ReadOnly, 2),
+        // Workgroup
+        (AddrSpaceKind::Named("workgroup".into()), 3),
+        // Generic
+        (AddrSpaceKind::Flat, 4),
+        (AddrSpaceKind::Instruction, 0),
+    ];
+
+    let mut asp = BTreeMap::new();
+    for (kind, idx) in spaces.iter() {
+        // Everything can be cast to and from the generic address space, but nothing
+        // else.
+        let shared_with = if *kind == AddrSpaceKind::Flat {
+            spaces.iter()
+                .map(|(k, _)| k.clone() )
+                .filter(|k| *k != AddrSpaceKind::Flat)
+                .collect()
+        } else {
+            Some(AddrSpaceKind::Flat).into_iter().collect()
+        };
+        let props = AddrSpaceProps {
+            index: AddrSpaceIdx(*idx),
+            shared_with,
+        };
+        asp.insert(kind.clone(), props);
+    }
+
+    AddrSpaces(asp)
+}
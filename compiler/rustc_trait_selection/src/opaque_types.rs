@@ -716,9 +716,7 @@ where
             ty::Closure(_, ref substs) => {
                 // Skip lifetime parameters of the enclosing item(s)
 
-                for upvar_ty in substs.as_closure().upvar_tys() {
-                    upvar_ty.visit_with(self);
-                }
+                substs.as_closure().tupled_upvars_ty().visit_with(self);
 
                 substs.as_closure().sig_as_fn_ptr_ty().visit_with(self);
             }
@@ -727,9 +725,7 @@ where
                 // Skip lifetime parameters of the enclosing item(s)
                 // Also skip the witness type, because that has no free regions.
 
-                for upvar_ty in substs.as_generator().upvar_tys() {
-                    upvar_ty.visit_with(self);
-                }
+                substs.as_generator().tupled_upvars_ty().visit_with(self);
 
                 substs.as_generator().return_ty().visit_with(self);
                 substs.as_generator().yield_ty().visit_with(self);
@@ -511,6 +511,81 @@ fn vtable_methods<'tcx>(
     }))
 }
 
+/// Given a trait `trait_ref`, returns its strict supertraits, in the order
+/// their vtable pointers are laid out after the methods in its vtable.
+///
+/// This does not depend on `#![feature(trait_upcasting)]`: a vtable built in a
+/// crate without the feature may be upcast in a downstream crate that enables
+/// it, so every crate has to agree on the layout.
+fn vtable_supertraits<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    trait_ref: ty::PolyTraitRef<'tcx>,
+) -> &'tcx [ty::PolyTraitRef<'tcx>] {
+    debug!("vtable_supertraits({:?})", trait_ref);
+
+    // The first trait ref yielded by `supertraits` is `trait_ref` itself. Auto
+    // traits are skipped, as they can never be the principal of a trait object.
+    tcx.arena.alloc_from_iter(
+        supertraits(tcx, trait_ref)
+            .skip(1)
+            .filter(|supertrait_ref| !tcx.trait_is_auto(supertrait_ref.def_id())),
+    )
+}
+
+/// Given the trait object types `source` and `target` of a trait upcasting
+/// coercion, finds the vtable entry of `source` holding the vtable pointer
+/// for `target`. The index is relative to the first method, like the ones
+/// returned by `get_vtable_index_of_object_method`.
+fn vtable_trait_upcasting_coercion_new_vptr_slot<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    key: (Ty<'tcx>, Ty<'tcx>),
+) -> Option<usize> {
+    let (source, target) = key;
+    debug!(
+        "vtable_trait_upcasting_coercion_new_vptr_slot(source={:?}, target={:?})",
+        source, target
+    );
+
+    let (source_principal, target_principal) = match (source.kind(), target.kind()) {
+        (ty::Dynamic(source_data, _), ty::Dynamic(target_data, _)) => {
+            (source_data.principal()?, target_data.principal()?)
+        }
+        _ => {
+            bug!("trait upcasting coercion between non-object types {:?} and {:?}", source, target)
+        }
+    };
+
+    // Dropping auto traits or shortening the lifetime bound does not change
+    // the vtable, so it can be reused as is.
+    if source_principal.def_id() == target_principal.def_id() {
+        return None;
+    }
+
+    // Vtables do not depend on the erased self type, so any type will do.
+    let self_ty = tcx.types.trait_object_dummy_self;
+    let source_trait_ref = tcx.erase_regions(&source_principal.with_self_ty(tcx, self_ty));
+    let target_trait_ref = tcx.erase_regions(&target_principal.with_self_ty(tcx, self_ty));
+
+    // This is `vtable_methods(source_trait_ref).len()`, without needing
+    // to check the method predicates against the dummy self type.
+    let method_count: usize = supertraits(tcx, source_trait_ref)
+        .map(|trait_ref| {
+            tcx.associated_items(trait_ref.def_id())
+                .in_definition_order()
+                .filter(|item| item.kind == ty::AssocKind::Fn)
+                .count()
+        })
+        .sum();
+
+    let supertrait_index = tcx
+        .vtable_supertraits(source_trait_ref)
+        .iter()
+        .position(|&trait_ref| tcx.erase_regions(&trait_ref) == target_trait_ref)
+        .unwrap_or_else(|| bug!("`{:?}` is not a supertrait of `{:?}`", target, source));
+
+    Some(method_count + supertrait_index)
+}
+
 /// Check whether a `ty` implements given trait(trait_def_id).
 ///
 /// NOTE: Always return `false` for a type which needs inference.
@@ -549,6 +624,8 @@ pub fn provide(providers: &mut ty::query::Providers) {
         specializes: specialize::specializes,
         codegen_fulfill_obligation: codegen::codegen_fulfill_obligation,
         vtable_methods,
+        vtable_supertraits,
+        vtable_trait_upcasting_coercion_new_vptr_slot,
         type_implements_trait,
         subst_and_check_impossible_predicates,
         mir_abstract_const: |tcx, def_id| {
@@ -702,18 +702,28 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         let may_apply = match (source.kind(), target.kind()) {
             // Trait+Kx+'a -> Trait+Ky+'b (upcasts).
             (&ty::Dynamic(ref data_a, ..), &ty::Dynamic(ref data_b, ..)) => {
-                // Upcasts permit two things:
+                // Upcasts permit three things:
                 //
                 // 1. Dropping auto traits, e.g., `Foo + Send` to `Foo`
                 // 2. Tightening the region bound, e.g., `Foo + 'a` to `Foo + 'b` if `'a: 'b`
+                // 3. Replacing the principal with one of its supertraits, e.g., `Foo` to
+                //    `Bar` if `trait Foo: Bar`
                 //
-                // Note that neither of these changes requires any
-                // change at runtime. Eventually this will be
-                // generalized.
+                // Neither of the first two changes requires any change at
+                // runtime, while the last one loads the vtable for `Bar`
+                // from the vtable for `Foo`. It is feature gated during
+                // coercion, see `Coerce::coerce_unsized`.
                 //
                 // We always upcast when we can because of reason
                 // #2 (region bounds).
-                data_a.principal_def_id() == data_b.principal_def_id()
+                let principal_compatible =
+                    match (data_a.principal_def_id(), data_b.principal_def_id()) {
+                        (Some(a), Some(b)) if a != b => {
+                            util::supertrait_def_ids(self.tcx(), a).any(|did| did == b)
+                        }
+                        (a, b) => a == b,
+                    };
+                principal_compatible
                     && data_b
                         .auto_traits()
                         // All of a's auto traits need to be in b's auto traits.
@@ -594,24 +594,67 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             // Trait+Kx+'a -> Trait+Ky+'b (upcasts).
             (&ty::Dynamic(ref data_a, r_a), &ty::Dynamic(ref data_b, r_b)) => {
                 // See `assemble_candidates_for_unsizing` for more info.
-                let existential_predicates = data_a.map_bound(|data_a| {
-                    let iter = data_a
-                        .principal()
-                        .map(ty::ExistentialPredicate::Trait)
-                        .into_iter()
-                        .chain(data_a.projection_bounds().map(ty::ExistentialPredicate::Projection))
-                        .chain(data_b.auto_traits().map(ty::ExistentialPredicate::AutoTrait));
-                    tcx.mk_existential_predicates(iter)
-                });
-                let source_trait = tcx.mk_dynamic(existential_predicates, r_b);
+                let (principals, upcast_trait_def_ids) =
+                    match (data_a.principal(), data_b.principal_def_id()) {
+                        // Trait upcasting, e.g., `dyn Foo` to `dyn Bar` if `trait Foo: Bar`.
+                        // The same supertrait can appear with different generic arguments,
+                        // so we try each of them in turn below.
+                        (Some(principal_a), Some(principal_b))
+                            if principal_a.def_id() != principal_b =>
+                        {
+                            let self_ty = tcx.types.trait_object_dummy_self;
+                            let principals =
+                                util::supertraits(tcx, principal_a.with_self_ty(tcx, self_ty))
+                                    .filter(|trait_ref| trait_ref.def_id() == principal_b)
+                                    .map(|trait_ref| {
+                                        ty::ExistentialTraitRef::erase_self_ty(
+                                            tcx,
+                                            trait_ref.skip_binder(),
+                                        )
+                                    })
+                                    .map(Some)
+                                    .collect();
+                            let upcast_trait_def_ids: Vec<_> =
+                                util::supertrait_def_ids(tcx, principal_b).collect();
+                            (principals, Some(upcast_trait_def_ids))
+                        }
+                        (principal_a, _) => (vec![principal_a.map(|p| p.skip_binder())], None),
+                    };
+                let source_trait_with_principal = |principal: Option<_>| {
+                    let existential_predicates = data_a.map_bound(|data_a| {
+                        // When upcasting, only the projections on the new principal
+                        // and its own supertraits are kept.
+                        let projection_bounds =
+                            data_a.projection_bounds().filter(|p| match &upcast_trait_def_ids {
+                                Some(def_ids) => def_ids.contains(&p.trait_ref(tcx).def_id),
+                                None => true,
+                            });
+                        let iter = principal
+                            .map(ty::ExistentialPredicate::Trait)
+                            .into_iter()
+                            .chain(projection_bounds.map(ty::ExistentialPredicate::Projection))
+                            .chain(data_b.auto_traits().map(ty::ExistentialPredicate::AutoTrait));
+                        tcx.mk_existential_predicates(iter)
+                    });
+                    tcx.mk_dynamic(existential_predicates, r_b)
+                };
 
-                // Require that the traits involved in this upcast are **equal**;
+                // Require that the traits involved in this upcast are **equal**,
+                // once the principal has been replaced by the matching supertrait;
                 // only the **lifetime bound** is changed.
-                let InferOk { obligations, .. } = self
-                    .infcx
-                    .at(&obligation.cause, obligation.param_env)
-                    .sup(target, source_trait)
-                    .map_err(|_| Unimplemented)?;
+                let InferOk { obligations, .. } = principals
+                    .into_iter()
+                    .find_map(|principal| {
+                        let source_trait = source_trait_with_principal(principal);
+                        self.infcx
+                            .commit_if_ok(|_| {
+                                self.infcx
+                                    .at(&obligation.cause, obligation.param_env)
+                                    .sup(target, source_trait)
+                            })
+                            .ok()
+                    })
+                    .ok_or(Unimplemented)?;
                 nested.extend(obligations);
 
                 // Register one obligation for 'a: 'b.
@@ -44,7 +44,6 @@ use rustc_span::symbol::sym;
 use std::cell::{Cell, RefCell};
 use std::cmp;
 use std::fmt::{self, Display};
-use std::iter;
 use std::rc::Rc;
 
 pub use rustc_middle::traits::select::*;
@@ -1523,7 +1522,13 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
 
             ty::Closure(_, substs) => {
                 // (*) binder moved here
-                Where(ty::Binder::bind(substs.as_closure().upvar_tys().collect()))
+                let ty = self.infcx.shallow_resolve(substs.as_closure().tupled_upvars_ty());
+                if let ty::Infer(ty::TyVar(_)) = ty.kind() {
+                    // The captures are not known until upvar analysis is done.
+                    Ambiguous
+                } else {
+                    Where(ty::Binder::bind(self.upvar_tys(ty)))
+                }
             }
 
             ty::Adt(..) | ty::Projection(..) | ty::Param(..) | ty::Opaque(..) => {
@@ -1546,6 +1551,20 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         }
     }
 
+    /// Returns the types of the upvars of a closure or generator, given the tuple of them.
+    ///
+    /// With `capture_disjoint_fields`, the tuple is an inference variable until upvar analysis
+    /// is done, and is then returned as is. Once it is known, the upvars are used directly, so
+    /// that the tuple doesn't show up in the "required because it appears within the type"
+    /// notes of errors.
+    fn upvar_tys(&self, tupled_upvars_ty: Ty<'tcx>) -> Vec<Ty<'tcx>> {
+        let ty = self.infcx.shallow_resolve(tupled_upvars_ty);
+        match ty.kind() {
+            ty::Tuple(_) => ty.tuple_fields().collect(),
+            _ => vec![ty],
+        }
+    }
+
     /// For default impls, we need to break apart a type into its
     /// "constituent types" -- meaning, the types that it contains.
     ///
@@ -1592,11 +1611,13 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 tys.iter().map(|k| k.expect_ty()).collect()
             }
 
-            ty::Closure(_, ref substs) => substs.as_closure().upvar_tys().collect(),
+            ty::Closure(_, ref substs) => self.upvar_tys(substs.as_closure().tupled_upvars_ty()),
 
             ty::Generator(_, ref substs, _) => {
                 let witness = substs.as_generator().witness();
-                substs.as_generator().upvar_tys().chain(iter::once(witness)).collect()
+                let mut tys = self.upvar_tys(substs.as_generator().tupled_upvars_ty());
+                tys.push(witness);
+                tys
             }
 
             ty::GeneratorWitness(types) => {
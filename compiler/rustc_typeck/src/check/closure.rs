@@ -81,7 +81,14 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             self.tcx.closure_base_def_id(expr_def_id.to_def_id()),
         );
 
-        let tupled_upvars_ty =
+        let tupled_upvars_ty = if self.tcx.features().capture_disjoint_fields {
+            // The number of captured places is only known after the upvar inference
+            // phase (`upvar.rs`), so the whole tuple is left to be inferred there.
+            self.infcx.next_ty_var(TypeVariableOrigin {
+                kind: TypeVariableOriginKind::ClosureSynthetic,
+                span: expr.span,
+            })
+        } else {
             self.tcx.mk_tup(self.tcx.upvars_mentioned(expr_def_id).iter().flat_map(|upvars| {
                 upvars.iter().map(|(&var_hir_id, _)| {
                     // Create type variables (for now) to represent the transformed
@@ -93,7 +100,8 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                         span: self.tcx.hir().span(var_hir_id),
                     })
                 })
-            }));
+            }))
+        };
 
         if let Some(GeneratorTypes { resume_ty, yield_ty, interior, movability }) = generator_types
         {
@@ -286,6 +286,12 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             }
             ExprKind::DropTemps(ref e) => self.check_expr_with_expectation(e, expected),
             ExprKind::Array(ref args) => self.check_expr_array(args, expected, expr),
+            ExprKind::ConstBlock(ref anon_const) => {
+                // Unlike array lengths, inline consts are allowed to depend on generic
+                // parameters, so we don't require them to be evaluatable here. Errors are
+                // reported once they get evaluated after monomorphization.
+                tcx.type_of(tcx.hir().local_def_id(anon_const.hir_id))
+            }
             ExprKind::Repeat(ref element, ref count) => {
                 self.check_expr_repeat(element, count, expected, expr)
             }
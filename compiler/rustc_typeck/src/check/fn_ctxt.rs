@@ -1687,6 +1687,20 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         let ref_bindings = local.pat.contains_explicit_ref_binding();
 
         let local_ty = self.local_ty(init.span, local.hir_id).revealed_ty;
+        if local.source == hir::LocalSource::LetElseDesugar {
+            // This is the `let _: ! = ELSE_BLOCK;` from the lowering of `let...else`. The block
+            // is checked without expecting `!`, so that the error points at all of it.
+            let init_ty = self.check_expr(init);
+            if let Some(mut err) = self.demand_suptype_diag(init.span, local_ty, init_ty) {
+                err.note("the `else` block of a `let...else` statement must diverge");
+                err.help("try adding a diverging expression, such as `return` or `panic!(..)`");
+                err.emit();
+            }
+            // Even if the block doesn't diverge, treat it as such, to avoid also
+            // reporting the arms of the desugared `match` as incompatible.
+            self.diverges.set(self.diverges.get() | Diverges::always(init.span));
+            return init_ty;
+        }
         if let Some(m) = ref_bindings {
             // Somewhat subtle: if we have a `ref` binding in the pattern,
             // we want to avoid introducing coercions for the RHS. This is
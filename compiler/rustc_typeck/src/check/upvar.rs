@@ -29,6 +29,18 @@
 //! `ty::InferBorrow(upvar_id)` or something like that, but this would
 //! then mean that all later passes would have to check for these figments
 //! and report an error, and it just seems like more mess in the end.)
+//!
+//! ### Computing the captured places
+//!
+//! Alongside the borrow kind of each upvar, we record every place starting
+//! at an upvar that the closure uses, and how it uses it. From those, we
+//! compute the minimal set of places the closure has to capture (see
+//! `compute_min_captures()`). With `#![feature(capture_disjoint_fields)]`,
+//! these are precise paths such as `p.x`, otherwise they are always the
+//! root variables. The captured places make up the fields of the closure
+//! environment: they are ordered by root variable, in the order those are
+//! mentioned, and then by field, which is also the order in which they are
+//! dropped along with the closure.
 
 use super::FnCtxt;
 
@@ -39,8 +51,10 @@ use rustc_hir::def_id::DefId;
 use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_infer::infer::UpvarRegion;
-use rustc_middle::hir::place::{PlaceBase, PlaceWithHirId};
-use rustc_middle::ty::{self, Ty, TyCtxt, UpvarSubsts};
+use rustc_middle::hir::place::{Place, PlaceBase, PlaceWithHirId, Projection, ProjectionKind};
+use rustc_middle::ty::{self, Ty, TyCtxt, TypeFoldable, UpvarSubsts};
+use rustc_session::lint;
+use rustc_span::sym;
 use rustc_span::{Span, Symbol};
 use std::collections::hash_map::Entry;
 
@@ -153,9 +167,11 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         let mut delegate = InferBorrowKind {
             fcx: self,
             closure_def_id,
+            capture_clause,
             current_closure_kind: ty::ClosureKind::LATTICE_BOTTOM,
             current_origin: None,
             adjust_upvar_captures: ty::UpvarCaptureMap::default(),
+            capture_information: Default::default(),
         };
         euv::ExprUseVisitor::new(
             &mut delegate,
@@ -182,8 +198,22 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             }
         }
 
+        let capture_information = delegate.capture_information;
         self.typeck_results.borrow_mut().upvar_capture_map.extend(delegate.adjust_upvar_captures);
 
+        self.compute_min_captures(closure_def_id, span, &capture_information);
+        if !self.tcx.features().capture_disjoint_fields {
+            self.compute_2229_migrations(
+                closure_hir_id,
+                closure_def_id,
+                span,
+                &capture_information,
+            );
+        }
+        if self.tcx.has_attr(closure_def_id, sym::rustc_capture_analysis) {
+            self.log_capture_analysis(closure_def_id, span, &capture_information);
+        }
+
         // Now that we've analyzed the closure, we know how each
         // variable is borrowed, and we know what traits the closure
         // implements (Fn vs FnMut etc). We now have some updates to do
@@ -197,14 +227,17 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         // inference algorithm will reject it).
 
         // Equate the type variables for the upvars with the actual types.
-        let final_upvar_tys = self.final_upvar_tys(closure_hir_id);
+        let final_upvar_tys = self.final_upvar_tys(closure_def_id);
         debug!(
             "analyze_closure: id={:?} substs={:?} final_upvar_tys={:?}",
             closure_hir_id, substs, final_upvar_tys
         );
-        for (upvar_ty, final_upvar_ty) in substs.upvar_tys().zip(final_upvar_tys) {
-            self.demand_suptype(span, upvar_ty, final_upvar_ty);
-        }
+
+        // Build a tuple (U0..Un) of the final upvar types U0..Un and unify the tupled upvars
+        // type of the closure with it. With `capture_disjoint_fields`, the latter is a single
+        // type variable, as the number of captured places is only known now.
+        let final_tupled_upvars_type = self.tcx.mk_tup(final_upvar_tys.iter());
+        self.demand_suptype(span, substs.tupled_upvars_ty(), final_tupled_upvars_type);
 
         // If we are also inferred the closure kind here,
         // process any deferred resolutions.
@@ -214,40 +247,270 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         }
     }
 
-    // Returns a list of `Ty`s for each upvar.
-    fn final_upvar_tys(&self, closure_id: hir::HirId) -> Vec<Ty<'tcx>> {
+    // Returns a list of `Ty`s for each captured place.
+    fn final_upvar_tys(&self, closure_def_id: DefId) -> Vec<Ty<'tcx>> {
         // Presently an unboxed closure type cannot "escape" out of a
         // function, so we will only encounter ones that originated in the
         // local crate or were inlined into it along with some function.
         // This may change if abstract return types of some sort are
         // implemented.
         let tcx = self.tcx;
-        let closure_def_id = tcx.hir().local_def_id(closure_id);
 
-        tcx.upvars_mentioned(closure_def_id)
-            .iter()
-            .flat_map(|upvars| {
-                upvars.iter().map(|(&var_hir_id, _)| {
-                    let upvar_ty = self.node_ty(var_hir_id);
-                    let upvar_id = ty::UpvarId {
-                        var_path: ty::UpvarPath { hir_id: var_hir_id },
-                        closure_expr_id: closure_def_id,
-                    };
-                    let capture = self.typeck_results.borrow().upvar_capture(upvar_id);
+        self.typeck_results
+            .borrow()
+            .closure_min_captures_flattened(closure_def_id)
+            .map(|captured_place| {
+                let upvar_ty = captured_place.place.ty();
+                let capture = captured_place.info.capture_kind;
 
-                    debug!("var_id={:?} upvar_ty={:?} capture={:?}", var_hir_id, upvar_ty, capture);
+                debug!(
+                    "place={:?} upvar_ty={:?} capture={:?}",
+                    captured_place.place, upvar_ty, capture
+                );
 
-                    match capture {
-                        ty::UpvarCapture::ByValue(_) => upvar_ty,
-                        ty::UpvarCapture::ByRef(borrow) => tcx.mk_ref(
-                            borrow.region,
-                            ty::TypeAndMut { ty: upvar_ty, mutbl: borrow.kind.to_mutbl_lossy() },
-                        ),
-                    }
-                })
+                match capture {
+                    ty::UpvarCapture::ByValue(_) => upvar_ty,
+                    ty::UpvarCapture::ByRef(borrow) => tcx.mk_ref(
+                        borrow.region,
+                        ty::TypeAndMut { ty: upvar_ty, mutbl: borrow.kind.to_mutbl_lossy() },
+                    ),
+                }
             })
             .collect()
     }
+
+    /// Computes the minimal set of places the closure has to capture to support all of its
+    /// uses of upvars, and records it in `closure_min_captures`.
+    ///
+    /// Unless `capture_disjoint_fields` is enabled, every upvar is captured as a whole, just
+    /// like in the `upvar_capture_map`. Upvars which are mentioned by the closure without
+    /// being used are captured as a whole in either case.
+    fn compute_min_captures(
+        &self,
+        closure_def_id: DefId,
+        closure_span: Span,
+        capture_information: &FxIndexMap<Place<'tcx>, ty::CaptureInfo<'tcx>>,
+    ) {
+        let capture_disjoint_fields = self.tcx.features().capture_disjoint_fields;
+        let mut captures_by_var = if capture_disjoint_fields {
+            min_captures(capture_information.iter().map(|(place, &capture_info)| {
+                restrict_capture_precision(self.tcx, place.clone(), capture_info)
+            }))
+        } else {
+            min_captures(
+                capture_information
+                    .iter()
+                    .map(|(place, &capture_info)| truncate_place(place.clone(), capture_info, 0)),
+            )
+        };
+
+        let upvars = match self.typeck_results.borrow().closure_captures.get(&closure_def_id) {
+            Some(upvars) => upvars.clone(),
+            None => return,
+        };
+        let mut root_var_min_capture_list = ty::RootVariableMinCaptureList::default();
+        for (var_hir_id, upvar_id) in upvars {
+            let upvar_capture = self.typeck_results.borrow().upvar_capture(upvar_id);
+            let mut min_cap_list = match captures_by_var.remove(&var_hir_id) {
+                Some(min_cap_list) => min_cap_list,
+                None => {
+                    let place = Place {
+                        base_ty: self.node_ty(var_hir_id),
+                        base: PlaceBase::Upvar(upvar_id),
+                        projections: vec![],
+                    };
+                    let info = ty::CaptureInfo { expr_id: None, capture_kind: upvar_capture };
+                    vec![ty::CapturedPlace { place, info, mutability: hir::Mutability::Not }]
+                }
+            };
+
+            for captured_place in &mut min_cap_list {
+                if !capture_disjoint_fields {
+                    // The root variable is captured just like in the `upvar_capture_map`,
+                    // whose borrows carry the region variables of the upvars.
+                    captured_place.info.capture_kind = upvar_capture;
+                } else if let ty::UpvarCapture::ByRef(ref mut upvar_borrow) =
+                    captured_place.info.capture_kind
+                {
+                    let origin = UpvarRegion(upvar_id, closure_span);
+                    upvar_borrow.region = self.next_region_var(origin);
+                }
+                captured_place.mutability = self.place_mutability(&captured_place.place);
+            }
+
+            // Order the places by field, so that they are dropped in the same order as the
+            // fields of the root variable.
+            min_cap_list.sort_by_cached_key(|captured_place| {
+                captured_place
+                    .place
+                    .projections
+                    .iter()
+                    .filter_map(|projection| match projection.kind {
+                        ProjectionKind::Field(index, _) => Some(index),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            });
+            root_var_min_capture_list.insert(var_hir_id, min_cap_list);
+        }
+
+        debug!("compute_min_captures({:?}) = {:?}", closure_def_id, root_var_min_capture_list);
+        if !root_var_min_capture_list.is_empty() {
+            self.typeck_results
+                .borrow_mut()
+                .closure_min_captures
+                .insert(closure_def_id, root_var_min_capture_list);
+        }
+    }
+
+    /// Returns whether the captured `place` can be mutated: the root variable has to be bound
+    /// mutably, unless the place is behind a mutable reference, and it must not be behind an
+    /// immutable reference.
+    fn place_mutability(&self, place: &Place<'tcx>) -> hir::Mutability {
+        let var_hir_id = match place.base {
+            PlaceBase::Upvar(upvar_id) => upvar_id.var_path.hir_id,
+            base => bug!("expected an upvar, found {:?}", base),
+        };
+        let mut mutability = match self.typeck_results.borrow().pat_binding_modes().get(var_hir_id)
+        {
+            Some(ty::BindByValue(hir::Mutability::Mut)) => hir::Mutability::Mut,
+            _ => hir::Mutability::Not,
+        };
+
+        for (index, projection) in place.projections.iter().enumerate() {
+            if projection.kind != ProjectionKind::Deref {
+                continue;
+            }
+            match place.ty_before_projection(index).kind() {
+                ty::Ref(.., hir::Mutability::Mut) => mutability = hir::Mutability::Mut,
+                ty::Ref(.., hir::Mutability::Not) => return hir::Mutability::Not,
+                // What raw pointers point to is never captured, and boxes own their contents.
+                _ => {}
+            }
+        }
+        mutability
+    }
+
+    /// Lints about the variables captured by value by the closure which would only be
+    /// captured partially with `capture_disjoint_fields`, and which need to be dropped.
+    /// The parts of those which aren't captured would then be dropped at the end of the
+    /// enclosing scope, rather than along with the closure.
+    fn compute_2229_migrations(
+        &self,
+        closure_hir_id: hir::HirId,
+        closure_def_id: DefId,
+        span: Span,
+        capture_information: &FxIndexMap<Place<'tcx>, ty::CaptureInfo<'tcx>>,
+    ) {
+        let drop_reorder_lint = lint::builtin::DISJOINT_CAPTURE_DROP_REORDER;
+        if self.tcx.lint_level_at_node(drop_reorder_lint, closure_hir_id).0 == lint::Allow {
+            return;
+        }
+
+        let precise_captures =
+            min_captures(capture_information.iter().map(|(place, &capture_info)| {
+                restrict_capture_precision(self.tcx, place.clone(), capture_info)
+            }));
+
+        let typeck_results = self.typeck_results.borrow();
+        let upvars = match typeck_results.closure_captures.get(&closure_def_id) {
+            Some(upvars) => upvars,
+            None => return,
+        };
+        let mut need_migrations = vec![];
+        for (&var_hir_id, &upvar_id) in upvars {
+            if !matches!(typeck_results.upvar_capture(upvar_id), ty::UpvarCapture::ByValue(_)) {
+                continue;
+            }
+            let captured: Vec<&[Projection<'tcx>]> = precise_captures
+                .get(&var_hir_id)
+                .into_iter()
+                .flatten()
+                .map(|captured_place| &captured_place.place.projections[..])
+                .collect();
+            let var_ty = self.resolve_vars_if_possible(&self.node_ty(var_hir_id));
+            if !var_ty.needs_infer() && self.uncaptured_parts_need_drop(var_ty, &captured) {
+                need_migrations.push(var_hir_id);
+            }
+        }
+
+        if need_migrations.is_empty() {
+            return;
+        }
+        let vars = need_migrations
+            .iter()
+            .map(|&var_hir_id| format!("`{}`", var_name(self.tcx, var_hir_id)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.tcx.struct_span_lint_hir(drop_reorder_lint, closure_hir_id, span, |lint| {
+            lint.build("drop order affected for closure because of `capture_disjoint_fields`")
+                .note(&format!("add a dummy let to cause {} to be fully captured", vars))
+                .emit()
+        });
+    }
+
+    /// Whether the parts of a variable of type `ty` that aren't captured, given the
+    /// `captured` places relative to the variable, need to be dropped. If no place is
+    /// captured, that is the whole variable, and if the variable is captured as a whole,
+    /// there is nothing left.
+    fn uncaptured_parts_need_drop(&self, ty: Ty<'tcx>, captured: &[&[Projection<'tcx>]]) -> bool {
+        if captured.is_empty() {
+            return ty.needs_drop(self.tcx, self.param_env);
+        }
+        if captured.iter().any(|projections| projections.is_empty()) {
+            return false;
+        }
+        let field_tys: Vec<Ty<'tcx>> = match ty.kind() {
+            ty::Tuple(..) => ty.tuple_fields().collect(),
+            ty::Adt(def, substs) if def.is_struct() => {
+                def.non_enum_variant().fields.iter().map(|f| f.ty(self.tcx, substs)).collect()
+            }
+            // Places are only captured precisely through fields of tuples and structs.
+            _ => return ty.needs_drop(self.tcx, self.param_env),
+        };
+        field_tys.into_iter().enumerate().any(|(index, field_ty)| {
+            let captured_in_field: Vec<&[Projection<'tcx>]> = captured
+                .iter()
+                .filter(|projections| {
+                    matches!(projections[0].kind, ProjectionKind::Field(field, _)
+                        if field as usize == index)
+                })
+                .map(|projections| &projections[1..])
+                .collect();
+            self.uncaptured_parts_need_drop(field_ty, &captured_in_field)
+        })
+    }
+
+    /// Dumps the places used by the closure and the places it captures as errors, for
+    /// `#[rustc_capture_analysis]`.
+    fn log_capture_analysis(
+        &self,
+        closure_def_id: DefId,
+        closure_span: Span,
+        capture_information: &FxIndexMap<Place<'tcx>, ty::CaptureInfo<'tcx>>,
+    ) {
+        let tcx = self.tcx;
+        let span_of = |capture_info: &ty::CaptureInfo<'_>| {
+            capture_info.expr_id.map_or(closure_span, |expr_id| tcx.hir().span(expr_id))
+        };
+
+        for (place, capture_info) in capture_information {
+            let output = format!("Capturing {}", capture_info_string(tcx, place, capture_info));
+            tcx.sess.span_err(span_of(capture_info), &output);
+        }
+
+        if let Some(min_captures) =
+            self.typeck_results.borrow().closure_min_captures.get(&closure_def_id)
+        {
+            for captured_place in min_captures.values().flatten() {
+                let output = format!(
+                    "Min Capture {}",
+                    capture_info_string(tcx, &captured_place.place, &captured_place.info)
+                );
+                tcx.sess.span_err(span_of(&captured_place.info), &output);
+            }
+        }
+    }
 }
 
 struct InferBorrowKind<'a, 'tcx> {
@@ -256,6 +519,8 @@ struct InferBorrowKind<'a, 'tcx> {
     // The def-id of the closure whose kind and upvar accesses are being inferred.
     closure_def_id: DefId,
 
+    capture_clause: hir::CaptureBy,
+
     // The kind that we have inferred that the current closure
     // requires. Note that we *always* infer a minimal kind, even if
     // we don't always *use* that in the final result (i.e., sometimes
@@ -271,6 +536,11 @@ struct InferBorrowKind<'a, 'tcx> {
     // For each upvar that we access, we track the minimal kind of
     // access we need (ref, ref mut, move, etc).
     adjust_upvar_captures: ty::UpvarCaptureMap<'tcx>,
+
+    // For each place starting at an upvar that we access, the minimal kind of access
+    // we need. Unlike `adjust_upvar_captures`, this is used to figure out which parts of
+    // the upvars need to be captured, see `compute_min_captures`.
+    capture_information: FxIndexMap<Place<'tcx>, ty::CaptureInfo<'tcx>>,
 }
 
 impl<'a, 'tcx> InferBorrowKind<'a, 'tcx> {
@@ -284,9 +554,10 @@ impl<'a, 'tcx> InferBorrowKind<'a, 'tcx> {
             place_with_id, mode
         );
 
-        // we only care about moves
+        // we only care about moves, copies just need the place to be readable
         match mode {
             euv::Copy => {
+                self.capture_place(place_with_id, ty::ImmBorrow);
                 return;
             }
             euv::Move => {}
@@ -319,6 +590,7 @@ impl<'a, 'tcx> InferBorrowKind<'a, 'tcx> {
         };
 
         let new_capture = ty::UpvarCapture::ByValue(by_value_span);
+        self.record_capture(place_with_id, new_capture);
         match self.adjust_upvar_captures.entry(upvar_id) {
             Entry::Occupied(mut e) => {
                 match e.get() {
@@ -454,6 +726,41 @@ impl<'a, 'tcx> InferBorrowKind<'a, 'tcx> {
         }
     }
 
+    /// Records that the closure borrows `place_with_id` with `kind`, or moves it if this is
+    /// a `move` closure.
+    fn capture_place(&mut self, place_with_id: &PlaceWithHirId<'tcx>, kind: ty::BorrowKind) {
+        let capture_kind = match self.capture_clause {
+            hir::CaptureBy::Value => ty::UpvarCapture::ByValue(None),
+            hir::CaptureBy::Ref => {
+                // The captured places aren't used for region checking, so there is
+                // no need for a region variable here.
+                let region = self.fcx.tcx.lifetimes.re_erased;
+                ty::UpvarCapture::ByRef(ty::UpvarBorrow { kind, region })
+            }
+        };
+        self.record_capture(place_with_id, capture_kind);
+    }
+
+    fn record_capture(
+        &mut self,
+        place_with_id: &PlaceWithHirId<'tcx>,
+        capture_kind: ty::UpvarCapture<'tcx>,
+    ) {
+        match place_with_id.place.base {
+            PlaceBase::Upvar(upvar_id)
+                if upvar_id.closure_expr_id.to_def_id() == self.closure_def_id => {}
+            _ => return,
+        }
+
+        let capture_info = ty::CaptureInfo { expr_id: Some(place_with_id.hir_id), capture_kind };
+        let capture_info = match self.capture_information.get(&place_with_id.place) {
+            Some(&existing) => determine_capture_info(existing, capture_info),
+            None => capture_info,
+        };
+        debug!("record_capture(place={:?}, capture_info={:?})", place_with_id.place, capture_info);
+        self.capture_information.insert(place_with_id.place.clone(), capture_info);
+    }
+
     fn adjust_closure_kind(
         &mut self,
         closure_id: LocalDefId,
@@ -515,13 +822,201 @@ impl<'a, 'tcx> euv::Delegate<'tcx> for InferBorrowKind<'a, 'tcx> {
                 self.adjust_upvar_borrow_kind_for_mut(place_with_id);
             }
         }
+        self.capture_place(place_with_id, bk);
     }
 
     fn mutate(&mut self, assignee_place: &PlaceWithHirId<'tcx>) {
         debug!("mutate(assignee_place={:?})", assignee_place);
 
         self.adjust_upvar_borrow_kind_for_mut(assignee_place);
+        self.capture_place(assignee_place, ty::MutBorrow);
+    }
+
+    fn fake_read(&mut self, place_with_id: &PlaceWithHirId<'tcx>) {
+        debug!("fake_read(place_with_id={:?})", place_with_id);
+
+        // MIR building matches the pattern against the whole place, so it has to be
+        // available in the closure even if no binding uses all of it.
+        self.capture_place(place_with_id, ty::ImmBorrow);
+    }
+}
+
+/// Truncates `place` to its first `len` projections. As the truncated place is then
+/// accessed through the projections which got dropped, the capture kind may be weakened:
+/// mutating the referent of a `&mut` only requires a unique borrow of the reference, and
+/// mutating what a raw pointer points to only requires reading the pointer.
+fn truncate_place<'tcx>(
+    mut place: Place<'tcx>,
+    mut capture_info: ty::CaptureInfo<'tcx>,
+    len: usize,
+) -> (Place<'tcx>, ty::CaptureInfo<'tcx>) {
+    if let ty::UpvarCapture::ByRef(ref mut upvar_borrow) = capture_info.capture_kind {
+        for (index, projection) in place.projections.iter().enumerate().skip(len) {
+            if projection.kind != ProjectionKind::Deref {
+                continue;
+            }
+            match place.ty_before_projection(index).kind() {
+                // Raw pointers don't inherit mutability.
+                ty::RawPtr(_) => {
+                    upvar_borrow.kind = ty::ImmBorrow;
+                    break;
+                }
+                ty::Ref(.., hir::Mutability::Mut) if upvar_borrow.kind == ty::MutBorrow => {
+                    upvar_borrow.kind = ty::UniqueImmBorrow;
+                }
+                _ => {}
+            }
+        }
     }
+    place.projections.truncate(len);
+    (place, capture_info)
+}
+
+/// Truncates `place` to the longest prefix which can be captured on its own.
+fn restrict_capture_precision<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    place: Place<'tcx>,
+    capture_info: ty::CaptureInfo<'tcx>,
+) -> (Place<'tcx>, ty::CaptureInfo<'tcx>) {
+    let is_by_value = matches!(capture_info.capture_kind, ty::UpvarCapture::ByValue(_));
+    let len = place.projections.iter().enumerate().position(|(index, projection)| {
+        let base_ty = place.ty_before_projection(index);
+        match projection.kind {
+            // Elements of arrays and slices can't be told apart at compile time.
+            ProjectionKind::Index | ProjectionKind::Subslice => true,
+            // Values can't be moved out from behind a pointer, and what a raw pointer
+            // points to can't be captured without `unsafe`.
+            ProjectionKind::Deref => is_by_value || base_ty.is_unsafe_ptr(),
+            // The fields of enums and unions overlap, and those of packed structs may
+            // not be aligned, so only the fields of other structs and tuples are split.
+            // Values also can't be moved out of a type which implements `Drop`.
+            ProjectionKind::Field(..) => match base_ty.kind() {
+                ty::Adt(def, _) => {
+                    !def.is_struct() || def.repr.packed() || is_by_value && def.has_dtor(tcx)
+                }
+                _ => false,
+            },
+        }
+    });
+    match len {
+        Some(len) => truncate_place(place, capture_info, len),
+        None => (place, capture_info),
+    }
+}
+
+/// Computes the minimal list of places to capture for each root variable, such that none
+/// of the places is an ancestor of another one. Places are merged into their ancestors,
+/// which are then captured with the strongest of the capture kinds. For example, using
+/// `p.x` by value and `p` by immutable reference results in `p` being captured by value.
+fn min_captures<'tcx>(
+    captures: impl Iterator<Item = (Place<'tcx>, ty::CaptureInfo<'tcx>)>,
+) -> ty::RootVariableMinCaptureList<'tcx> {
+    let mut root_var_min_capture_list = ty::RootVariableMinCaptureList::default();
+
+    for (place, capture_info) in captures {
+        let var_hir_id = match place.base {
+            PlaceBase::Upvar(upvar_id) => upvar_id.var_path.hir_id,
+            base => bug!("expected an upvar, found {:?}", base),
+        };
+        let min_cap_list = root_var_min_capture_list.entry(var_hir_id).or_insert_with(Vec::new);
+
+        // If `place` or one of its ancestors is already captured, fold it into that capture.
+        if let Some(ancestor) = min_cap_list
+            .iter_mut()
+            .find(|possible_ancestor| is_ancestor_or_same_capture(&possible_ancestor.place, &place))
+        {
+            ancestor.info = determine_capture_info(ancestor.info, capture_info);
+            continue;
+        }
+
+        // Otherwise, fold the captured descendants of `place` into its capture instead.
+        let mut capture_info = capture_info;
+        min_cap_list.retain(|possible_descendant| {
+            if is_ancestor_or_same_capture(&place, &possible_descendant.place) {
+                capture_info = determine_capture_info(capture_info, possible_descendant.info);
+                false
+            } else {
+                true
+            }
+        });
+        // The mutability is computed once the list is complete.
+        let mutability = hir::Mutability::Not;
+        min_cap_list.push(ty::CapturedPlace { place, info: capture_info, mutability });
+    }
+
+    root_var_min_capture_list
+}
+
+/// Returns the capture info with the strongest capture kind. For equally strong capture
+/// kinds, this prefers the first one, unless only the second one points to a use.
+fn determine_capture_info<'tcx>(
+    info_a: ty::CaptureInfo<'tcx>,
+    info_b: ty::CaptureInfo<'tcx>,
+) -> ty::CaptureInfo<'tcx> {
+    let eq_capture_kind = match (info_a.capture_kind, info_b.capture_kind) {
+        (ty::UpvarCapture::ByValue(_), ty::UpvarCapture::ByValue(_)) => true,
+        (ty::UpvarCapture::ByRef(ref_a), ty::UpvarCapture::ByRef(ref_b)) => {
+            ref_a.kind == ref_b.kind
+        }
+        _ => false,
+    };
+
+    if eq_capture_kind {
+        match (info_a.expr_id, info_b.expr_id) {
+            (None, Some(_)) => info_b,
+            _ => info_a,
+        }
+    } else {
+        match (info_a.capture_kind, info_b.capture_kind) {
+            (ty::UpvarCapture::ByValue(_), _) => info_a,
+            (_, ty::UpvarCapture::ByValue(_)) => info_b,
+            (ty::UpvarCapture::ByRef(ref_a), ty::UpvarCapture::ByRef(ref_b)) => {
+                match (ref_a.kind, ref_b.kind) {
+                    (ty::UniqueImmBorrow | ty::MutBorrow, ty::ImmBorrow)
+                    | (ty::MutBorrow, ty::UniqueImmBorrow) => info_a,
+                    _ => info_b,
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if `place_a` is `place_b`, or one of its ancestors.
+fn is_ancestor_or_same_capture(place_a: &Place<'_>, place_b: &Place<'_>) -> bool {
+    place_a.base == place_b.base
+        && place_a.projections.len() <= place_b.projections.len()
+        && place_a
+            .projections
+            .iter()
+            .zip(&place_b.projections)
+            .all(|(proj_a, proj_b)| proj_a.kind == proj_b.kind)
+}
+
+fn capture_info_string(
+    tcx: TyCtxt<'_>,
+    place: &Place<'_>,
+    capture_info: &ty::CaptureInfo<'_>,
+) -> String {
+    let var_hir_id = match place.base {
+        PlaceBase::Upvar(upvar_id) => upvar_id.var_path.hir_id,
+        base => bug!("expected an upvar, found {:?}", base),
+    };
+    let projections = place
+        .projections
+        .iter()
+        .map(|projection| match projection.kind {
+            ProjectionKind::Field(field, variant) => format!("({:?}, {:?})", field, variant),
+            ProjectionKind::Deref => String::from("Deref"),
+            ProjectionKind::Index => String::from("Index"),
+            ProjectionKind::Subslice => String::from("Subslice"),
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let capture_kind = match capture_info.capture_kind {
+        ty::UpvarCapture::ByValue(_) => String::from("ByValue"),
+        ty::UpvarCapture::ByRef(upvar_borrow) => format!("{:?}", upvar_borrow.kind),
+    };
+    format!("{}[{}] -> {}", var_name(tcx, var_hir_id), projections, capture_kind)
 }
 
 fn var_name(tcx: TyCtxt<'_>, var_hir_id: hir::HirId) -> Symbol {
@@ -9,6 +9,7 @@ use rustc_hir as hir;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_infer::infer::error_reporting::TypeAnnotationNeeded::E0282;
 use rustc_infer::infer::InferCtxt;
+use rustc_middle::hir::place::{Place as HirPlace, Projection as HirProjection};
 use rustc_middle::ty::adjustment::{Adjust, Adjustment, PointerCast};
 use rustc_middle::ty::fold::{TypeFoldable, TypeFolder};
 use rustc_middle::ty::{self, Ty, TyCtxt};
@@ -56,6 +57,7 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         }
         wbcx.visit_body(body);
         wbcx.visit_upvar_capture_map();
+        wbcx.visit_min_capture_map();
         wbcx.visit_closures();
         wbcx.visit_liberated_fn_sigs();
         wbcx.visit_fru_field_types();
@@ -342,6 +344,51 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
         }
     }
 
+    fn visit_min_capture_map(&mut self) {
+        for (&closure_def_id, root_var_map) in
+            self.fcx.typeck_results.borrow().closure_min_captures.iter()
+        {
+            let closure_hir_id =
+                self.tcx().hir().local_def_id_to_hir_id(closure_def_id.expect_local());
+            let mut root_var_map_wb = ty::RootVariableMinCaptureList::default();
+            for (&var_hir_id, min_list) in root_var_map.iter() {
+                let min_list_wb = min_list
+                    .iter()
+                    .map(|captured_place| {
+                        let place = &captured_place.place;
+                        let place = HirPlace {
+                            base_ty: self.resolve(&place.base_ty, &closure_hir_id),
+                            base: place.base,
+                            projections: place
+                                .projections
+                                .iter()
+                                .map(|projection| HirProjection {
+                                    ty: self.resolve(&projection.ty, &closure_hir_id),
+                                    kind: projection.kind,
+                                })
+                                .collect(),
+                        };
+                        let capture_kind = match captured_place.info.capture_kind {
+                            ty::UpvarCapture::ByValue(span) => ty::UpvarCapture::ByValue(span),
+                            ty::UpvarCapture::ByRef(ref upvar_borrow) => {
+                                ty::UpvarCapture::ByRef(ty::UpvarBorrow {
+                                    kind: upvar_borrow.kind,
+                                    region: self.tcx().lifetimes.re_erased,
+                                })
+                            }
+                        };
+                        let info =
+                            ty::CaptureInfo { expr_id: captured_place.info.expr_id, capture_kind };
+                        ty::CapturedPlace { place, info, mutability: captured_place.mutability }
+                    })
+                    .collect();
+                root_var_map_wb.insert(var_hir_id, min_list_wb);
+            }
+            debug!("Min captures for {:?} resolved to {:?}", closure_def_id, root_var_map_wb);
+            self.typeck_results.closure_min_captures.insert(closure_def_id, root_var_map_wb);
+        }
+    }
+
     fn visit_closures(&mut self) {
         let fcx_typeck_results = self.fcx.typeck_results.borrow();
         assert_eq!(fcx_typeck_results.hir_owner, self.typeck_results.hir_owner);
@@ -4,30 +4,32 @@ use rustc_span::{symbol::Ident, Span, Symbol};
 
 #[derive(SessionDiagnostic)]
 #[error = "E0062"]
+#[slug = "typeck-field-multiply-specified-in-initializer"]
 pub struct FieldMultiplySpecifiedInInitializer {
-    #[message = "field `{ident}` specified more than once"]
-    #[label = "used more than once"]
+    #[message]
+    #[label]
     pub span: Span,
-    #[label = "first use of `{ident}`"]
+    #[label = "previous-use-label"]
     pub prev_span: Span,
     pub ident: Ident,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0092"]
+#[slug = "typeck-unrecognized-atomic-operation"]
 pub struct UnrecognizedAtomicOperation<'a> {
-    #[message = "unrecognized atomic operation function: `{op}`"]
-    #[label = "unrecognized atomic operation"]
+    #[message]
+    #[label]
     pub span: Span,
     pub op: &'a str,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0094"]
+#[slug = "typeck-wrong-number-of-type-arguments-to-intrinsic"]
 pub struct WrongNumberOfTypeArgumentsToInstrinsic {
-    #[message = "intrinsic has wrong number of type \
-                         parameters: found {found}, expected {expected}"]
-    #[label = "expected {expected} type parameter"]
+    #[message]
+    #[label]
     pub span: Span,
     pub found: usize,
     pub expected: usize,
@@ -35,20 +37,22 @@ pub struct WrongNumberOfTypeArgumentsToInstrinsic {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0093"]
+#[slug = "typeck-unrecognized-intrinsic-function"]
 pub struct UnrecognizedIntrinsicFunction {
-    #[message = "unrecognized intrinsic function: `{name}`"]
-    #[label = "unrecognized intrinsic"]
+    #[message]
+    #[label]
     pub span: Span,
     pub name: Symbol,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0195"]
+#[slug = "typeck-lifetimes-or-bounds-mismatch-on-trait"]
 pub struct LifetimesOrBoundsMismatchOnTrait {
-    #[message = "lifetime parameters or bounds on {item_kind} `{ident}` do not match the trait declaration"]
-    #[label = "lifetimes do not match {item_kind} in trait"]
+    #[message]
+    #[label]
     pub span: Span,
-    #[label = "lifetimes in impl do not match this {item_kind} in trait"]
+    #[label = "generics-label"]
     pub generics_span: Option<Span>,
     pub item_kind: &'static str,
     pub ident: Ident,
@@ -56,135 +60,151 @@ pub struct LifetimesOrBoundsMismatchOnTrait {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0120"]
+#[slug = "typeck-drop-impl-on-wrong-item"]
 pub struct DropImplOnWrongItem {
-    #[message = "the `Drop` trait may only be implemented for structs, enums, and unions"]
-    #[label = "must be a struct, enum, or union"]
+    #[message]
+    #[label]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0124"]
+#[slug = "typeck-field-already-declared"]
 pub struct FieldAlreadyDeclared {
     pub field_name: Ident,
-    #[message = "field `{field_name}` is already declared"]
-    #[label = "field already declared"]
+    #[message]
+    #[label]
     pub span: Span,
-    #[label = "`{field_name}` first declared here"]
+    #[label = "previous-decl-label"]
     pub prev_span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0184"]
+#[slug = "typeck-copy-impl-on-type-with-dtor"]
 pub struct CopyImplOnTypeWithDtor {
-    #[message = "the trait `Copy` may not be implemented for this type; the \
-                              type has a destructor"]
-    #[label = "Copy not allowed on types with destructors"]
+    #[message]
+    #[label]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0202"]
+#[slug = "typeck-assoc-type-on-inherent-impl"]
 pub struct AssocTypeOnInherentImpl {
-    #[message = "associated types are not yet supported in inherent impls (see #8995)"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0203"]
+#[slug = "typeck-multiple-relaxed-default-bounds"]
 pub struct MultipleRelaxedDefaultBounds {
-    #[message = "type parameter has more than one relaxed default bound, only one is supported"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0206"]
+#[slug = "typeck-copy-impl-on-non-adt"]
 pub struct CopyImplOnNonAdt {
-    #[message = "the trait `Copy` may not be implemented for this type"]
-    #[label = "type is not a structure or enumeration"]
+    #[message]
+    #[label]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0224"]
+#[slug = "typeck-trait-object-declared-with-no-traits"]
 pub struct TraitObjectDeclaredWithNoTraits {
-    #[message = "at least one trait is required for an object type"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0227"]
+#[slug = "typeck-ambiguous-lifetime-bound"]
 pub struct AmbiguousLifetimeBound {
-    #[message = "ambiguous lifetime bound, explicit lifetime bound required"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0229"]
+#[slug = "typeck-assoc-type-binding-not-allowed"]
 pub struct AssocTypeBindingNotAllowed {
-    #[message = "associated type bindings are not allowed here"]
-    #[label = "associated type not allowed here"]
+    #[message]
+    #[label]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0439"]
+#[slug = "typeck-simd-shuffle-missing-length"]
 pub struct SimdShuffleMissingLength {
-    #[message = "invalid `simd_shuffle`, needs length: `{name}`"]
+    #[message]
     pub span: Span,
     pub name: Symbol,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0436"]
+#[slug = "typeck-functional-record-update-on-non-struct"]
 pub struct FunctionalRecordUpdateOnNonStruct {
-    #[message = "functional record update syntax requires a struct"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0516"]
+#[slug = "typeck-typeof-reserved-keyword-used"]
 pub struct TypeofReservedKeywordUsed {
-    #[message = "`typeof` is a reserved keyword but unimplemented"]
-    #[label = "reserved keyword"]
+    #[message]
+    #[label]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0572"]
+#[slug = "typeck-return-stmt-outside-of-fn-body"]
 pub struct ReturnStmtOutsideOfFnBody {
-    #[message = "return statement outside of function body"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0627"]
+#[slug = "typeck-yield-expr-outside-of-generator"]
 pub struct YieldExprOutsideOfGenerator {
-    #[message = "yield expression outside of generator literal"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0639"]
+#[slug = "typeck-struct-expr-non-exhaustive"]
 pub struct StructExprNonExhaustive {
-    #[message = "cannot create non-exhaustive {what} using struct expression"]
+    #[message]
     pub span: Span,
     pub what: &'static str,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0699"]
+#[slug = "typeck-method-call-on-unknown-type"]
 pub struct MethodCallOnUnknownType {
-    #[message = "the type of this value must be known to call a method on a raw pointer on it"]
+    #[message]
     pub span: Span,
 }
 
 #[derive(SessionDiagnostic)]
 #[error = "E0719"]
+#[slug = "typeck-value-of-associated-struct-already-specified"]
 pub struct ValueOfAssociatedStructAlreadySpecified {
-    #[message = "the value of the associated type `{item_name}` (from trait `{def_path}`) is already specified"]
-    #[label = "re-bound here"]
+    #[message]
+    #[label]
     pub span: Span,
-    #[label = "`{item_name}` bound here first"]
+    #[label = "previous-bound-label"]
     pub prev_span: Span,
     pub item_name: Ident,
     pub def_path: String,
@@ -192,8 +212,9 @@ pub struct ValueOfAssociatedStructAlreadySpecified {
 
 #[derive(SessionDiagnostic)]
 #[error = "E0745"]
+#[slug = "typeck-address-of-temporary-taken"]
 pub struct AddressOfTemporaryTaken {
-    #[message = "cannot take address of a temporary"]
-    #[label = "temporary value"]
+    #[message]
+    #[label]
     pub span: Span,
 }
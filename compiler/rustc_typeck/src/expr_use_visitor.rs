@@ -35,6 +35,10 @@ pub trait Delegate<'tcx> {
 
     // The path at `place_with_id` is being assigned to.
     fn mutate(&mut self, assignee_place: &PlaceWithHirId<'tcx>);
+
+    // The place at `place_with_id` is inspected by the pattern of a `let` statement, which
+    // does not necessarily use its value, e.g. `let _ = x`.
+    fn fake_read(&mut self, place_with_id: &PlaceWithHirId<'tcx>);
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -258,7 +262,10 @@ impl<'a, 'tcx> ExprUseVisitor<'a, 'tcx> {
                 self.consume_exprs(&ia.inputs_exprs);
             }
 
-            hir::ExprKind::Continue(..) | hir::ExprKind::Lit(..) | hir::ExprKind::Err => {}
+            hir::ExprKind::Continue(..)
+            | hir::ExprKind::Lit(..)
+            | hir::ExprKind::ConstBlock(..)
+            | hir::ExprKind::Err => {}
 
             hir::ExprKind::Loop(ref blk, _, _) => {
                 self.walk_block(blk);
@@ -348,6 +355,7 @@ impl<'a, 'tcx> ExprUseVisitor<'a, 'tcx> {
             // `walk_pat`:
             self.walk_expr(&expr);
             let init_place = return_if_err!(self.mc.cat_expr(&expr));
+            self.delegate.fake_read(&init_place);
             self.walk_irrefutable_pat(&init_place, &local.pat);
         }
     }
@@ -546,26 +554,22 @@ impl<'a, 'tcx> ExprUseVisitor<'a, 'tcx> {
         debug!("walk_captures({:?})", closure_expr);
 
         let closure_def_id = self.tcx().hir().local_def_id(closure_expr.hir_id);
-        if let Some(upvars) = self.tcx().upvars_mentioned(closure_def_id) {
-            for &var_id in upvars.keys() {
-                let upvar_id = ty::UpvarId {
-                    var_path: ty::UpvarPath { hir_id: var_id },
-                    closure_expr_id: closure_def_id,
-                };
-                let upvar_capture = self.mc.typeck_results.upvar_capture(upvar_id);
-                let captured_place = return_if_err!(self.cat_captured_var(
-                    closure_expr.hir_id,
-                    fn_decl_span,
-                    var_id,
-                ));
-                match upvar_capture {
-                    ty::UpvarCapture::ByValue(_) => {
-                        let mode = copy_or_move(&self.mc, &captured_place);
-                        self.delegate.consume(&captured_place, mode);
-                    }
-                    ty::UpvarCapture::ByRef(upvar_borrow) => {
-                        self.delegate.borrow(&captured_place, upvar_borrow.kind);
-                    }
+        let captured_places =
+            self.mc.typeck_results.closure_min_captures_flattened(closure_def_id.to_def_id());
+        for captured_place in captured_places {
+            let var_id = captured_place.get_root_variable();
+            let mut place_with_id =
+                return_if_err!(self.cat_captured_var(closure_expr.hir_id, fn_decl_span, var_id));
+            // The closure may only capture a part of the variable.
+            let projections = captured_place.place.projections.iter().cloned();
+            place_with_id.place.projections.extend(projections);
+            match captured_place.info.capture_kind {
+                ty::UpvarCapture::ByValue(_) => {
+                    let mode = copy_or_move(&self.mc, &place_with_id);
+                    self.delegate.consume(&place_with_id, mode);
+                }
+                ty::UpvarCapture::ByRef(upvar_borrow) => {
+                    self.delegate.borrow(&place_with_id, upvar_borrow.kind);
                 }
             }
         }
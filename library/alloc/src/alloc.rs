@@ -44,7 +44,9 @@ pub struct Global;
 ///
 /// This function forwards calls to the [`GlobalAlloc::alloc`] method
 /// of the allocator registered with the `#[global_allocator]` attribute
-/// if there is one, or the `std` crate’s default.
+/// if there is one, or the `std` crate’s default. On HSA devices, the memory comes
+/// from `core::geobacter::alloc::HSA_HEAP` instead; the same goes for the other
+/// functions in this module.
 ///
 /// This function is expected to be deprecated in favor of the `alloc` method
 /// of the [`Global`] type when it and the [`AllocRef`] trait become stable.
@@ -71,6 +73,12 @@ pub struct Global;
 #[stable(feature = "global_alloc", since = "1.28.0")]
 #[inline]
 pub unsafe fn alloc(layout: Layout) -> *mut u8 {
+    #[cfg(stage2)]
+    {
+        if let Some(ptr) = core::geobacter::alloc::device_alloc(layout) {
+            return ptr;
+        }
+    }
     unsafe { __rust_alloc(layout.size(), layout.align()) }
 }
 
@@ -89,6 +97,12 @@ pub unsafe fn alloc(layout: Layout) -> *mut u8 {
 #[stable(feature = "global_alloc", since = "1.28.0")]
 #[inline]
 pub unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+    #[cfg(stage2)]
+    {
+        if core::geobacter::alloc::device_dealloc(ptr, layout) {
+            return;
+        }
+    }
     unsafe { __rust_dealloc(ptr, layout.size(), layout.align()) }
 }
 
@@ -107,6 +121,13 @@ pub unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
 #[stable(feature = "global_alloc", since = "1.28.0")]
 #[inline]
 pub unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    #[cfg(stage2)]
+    {
+        let device_ptr = unsafe { core::geobacter::alloc::device_realloc(ptr, layout, new_size) };
+        if let Some(ptr) = device_ptr {
+            return ptr;
+        }
+    }
     unsafe { __rust_realloc(ptr, layout.size(), layout.align(), new_size) }
 }
 
@@ -140,6 +161,12 @@ pub unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8
 #[stable(feature = "global_alloc", since = "1.28.0")]
 #[inline]
 pub unsafe fn alloc_zeroed(layout: Layout) -> *mut u8 {
+    #[cfg(stage2)]
+    {
+        if let Some(ptr) = core::geobacter::alloc::device_alloc_zeroed(layout) {
+            return ptr;
+        }
+    }
     unsafe { __rust_alloc_zeroed(layout.size(), layout.align()) }
 }
 
@@ -136,6 +136,7 @@
 #![feature(try_trait)]
 #![feature(type_alias_impl_trait)]
 #![feature(associated_type_bounds)]
+#![cfg_attr(stage2, feature(geobacter))]
 // Allow testing this library
 
 #[cfg(test)]
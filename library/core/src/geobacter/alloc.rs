@@ -0,0 +1,163 @@
+//! A `GlobalAlloc` which works on both the host and accelerator devices. On the host
+//! it forwards to a normal host allocator (ie `std::alloc::System`); on devices it
+//! allocates out of a region preallocated by the host. Which one gets used is
+//! decided with `platform()`, so LLVM will remove the other path entirely.
+//!
+//! `alloc::alloc` does the same on its own: on HSA devices, `alloc`, `realloc`, etc
+//! allocate from `HSA_HEAP` without going through the `#[global_allocator]`, so code
+//! using `Box`, `Vec` and so on works in kernels as long as the host has initialized
+//! that heap. `DeviceAlloc` is for code which wants to allocate through a
+//! `GlobalAlloc` directly, eg out of a heap other than `HSA_HEAP`:
+//!
+//! ```ignore (requires-device)
+//! use std::alloc::{GlobalAlloc, Layout, System};
+//! use std::geobacter::alloc::DeviceAlloc;
+//! use std::geobacter::amdgpu::heap::Heap;
+//!
+//! static HEAP: Heap = Heap::new();
+//! static ALLOC: DeviceAlloc<System> = DeviceAlloc::new(System, &HEAP);
+//!
+//! let ptr = unsafe { ALLOC.alloc(Layout::new::<u64>()) };
+//! ```
+
+use crate::alloc::{GlobalAlloc, Layout};
+use crate::geobacter::amdgpu::heap::Heap;
+use crate::geobacter::platform::{platform, Platform};
+use crate::ptr::null_mut;
+
+pub struct DeviceAlloc<A>
+    where A: GlobalAlloc,
+{
+    host: A,
+    hsa: &'static Heap,
+}
+
+impl<A> DeviceAlloc<A>
+    where A: GlobalAlloc,
+{
+    #[inline(always)]
+    pub const fn new(host: A, hsa: &'static Heap) -> Self {
+        DeviceAlloc {
+            host,
+            hsa,
+        }
+    }
+
+    #[inline(always)]
+    pub fn host(&self) -> &A { &self.host }
+    #[inline(always)]
+    pub fn hsa_heap(&self) -> &'static Heap { self.hsa }
+}
+
+unsafe impl<A> GlobalAlloc for DeviceAlloc<A>
+    where A: GlobalAlloc,
+{
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match platform() {
+            Platform::Host => unsafe { self.host.alloc(layout) },
+            _ => alloc_in(self.hsa, layout),
+        }
+    }
+    #[inline(always)]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        match platform() {
+            Platform::Host => unsafe { self.host.alloc_zeroed(layout) },
+            _ => alloc_zeroed_in(self.hsa, layout),
+        }
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        match platform() {
+            Platform::Host => unsafe { self.host.dealloc(ptr, layout) },
+            // Device heap memory is reclaimed in bulk by the host.
+            _ => { },
+        }
+    }
+    #[inline(always)]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        match platform() {
+            Platform::Host => unsafe { self.host.realloc(ptr, layout, new_size) },
+            _ => unsafe { realloc_in(self.hsa, ptr, layout, new_size) },
+        }
+    }
+}
+
+/// The heap `alloc::alloc` allocates from, in place of the `#[global_allocator]`, when
+/// running on an HSA device. The host runtime must `init` it with a region the device
+/// can access before dispatching kernels which allocate, and should `reset` it between
+/// dispatches.
+pub static HSA_HEAP: Heap = Heap::new();
+
+/// `alloc::alloc::alloc` on devices. Returns `None` on the host, where the global
+/// allocator should be used instead.
+#[inline(always)]
+pub fn device_alloc(layout: Layout) -> Option<*mut u8> {
+    if platform().is_host() {
+        None
+    } else {
+        Some(alloc_in(&HSA_HEAP, layout))
+    }
+}
+/// `alloc::alloc::alloc_zeroed` on devices. Returns `None` on the host.
+#[inline(always)]
+pub fn device_alloc_zeroed(layout: Layout) -> Option<*mut u8> {
+    if platform().is_host() {
+        None
+    } else {
+        Some(alloc_zeroed_in(&HSA_HEAP, layout))
+    }
+}
+/// `alloc::alloc::dealloc` on devices, which does nothing. Returns `false` on the host.
+#[inline(always)]
+pub fn device_dealloc(_ptr: *mut u8, _layout: Layout) -> bool {
+    !platform().is_host()
+}
+/// `alloc::alloc::realloc` on devices. Returns `None` on the host.
+///
+/// # Safety
+///
+/// Same as `GlobalAlloc::realloc`.
+#[inline(always)]
+pub unsafe fn device_realloc(ptr: *mut u8, layout: Layout, new_size: usize)
+    -> Option<*mut u8>
+{
+    if platform().is_host() {
+        None
+    } else {
+        Some(unsafe { realloc_in(&HSA_HEAP, ptr, layout, new_size) })
+    }
+}
+
+#[inline(always)]
+fn alloc_in(hsa: &Heap, layout: Layout) -> *mut u8 {
+    match platform() {
+        Platform::Hsa(_) => hsa.alloc(layout),
+        // No device heap on these (yet); report OOM.
+        _ => null_mut(),
+    }
+}
+#[inline(always)]
+fn alloc_zeroed_in(hsa: &Heap, layout: Layout) -> *mut u8 {
+    let ptr = alloc_in(hsa, layout);
+    if !ptr.is_null() {
+        unsafe { crate::ptr::write_bytes(ptr, 0, layout.size()); }
+    }
+    ptr
+}
+#[inline(always)]
+unsafe fn realloc_in(hsa: &Heap, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    if new_size <= layout.size() {
+        return ptr;
+    }
+    let new_layout = unsafe {
+        Layout::from_size_align_unchecked(new_size, layout.align())
+    };
+    let new_ptr = alloc_in(hsa, new_layout);
+    if !new_ptr.is_null() {
+        unsafe {
+            crate::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size());
+        }
+    }
+    new_ptr
+}
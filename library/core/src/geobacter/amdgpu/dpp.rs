@@ -1,6 +1,9 @@
 use crate::geobacter::intrinsics::*;
 use crate::marker::{Copy, Sized};
 use crate::mem::{transmute, size_of};
+use crate::ops::Fn;
+
+use super::wavefront_size;
 
 // From the LLVM AMDGPU target machine:
 // enum DppCtrl : unsigned {
@@ -51,6 +54,24 @@ use crate::mem::{transmute, size_of};
 //   DPP8_FI_1 = 0xEA,
 // };
 
+/// A DPP row is 16 lanes. A wave64 wavefront has four rows, a wave32 wavefront two.
+pub const ROW_SIZE: u32 = 16;
+/// Enables every bank (group of four lanes) in a row.
+pub const ALL_BANKS_MASK: i32 = 0xF;
+
+/// The number of DPP rows in a wavefront. On the host, where a wavefront is a
+/// single lane, this is one (partial) row.
+#[inline(always)]
+pub const fn rows() -> u32 {
+    (wavefront_size() + ROW_SIZE - 1) / ROW_SIZE
+}
+/// A row mask which enables every row of the wavefront. On wave32, only the lower
+/// two bits of the row mask are meaningful.
+#[inline(always)]
+pub const fn all_rows_mask() -> i32 {
+    (1i32 << rows()) - 1
+}
+
 /// Except for `self` and `default`, all arguments must be constants.
 pub unsafe trait Dpp: Copy + Sized + 'static {
     /// You probably shouldn't call this directly. dpp_ctrl has a special meaning
@@ -59,6 +80,9 @@ pub unsafe trait Dpp: Copy + Sized + 'static {
     fn update_dpp(self, default: Self, dpp_ctrl: i32,
                   row_mask: i32, bank_mask: i32,
                   bound_ctrl: bool) -> Self;
+    /// Read the value of `self` from lane `lane`, which must be uniform.
+    #[doc(hidden)]
+    fn read_lane(self, lane: u32) -> Self;
 
     /// `perm` selects the element in a single bank.
     /// So only values 0-3. This function masks each perm element by 0x3.
@@ -114,6 +138,7 @@ pub unsafe trait Dpp: Copy + Sized + 'static {
             self
         }
     }
+    /// GFX8/9 only; wavefront shifts and rotates were removed in GFX10.
     #[inline(always)]
     fn wave_shl1(self, default: Self, row_mask: i32, bank_mask: i32,
                  bound_ctrl: bool) -> Self {
@@ -121,6 +146,7 @@ pub unsafe trait Dpp: Copy + Sized + 'static {
                         row_mask, bank_mask,
                         bound_ctrl)
     }
+    /// GFX8/9 only; wavefront shifts and rotates were removed in GFX10.
     #[inline(always)]
     fn wave_rol1(self, default: Self, row_mask: i32, bank_mask: i32,
                  bound_ctrl: bool) -> Self {
@@ -128,6 +154,7 @@ pub unsafe trait Dpp: Copy + Sized + 'static {
                         row_mask, bank_mask,
                         bound_ctrl)
     }
+    /// GFX8/9 only; wavefront shifts and rotates were removed in GFX10.
     #[inline(always)]
     fn wave_shr1(self, default: Self, row_mask: i32, bank_mask: i32,
                  bound_ctrl: bool) -> Self {
@@ -135,6 +162,7 @@ pub unsafe trait Dpp: Copy + Sized + 'static {
                         row_mask, bank_mask,
                         bound_ctrl)
     }
+    /// GFX8/9 only; wavefront shifts and rotates were removed in GFX10.
     #[inline(always)]
     fn wave_ror1(self, default: Self, row_mask: i32, bank_mask: i32,
                  bound_ctrl: bool) -> Self {
@@ -142,6 +170,59 @@ pub unsafe trait Dpp: Copy + Sized + 'static {
                         row_mask, bank_mask,
                         bound_ctrl)
     }
+    /// GFX10+ only. Every lane in a row reads from lane `lane` (0-15) of the same row.
+    #[inline(always)]
+    fn row_share(self, default: Self, lane: u8,
+                 row_mask: i32, bank_mask: i32,
+                 bound_ctrl: bool) -> Self {
+        let lane = (lane & 0xF) as i32;
+        self.update_dpp(default, 0x150 + lane,
+                        row_mask, bank_mask,
+                        bound_ctrl)
+    }
+    /// GFX10+ only. Every lane `i` in a row reads from lane `i ^ mask` of the same row.
+    #[inline(always)]
+    fn row_xmask(self, default: Self, mask: u8,
+                 row_mask: i32, bank_mask: i32,
+                 bound_ctrl: bool) -> Self {
+        let mask = (mask & 0xF) as i32;
+        self.update_dpp(default, 0x160 + mask,
+                        row_mask, bank_mask,
+                        bound_ctrl)
+    }
+}
+
+/// Inclusive scan within each row: lane `i` of a row gets `op` applied over lanes
+/// `0..=i` of its row. Every lane of the wavefront must be active. `identity` must be
+/// the identity of `op`.
+#[inline(always)]
+pub fn row_scan<T, F>(v: T, identity: T, op: F) -> T
+    where T: Dpp,
+          F: Fn(T, T) -> T,
+{
+    let rows = all_rows_mask();
+    let v = op(v, v.row_shr(identity, 1, rows, ALL_BANKS_MASK, false));
+    let v = op(v, v.row_shr(identity, 2, rows, ALL_BANKS_MASK, false));
+    let v = op(v, v.row_shr(identity, 4, rows, ALL_BANKS_MASK, false));
+    op(v, v.row_shr(identity, 8, rows, ALL_BANKS_MASK, false))
+}
+/// Reduce `v` over the whole wavefront; every lane gets the result. Every lane of
+/// the wavefront must be active. `identity` must be the identity of `op`.
+/// Works for both wave32 and wave64.
+#[inline(always)]
+pub fn wavefront_reduce<T, F>(v: T, identity: T, op: F) -> T
+    where T: Dpp,
+          F: Fn(T, T) -> T,
+{
+    let v = row_scan(v, identity, &op);
+    // The last lane of each row now has the reduction of its row.
+    let mut r = v.read_lane(ROW_SIZE - 1);
+    let mut row = 1;
+    while row < rows() {
+        r = op(r, v.read_lane(row * ROW_SIZE + ROW_SIZE - 1));
+        row += 1;
+    }
+    r
 }
 
 macro_rules! impl_dpp {
@@ -162,6 +243,14 @@ unsafe impl Dpp for $this {
       transmute(r as $ity)
     }
   }
+  #[inline(always)]
+  fn read_lane(self, lane: u32) -> Self {
+    unsafe {
+      let src: $ity = transmute(self);
+      let r = geobacter_amdgpu_readlane(src as u32, lane);
+      transmute(r as $ity)
+    }
+  }
 }
 
   )*)
@@ -191,6 +280,20 @@ unsafe impl Dpp for $this {
       transmute(old)
     }
   }
+  #[inline(always)]
+  fn read_lane(self, lane: u32) -> Self {
+    unsafe {
+      let mut v: [u32; size_of::<Self>() / size_of::<u32>()] =
+        transmute(self);
+      let mut iter = 0u8;
+      while iter < (size_of::<Self>() / size_of::<u32>()) as u8 {
+        let t = &mut (*v.as_mut_ptr().add(iter as usize));
+        *t = t.read_lane(lane);
+        iter += 1;
+      }
+      transmute(v)
+    }
+  }
 }
 
   )*)
@@ -0,0 +1,216 @@
+//! A lock-free bump allocator which carves allocations out of a region of memory
+//! preallocated by the host. There is no way to allocate memory from inside a kernel
+//! on AMDGPU, so instead the host hands the device a (large) region before dispatch,
+//! and the device sub-allocates from that.
+//!
+//! Allocation happens in two levels: the region is split into `CHUNK_SIZE` chunks,
+//! which are handed out to wavefronts via a device scoped bump pointer. Every lane of
+//! a wavefront then bumps a per-wavefront cursor into the current chunk. This keeps
+//! most atomics on a cache line which only the lanes of a few wavefronts touch,
+//! instead of having every workitem on the device hammer the same global counter.
+//!
+//! Memory is never returned to the heap; `dealloc` is a no-op. The host should
+//! `reset` the heap between dispatches.
+
+use crate::alloc::Layout;
+use crate::ptr::null_mut;
+use crate::sync::atomic::{AtomicUsize, Ordering};
+
+use super::{dispatch_packet, wavefront_size};
+use super::sync::atomic::{atomic_work_item_fence, Scope};
+use super::workitem::{workgroup_ids, workitem_ids};
+
+/// The granularity of the per-wavefront batches. Allocations larger than this
+/// go straight to the global bump pointer.
+pub const CHUNK_SIZE: usize = 4096;
+/// The number of per-wavefront cursors. Wavefronts are assigned a slot by their
+/// dispatch index modulo this; wavefronts which share a slot also share chunks.
+pub const WAVEFRONT_SLOTS: usize = 256;
+
+/// A cursor into the chunk a wavefront is currently allocating from. The value is
+/// an offset from the start of the region; a value which is a multiple of
+/// `CHUNK_SIZE` (including zero) means the chunk is used up, or there isn't one.
+#[repr(align(64))]
+struct WavefrontSlot(AtomicUsize);
+
+/// The heap header. This must be shared between the host and the device; eg
+/// a static placed in host-coherent memory.
+pub struct Heap {
+    /// Start of the region. Zero if the heap hasn't been initialized.
+    base: AtomicUsize,
+    /// Size of the region in bytes.
+    len: AtomicUsize,
+    /// Offset of the next unused byte in the region.
+    next: AtomicUsize,
+    slots: [WavefrontSlot; WAVEFRONT_SLOTS],
+}
+
+impl Heap {
+    pub const fn new() -> Self {
+        const EMPTY_SLOT: WavefrontSlot = WavefrontSlot(AtomicUsize::new(0));
+        Heap {
+            base: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+            next: AtomicUsize::new(0),
+            slots: [EMPTY_SLOT; WAVEFRONT_SLOTS],
+        }
+    }
+
+    /// Give this heap the region `[region, region + len)` to allocate from.
+    /// `region` must be aligned to `CHUNK_SIZE`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called from the host, and not while any kernel using this heap
+    /// is running. The region must be accessible from the device, and must not be
+    /// used for anything else until it is replaced by another call to this function.
+    pub unsafe fn init(&self, region: *mut u8, len: usize) {
+        assert_eq!(region as usize % CHUNK_SIZE, 0,
+                   "device heap region must be aligned to CHUNK_SIZE");
+        self.len.store(len, Ordering::Relaxed);
+        unsafe { self.reset(); }
+        // Devices check `base` before anything else, so publish it last.
+        self.base.store(region as usize, Ordering::Release);
+    }
+
+    /// Forget every allocation made so far.
+    ///
+    /// # Safety
+    ///
+    /// Same as `init`; additionally, nothing may still reference memory allocated
+    /// from this heap.
+    pub unsafe fn reset(&self) {
+        for slot in self.slots.iter() {
+            slot.0.store(0, Ordering::Relaxed);
+        }
+        self.next.store(0, Ordering::Release);
+    }
+
+    /// How many bytes have been handed out (including the unused tails of the
+    /// chunks currently held by wavefronts).
+    #[inline(always)]
+    pub fn used(&self) -> usize {
+        self.next.load(Ordering::Relaxed)
+            .min(self.len.load(Ordering::Relaxed))
+    }
+
+    /// Allocate from the slot of the calling wavefront.
+    #[inline(always)]
+    pub fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.alloc_in_slot(layout, wavefront_slot())
+    }
+
+    /// Allocate using the cursor `slot`. `slot` is taken modulo `WAVEFRONT_SLOTS`.
+    pub fn alloc_in_slot(&self, layout: Layout, slot: usize) -> *mut u8 {
+        let base = self.base.load(Ordering::Relaxed);
+        if base == 0 {
+            return null_mut();
+        }
+        // Pairs with the release of `base` in `init`, so we see the rest of the header.
+        // That was written by the host, so this needs system scope.
+        atomic_work_item_fence(Ordering::Acquire, Scope::System);
+
+        if layout.size() == 0 {
+            return layout.align() as *mut u8;
+        }
+        if layout.size() > CHUNK_SIZE / 4 || layout.align() > CHUNK_SIZE {
+            // Too large to batch; don't waste most of a chunk on it.
+            return match self.bump(layout.size(), layout.align()) {
+                Some(offset) => (base + offset) as *mut u8,
+                None => null_mut(),
+            };
+        }
+
+        let cursor = &self.slots[slot % WAVEFRONT_SLOTS].0;
+        let mut cur = cursor.load(Ordering::Relaxed);
+        loop {
+            if cur % CHUNK_SIZE != 0 {
+                let start = align_up(base + cur, layout.align()) - base;
+                let end = start + layout.size();
+                let chunk_end = (cur / CHUNK_SIZE + 1) * CHUNK_SIZE;
+                if end <= chunk_end {
+                    atomic_work_item_fence(Ordering::Release, Scope::Device);
+                    match cursor.compare_exchange_weak(cur, end, Ordering::Relaxed,
+                                                       Ordering::Relaxed) {
+                        Ok(_) => {
+                            atomic_work_item_fence(Ordering::Acquire, Scope::Device);
+                            return (base + start) as *mut u8;
+                        },
+                        Err(actual) => {
+                            cur = actual;
+                            continue;
+                        },
+                    }
+                }
+            }
+
+            // The chunk is used up (or we don't have one yet). Grab a new one and
+            // make our allocation at the start of it.
+            let chunk = match self.bump(CHUNK_SIZE, CHUNK_SIZE) {
+                Some(chunk) => chunk,
+                None => { return null_mut(); },
+            };
+            let end = chunk + layout.size();
+            // If this fails, another lane replaced the chunk before we could. We
+            // keep our allocation anyway; the rest of our chunk is lost.
+            atomic_work_item_fence(Ordering::Release, Scope::Device);
+            let _ = cursor.compare_exchange(cur, end, Ordering::Relaxed,
+                                            Ordering::Relaxed);
+            return (base + chunk) as *mut u8;
+        }
+    }
+
+    /// Bump the global pointer, returning the offset of the allocation.
+    ///
+    /// Updates of `next` and of the wavefront cursors are acquire/release, so that
+    /// everything a lane did before handing out memory is visible to whichever lane
+    /// hands out the memory after it. Only this device's wavefronts update them while
+    /// a kernel is running, so that only needs agent (`Scope::Device`) scope, which
+    /// is much cheaper than the system scope `AtomicUsize` orderings imply.
+    fn bump(&self, size: usize, align: usize) -> Option<usize> {
+        let base = self.base.load(Ordering::Relaxed);
+        let len = self.len.load(Ordering::Relaxed);
+        let mut next = self.next.load(Ordering::Relaxed);
+        loop {
+            let start = align_up(base + next, align) - base;
+            let end = start.checked_add(size)?;
+            if end > len {
+                return None;
+            }
+            atomic_work_item_fence(Ordering::Release, Scope::Device);
+            match self.next.compare_exchange_weak(next, end, Ordering::Relaxed,
+                                                  Ordering::Relaxed) {
+                Ok(_) => {
+                    atomic_work_item_fence(Ordering::Acquire, Scope::Device);
+                    return Some(start);
+                },
+                Err(actual) => { next = actual; },
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn align_up(v: usize, align: usize) -> usize {
+    (v + align - 1) & !(align - 1)
+}
+
+/// The index of the calling wavefront in the dispatch, modulo `WAVEFRONT_SLOTS`.
+#[inline(always)]
+fn wavefront_slot() -> usize {
+    let p = dispatch_packet();
+    let [l0, l1, l2] = workitem_ids();
+    let [g0, g1, g2] = workgroup_ids();
+    let [s0, s1, s2] = p.workgroup_sizes();
+    let [n0, n1, _n2] = p.grid_sizes();
+
+    let groups0 = (n0 + s0 - 1) / s0;
+    let groups1 = (n1 + s1 - 1) / s1;
+    let group = ((g2 * groups1 + g1) * groups0 + g0) as usize;
+
+    let local = (l2 * s1 + l1) * s0 + l0;
+    let lanes = wavefront_size();
+    let waves_per_group = (s0 * s1 * s2 + lanes - 1) / lanes;
+    let wave = group * waves_per_group as usize + (local / lanes) as usize;
+    wave % WAVEFRONT_SLOTS
+}
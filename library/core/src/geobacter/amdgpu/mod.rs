@@ -3,6 +3,7 @@ use crate::geobacter::intrinsics::geobacter_amdgpu_dispatch_ptr;
 use crate::geobacter::platform::platform;
 
 pub mod dpp;
+pub mod heap;
 pub mod interrupt;
 pub mod sync;
 pub mod workitem;
@@ -70,6 +71,30 @@ pub fn dispatch_packet() -> &'static DispatchPacket {
     }
 }
 
+/// The number of lanes in a wavefront, either 32 or 64. This is a property of the
+/// code being compiled, not of the hardware it runs on: it is derived from the target
+/// processor and the `wavefrontsize32`/`wavefrontsize64` target features. GFX10+
+/// processors default to wave32, older ones only support wave64.
+///
+/// Returns 1 on non-AMDGPU platforms.
+#[inline(always)]
+pub const fn wavefront_size() -> u32 {
+    extern "rust-intrinsic" {
+        #[rustc_const_unstable(feature = "geobacter", issue = "none")]
+        fn geobacter_amdgpu_wavefront_size() -> u32;
+    }
+
+    unsafe { geobacter_amdgpu_wavefront_size() }
+}
+#[inline(always)]
+pub const fn is_wave32() -> bool {
+    wavefront_size() == 32
+}
+#[inline(always)]
+pub const fn is_wave64() -> bool {
+    wavefront_size() == 64
+}
+
 #[inline(always)]
 fn ensure_amdgpu(what: &str) {
     if !platform().is_amdgcn() {
@@ -1,4 +1,5 @@
 use crate::geobacter::intrinsics::*;
+use crate::geobacter::platform::platform;
 use crate::sync::atomic::Ordering;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -12,7 +13,17 @@ pub enum Scope {
 
 /// XXX "work_item"??
 #[inline(always)]
-fn atomic_work_item_fence(order: Ordering, scope: Scope) {
+pub fn atomic_work_item_fence(order: Ordering, scope: Scope) {
+    if !platform().is_amdgcn() {
+        // The narrower scopes are AMDGPU sync scopes, which other targets (ie the host,
+        // where the heap tests run) don't have. A system scoped fence is at least as
+        // strong as any of them.
+        if order != Ordering::Relaxed {
+            crate::sync::atomic::fence(order);
+        }
+        return;
+    }
+
     match (scope, order) {
         (Scope::WorkItem, Ordering::Release) => unsafe {
             atomic_scoped_fence_singlethread_rel()
@@ -0,0 +1,174 @@
+//! eBPF map declarations. Maps are created by the loader before the program is
+//! loaded; the program refers to them through (relocated) statics. The statics
+//! must be placed in the `maps` section, where `libbpf` looks for them:
+//!
+//! ```ignore (requires-device)
+//! use std::geobacter::bpf::map::HashMap;
+//!
+//! #[link_section = "maps"]
+//! static COUNTS: HashMap<u32, u64, 1024> = HashMap::new();
+//! ```
+
+use crate::ffi::c_void;
+use crate::geobacter::platform::platform;
+use crate::marker::PhantomData;
+use crate::mem::size_of;
+
+/// Taken from the kernel's `enum bpf_map_type`.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[repr(u32)]
+pub enum MapType {
+    Hash = 1,
+    Array = 2,
+    ProgArray = 3,
+    PerfEventArray = 4,
+    PercpuHash = 5,
+    PercpuArray = 6,
+    LruHash = 9,
+    RingBuf = 27,
+}
+
+/// Same layout as `libbpf`'s `struct bpf_map_def`.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[repr(C)]
+pub struct MapDef {
+    pub ty: u32,
+    pub key_size: u32,
+    pub value_size: u32,
+    pub max_entries: u32,
+    pub map_flags: u32,
+}
+
+const BPF_ANY: u64 = 0;
+
+/// A keyed map. `TYPE` is a `MapType`.
+/// This type *must* be used for statics only.
+#[cfg_attr(not(stage0), lang = "bpf_map")]
+#[repr(C)]
+pub struct Map<K, V, const TYPE: u32, const MAX_ENTRIES: u32> {
+    def: MapDef,
+    _m: PhantomData<(K, V)>,
+}
+pub type HashMap<K, V, const MAX_ENTRIES: u32> = Map<K, V, 1, {MAX_ENTRIES}>;
+pub type Array<V, const MAX_ENTRIES: u32> = Map<u32, V, 2, {MAX_ENTRIES}>;
+pub type LruHashMap<K, V, const MAX_ENTRIES: u32> = Map<K, V, 9, {MAX_ENTRIES}>;
+
+impl<K, V, const TYPE: u32, const MAX_ENTRIES: u32> Map<K, V, {TYPE}, {MAX_ENTRIES}> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::with_flags(0)
+    }
+    #[inline(always)]
+    pub const fn with_flags(map_flags: u32) -> Self {
+        Map {
+            def: MapDef {
+                ty: TYPE,
+                key_size: size_of::<K>() as u32,
+                value_size: size_of::<V>() as u32,
+                max_entries: MAX_ENTRIES,
+                map_flags,
+            },
+            _m: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn def(&self) -> &MapDef { &self.def }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *mut c_void {
+        &self.def as *const MapDef as *mut c_void
+    }
+
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        assert!(platform().is_bpf());
+        let lookup = helper!(1, fn(*mut c_void, *const c_void) -> *mut c_void);
+        unsafe {
+            let v = lookup(self.as_ptr(), key as *const K as *const c_void);
+            (v as *const V).as_ref()
+        }
+    }
+    /// Get a mutable reference to the value of `key`.
+    ///
+    /// # Safety
+    ///
+    /// The kernel doesn't stop other CPUs, other programs, or userspace from accessing
+    /// the value while the returned reference lives, and neither does this type: `get`
+    /// and `get_mut` can be called any number of times for the same key. The caller must
+    /// ensure that nothing else reads or writes the value for as long as the reference is
+    /// live, eg because the map is per-CPU and the reference doesn't outlive the current
+    /// program invocation. Use `get` and atomics for values which are shared.
+    #[inline(always)]
+    pub unsafe fn get_mut(&self, key: &K) -> Option<&mut V> {
+        assert!(platform().is_bpf());
+        let lookup = helper!(1, fn(*mut c_void, *const c_void) -> *mut c_void);
+        unsafe {
+            let v = lookup(self.as_ptr(), key as *const K as *const c_void);
+            (v as *mut V).as_mut()
+        }
+    }
+    /// Returns the (negative) kernel error code on failure.
+    #[inline(always)]
+    pub fn insert(&self, key: &K, value: &V) -> Result<(), i64> {
+        assert!(platform().is_bpf());
+        let update = helper!(2, fn(*mut c_void, *const c_void, *const c_void, u64) -> i64);
+        let r = unsafe {
+            update(self.as_ptr(), key as *const K as *const c_void,
+                   value as *const V as *const c_void, BPF_ANY)
+        };
+        if r == 0 { Ok(()) } else { Err(r) }
+    }
+    /// Returns the (negative) kernel error code on failure.
+    #[inline(always)]
+    pub fn remove(&self, key: &K) -> Result<(), i64> {
+        assert!(platform().is_bpf());
+        let delete = helper!(3, fn(*mut c_void, *const c_void) -> i64);
+        let r = unsafe { delete(self.as_ptr(), key as *const K as *const c_void) };
+        if r == 0 { Ok(()) } else { Err(r) }
+    }
+}
+unsafe impl<K, V, const TYPE: u32, const MAX_ENTRIES: u32> Sync
+    for Map<K, V, {TYPE}, {MAX_ENTRIES}>
+{ }
+
+/// A `BPF_MAP_TYPE_RINGBUF` map, `SIZE` bytes large. `SIZE` must be a power of two
+/// multiple of the page size.
+/// This type *must* be used for statics only.
+#[cfg_attr(not(stage0), lang = "bpf_ring_buf")]
+#[repr(C)]
+pub struct RingBuf<const SIZE: u32> {
+    def: MapDef,
+}
+impl<const SIZE: u32> RingBuf<{SIZE}> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        RingBuf {
+            def: MapDef {
+                ty: MapType::RingBuf as u32,
+                key_size: 0,
+                value_size: 0,
+                max_entries: SIZE,
+                map_flags: 0,
+            },
+        }
+    }
+
+    #[inline(always)]
+    pub const fn def(&self) -> &MapDef { &self.def }
+
+    /// Copy `data` into the ring buffer. Returns the (negative) kernel error code
+    /// on failure.
+    #[inline(always)]
+    pub fn output<T>(&self, data: &T) -> Result<(), i64> {
+        assert!(platform().is_bpf());
+        let output = helper!(130, fn(*mut c_void, *const c_void, u64, u64) -> i64);
+        let r = unsafe {
+            output(&self.def as *const MapDef as *mut c_void,
+                   data as *const T as *const c_void,
+                   size_of::<T>() as u64, 0)
+        };
+        if r == 0 { Ok(()) } else { Err(r) }
+    }
+}
+unsafe impl<const SIZE: u32> Sync for RingBuf<{SIZE}> { }
@@ -0,0 +1,7 @@
+//! The description of the maps a set of eBPF programs uses, as produced by the
+//! compiler. The host uses this to create the maps before loading the programs.
+
+/// `(symbol, MapDef::ty, key_size, value_size, max_entries, map_flags)`. `symbol` is
+/// the (mangled) name of the map static, which is what the loader relocates.
+pub type CompilerMapDesc = (&'static str, u32, u32, u32, u32, u32);
+pub type CompilerMapLayoutDesc = &'static [CompilerMapDesc];
@@ -0,0 +1,68 @@
+//! eBPF programs, ie packet filters and tracing probes.
+//!
+//! eBPF code is checked by the kernel's verifier before it is allowed to run, so
+//! a lot of otherwise normal Rust is off limits: loops must be bounded, the only
+//! calls allowed are to other functions in the program and to kernel helpers, and
+//! there is no way to allocate memory. Geobacter checks the first two when compiling
+//! for the `bpf` arch, so you get an error with a span instead of a verifier log.
+
+use crate::geobacter::platform::{platform, Platform};
+
+/// Get the kernel helper `$id` as a function pointer. Helpers are called by number;
+/// LLVM turns a call through the integer `$id` cast to a function pointer into
+/// `call $id`.
+macro_rules! helper {
+    ($id:expr, fn($($arg:ty),*) -> $ret:ty) => {{
+        let f: unsafe extern "C" fn($($arg),*) -> $ret = unsafe {
+            crate::mem::transmute($id as usize)
+        };
+        f
+    }};
+}
+
+pub mod map;
+pub mod map_layout;
+
+/// The program type of the current program, if we're running as eBPF.
+#[inline(always)]
+pub fn program_type() -> Option<crate::geobacter::platform::bpf::ProgramType> {
+    match platform() {
+        Platform::Bpf(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Return from the program with `verdict`.
+///
+/// # Safety
+///
+/// eBPF's `exit` instruction returns from the current BPF function, not from the
+/// whole program. It is only an exit from the program if every call leading here
+/// has been inlined into the program entry; inside a BPF to BPF call, the callee
+/// returns to its caller, which then continues as if this function had returned.
+/// The caller must ensure the former, eg by only calling this from the entry
+/// function itself or from `#[inline(always)]` functions which are only called
+/// from there. When compiling for eBPF, functions which can reach this (or a panic)
+/// without being `#[inline(always)]` are rejected.
+#[inline(always)]
+pub unsafe fn exit(verdict: u64) -> ! {
+    assert!(platform().is_bpf());
+    unsafe {
+        llvm_asm!("exit" :: "{r0}"(verdict) :: "volatile");
+        crate::hint::unreachable_unchecked()
+    }
+}
+
+/// `exit` with the drop verdict of the current program type.
+///
+/// # Safety
+///
+/// See `exit`.
+#[inline(always)]
+pub unsafe fn exit_drop() -> ! {
+    let verdict = match program_type() {
+        Some(ty) => ty.drop_verdict(),
+        None => 0,
+    };
+    unsafe { exit(verdict) }
+}
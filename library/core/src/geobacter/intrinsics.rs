@@ -52,6 +52,7 @@ extern "rust-intrinsic" {
     pub fn geobacter_amdgpu_wave_barrier();
     pub fn geobacter_amdgpu_sendmsg(_: i32, _: u32);
     pub fn geobacter_amdgpu_readfirstlane(_: u32) -> u32;
+    pub fn geobacter_amdgpu_readlane(_: u32, lane: u32) -> u32;
 
     pub fn geobacter_amdgpu_workitem_x_id() -> u32;
     pub fn geobacter_amdgpu_workitem_y_id() -> u32;
@@ -61,6 +62,19 @@ extern "rust-intrinsic" {
     pub fn geobacter_amdgpu_workgroup_z_id() -> u32;
 }
 
+/// SPIRV intrinsics
+#[cfg(stage2)]
+extern "rust-intrinsic" {
+    /// `Entries` is a tuple of entry point function types, of any arity. `()`
+    /// elements are skipped.
+    pub fn geobacter_spirv_pipeline_layout_desc<Entries>()
+        -> crate::geobacter::spirv::pipeline_layout::CompilerDescriptorSetBindingsDesc;
+    /// `F` is an OpenCL kernel function type. Returns the host visible arguments of
+    /// the kernel.
+    pub fn geobacter_spirv_kernel_args<F>()
+        -> &'static [crate::geobacter::spirv::opencl::CompilerKernelArg];
+}
+
 /// Scoped atomic fences.
 #[cfg(stage2)]
 extern "rust-intrinsic" {
@@ -84,3 +98,12 @@ extern "rust-intrinsic" {
     pub fn atomic_scoped_fence_agent_acqrel();
     pub fn atomic_scoped_fence_agent_seqcst();
 }
+
+/// eBPF intrinsics
+#[cfg(stage2)]
+extern "rust-intrinsic" {
+    /// `Entries` is a tuple of program function types, of any arity. `()` elements
+    /// are skipped.
+    pub fn geobacter_bpf_map_layout_desc<Entries>()
+        -> crate::geobacter::bpf::map_layout::CompilerMapLayoutDesc;
+}
@@ -5,8 +5,11 @@
 )]
 #![allow(missing_docs)]
 
+#[cfg(stage2)]
+pub mod alloc;
 #[cfg(stage2)]
 pub mod amdgpu;
+pub mod bpf;
 #[cfg(stage2)]
 pub mod cuda;
 pub mod spirv;
@@ -18,10 +18,12 @@ pub enum Platform {
     Cuda,
     Vulkan(self::spirv::ExeModel),
     OpenGl(self::spirv::ExeModel),
+    /// OpenCL flavored SPIR-V; always uses the `Kernel` execution model.
     OpenCl,
 
-    /// Berkley Packet Filter; ATM unsupported/incomplete.
-    Bpf,
+    /// (extended) Berkley Packet Filter. The program type decides what the return
+    /// value of the program means.
+    Bpf(self::bpf::ProgramType),
 }
 
 pub mod hsa {
@@ -43,8 +45,76 @@ pub mod hsa {
         Polaris11,
         Stoney,
 
+        //===----------------------------------------------------------------------===//
+        // GCN GFX9.
+        //===----------------------------------------------------------------------===//
+        Gfx900,
+        Gfx902,
+        Gfx904,
+        Gfx906,
+        Gfx908,
+        Gfx909,
+        Gfx90a,
+        Gfx90c,
+
+        //===----------------------------------------------------------------------===//
+        // GCN GFX10 (RDNA 1 & 2).
+        //===----------------------------------------------------------------------===//
+        Gfx1010,
+        Gfx1011,
+        Gfx1012,
+        Gfx1013,
+        Gfx1030,
+        Gfx1031,
+        Gfx1032,
+        Gfx1033,
+        Gfx1034,
+        Gfx1035,
+        Gfx1036,
+
+        //===----------------------------------------------------------------------===//
+        // GCN GFX11 (RDNA 3).
+        //===----------------------------------------------------------------------===//
+        Gfx1100,
+        Gfx1101,
+        Gfx1102,
+        Gfx1103,
+
+        /// Other processors, by their numeric name, ie `gfx801` or `gfx940`.
         Gfx(u16),
     }
+    impl AmdGcn {
+        /// The major GFX version, ie `9` for Vega.
+        pub const fn gfx_major(self) -> u16 {
+            use self::AmdGcn::*;
+            match self {
+                Carrizo | Iceland | Tonga | Fiji |
+                Polaris10 | Polaris11 | Stoney => 8,
+
+                Gfx900 | Gfx902 | Gfx904 | Gfx906 |
+                Gfx908 | Gfx909 | Gfx90a | Gfx90c => 9,
+
+                Gfx1010 | Gfx1011 | Gfx1012 | Gfx1013 |
+                Gfx1030 | Gfx1031 | Gfx1032 | Gfx1033 |
+                Gfx1034 | Gfx1035 | Gfx1036 => 10,
+
+                Gfx1100 | Gfx1101 | Gfx1102 | Gfx1103 => 11,
+
+                // The last two digits are the minor version and the stepping.
+                Gfx(ver) => ver / 100,
+            }
+        }
+        /// Does this processor support wave32 (in addition to wave64)?
+        pub const fn supports_wave32(self) -> bool {
+            self.gfx_major() >= 10
+        }
+        /// The wavefront size LLVM uses for this processor when the target features
+        /// don't say otherwise. Note the wavefront size code is actually compiled for
+        /// is available from `amdgpu::wavefront_size()`.
+        pub const fn default_wavefront_size(self) -> u32 {
+            if self.supports_wave32() { 32 } else { 64 }
+        }
+    }
     impl FromStr for AmdGcn {
         type Err = ();
         fn from_str(s: &str) -> Result<Self, ()> {
@@ -59,6 +129,32 @@ pub mod hsa {
                 "polaris11" => Polaris11,
                 "stoney" => Stoney,
 
+                "gfx900" => Gfx900,
+                "gfx902" => Gfx902,
+                "gfx904" => Gfx904,
+                "gfx906" => Gfx906,
+                "gfx908" => Gfx908,
+                "gfx909" => Gfx909,
+                "gfx90a" => Gfx90a,
+                "gfx90c" => Gfx90c,
+
+                "gfx1010" => Gfx1010,
+                "gfx1011" => Gfx1011,
+                "gfx1012" => Gfx1012,
+                "gfx1013" => Gfx1013,
+                "gfx1030" => Gfx1030,
+                "gfx1031" => Gfx1031,
+                "gfx1032" => Gfx1032,
+                "gfx1033" => Gfx1033,
+                "gfx1034" => Gfx1034,
+                "gfx1035" => Gfx1035,
+                "gfx1036" => Gfx1036,
+
+                "gfx1100" => Gfx1100,
+                "gfx1101" => Gfx1101,
+                "gfx1102" => Gfx1102,
+                "gfx1103" => Gfx1103,
+
                 _ if s.starts_with("gfx") => {
                     let ver = &s[3..];
                     let ver = u16::from_str(ver)
@@ -92,7 +188,7 @@ pub mod spirv {
         Fragment,
         /// Vulkan/OpenGL compute kernel.
         GLCompute,
-        /// OpenCL kernel; here for completeness.
+        /// OpenCL kernel. Only used by `Platform::OpenCl`.
         Kernel,
     }
     impl FromStr for ExeModel {
@@ -114,6 +210,73 @@ pub mod spirv {
     }
 }
 
+pub mod bpf {
+    use crate::prelude::v1::*;
+    use crate::str::FromStr;
+
+    /// The kinds of eBPF programs Geobacter knows how to compile. These are named
+    /// after the kernel's `enum bpf_prog_type`.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+    #[repr(u32)]
+    pub enum ProgramType {
+        SocketFilter,
+        Kprobe,
+        /// `tc` classifier.
+        SchedCls,
+        /// `tc` action.
+        SchedAct,
+        Tracepoint,
+        RawTracepoint,
+        Xdp,
+        PerfEvent,
+        CgroupSkb,
+    }
+    impl ProgramType {
+        /// The value the program returns when it's killed (ie by a panic). For
+        /// packet processing programs, this drops the packet.
+        #[inline(always)]
+        pub const fn drop_verdict(self) -> u64 {
+            match self {
+                // Truncate the packet to zero bytes.
+                ProgramType::SocketFilter => 0,
+                // `TC_ACT_SHOT`
+                ProgramType::SchedCls |
+                ProgramType::SchedAct => 2,
+                // `XDP_ABORTED`: drop the packet, and let anyone watching the
+                // `xdp_exception` tracepoint know something went wrong.
+                ProgramType::Xdp => 0,
+                // Drop.
+                ProgramType::CgroupSkb => 0,
+                // The return value of tracing programs is ignored (mostly).
+                ProgramType::Kprobe |
+                ProgramType::Tracepoint |
+                ProgramType::RawTracepoint |
+                ProgramType::PerfEvent => 0,
+            }
+        }
+    }
+    impl FromStr for ProgramType {
+        type Err = ();
+        /// Parse the section name prefix `libbpf` uses for the program type.
+        fn from_str(s: &str) -> Result<Self, ()> {
+            let r = match s {
+                "socket" => ProgramType::SocketFilter,
+                "kprobe" | "kretprobe" => ProgramType::Kprobe,
+                "classifier" | "tc" => ProgramType::SchedCls,
+                "action" => ProgramType::SchedAct,
+                "tracepoint" | "tp" => ProgramType::Tracepoint,
+                "raw_tracepoint" | "raw_tp" => ProgramType::RawTracepoint,
+                "xdp" => ProgramType::Xdp,
+                "perf_event" => ProgramType::PerfEvent,
+                "cgroup_skb" | "cgroup/skb" => ProgramType::CgroupSkb,
+                _ => { return Err(()); },
+            };
+
+            Ok(r)
+        }
+    }
+}
+
 impl Platform {
     #[inline(always)]
     pub fn is_host(self) -> bool {
@@ -209,6 +372,13 @@ impl Platform {
         }
     }
     #[inline(always)]
+    pub fn is_spirv_kernel(self) -> bool {
+        match self {
+            Platform::OpenCl => true,
+            _ => false,
+        }
+    }
+    #[inline(always)]
     pub fn is_vulkan(self) -> bool {
         match self {
             Platform::Vulkan(_) => true,
@@ -229,6 +399,13 @@ impl Platform {
             _ => false,
         }
     }
+    #[inline(always)]
+    pub fn is_bpf(self) -> bool {
+        match self {
+            Platform::Bpf(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl Default for Platform {
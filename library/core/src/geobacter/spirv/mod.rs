@@ -13,6 +13,7 @@ use crate::{fmt, ptr};
 
 pub mod builtin;
 pub mod matrix;
+pub mod opencl;
 pub mod pipeline_layout;
 pub mod shader_interface;
 pub mod workitem;
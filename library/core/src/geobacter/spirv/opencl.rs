@@ -0,0 +1,54 @@
+//! Helpers for OpenCL (ie `Kernel` execution model) SPIR-V kernels.
+//!
+//! Unlike Vulkan, OpenCL kernels receive their resources as function arguments,
+//! which the host sets one by one with `clSetKernelArg`. The compiler describes
+//! what each (ABI level) argument looks like, so that the host side doesn't have
+//! to guess.
+
+use crate::convert::{Into, TryFrom};
+use crate::result::Result;
+
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[repr(u32)]
+pub enum CompilerKernelArgKind {
+    /// Plain old data; the bytes are copied into the argument.
+    ByValue = 0,
+    /// A pointer into a `__global` buffer.
+    GlobalPtr,
+    /// A pointer into a `__constant` buffer.
+    ConstantPtr,
+}
+impl Into<u32> for CompilerKernelArgKind {
+    #[inline(always)]
+    fn into(self) -> u32 {
+        self as _
+    }
+}
+impl TryFrom<u32> for CompilerKernelArgKind {
+    type Error = ();
+    #[inline(always)]
+    fn try_from(v: u32) -> Result<Self, ()> {
+        use self::CompilerKernelArgKind::*;
+        Result::Ok(match v {
+            0 => ByValue,
+            1 => GlobalPtr,
+            2 => ConstantPtr,
+            _ => { return Result::Err(()); },
+        })
+    }
+}
+
+/// `(CompilerKernelArgKind, size, align)`. For pointers, `size` and `align` are
+/// those of the pointee, or zero if the pointee is unsized or unknown. Pointers the
+/// argument's type doesn't name directly (ie fields of a struct) are `GlobalPtr`s
+/// with an unknown pointee.
+pub type CompilerKernelArg = (u32 /*CompilerKernelArgKind*/, u64, u64);
+
+/// Describe the kernel arguments of `F`, in the order the host must set them.
+/// Slices and other fat pointers take two arguments: the pointer, then the
+/// (`usize`) metadata.
+#[cfg(stage2)]
+#[inline(always)]
+pub fn kernel_args<F>(_: &F) -> &'static [CompilerKernelArg] {
+    unsafe { crate::geobacter::intrinsics::geobacter_spirv_kernel_args::<F>() }
+}
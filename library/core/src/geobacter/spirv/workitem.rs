@@ -23,3 +23,75 @@ pub fn global_invocation_id() -> [u32; 3] {
     let v = unsafe { giid() };
     [v.0, v.1, v.2]
 }
+#[inline(always)]
+pub fn workgroup_id() -> [u32; 3] {
+    assert!(platform().is_spirv());
+    extern "C" {
+        #[link_name = "llvm.spirv.workgroup.id"]
+        fn wgid() -> V3<u32>;
+    }
+    let v = unsafe { wgid() };
+    [v.0, v.1, v.2]
+}
+#[inline(always)]
+pub fn num_workgroups() -> [u32; 3] {
+    assert!(platform().is_spirv());
+    extern "C" {
+        #[link_name = "llvm.spirv.num.workgroups"]
+        fn nwg() -> V3<u32>;
+    }
+    let v = unsafe { nwg() };
+    [v.0, v.1, v.2]
+}
+#[inline(always)]
+pub fn workgroup_size() -> [u32; 3] {
+    assert!(platform().is_spirv());
+    extern "C" {
+        #[link_name = "llvm.spirv.workgroup.size"]
+        fn wgs() -> V3<u32>;
+    }
+    let v = unsafe { wgs() };
+    [v.0, v.1, v.2]
+}
+#[inline(always)]
+pub fn local_invocation_index() -> u32 {
+    assert!(platform().is_spirv());
+    extern "C" {
+        #[link_name = "llvm.spirv.local.invocation.index"]
+        fn lii() -> u32;
+    }
+    unsafe { lii() }
+}
+
+// The following builtins only exist in the `Kernel` execution model, ie OpenCL.
+// They are `size_t` sized there.
+
+#[inline(always)]
+pub fn global_size() -> [usize; 3] {
+    assert!(platform().is_opencl());
+    extern "C" {
+        #[link_name = "llvm.spirv.global.size"]
+        fn gs() -> V3<usize>;
+    }
+    let v = unsafe { gs() };
+    [v.0, v.1, v.2]
+}
+#[inline(always)]
+pub fn global_offset() -> [usize; 3] {
+    assert!(platform().is_opencl());
+    extern "C" {
+        #[link_name = "llvm.spirv.global.offset"]
+        fn go() -> V3<usize>;
+    }
+    let v = unsafe { go() };
+    [v.0, v.1, v.2]
+}
+#[inline(always)]
+pub fn global_linear_id() -> usize {
+    assert!(platform().is_opencl());
+    extern "C" {
+        #[link_name = "llvm.spirv.global.linear.id"]
+        fn glid() -> usize;
+    }
+    unsafe { glid() }
+}
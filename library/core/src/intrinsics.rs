@@ -1732,6 +1732,23 @@ extern "rust-intrinsic" {
     /// See documentation of `<*const T>::guaranteed_ne` for details.
     #[rustc_const_unstable(feature = "const_raw_ptr_comparison", issue = "53020")]
     pub fn ptr_guaranteed_ne<T>(ptr: *const T, other: *const T) -> bool;
+
+    /// Allocates a block of memory at compile time.
+    /// At runtime, just returns a null pointer.
+    ///
+    /// Only the allocations reachable through references from the final value
+    /// of a constant are kept, as immutable memory. Allocations reachable only
+    /// through raw pointers are rejected.
+    #[rustc_const_unstable(feature = "const_heap", issue = "none")]
+    pub fn const_allocate(size: usize, align: usize) -> *mut u8;
+
+    /// Deallocates memory that was allocated by `intrinsics::const_allocate` at compile time.
+    /// At runtime, does nothing.
+    ///
+    /// Memory that is already part of the final value of another constant is
+    /// left untouched.
+    #[rustc_const_unstable(feature = "const_heap", issue = "none")]
+    pub fn const_deallocate(ptr: *mut u8, size: usize, align: usize);
 }
 
 // Some functions are defined here because they accidentally got made
@@ -0,0 +1,16 @@
+use core::alloc::Layout;
+use core::geobacter::alloc::{
+    device_alloc, device_alloc_zeroed, device_dealloc, device_realloc, HSA_HEAP,
+};
+
+#[test]
+fn host_uses_the_global_allocator() {
+    let layout = Layout::new::<u64>();
+    let mut x = 0u64;
+    let ptr = &mut x as *mut u64 as *mut u8;
+    assert_eq!(device_alloc(layout), None);
+    assert_eq!(device_alloc_zeroed(layout), None);
+    assert!(!device_dealloc(ptr, layout));
+    assert_eq!(unsafe { device_realloc(ptr, layout, 16) }, None);
+    assert_eq!(HSA_HEAP.used(), 0);
+}
@@ -0,0 +1,60 @@
+use core::alloc::Layout;
+use core::geobacter::amdgpu::heap::{Heap, CHUNK_SIZE};
+
+#[repr(align(4096))]
+struct Region([u8; CHUNK_SIZE * 4]);
+
+#[test]
+fn uninit_heap_is_empty() {
+    let heap = Heap::new();
+    let layout = Layout::new::<u32>();
+    assert!(heap.alloc_in_slot(layout, 0).is_null());
+}
+
+#[test]
+fn slot_allocs_share_a_chunk() {
+    let mut region = Region([0; CHUNK_SIZE * 4]);
+    let heap = Heap::new();
+    unsafe {
+        heap.init(region.0.as_mut_ptr(), region.0.len());
+    }
+
+    let layout = Layout::from_size_align(24, 8).unwrap();
+    let a = heap.alloc_in_slot(layout, 0) as usize;
+    let b = heap.alloc_in_slot(layout, 0) as usize;
+    let c = heap.alloc_in_slot(layout, 1) as usize;
+    assert_eq!(a + 24, b);
+    assert_eq!(a + CHUNK_SIZE, c);
+    assert_eq!(heap.used(), CHUNK_SIZE * 2);
+}
+
+#[test]
+fn large_allocs_bypass_slots() {
+    let mut region = Region([0; CHUNK_SIZE * 4]);
+    let heap = Heap::new();
+    unsafe {
+        heap.init(region.0.as_mut_ptr(), region.0.len());
+    }
+
+    let layout = Layout::from_size_align(CHUNK_SIZE * 2, 16).unwrap();
+    assert!(!heap.alloc_in_slot(layout, 0).is_null());
+    assert!(!heap.alloc_in_slot(layout, 0).is_null());
+    assert!(heap.alloc_in_slot(layout, 0).is_null());
+}
+
+#[test]
+fn reset_forgets_allocations() {
+    let mut region = Region([0; CHUNK_SIZE * 4]);
+    let heap = Heap::new();
+    unsafe {
+        heap.init(region.0.as_mut_ptr(), region.0.len());
+    }
+
+    let layout = Layout::from_size_align(24, 8).unwrap();
+    let a = heap.alloc_in_slot(layout, 0);
+    unsafe {
+        heap.reset();
+    }
+    assert_eq!(heap.used(), 0);
+    assert_eq!(heap.alloc_in_slot(layout, 0), a);
+}
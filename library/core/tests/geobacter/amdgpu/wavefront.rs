@@ -0,0 +1,15 @@
+use core::geobacter::amdgpu::dpp::{all_rows_mask, rows};
+use core::geobacter::amdgpu::{is_wave32, is_wave64, wavefront_size};
+
+#[test]
+fn host_wavefront_is_one_lane() {
+    assert_eq!(wavefront_size(), 1);
+    assert!(!is_wave32());
+    assert!(!is_wave64());
+}
+
+#[test]
+fn host_wavefront_is_one_row() {
+    assert_eq!(rows(), 1);
+    assert_eq!(all_rows_mask(), 1);
+}
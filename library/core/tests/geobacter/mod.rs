@@ -0,0 +1,3 @@
+mod alloc;
+mod amdgpu;
+mod platform;
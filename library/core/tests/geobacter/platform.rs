@@ -0,0 +1,35 @@
+use core::geobacter::platform::hsa::AmdGcn;
+use core::str::FromStr;
+
+#[test]
+fn amdgcn_from_str() {
+    assert_eq!(AmdGcn::from_str("fiji"), Ok(AmdGcn::Fiji));
+    assert_eq!(AmdGcn::from_str("gfx90a"), Ok(AmdGcn::Gfx90a));
+    assert_eq!(AmdGcn::from_str("gfx1030"), Ok(AmdGcn::Gfx1030));
+    assert_eq!(AmdGcn::from_str("gfx801"), Ok(AmdGcn::Gfx(801)));
+    // Processors without a variant of their own.
+    assert_eq!(AmdGcn::from_str("gfx940"), Ok(AmdGcn::Gfx(940)));
+    assert_eq!(AmdGcn::from_str("gfx1150"), Ok(AmdGcn::Gfx(1150)));
+    assert_eq!(AmdGcn::from_str("gfx"), Err(()));
+    assert_eq!(AmdGcn::from_str("gfx9x"), Err(()));
+    assert_eq!(AmdGcn::from_str("vega"), Err(()));
+}
+
+#[test]
+fn amdgcn_gfx_major() {
+    assert_eq!(AmdGcn::Polaris10.gfx_major(), 8);
+    assert_eq!(AmdGcn::Gfx(810).gfx_major(), 8);
+    assert_eq!(AmdGcn::Gfx906.gfx_major(), 9);
+    assert_eq!(AmdGcn::Gfx(940).gfx_major(), 9);
+    assert_eq!(AmdGcn::Gfx1036.gfx_major(), 10);
+    assert_eq!(AmdGcn::Gfx1103.gfx_major(), 11);
+    assert_eq!(AmdGcn::Gfx(1150).gfx_major(), 11);
+}
+
+#[test]
+fn amdgcn_default_wavefront_size() {
+    assert_eq!(AmdGcn::Gfx908.default_wavefront_size(), 64);
+    assert_eq!(AmdGcn::Gfx(940).default_wavefront_size(), 64);
+    assert_eq!(AmdGcn::Gfx1010.default_wavefront_size(), 32);
+    assert_eq!(AmdGcn::Gfx(1150).default_wavefront_size(), 32);
+}
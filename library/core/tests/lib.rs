@@ -59,6 +59,7 @@
 #![feature(once_cell)]
 #![feature(unsafe_block_in_unsafe_fn)]
 #![feature(int_bits_const)]
+#![feature(geobacter)]
 #![deny(unsafe_op_in_unsafe_fn)]
 
 extern crate test;
@@ -74,6 +75,9 @@ mod char;
 mod clone;
 mod cmp;
 mod fmt;
+// The AMDGPU support is only built into the stage2 `core`.
+#[cfg(stage2)]
+mod geobacter;
 mod hash;
 mod intrinsics;
 mod iter;
@@ -539,6 +539,19 @@ impl Step for Rustc {
                 builder.copy(&src, &dst);
             }
 
+            // Copy over llvm-dwp if it's there
+            {
+                let exe = exe("rust-llvm-dwp", compiler.host);
+                let src =
+                    builder.sysroot_libdir(compiler, host).parent().unwrap().join("bin").join(&exe);
+                // for the rationale about this rename check `compile::copy_lld_to_sysroot`
+                let dst = image.join("lib/rustlib").join(&*host.triple).join("bin").join(&exe);
+                if src.exists() {
+                    t!(fs::create_dir_all(&dst.parent().unwrap()));
+                    builder.copy(&src, &dst);
+                }
+            }
+
             // Man pages
             t!(fs::create_dir_all(image.join("share/man/man1")));
             let man_src = builder.src.join("src/doc/man");
@@ -1132,6 +1145,8 @@ impl Step for PlainSourceTarball {
             cmd.arg("vendor")
                 .arg("--sync")
                 .arg(builder.src.join("./src/tools/rust-analyzer/Cargo.toml"))
+                .arg("--sync")
+                .arg(builder.src.join("./compiler/rustc_codegen_cranelift/Cargo.toml"))
                 .current_dir(&plain_dst_src);
             builder.run(&mut cmd);
         }
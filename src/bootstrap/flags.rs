@@ -68,6 +68,7 @@ pub enum Subcommand {
         bless: bool,
         compare_mode: Option<String>,
         pass: Option<String>,
+        codegen_backend: Option<String>,
         test_args: Vec<String>,
         rustc_args: Vec<String>,
         fail_fast: bool,
@@ -243,6 +244,13 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`",
                     "force {check,build,run}-pass tests to this mode.",
                     "check | build | run",
                 );
+                opts.optopt(
+                    "",
+                    "codegen-backend",
+                    "compile the tests with this codegen backend, which must be one of \
+                        `rust.codegen-backends`",
+                    "BACKEND",
+                );
                 opts.optflag(
                     "",
                     "rustfix-coverage",
@@ -412,6 +420,7 @@ Arguments:
         ./x.py test library/std --stage 0 --no-doc
         ./x.py test src/test/ui --bless
         ./x.py test src/test/ui --compare-mode nll
+        ./x.py test src/test/ui --codegen-backend cranelift
 
     Note that `test src/test/* --stage N` does NOT depend on `build compiler/rustc --stage N`;
     just like `build library/std --stage N` it tests the compiler produced by the previous
@@ -492,6 +501,7 @@ Arguments:
                 bless: matches.opt_present("bless"),
                 compare_mode: matches.opt_str("compare-mode"),
                 pass: matches.opt_str("pass"),
+                codegen_backend: matches.opt_str("codegen-backend"),
                 test_args: matches.opt_strs("test-args"),
                 rustc_args: matches.opt_strs("rustc-args"),
                 fail_fast: !matches.opt_present("no-fail-fast"),
@@ -670,6 +680,15 @@ impl Subcommand {
         }
     }
 
+    pub fn codegen_backend(&self) -> Option<&str> {
+        match *self {
+            Subcommand::Test { ref codegen_backend, .. } => {
+                codegen_backend.as_ref().map(|s| &s[..])
+            }
+            _ => None,
+        }
+    }
+
     pub fn open(&self) -> bool {
         match *self {
             Subcommand::Doc { open, .. } => open,
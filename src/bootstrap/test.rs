@@ -931,6 +931,12 @@ host_test!(UiFullDeps { path: "src/test/ui-fulldeps", mode: "ui", suite: "ui-ful
 
 host_test!(Rustdoc { path: "src/test/rustdoc", mode: "rustdoc", suite: "rustdoc" });
 
+host_test!(RustdocJson {
+    path: "src/test/rustdoc-json",
+    mode: "rustdoc-json",
+    suite: "rustdoc-json"
+});
+
 host_test!(Pretty { path: "src/test/pretty", mode: "pretty", suite: "pretty" });
 
 default_test!(RunMake { path: "src/test/run-make", mode: "run-make", suite: "run-make" });
@@ -1015,6 +1021,7 @@ impl Step for Compiletest {
 
         // Avoid depending on rustdoc when we don't need it.
         if mode == "rustdoc"
+            || mode == "rustdoc-json"
             || (mode == "run-make" && suite.ends_with("fulldeps"))
             || (mode == "ui" && is_rustdoc)
             || mode == "js-doc-test"
@@ -1048,6 +1055,11 @@ impl Step for Compiletest {
             cmd.arg(pass);
         }
 
+        if let Some(backend) = builder.config.cmd.codegen_backend() {
+            cmd.arg("--codegen-backend");
+            cmd.arg(backend);
+        }
+
         if let Some(ref nodejs) = builder.config.nodejs {
             cmd.arg("--nodejs").arg(nodejs);
         }
@@ -1,47 +1,252 @@
+//! Rustdoc's JSON backend
+//!
+//! This module contains the logic for rendering a crate as JSON rather than the normal static HTML
+//! output. See [the RFC](https://github.com/rust-lang/rfcs/pull/2963) and the [`types`] module
+//! docs for usage and details.
+
+mod conversions;
+pub mod types;
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_span::def_id::{DefId, CRATE_DEF_INDEX, LOCAL_CRATE};
+use rustc_span::edition::Edition;
+
 use crate::clean;
 use crate::config::{RenderInfo, RenderOptions};
 use crate::error::Error;
 use crate::formats::cache::Cache;
 use crate::formats::FormatRenderer;
-
-use rustc_span::edition::Edition;
+use crate::html::render::cache::ExternalLocation;
 
 #[derive(Clone)]
-pub struct JsonRenderer {}
+pub struct JsonRenderer {
+    /// A mapping of IDs that contains all local items for this crate which gets output as a top
+    /// level field of the JSON blob.
+    index: Rc<RefCell<FxHashMap<types::Id, types::Item>>>,
+    /// The directory where the blob will be written to.
+    out_path: PathBuf,
+}
+
+impl JsonRenderer {
+    /// Converts `item` and inserts it into the index, along with the items it contains (fields,
+    /// variants, trait and impl items). Returns the id of the item if it has a JSON form.
+    fn insert(&self, item: clean::Item) -> Option<types::Id> {
+        if item.is_stripped() {
+            return None;
+        }
+        match item.inner {
+            clean::StructItem(clean::Struct { ref fields, .. })
+            | clean::UnionItem(clean::Union { ref fields, .. })
+            | clean::VariantItem(clean::Variant {
+                kind: clean::VariantKind::Struct(clean::VariantStruct { ref fields, .. }),
+            }) => self.insert_all(fields),
+            clean::EnumItem(ref e) => self.insert_all(&e.variants.raw),
+            clean::TraitItem(ref t) => self.insert_all(&t.items),
+            clean::ImplItem(ref i) => self.insert_all(&i.items),
+            _ => {}
+        }
+
+        let new_item = item.into_json()?;
+        let id = new_item.id.clone();
+        self.index.borrow_mut().insert(id.clone(), new_item);
+        Some(id)
+    }
+
+    fn insert_all(&self, items: &[clean::Item]) {
+        for item in items {
+            self.insert(item.clone());
+        }
+    }
+
+    /// Inserts the impls for the type `did` (including blanket and synthesized auto trait impls)
+    /// into the index, and returns their ids.
+    fn get_impls(&self, did: DefId, cache: &Cache) -> Vec<types::Id> {
+        cache
+            .impls
+            .get(&did)
+            .map(|impls| impls.iter().filter_map(|i| self.insert(i.impl_item.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    /// Inserts the local impls of the trait `did` into the index, and returns their ids.
+    fn get_trait_implementors(&self, did: DefId, cache: &Cache) -> Vec<types::Id> {
+        cache
+            .implementors
+            .get(&did)
+            .map(|implementors| {
+                implementors
+                    .iter()
+                    .filter_map(|i| self.insert(i.impl_item.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Inserts the traits from other crates which are implemented or referenced locally.
+    fn get_trait_items(&self, cache: &Cache) -> Vec<(types::Id, types::Item)> {
+        cache
+            .traits
+            .iter()
+            .filter_map(|(&did, trait_item)| {
+                // only need to synthesize items for external traits
+                if did.is_local() {
+                    return None;
+                }
+                self.insert_all(&trait_item.items);
+                let (path, _) = cache.external_paths.get(&did)?;
+                let mut inner: types::Trait = trait_item.clone().into();
+                inner.implementors = self.get_trait_implementors(did, cache);
+                Some((
+                    did.into(),
+                    types::Item {
+                        id: did.into(),
+                        crate_id: did.krate.as_u32(),
+                        name: path.last().cloned(),
+                        source: None,
+                        visibility: types::Visibility::Public,
+                        docs: String::new(),
+                        links: FxHashMap::default(),
+                        attrs: Vec::new(),
+                        deprecation: None,
+                        inner: types::ItemEnum::Trait(inner),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
 
 impl FormatRenderer for JsonRenderer {
     fn init(
-        _krate: clean::Crate,
-        _options: RenderOptions,
+        krate: clean::Crate,
+        options: RenderOptions,
         _render_info: RenderInfo,
         _edition: Edition,
         _cache: &mut Cache,
     ) -> Result<(Self, clean::Crate), Error> {
-        unimplemented!()
+        debug!("Initializing json renderer");
+        Ok((
+            JsonRenderer {
+                index: Rc::new(RefCell::new(FxHashMap::default())),
+                out_path: options.output,
+            },
+            krate,
+        ))
     }
 
-    fn item(&mut self, _item: clean::Item, _cache: &Cache) -> Result<(), Error> {
-        unimplemented!()
+    /// Inserts an item into the index. This should be used rather than directly calling `insert`
+    /// on the index, because items which contain impls (structs, enums, unions and traits) have
+    /// those impls looked up in the cache here.
+    fn item(&mut self, item: clean::Item, cache: &Cache) -> Result<(), Error> {
+        let id = item.def_id;
+        let impls = match item.inner {
+            clean::StructItem(_) | clean::EnumItem(_) | clean::UnionItem(_) => {
+                Some(self.get_impls(id, cache))
+            }
+            _ => None,
+        };
+        let implementors = match item.inner {
+            clean::TraitItem(_) => Some(self.get_trait_implementors(id, cache)),
+            _ => None,
+        };
+
+        if let Some(json_id) = self.insert(item) {
+            let mut index = self.index.borrow_mut();
+            match index.get_mut(&json_id).map(|i| &mut i.inner) {
+                Some(types::ItemEnum::Struct(s)) => s.impls = impls.unwrap_or_default(),
+                Some(types::ItemEnum::Enum(e)) => e.impls = impls.unwrap_or_default(),
+                Some(types::ItemEnum::Union(u)) => u.impls = impls.unwrap_or_default(),
+                Some(types::ItemEnum::Trait(t)) => {
+                    t.implementors = implementors.unwrap_or_default()
+                }
+                _ => {}
+            }
+        }
+        Ok(())
     }
 
     fn mod_item_in(
         &mut self,
-        _item: &clean::Item,
-        _item_name: &str,
+        item: &clean::Item,
+        _module_name: &str,
         _cache: &Cache,
     ) -> Result<(), Error> {
-        unimplemented!()
+        // Only the module itself is inserted here; its children are passed to `item` (or here,
+        // for nested modules) by `run_format`.
+        self.insert(item.clone());
+        Ok(())
     }
 
     fn mod_item_out(&mut self, _item_name: &str) -> Result<(), Error> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn after_krate(&mut self, _krate: &clean::Crate, _cache: &Cache) -> Result<(), Error> {
-        unimplemented!()
+    fn after_krate(&mut self, krate: &clean::Crate, cache: &Cache) -> Result<(), Error> {
+        debug!("Done with crate");
+        // This has to happen before the index is taken, as it inserts the items of the external
+        // traits and their implementors.
+        let trait_items = self.get_trait_items(cache);
+        let mut index = self.index.borrow().clone();
+        index.extend(trait_items);
+
+        let output = types::Crate {
+            root: DefId { krate: LOCAL_CRATE, index: CRATE_DEF_INDEX }.into(),
+            crate_version: krate.version.clone(),
+            includes_private: cache.document_private,
+            index,
+            paths: cache
+                .paths
+                .clone()
+                .into_iter()
+                .chain(cache.external_paths.clone().into_iter())
+                .map(|(k, (path, kind))| {
+                    (
+                        k.into(),
+                        types::ItemSummary {
+                            crate_id: k.krate.as_u32(),
+                            path,
+                            kind: kind.into(),
+                        },
+                    )
+                })
+                .collect(),
+            external_crates: cache
+                .extern_locations
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.as_u32(),
+                        types::ExternalCrate {
+                            name: v.0.clone(),
+                            html_root_url: match &v.2 {
+                                ExternalLocation::Remote(s) => Some(s.clone()),
+                                _ => None,
+                            },
+                        },
+                    )
+                })
+                .collect(),
+            format_version: types::FORMAT_VERSION,
+        };
+
+        std::fs::create_dir_all(&self.out_path)
+            .map_err(|error| Error { error: error.to_string(), file: self.out_path.clone() })?;
+        let mut p = self.out_path.clone();
+        p.push(&krate.name);
+        p.set_extension("json");
+        let file = File::create(&p)
+            .map_err(|error| Error { error: error.to_string(), file: p.clone() })?;
+        serde_json::ser::to_writer(&file, &output)
+            .map_err(|error| Error { error: error.to_string(), file: p })?;
+        Ok(())
     }
 
     fn after_run(&mut self, _diag: &rustc_errors::Handler) -> Result<(), Error> {
-        unimplemented!()
+        Ok(())
     }
 }
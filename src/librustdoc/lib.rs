@@ -317,7 +317,7 @@ fn opts() -> Vec<RustcOptGroup> {
                 "",
                 "error-format",
                 "How errors and other messages are produced",
-                "human|json|short",
+                "human|json|sarif|short",
             )
         }),
         stable("json", |o| {
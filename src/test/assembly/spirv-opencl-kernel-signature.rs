@@ -0,0 +1,62 @@
+// Checks the signatures of OpenCL kernels in the emitted SPIR-V, which must match the
+// arguments `geobacter_spirv_kernel_args` describes to the host: aggregates are a single
+// `ByVal` pointer parameter, and scalar pairs (e.g. slices) are two parameters.
+
+// assembly-output: emit-asm
+// needs-llvm-components: spirv
+// compile-flags: --target spirv64-unknown-opencl --crate-type rlib
+
+#![feature(no_core, lang_items, abi_spir_kernel)]
+#![no_core]
+
+#[lang="sized"]
+trait Sized { }
+#[lang="freeze"]
+trait Freeze { }
+#[lang="copy"]
+trait Copy { }
+
+#[repr(C)]
+pub struct Params {
+    a: u32,
+    b: u64,
+    c: f32,
+}
+
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+// CHECK-DAG: OpEntryPoint Kernel [[SCALAR:%[0-9a-z_]+]] "scalar"
+// CHECK-DAG: OpEntryPoint Kernel [[AGGREGATE:%[0-9a-z_]+]] "aggregate"
+// CHECK-DAG: OpEntryPoint Kernel [[PAIR:%[0-9a-z_]+]] "pair"
+// CHECK-DAG: OpEntryPoint Kernel [[SLICE:%[0-9a-z_]+]] "slice"
+// CHECK-DAG: OpDecorate [[BYVAL:%[0-9a-z_]+]] FuncParamAttr ByVal
+
+// CHECK: [[SCALAR]] = OpFunction
+// CHECK-NEXT: OpFunctionParameter
+// CHECK-NEXT: OpFunctionParameter
+// CHECK-NEXT: OpLabel
+#[no_mangle]
+pub extern "spir-kernel" fn scalar(a: u32, out: &mut u8) { }
+
+// CHECK: [[AGGREGATE]] = OpFunction
+// CHECK-NEXT: [[BYVAL]] = OpFunctionParameter
+// CHECK-NEXT: OpLabel
+#[no_mangle]
+pub extern "spir-kernel" fn aggregate(p: Params) { }
+
+// CHECK: [[PAIR]] = OpFunction
+// CHECK-NEXT: OpFunctionParameter
+// CHECK-NEXT: OpFunctionParameter
+// CHECK-NEXT: OpLabel
+#[no_mangle]
+pub extern "spir-kernel" fn pair(p: Pair) { }
+
+// CHECK: [[SLICE]] = OpFunction
+// CHECK-NEXT: OpFunctionParameter
+// CHECK-NEXT: OpFunctionParameter
+// CHECK-NEXT: OpLabel
+#[no_mangle]
+pub extern "spir-kernel" fn slice(s: &[u32]) { }
@@ -0,0 +1,24 @@
+// Verify that functions inlined by the MIR inliner keep their own debuginfo scope,
+// with the location of the call site as `inlinedAt`.
+//
+// ignore-tidy-linelength
+// compile-flags: -C debuginfo=2 -C opt-level=0 -Z inline-mir
+
+#![crate_type = "lib"]
+
+#[inline]
+fn set_low_bit(x: u32) -> u32 {
+    let y = x | 1;
+    y
+}
+
+// CHECK-LABEL: @caller
+#[no_mangle]
+pub fn caller(x: u32) -> u32 {
+    // CHECK-NOT: call
+    // CHECK: ret i32
+    set_low_bit(x)
+}
+
+// CHECK-DAG: [[CALLEE:![0-9]+]] = distinct !DISubprogram(name: "set_low_bit"
+// CHECK-DAG: !DILocation(line: {{[0-9]+}}, column: {{[0-9]+}}, scope: [[CALLEE]], inlinedAt: {{![0-9]+}})
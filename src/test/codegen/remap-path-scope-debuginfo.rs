@@ -0,0 +1,13 @@
+// ignore-windows
+// ignore-tidy-linelength
+
+// compile-flags: -g -C no-prepopulate-passes --remap-path-prefix={{cwd}}=/the/cwd --remap-path-prefix={{src-base}}=/the/src -Z remap-path-scope=debuginfo
+
+#![crate_type = "lib"]
+
+// Here we check that the expansion of the file!() macro is not mapped.
+// CHECK-NOT: c"/the/src/remap-path-scope-debuginfo.rs"
+pub static FILE_PATH: &'static str = file!();
+
+// And here that debuginfo is.
+// CHECK: !DIFile(filename: "/the/src/remap-path-scope-debuginfo.rs", directory: "/the/cwd/"
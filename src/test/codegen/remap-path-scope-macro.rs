@@ -0,0 +1,14 @@
+// ignore-windows
+// ignore-tidy-linelength
+
+// compile-flags: -g -C no-prepopulate-passes --remap-path-prefix={{cwd}}=/the/cwd --remap-path-prefix={{src-base}}=/the/src -Z remap-path-scope=macro
+
+#![crate_type = "lib"]
+
+// Here we check that the expansion of the file!() macro is mapped.
+// CHECK: c"/the/src/remap-path-scope-macro.rs"
+pub static FILE_PATH: &'static str = file!();
+
+// And here that debuginfo is not.
+// CHECK-NOT: !DIFile(filename: "/the/src/
+// CHECK-NOT: directory: "/the/cwd/"
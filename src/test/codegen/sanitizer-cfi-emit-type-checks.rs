@@ -0,0 +1,39 @@
+// Verifies that pointer type membership tests for indirect calls are emitted when CFI is enabled.
+//
+// needs-sanitizer-cfi
+// compile-flags: -Clto -Cno-prepopulate-passes -Ctarget-feature=-crt-static -Zsanitizer=cfi
+
+#![crate_type="lib"]
+#![feature(no_sanitize)]
+
+// CHECK-LABEL: define{{.*}}checked{{.*}}!type !{{[0-9]+}}
+// CHECK:       start:
+// CHECK:         [[TT:%.+]] = call i1 @llvm.type.test(i8* {{%f|%[0-9]}}, metadata !"_ZTSFiiE")
+// CHECK-NEXT:    br i1 [[TT]], label %type_test.pass, label %type_test.fail
+// CHECK:       type_test.pass:
+// CHECK:         {{%.+}} = call i32 %f(i32 %arg)
+// CHECK:       type_test.fail:
+// CHECK-NEXT:    call void @llvm.trap()
+// CHECK-NEXT:    unreachable
+pub fn checked(f: fn(i32) -> i32, arg: i32) -> i32 {
+    f(arg)
+}
+
+// CHECK-LABEL: define{{.*}}unchecked
+// CHECK-NOT:     call i1 @llvm.type.test
+// CHECK:       }
+#[no_sanitize(cfi)]
+pub fn unchecked(f: fn(i32) -> i32, arg: i32) -> i32 {
+    f(arg)
+}
+
+pub trait Trait {
+    fn method(&self, arg: i32) -> i32;
+}
+
+// Virtual calls are checked against the type id with the receiver left out.
+// CHECK-LABEL: define{{.*}}checked_virtual
+// CHECK:         {{%.+}} = call i1 @llvm.type.test(i8* {{%.+}}, metadata !"_ZTSFiu4selfiE")
+pub fn checked_virtual(t: &dyn Trait, arg: i32) -> i32 {
+    t.method(arg)
+}
@@ -0,0 +1,31 @@
+// Verifies that type metadata is emitted for functions when CFI is enabled.
+//
+// needs-sanitizer-cfi
+// compile-flags: -Clto -Ctarget-feature=-crt-static -Zsanitizer=cfi
+
+#![crate_type="lib"]
+
+pub trait Trait {
+    fn method(&self, arg: i32) -> i32;
+}
+
+pub struct S;
+
+// CHECK-LABEL: define{{.*}}foo{{.*}}!type ![[TYPE1:[0-9]+]]
+// CHECK-NOT:   !type
+pub fn foo(f: fn(i32) -> i32, arg: i32) -> i32 {
+    f(arg)
+}
+
+// Trait methods may be called through a vtable, so they're also tagged with the type id
+// which virtual calls are checked against.
+// CHECK-LABEL: define{{.*}}method{{.*}}!type ![[TYPE2:[0-9]+]] !type ![[TYPE3:[0-9]+]]
+impl Trait for S {
+    fn method(&self, arg: i32) -> i32 {
+        arg
+    }
+}
+
+// CHECK: ![[TYPE1]] = !{i64 0, !"_ZTSFiPFiiEiE"}
+// CHECK: ![[TYPE2]] = !{i64 0, !"_ZTSFiRKu{{[0-9]+}}{{[_A-Za-z0-9]+}}iE"}
+// CHECK: ![[TYPE3]] = !{i64 0, !"_ZTSFiu4selfiE"}
@@ -0,0 +1,29 @@
+// Verifies that HWAddressSanitizer instrumentation is applied, and that it can be disabled
+// selectively with the no_sanitize attribute.
+//
+// needs-sanitizer-hwaddress
+// compile-flags: -Zsanitizer=hwaddress -Ctarget-feature=-crt-static
+
+#![crate_type="lib"]
+#![feature(no_sanitize)]
+
+// CHECK-LABEL: ; sanitizer_hwaddress::unsanitized
+// CHECK-NEXT:  ; Function Attrs:
+// CHECK-NOT:   sanitize_hwaddress
+// CHECK:       start:
+// CHECK-NOT:   call void @{{.*}}hwasan
+// CHECK:       }
+#[no_sanitize(hwaddress)]
+pub fn unsanitized(b: &mut u8) -> u8 {
+    *b
+}
+
+// CHECK-LABEL: ; sanitizer_hwaddress::sanitized
+// CHECK-NEXT:  ; Function Attrs:
+// CHECK:       sanitize_hwaddress
+// CHECK:       start:
+// CHECK:       call void @{{.*}}hwasan
+// CHECK:       }
+pub fn sanitized(b: &mut u8) -> u8 {
+    *b
+}
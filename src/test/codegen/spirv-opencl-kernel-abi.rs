@@ -0,0 +1,44 @@
+// Checks that OpenCL kernels use the `spir_kernel` calling convention and pass
+// aggregates `byval`, while scalar pairs (e.g. slices) are split into two arguments.
+
+// needs-llvm-components: spirv
+// compile-flags: --target spirv64-unknown-opencl -C no-prepopulate-passes
+
+#![crate_type = "lib"]
+#![feature(no_core, lang_items, abi_spir_kernel)]
+#![no_core]
+
+#[lang="sized"]
+trait Sized { }
+#[lang="freeze"]
+trait Freeze { }
+#[lang="copy"]
+trait Copy { }
+
+#[repr(C)]
+pub struct Params {
+    a: u32,
+    b: u64,
+    c: f32,
+}
+
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+// CHECK: define spir_kernel void @scalar(i32 %a, {{.*}}%out)
+#[no_mangle]
+pub extern "spir-kernel" fn scalar(a: u32, out: &mut u8) { }
+
+// CHECK: define spir_kernel void @aggregate({{.*}}byval{{.*}} %p)
+#[no_mangle]
+pub extern "spir-kernel" fn aggregate(p: Params) { }
+
+// CHECK: define spir_kernel void @slice({{.*}}%s.0, i64 %s.1)
+#[no_mangle]
+pub extern "spir-kernel" fn slice(s: &[u32]) { }
+
+// CHECK: define spir_kernel void @pair(i32 %p.0, i32 %p.1)
+#[no_mangle]
+pub extern "spir-kernel" fn pair(p: Pair) { }
@@ -0,0 +1,66 @@
+// Checks that `geobacter_spirv_kernel_args` describes pointers that the kernel's argument
+// types don't name directly, ie `Box`es and pointers inside scalar pairs, as `__global`
+// pointers rather than as bytes to copy.
+
+// compile-flags: -O
+
+#![crate_type = "lib"]
+#![feature(geobacter, geobacter_intrinsics)]
+
+use std::geobacter::intrinsics::geobacter_spirv_kernel_args;
+use std::geobacter::spirv::opencl::CompilerKernelArg;
+
+pub struct WithPtr<'a> {
+    p: &'a mut u32,
+    n: u32,
+}
+
+fn kernel(_boxed: Box<u64>, _pair: WithPtr<'_>, _n: u32) {}
+
+fn args_of<F>(_: &F) -> &'static [CompilerKernelArg] {
+    unsafe { geobacter_spirv_kernel_args::<F>() }
+}
+
+// `ByValue` is 0, `GlobalPtr` 1.
+
+// CHECK-LABEL: @arg_count
+// CHECK: ret i64 4
+#[no_mangle]
+pub fn arg_count() -> usize {
+    args_of(&kernel).len()
+}
+
+// CHECK-LABEL: @boxed_kind
+// CHECK: ret i32 1
+#[no_mangle]
+pub fn boxed_kind() -> u32 {
+    args_of(&kernel)[0].0
+}
+
+// CHECK-LABEL: @boxed_pointee_size
+// CHECK: ret i64 8
+#[no_mangle]
+pub fn boxed_pointee_size() -> u64 {
+    args_of(&kernel)[0].1
+}
+
+// CHECK-LABEL: @pair_ptr_kind
+// CHECK: ret i32 1
+#[no_mangle]
+pub fn pair_ptr_kind() -> u32 {
+    args_of(&kernel)[1].0
+}
+
+// CHECK-LABEL: @pair_int_kind
+// CHECK: ret i32 0
+#[no_mangle]
+pub fn pair_int_kind() -> u32 {
+    args_of(&kernel)[2].0
+}
+
+// CHECK-LABEL: @scalar_kind
+// CHECK: ret i32 0
+#[no_mangle]
+pub fn scalar_kind() -> u32 {
+    args_of(&kernel)[3].0
+}
@@ -0,0 +1,12 @@
+// Verify that with `-Z split-dwarf=single` the compile unit points at the object
+// file itself, which keeps the split DWARF sections.
+//
+// only-linux
+// compile-flags: -Z unstable-options -C debuginfo=2 -C split-debuginfo=unpacked
+// compile-flags: -Z split-dwarf=single
+
+// CHECK: !DICompileUnit({{.*}}splitDebugFilename: "{{.*}}.rcgu.o"
+
+#![crate_type = "lib"]
+
+pub fn foo() {}
@@ -0,0 +1,15 @@
+// Verify that `-C split-debuginfo` points each compile unit at the DWARF object
+// of its codegen unit, and that `-Z split-dwarf-inlining` is passed through.
+//
+// only-linux
+// revisions: INLINING NO-INLINING
+// compile-flags: -Z unstable-options -C debuginfo=2 -C split-debuginfo=unpacked
+//[NO-INLINING] compile-flags: -Z split-dwarf-inlining=no
+
+// INLINING: !DICompileUnit({{.*}}splitDebugFilename: "{{.*}}.rcgu.dwo"
+// INLINING-NOT: splitDebugInlining: false
+// NO-INLINING: !DICompileUnit({{.*}}splitDebugFilename: "{{.*}}.rcgu.dwo"{{.*}}splitDebugInlining: false
+
+#![crate_type = "lib"]
+
+pub fn foo() {}
@@ -0,0 +1,17 @@
+// Checks that a callee which is still generic over the caller's type parameters is inlined,
+// while a call through a trait method of a type parameter, which can't be resolved to a
+// single function yet, is left alone.
+
+// EMIT_MIR inline_generic.outer.Inline.diff
+pub fn outer<T: Default>() -> T {
+    inner::<T>()
+}
+
+#[inline]
+fn inner<T: Default>() -> T {
+    T::default()
+}
+
+fn main() {
+    let _: u32 = outer();
+}
@@ -0,0 +1,57 @@
+// compile-flags: -Z mir-opt-level=4
+// Checks that a switch on a value set in a predecessor is bypassed.
+
+// EMIT_MIR jump_threading.known_after_branch.JumpThreading.diff
+fn known_after_branch(x: bool) -> u32 {
+    let state = if x { 1 } else { 2 };
+    match state {
+        1 => 10,
+        _ => 20,
+    }
+}
+
+// EMIT_MIR jump_threading.fresh_enum.JumpThreading.diff
+fn fresh_enum(x: bool) -> u32 {
+    let e = if x { Some(1) } else { None };
+    match e {
+        Some(_) => 10,
+        None => 20,
+    }
+}
+
+// EMIT_MIR jump_threading.try_option.JumpThreading.diff
+fn try_option(x: Option<u32>) -> Option<u32> {
+    let y = x?;
+    Some(y)
+}
+
+// `Result::into_result` returns its argument unchanged, so nothing is known about the
+// discriminant when it is switched on.
+// EMIT_MIR jump_threading.try_result.JumpThreading.diff
+fn try_result(x: Result<u32, i32>) -> Result<u32, i32> {
+    let y = x?;
+    Ok(y)
+}
+
+// `e` is set right before the switch, but may have been changed through `p` since.
+// EMIT_MIR jump_threading.clobbered_through_borrow.JumpThreading.diff
+fn clobbered_through_borrow(x: bool) -> u32 {
+    let mut e = None;
+    let p = &mut e as *mut Option<u32>;
+    if x {
+        e = Some(1);
+        unsafe { *p = None };
+    }
+    match e {
+        Some(_) => 10,
+        None => 20,
+    }
+}
+
+fn main() {
+    known_after_branch(true);
+    fresh_enum(true);
+    try_option(Some(1));
+    try_result(Ok(1));
+    clobbered_through_borrow(true);
+}
@@ -0,0 +1,62 @@
+// compile-flags: -Z mir-opt-level=4
+// Checks that locals only accessed through their fields are split into one local per field.
+
+#![feature(raw_ref_op)]
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+struct Line {
+    start: Point,
+    end: Point,
+}
+
+enum Wrapper {
+    Wrap(u32, u32),
+}
+
+// EMIT_MIR sroa.structs.ScalarReplacementOfAggregates.diff
+fn structs(x: u32, y: u32) -> u32 {
+    let p = Point { x, y };
+    p.x ^ p.y
+}
+
+// `l` is split into two `Point`s, which are then split in turn.
+// EMIT_MIR sroa.nested.ScalarReplacementOfAggregates.diff
+fn nested(x: u32, y: u32) -> u32 {
+    let l = Line { start: Point { x, y }, end: Point { x: y, y: x } };
+    l.start.x ^ l.end.y
+}
+
+// EMIT_MIR sroa.single_variant_enum.ScalarReplacementOfAggregates.diff
+fn single_variant_enum(x: u32, y: u32) -> u32 {
+    let w = Wrapper::Wrap(x, y);
+    let Wrapper::Wrap(a, b) = w;
+    a ^ b
+}
+
+// Borrowing a field keeps the whole aggregate in place.
+// EMIT_MIR sroa.escaping.ScalarReplacementOfAggregates.diff
+fn escaping(x: u32, y: u32) -> u32 {
+    let p = Point { x, y };
+    let r = &p.x;
+    *r ^ p.y
+}
+
+// So does taking its address, which may outlive the function.
+// EMIT_MIR sroa.address_escapes.ScalarReplacementOfAggregates.diff
+fn address_escapes(x: u32, y: u32, out: &mut *const u32) -> u32 {
+    let p = Point { x, y };
+    *out = &raw const p.x;
+    p.y
+}
+
+fn main() {
+    structs(1, 2);
+    nested(1, 2);
+    single_variant_enum(1, 2);
+    escaping(1, 2);
+    address_escapes(1, 2, &mut std::ptr::null());
+}
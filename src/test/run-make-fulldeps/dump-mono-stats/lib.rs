@@ -0,0 +1,9 @@
+pub fn generic<T: Copy>(x: T) -> (T, T) {
+    (x, x)
+}
+
+pub fn not_generic() -> u64 {
+    let (a, _) = generic(1u32);
+    let (b, _) = generic(2u64);
+    a as u64 + b
+}
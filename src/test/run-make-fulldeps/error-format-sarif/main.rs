@@ -0,0 +1,6 @@
+fn main() {
+    let unused = 1;
+    let s = String::new();
+    drop(s);
+    drop(s);
+}
@@ -0,0 +1,42 @@
+#![allow(unused_assignments)]
+
+fn classify(value: u32) -> &'static str {
+    if value > 10 {
+        "large"
+    } else {
+        "small"
+    }
+}
+
+fn both(a: bool, b: bool) -> bool {
+    a && b
+}
+
+fn describe(value: Option<u32>) -> u32 {
+    match value {
+        Some(0) => 0,
+        Some(n) => n,
+        None => 1,
+    }
+}
+
+fn either(a: bool, b: bool) -> bool {
+    a || b
+}
+
+fn main() {
+    let mut total = 0;
+    for value in &[1, 5, 20] {
+        total += classify(*value).len();
+    }
+    if both(total > 0, false) {
+        total = 0;
+    }
+    for value in &[Some(0), Some(4), None] {
+        total += describe(*value) as usize;
+    }
+    if !either(total == 0, true) {
+        total = 0;
+    }
+    assert!(total > 0);
+}
@@ -0,0 +1,17 @@
+#![allow(dead_code)]
+
+#[repr(C)]
+pub struct Padded {
+    a: u8,
+    b: u32,
+}
+
+pub enum Tagged {
+    Small(u8),
+    Large(Padded),
+}
+
+fn main() {
+    let _padded = Padded { a: 0, b: 1 };
+    let _tagged = Tagged::Small(2);
+}
@@ -0,0 +1,8 @@
+fn name_of<T>(_: &T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+fn main() {
+    let closure = || {};
+    println!("{}", name_of(&closure));
+}
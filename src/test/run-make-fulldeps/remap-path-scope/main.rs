@@ -0,0 +1,4 @@
+fn main() {
+    let unused = 0;
+    println!("{}", file!());
+}
@@ -0,0 +1,33 @@
+#![feature(test)]
+
+use std::hint::black_box;
+
+fn add_one(x: i32) -> i32 {
+    x + 1
+}
+
+fn add_one_unsigned(x: u32) -> u32 {
+    x + 1
+}
+
+fn add_two(x: i32, y: i32) -> i32 {
+    x + y + 2
+}
+
+fn main() {
+    let kind = std::env::args().nth(1).unwrap();
+    // Except for `valid`, these are pointers to functions of a different type, which CFI must
+    // reject before the call.
+    let (name, f): (&str, fn(i32) -> i32) = match &*kind {
+        "valid" => ("add_one", add_one),
+        "invalid-arity" => {
+            ("add_two", unsafe { std::mem::transmute(add_two as fn(i32, i32) -> i32) })
+        }
+        "invalid-type" => {
+            ("add_one_unsigned", unsafe { std::mem::transmute(add_one_unsigned as fn(u32) -> u32) })
+        }
+        _ => unreachable!(),
+    };
+    let f = black_box(f);
+    println!("{}(41) = {}", name, f(41));
+}
@@ -0,0 +1,246 @@
+#!/usr/bin/env python
+
+# This script validates the output of `rustdoc --output-format json` against the schema in
+# `src/librustdoc/json/types.rs`: every item reachable from the root has the fields and kinds
+# described there, and every ID it references resolves to an item in `index` or `paths`. It does
+# not check that the output describes the crate correctly; that's what the `.expected` files are
+# for.
+
+import sys
+import json
+
+# The version of the format this checker understands; keep in sync with `FORMAT_VERSION`.
+FORMAT_VERSION = 1
+
+crate = json.load(open(sys.argv[1]))
+
+
+def fail(msg):
+    print("error: " + msg)
+    sys.exit(1)
+
+
+def expect_keys(obj, keys, what):
+    if not isinstance(obj, dict):
+        fail("expected {} to be an object, found {!r}".format(what, obj))
+    missing = [k for k in keys if k not in obj]
+    if missing:
+        fail("{} is missing {}".format(what, ", ".join(missing)))
+
+
+expect_keys(crate, ["root", "crate_version", "includes_private", "index", "paths",
+                    "external_crates", "format_version"], "crate")
+if crate["format_version"] != FORMAT_VERSION:
+    fail("unexpected format_version {}, this checker understands {}".format(
+        crate["format_version"], FORMAT_VERSION))
+if crate["root"] not in crate["index"]:
+    fail("root {} is not in the index".format(crate["root"]))
+
+for crate_id, ext in crate["external_crates"].items():
+    expect_keys(ext, ["name", "html_root_url"], "external crate " + crate_id)
+
+for id, summary in crate["paths"].items():
+    expect_keys(summary, ["crate_id", "path", "kind"], "path summary " + id)
+
+
+# Everything reachable from the root has to be in `index`. This includes items from other crates,
+# such as the blanket impls of their traits and the items of those impls.
+def get_item(item_id):
+    if item_id in crate["index"]:
+        return crate["index"][item_id]
+    print("Missing ID:", item_id)
+    sys.exit(1)
+
+
+# local IDs have to be in `index`, external ones can sometimes be in `index` but otherwise have
+# to be in `paths`
+def valid_id(item_id):
+    return item_id in crate["index"] or item_id[0] != "0" and item_id in crate["paths"]
+
+
+def check_generics(generics):
+    for param in generics["params"]:
+        check_generic_param(param)
+    for where_predicate in generics["where_predicates"]:
+        if "bound_predicate" in where_predicate:
+            pred = where_predicate["bound_predicate"]
+            check_type(pred["ty"])
+            for bound in pred["bounds"]:
+                check_generic_bound(bound)
+        elif "region_predicate" in where_predicate:
+            pred = where_predicate["region_predicate"]
+            for bound in pred["bounds"]:
+                check_generic_bound(bound)
+        elif "eq_predicate" in where_predicate:
+            pred = where_predicate["eq_predicate"]
+            check_type(pred["rhs"])
+            check_type(pred["lhs"])
+        else:
+            fail("unknown where predicate {!r}".format(where_predicate))
+
+
+def check_generic_param(param):
+    expect_keys(param, ["name", "kind"], "generic param")
+    if "type" in param["kind"]:
+        ty = param["kind"]["type"]
+        if ty["default"]:
+            check_type(ty["default"])
+        for bound in ty["bounds"]:
+            check_generic_bound(bound)
+    elif "const" in param["kind"]:
+        check_type(param["kind"]["const"])
+    elif param["kind"] != "lifetime":
+        fail("unknown generic param kind {!r}".format(param["kind"]))
+
+
+def check_generic_bound(bound):
+    if "trait_bound" in bound:
+        for param in bound["trait_bound"]["generic_params"]:
+            check_generic_param(param)
+        check_type(bound["trait_bound"]["trait"])
+    elif "outlives" not in bound:
+        fail("unknown generic bound {!r}".format(bound))
+
+
+def check_decl(decl):
+    for (_name, ty) in decl["inputs"]:
+        check_type(ty)
+    if decl["output"]:
+        check_type(decl["output"])
+
+
+def check_type(ty):
+    expect_keys(ty, ["kind", "inner"], "type")
+    kind = ty["kind"]
+    inner = ty["inner"]
+    if kind == "resolved_path":
+        if inner["args"]:
+            args = inner["args"]
+            if "angle_bracketed" in args:
+                for arg in args["angle_bracketed"]["args"]:
+                    if "type" in arg:
+                        check_type(arg["type"])
+                    elif "const" in arg:
+                        check_type(arg["const"]["type"])
+                for binding in args["angle_bracketed"]["bindings"]:
+                    if "equality" in binding["binding"]:
+                        check_type(binding["binding"]["equality"])
+                    elif "constraint" in binding["binding"]:
+                        for bound in binding["binding"]["constraint"]:
+                            check_generic_bound(bound)
+            elif "parenthesized" in args:
+                for input_ty in args["parenthesized"]["inputs"]:
+                    check_type(input_ty)
+                if args["parenthesized"]["output"]:
+                    check_type(args["parenthesized"]["output"])
+        if not valid_id(inner["id"]):
+            print("Type contained an invalid ID:", inner["id"])
+            sys.exit(1)
+    elif kind in ("tuple", "impl_trait"):
+        for elem in inner:
+            if kind == "tuple":
+                check_type(elem)
+            else:
+                check_generic_bound(elem)
+    elif kind == "slice":
+        check_type(inner)
+    elif kind in ("array", "raw_pointer", "borrowed_ref"):
+        check_type(inner["type"])
+    elif kind == "function_pointer":
+        for param in inner["generic_params"]:
+            check_generic_param(param)
+        check_decl(inner["decl"])
+    elif kind == "qualified_path":
+        check_type(inner["self_type"])
+        check_type(inner["trait"])
+    elif kind not in ("generic", "primitive", "never", "infer"):
+        fail("unknown type kind {!r}".format(kind))
+
+
+ITEM_KINDS = [
+    "module", "extern_crate", "import", "struct", "struct_field", "union", "enum", "variant",
+    "function", "trait", "trait_alias", "method", "impl", "typedef", "opaque_ty", "constant",
+    "static", "foreign_type", "macro", "proc_macro", "assoc_const", "assoc_type",
+]
+
+work_list = set([crate["root"]])
+visited = work_list.copy()
+
+while work_list:
+    current = work_list.pop()
+    visited.add(current)
+    item = get_item(current)
+    expect_keys(item, ["id", "crate_id", "name", "source", "visibility", "docs", "links",
+                       "attrs", "deprecation", "kind"], "item " + current)
+    if item["kind"] not in ITEM_KINDS:
+        fail("item {} has unknown kind {!r}".format(current, item["kind"]))
+    # check intradoc links
+    for (_name, link) in item["links"].items():
+        if not valid_id(link):
+            print("Intra-doc link contains invalid ID:", link)
+            sys.exit(1)
+
+    # check all fields that reference types such as generics as well as nested items
+    # (modules, structs, traits, and enums)
+    if item["kind"] == "module":
+        work_list |= set(item["inner"]["items"]) - visited
+    elif item["kind"] in ("struct", "union"):
+        check_generics(item["inner"]["generics"])
+        work_list |= (
+            set(item["inner"]["fields"]) | set(item["inner"]["impls"])
+        ) - visited
+    elif item["kind"] == "struct_field":
+        check_type(item["inner"])
+    elif item["kind"] == "enum":
+        check_generics(item["inner"]["generics"])
+        work_list |= (
+            set(item["inner"]["variants"]) | set(item["inner"]["impls"])
+        ) - visited
+    elif item["kind"] == "variant":
+        if item["inner"]["variant_kind"] == "tuple":
+            for ty in item["inner"]["variant_inner"]:
+                check_type(ty)
+        elif item["inner"]["variant_kind"] == "struct":
+            work_list |= set(item["inner"]["variant_inner"]) - visited
+    elif item["kind"] in ("function", "method"):
+        check_generics(item["inner"]["generics"])
+        check_decl(item["inner"]["decl"])
+    elif item["kind"] in ("static", "constant", "assoc_const"):
+        check_type(item["inner"]["type"])
+    elif item["kind"] == "typedef":
+        check_type(item["inner"]["type"])
+        check_generics(item["inner"]["generics"])
+    elif item["kind"] == "opaque_ty":
+        check_generics(item["inner"]["generics"])
+        for bound in item["inner"]["bounds"]:
+            check_generic_bound(bound)
+    elif item["kind"] == "trait_alias":
+        check_generics(item["inner"]["generics"])
+        for bound in item["inner"]["params"]:
+            check_generic_bound(bound)
+    elif item["kind"] == "trait":
+        check_generics(item["inner"]["generics"])
+        for bound in item["inner"]["bounds"]:
+            check_generic_bound(bound)
+        work_list |= (
+            set(item["inner"]["items"]) | set(item["inner"]["implementors"])
+        ) - visited
+    elif item["kind"] == "impl":
+        check_generics(item["inner"]["generics"])
+        if item["inner"]["trait"]:
+            check_type(item["inner"]["trait"])
+        if item["inner"]["blanket_impl"]:
+            check_type(item["inner"]["blanket_impl"])
+        check_type(item["inner"]["for"])
+        work_list |= set(item["inner"]["items"]) - visited
+    elif item["kind"] == "assoc_type":
+        for bound in item["inner"]["bounds"]:
+            check_generic_bound(bound)
+        if item["inner"]["default"]:
+            check_type(item["inner"]["default"])
+    elif item["kind"] == "import":
+        if item["inner"]["id"]:
+            inner_id = item["inner"]["id"]
+            assert valid_id(inner_id)
+            if inner_id in crate["index"] and inner_id not in visited:
+                work_list.add(inner_id)
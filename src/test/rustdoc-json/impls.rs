@@ -0,0 +1,47 @@
+// Blanket impls, synthesized auto trait impls and intra-doc links must all resolve to items in
+// the output.
+
+use std::fmt;
+
+/// A thing which can be [`Frob::frob`]bed. See also [`Wrapper`].
+pub trait Frob {
+    fn frob(&self) -> u32;
+
+    fn frob_twice(&self) -> u32 {
+        self.frob() * 2
+    }
+}
+
+impl<T: fmt::Debug> Frob for T {
+    fn frob(&self) -> u32 {
+        0
+    }
+}
+
+/// Not `Sync`, because of the raw pointer.
+pub struct Wrapper<T> {
+    pub inner: T,
+    pub ptr: *const u8,
+}
+
+impl<T> Wrapper<T> {
+    pub const SIZE: usize = std::mem::size_of::<T>();
+
+    pub fn new(inner: T) -> Self {
+        Wrapper { inner, ptr: std::ptr::null() }
+    }
+}
+
+pub enum Either<L, R> {
+    Left(L),
+    Right { value: R },
+}
+
+impl<L: Clone, R: Clone> Clone for Either<L, R> {
+    fn clone(&self) -> Self {
+        match self {
+            Either::Left(l) => Either::Left(l.clone()),
+            Either::Right { value } => Either::Right { value: value.clone() },
+        }
+    }
+}
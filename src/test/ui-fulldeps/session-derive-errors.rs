@@ -258,3 +258,33 @@ struct ErrorWithLifetime<'a> {
     span: Span,
     name: &'a str,
 }
+
+#[derive(SessionDiagnostic)]
+#[error = "E0123"]
+#[slug = "typeck-field-multiply-specified-in-initializer"]
+struct Translatable {
+    #[message]
+    #[label]
+    span: Span,
+    #[label = "previous-use-label"]
+    prev_span: Span,
+    ident: Ident,
+}
+
+#[derive(SessionDiagnostic)]
+#[error = "E0123"]
+struct LabelWithoutSlug {
+    #[message = "Something something"]
+    #[label]
+    //~^ ERROR `#[label]` can only be used with `#[slug = ...]`
+    span: Span,
+}
+
+#[derive(SessionDiagnostic)]
+#[error = "E0123"]
+#[slug = "typeck-field-multiply-specified-in-initializer"]
+struct MessageTextWithSlug {
+    #[message = "Something something"]
+    //~^ ERROR `#[message = ...]` can't be used with `#[slug = ...]`, use `#[message]`
+    span: Span,
+}
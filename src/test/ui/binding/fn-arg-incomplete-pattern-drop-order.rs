@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // Check that partially moved from function parameters are dropped after the
 // named bindings that move from them.
 
@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 
 #![allow(unused_variables)]
 #![allow(unused_imports)]
@@ -0,0 +1,19 @@
+#![feature(capture_disjoint_fields)]
+//~^ WARNING: the feature `capture_disjoint_fields` is incomplete
+#![feature(rustc_attrs)]
+#![feature(stmt_expr_attributes)]
+
+// Elements of an array can't be told apart at compile time, so the whole array is captured.
+
+fn main() {
+    let arr = [String::new(), String::new()];
+
+    let c = #[rustc_capture_analysis]
+    || {
+        let _s = &arr[0];
+        //~^ ERROR: Capturing arr[Index] -> ImmBorrow
+        //~| ERROR: Min Capture arr[] -> ImmBorrow
+    };
+
+    c();
+}
@@ -0,0 +1,16 @@
+// Without `capture_disjoint_fields`, closures capture whole variables, so borrowing a
+// field which the closure doesn't use still conflicts with the closure.
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut p = Point { x: 10, y: 20 };
+
+    let mut c = || p.x += 1;
+    let py = &mut p.y; //~ ERROR cannot borrow `p.y` as mutable more than once at a time
+    c();
+    *py += 1;
+}
@@ -0,0 +1,39 @@
+// run-pass
+
+#![feature(capture_disjoint_fields)]
+//~^ WARNING: the feature `capture_disjoint_fields` is incomplete
+
+// Closures only capture the fields they use, so the other fields can still be borrowed
+// or moved while the closure is alive.
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut p = Point { x: 10, y: 20 };
+    let mut c = || p.x += 1;
+    let py = &mut p.y;
+    c();
+    *py += 1;
+    assert_eq!(p.x, 11);
+    assert_eq!(p.y, 21);
+
+    let mut t = (String::from("a"), String::from("b"));
+    let c = move || t.0.len();
+    t.1.push('c');
+    assert_eq!(c(), 1);
+    assert_eq!(t.1, "bc");
+
+    // The outer closure only captures the field the inner one uses.
+    let mut q = Point { x: 1, y: 2 };
+    let mut outer = || {
+        let mut inner = || q.x *= 2;
+        inner();
+    };
+    let qy = &q.y;
+    outer();
+    assert_eq!(*qy, 2);
+    assert_eq!(q.x, 2);
+}
@@ -0,0 +1,25 @@
+#![feature(capture_disjoint_fields)]
+//~^ WARNING: the feature `capture_disjoint_fields` is incomplete
+#![feature(rustc_attrs)]
+#![feature(stmt_expr_attributes)]
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut p = Point { x: 10, y: 20 };
+
+    let mut c = #[rustc_capture_analysis]
+    || {
+        let _x = p.x;
+        //~^ ERROR: Capturing p[(0, 0)] -> ImmBorrow
+        //~| ERROR: Min Capture p[(0, 0)] -> ImmBorrow
+        p.y = 30;
+        //~^ ERROR: Capturing p[(1, 0)] -> MutBorrow
+        //~| ERROR: Min Capture p[(1, 0)] -> MutBorrow
+    };
+
+    c();
+}
@@ -0,0 +1,51 @@
+#![deny(disjoint_capture_drop_reorder)]
+//~^ NOTE: the lint level is defined here
+
+struct Foo(i32);
+
+impl Drop for Foo {
+    fn drop(&mut self) {
+        println!("{} dropped", self.0);
+    }
+}
+
+fn partially_captured() {
+    let t = (Foo(0), Foo(1));
+    let c = || { let _t = t.0; };
+    //~^ ERROR: drop order affected for closure because of `capture_disjoint_fields`
+    //~| NOTE: add a dummy let to cause `t` to be fully captured
+    c();
+}
+
+fn completely_captured() {
+    let t = (Foo(0), Foo(1));
+    let c = || { let _t = t; };
+    c();
+}
+
+fn no_drop() {
+    let t = (0, 1);
+    let c = move || { let _t = t.0; };
+    c();
+}
+
+// Only the part that isn't captured matters: here it doesn't need to be dropped.
+fn rest_needs_no_drop() {
+    let t = (Foo(0), 1);
+    let c = || { let _t = t.0; };
+    c();
+}
+
+fn nested_rest_needs_no_drop() {
+    let t = ((Foo(0), 1), 2);
+    let c = || { let _t = t.0.0; };
+    c();
+}
+
+fn main() {
+    partially_captured();
+    completely_captured();
+    no_drop();
+    rest_needs_no_drop();
+    nested_rest_needs_no_drop();
+}
@@ -0,0 +1,120 @@
+// Smoke test of the Cranelift backend: run with `./x.py test src/test/ui/codegen-backend
+// --codegen-backend cranelift`.
+
+// run-pass
+// only-cranelift
+// only-x86_64
+// only-linux
+
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fmt::Write;
+use std::os::raw::{c_char, c_int};
+
+extern "C" {
+    fn snprintf(buf: *mut c_char, len: usize, fmt: *const c_char, ...) -> c_int;
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+struct Rect {
+    w: f64,
+    h: f64,
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Rect {
+    fn area(&self) -> f64 {
+        self.w * self.h
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Num(i64),
+    Op(char),
+}
+
+fn tokenize(s: &str) -> Vec<Token> {
+    s.split_whitespace()
+        .map(|t| match t.parse() {
+            Ok(n) => Token::Num(n),
+            Err(_) => Token::Op(t.chars().next().unwrap()),
+        })
+        .collect()
+}
+
+fn apply<F: Fn(u32) -> u32>(f: F, x: u32) -> u32 {
+    f(x)
+}
+
+static GREETING: &str = "hello";
+
+fn main() {
+    // Integer arithmetic, including 128-bit integers and overflow checks.
+    let x: u128 = u64::MAX as u128 * 3;
+    assert_eq!(x, 55340232221128654845);
+    assert_eq!((-7i128 / 2, -7i128 % 2), (-3, -1));
+    assert_eq!(200u8.checked_add(100), None);
+    assert_eq!(i32::MIN.wrapping_abs(), i32::MIN);
+    assert_eq!(0x1234_5678u32.rotate_left(8).swap_bytes(), 0x1278_5634);
+
+    // Floats.
+    assert_eq!((2.5f64).mul_add(2.0, 1.0), 6.0);
+    assert_eq!(-1.5f32 as i32, -1);
+    assert_eq!(300.0f64 as u8, 255);
+
+    // Trait objects, closures and generics.
+    let shapes: Vec<Box<dyn Shape>> =
+        vec![Box::new(Square(2.0)), Box::new(Rect { w: 1.5, h: 2.0 })];
+    assert_eq!(shapes.iter().map(|s| s.area()).sum::<f64>(), 7.0);
+    let offset = 10;
+    assert_eq!(apply(move |x| x + offset, 5), 15);
+
+    // Enums, matches and allocation.
+    assert_eq!(tokenize("1 + 22"), vec![Token::Num(1), Token::Op('+'), Token::Num(22)]);
+    let mut counts = HashMap::new();
+    for word in "a b a c a".split(' ') {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    assert_eq!(counts["a"], 3);
+
+    // Formatting and statics.
+    let mut s = String::new();
+    write!(s, "{} {:?} {:.2}", GREETING, Some(1), 1.5).unwrap();
+    assert_eq!(s, "hello Some(1) 1.50");
+
+    // Threads and thread locals.
+    thread_local!(static COUNTER: std::cell::Cell<u32> = std::cell::Cell::new(0));
+    let handle = std::thread::spawn(|| {
+        COUNTER.with(|c| c.set(c.get() + 1));
+        COUNTER.with(|c| c.get())
+    });
+    assert_eq!(handle.join().unwrap(), 1);
+    assert_eq!(COUNTER.with(|c| c.get()), 0);
+
+    // Variadic calls, with floating point arguments that are passed in vector registers.
+    let mut buf = [0 as c_char; 64];
+    let len = unsafe {
+        snprintf(
+            buf.as_mut_ptr(),
+            buf.len(),
+            "%d %.1f %s %.2f\0".as_ptr() as *const c_char,
+            42 as c_int,
+            2.5f64,
+            "x\0".as_ptr() as *const c_char,
+            -0.25f64,
+        )
+    };
+    let out = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    assert_eq!(out.to_str().unwrap(), "42 2.5 x -0.25");
+    assert_eq!(len, 14);
+}
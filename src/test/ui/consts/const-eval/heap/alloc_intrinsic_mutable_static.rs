@@ -0,0 +1,10 @@
+#![feature(const_heap)]
+#![feature(const_raw_ptr_deref)]
+#![feature(const_mut_refs)]
+#![feature(core_intrinsics)]
+use std::intrinsics;
+
+static mut FOO: &mut i32 = unsafe { &mut *(intrinsics::const_allocate(4, 4) as *mut i32) };
+//~^ error: mutable heap allocations are not allowed in statics
+
+fn main() {}
@@ -0,0 +1,18 @@
+// run-pass
+#![feature(const_heap)]
+#![feature(const_raw_ptr_deref)]
+#![feature(const_mut_refs)]
+#![feature(core_intrinsics)]
+use std::intrinsics;
+
+const FOO: i32 = foo();
+const fn foo() -> i32 {
+    unsafe {
+        let ptr = intrinsics::const_allocate(4, 4) as *mut i32;
+        *ptr = 20;
+        *ptr
+    }
+}
+fn main() {
+    assert_eq!(FOO, 20);
+}
@@ -4,6 +4,7 @@
 // * Dropping one of the values panics while dropping the future.
 
 // run-pass
+// ignore-cranelift no unwinding
 // edition:2018
 // ignore-wasm32-bare compiled with panic=abort by default
 
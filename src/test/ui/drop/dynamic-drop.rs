@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-wasm32-bare compiled with panic=abort by default
 
 #![feature(generators, generator_trait, untagged_unions)]
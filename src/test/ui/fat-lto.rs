@@ -1,5 +1,6 @@
 // run-pass
 // compile-flags: -Clto=fat
+// ignore-cranelift no LTO
 // no-prefer-dynamic
 
 fn main() {
@@ -0,0 +1,10 @@
+trait Foo: Bar {}
+trait Bar {}
+
+impl Foo for () {}
+impl Bar for () {}
+
+fn main() {
+    let foo: &dyn Foo = &();
+    let _: &dyn Bar = foo; //~ ERROR trait upcasting coercion is experimental
+}
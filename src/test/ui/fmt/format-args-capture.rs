@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-wasm32
 // ignore-wasm64
 #![feature(format_args_capture)]
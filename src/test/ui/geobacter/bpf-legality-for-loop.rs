@@ -0,0 +1,97 @@
+// Checks that the eBPF device legality mode accepts `for` loops over ranges of
+// integers.
+
+// build-pass
+// compile-flags: --target bpfel-unknown-none --crate-type lib -C overflow-checks=off
+// needs-llvm-components: bpf
+
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang="sized"]
+trait Sized { }
+#[lang="copy"]
+trait Copy { }
+#[lang="eq"]
+trait PartialEq<Rhs = Self> {
+    fn eq(&self, other: &Rhs) -> bool;
+}
+#[lang="partial_ord"]
+trait PartialOrd<Rhs = Self>: PartialEq<Rhs> {
+    fn lt(&self, other: &Rhs) -> bool;
+}
+#[lang="add"]
+trait Add<Rhs = Self> {
+    type Output;
+    fn add(self, rhs: Rhs) -> Self::Output;
+}
+#[lang="add_assign"]
+trait AddAssign<Rhs = Self> {
+    fn add_assign(&mut self, rhs: Rhs);
+}
+
+impl Copy for u32 { }
+impl PartialEq for u32 {
+    fn eq(&self, other: &u32) -> bool { *self == *other }
+}
+impl PartialOrd for u32 {
+    fn lt(&self, other: &u32) -> bool { *self < *other }
+}
+impl Add for u32 {
+    type Output = u32;
+    fn add(self, rhs: u32) -> u32 { self + rhs }
+}
+impl AddAssign for u32 {
+    fn add_assign(&mut self, rhs: u32) { *self += rhs }
+}
+
+enum Option<T> {
+    #[lang="None"]
+    None,
+    #[lang="Some"]
+    Some(T),
+}
+use Option::*;
+
+#[lang="Range"]
+struct Range<Idx> {
+    start: Idx,
+    end: Idx,
+}
+
+trait Iterator {
+    type Item;
+    #[lang="next"]
+    fn next(&mut self) -> Option<Self::Item>;
+}
+trait IntoIterator {
+    type Item;
+    type IntoIter: Iterator<Item = Self::Item>;
+    #[lang="into_iter"]
+    fn into_iter(self) -> Self::IntoIter;
+}
+impl<I: Iterator> IntoIterator for I {
+    type Item = I::Item;
+    type IntoIter = I;
+    fn into_iter(self) -> I { self }
+}
+
+impl Iterator for Range<u32> {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        if self.start < self.end {
+            let n = self.start;
+            self.start = n + 1;
+            Some(n)
+        } else {
+            None
+        }
+    }
+}
+
+#[no_mangle]
+pub fn sum(n: &mut u32) {
+    for i in 0..16 {
+        *n += i;
+    }
+}
@@ -0,0 +1,100 @@
+// Checks that the eBPF device legality mode rejects calls to foreign functions,
+// loops which aren't bounded by a counter, and calls to functions which can panic
+// but aren't `#[inline(always)]`, but not loops which are bounded or inlined
+// functions.
+
+// build-fail
+// compile-flags: --target bpfel-unknown-none --crate-type lib
+// needs-llvm-components: bpf
+
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang="sized"]
+trait Sized { }
+#[lang="copy"]
+trait Copy { }
+#[lang="eq"]
+trait PartialEq<Rhs = Self> {
+    fn eq(&self, other: &Rhs) -> bool;
+}
+#[lang="partial_ord"]
+trait PartialOrd<Rhs = Self>: PartialEq<Rhs> {
+    fn lt(&self, other: &Rhs) -> bool;
+}
+#[lang="add_assign"]
+trait AddAssign<Rhs = Self> {
+    fn add_assign(&mut self, rhs: Rhs);
+}
+#[lang="div"]
+trait Div<Rhs = Self> {
+    type Output;
+    fn div(self, rhs: Rhs) -> Self::Output;
+}
+
+impl Copy for bool { }
+impl Copy for u32 { }
+impl PartialEq for u32 {
+    fn eq(&self, other: &u32) -> bool { *self == *other }
+}
+impl PartialOrd for u32 {
+    fn lt(&self, other: &u32) -> bool { *self < *other }
+}
+impl AddAssign for u32 {
+    fn add_assign(&mut self, rhs: u32) { *self += rhs }
+}
+impl Div for u32 {
+    type Output = u32;
+    fn div(self, rhs: u32) -> u32 { self / rhs }
+}
+
+extern "C" {
+    fn printk();
+}
+
+#[no_mangle]
+pub unsafe fn call_extern() {
+    printk(); //~ ERROR call to foreign function `printk` is not supported in eBPF programs
+}
+
+#[no_mangle]
+pub fn spin() {
+    loop { } //~ ERROR unbounded loop in eBPF program
+}
+
+#[no_mangle]
+pub fn spin_with_exit(exit: &bool) {
+    loop { //~ ERROR unbounded loop in eBPF program
+        if *exit {
+            break;
+        }
+    }
+}
+
+#[no_mangle]
+pub fn counted(n: &mut u32) {
+    let mut i = 0u32;
+    while i < 16 {
+        *n += i;
+        i += 1;
+    }
+}
+
+fn div(n: u32, d: u32) -> u32 {
+    n / d
+}
+
+#[no_mangle]
+pub fn divide(n: &mut u32, d: u32) {
+    *n = div(*n, d); //~ ERROR `div` can end the eBPF program, but is not `#[inline(always)]`
+}
+
+#[inline(always)]
+fn inlined_div(n: u32, d: u32) -> u32 {
+    n / d
+}
+
+#[no_mangle]
+pub fn divide_inlined(n: &mut u32, d: u32) {
+    *n = inlined_div(*n, d);
+}
@@ -0,0 +1,28 @@
+// run-pass
+
+#![allow(incomplete_features)]
+#![feature(inline_const)]
+
+use std::mem::size_of;
+
+trait Size {
+    const SIZE: usize;
+}
+
+impl Size for u16 {
+    const SIZE: usize = 2;
+}
+
+fn foo<T>() -> usize {
+    const { size_of::<T>() }
+}
+
+fn bar<T: Size>() -> usize {
+    const { T::SIZE * 2 }
+}
+
+fn main() {
+    assert_eq!(foo::<u8>(), 1);
+    assert_eq!(foo::<u64>(), 8);
+    assert_eq!(bar::<u16>(), 4);
+}
@@ -0,0 +1,10 @@
+// build-fail
+
+#![allow(incomplete_features)]
+#![feature(inline_const)]
+
+fn main() {
+    let _x = const { 255u8 + 1 };
+    //~^ ERROR evaluation of constant value failed
+    //~| ERROR erroneous constant used
+}
@@ -0,0 +1,17 @@
+// run-pass
+
+#![allow(incomplete_features)]
+#![feature(inline_const)]
+
+fn foo() -> &'static i32 {
+    &const { 3 + 4 }
+}
+
+fn bar<T>() -> &'static usize {
+    &const { std::mem::size_of::<T>() }
+}
+
+fn main() {
+    assert_eq!(*foo(), 7);
+    assert_eq!(*bar::<u32>(), 4);
+}
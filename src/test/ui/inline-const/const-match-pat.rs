@@ -0,0 +1,23 @@
+// run-pass
+
+#![allow(incomplete_features)]
+#![feature(inline_const)]
+
+const MMIO_BIT1: u8 = 4;
+const MMIO_BIT2: u8 = 5;
+
+fn foo(x: u8) -> &'static str {
+    match x {
+        const { 1 << MMIO_BIT1 } => "bit 1",
+        const { 1 << MMIO_BIT2 } => "bit 2",
+        const { 1 << 6 }..=const { u8::MAX } => "high",
+        _ => "other",
+    }
+}
+
+fn main() {
+    assert_eq!(foo(1 << 4), "bit 1");
+    assert_eq!(foo(1 << 5), "bit 2");
+    assert_eq!(foo(255), "high");
+    assert_eq!(foo(0), "other");
+}
@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-wasm32-bare compiled with panic=abort by default
 
 // This test checks panic emitted from `mem::{uninitialized,zeroed}`.
@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-wasm32-bare compiled with panic=abort by default
 
 // Check that values are not leaked when a dtor panics (#14875)
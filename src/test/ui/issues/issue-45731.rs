@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 #![allow(unused_variables)]
 // compile-flags:--test -g
 // ignore-asmjs wasm2js does not support source maps yet
@@ -4,6 +4,7 @@
 // entering the catch_unwind.
 //
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-wasm       no panic support
 // ignore-emscripten no panic support
 
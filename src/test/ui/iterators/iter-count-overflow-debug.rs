@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // only-32bit too impatient for 2⁶⁴ items
 // ignore-wasm32-bare compiled with panic=abort by default
 // compile-flags: -C debug_assertions=yes -C opt-level=3
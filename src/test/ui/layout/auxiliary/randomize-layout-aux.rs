@@ -0,0 +1,17 @@
+// compile-flags: -Z randomize-layout
+
+pub struct Shuffled {
+    pub a: u8,
+    pub b: u32,
+    pub c: u16,
+    pub d: u64,
+}
+
+pub enum Variants {
+    A(u8, u32, u16),
+    B { x: u16, y: u64, z: u8 },
+}
+
+pub fn make() -> (Shuffled, Variants) {
+    (Shuffled { a: 1, b: 2, c: 3, d: 4 }, Variants::B { x: 5, y: 6, z: 7 })
+}
@@ -0,0 +1,66 @@
+// run-pass
+// aux-build:randomize-layout-aux.rs
+// compile-flags: -Z randomize-layout -Z layout-seed=42
+
+// Check that a crate using a type with a randomized layout agrees with the crate defining it,
+// that the fields of `repr(Rust)` types are actually moved, and that `repr(C)` types keep their
+// declared field order.
+
+extern crate randomize_layout_aux;
+
+use randomize_layout_aux::{make, Shuffled, Variants};
+
+// Without `-Z randomize-layout`, each of these would be laid out as `b`, `c`, `a`. One in six
+// orders of the fields is that one, so with this many structs, at least one of them is all but
+// certain to be laid out differently. The seeds only depend on the paths of the structs and on
+// `-Z layout-seed`, so this passes or fails the same way every time.
+macro_rules! structs {
+    ($($name:ident)*) => {
+        $(
+            struct $name {
+                a: u8,
+                b: u32,
+                c: u16,
+            }
+        )*
+
+        fn any_fields_moved() -> bool {
+            false $(|| {
+                let s = $name { a: 0, b: 0, c: 0 };
+                let base = &s as *const $name as usize;
+                let offsets = (
+                    &s.a as *const u8 as usize - base,
+                    &s.b as *const u32 as usize - base,
+                    &s.c as *const u16 as usize - base,
+                );
+                offsets != (6, 0, 4)
+            })*
+        }
+    };
+}
+
+structs!(S0 S1 S2 S3 S4 S5 S6 S7);
+
+#[repr(C)]
+struct Ordered {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+fn main() {
+    let (Shuffled { a, b, c, d }, variants) = make();
+    assert_eq!((a, b, c, d), (1, 2, 3, 4));
+    match variants {
+        Variants::B { x, y, z } => assert_eq!((x, y, z), (5, 6, 7)),
+        Variants::A(..) => unreachable!(),
+    }
+
+    assert!(any_fields_moved());
+
+    let ordered = Ordered { a: 0, b: 0, c: 0 };
+    let base = &ordered as *const Ordered as usize;
+    assert_eq!(&ordered.a as *const u8 as usize - base, 0);
+    assert_eq!(&ordered.b as *const u32 as usize - base, 4);
+    assert_eq!(&ordered.c as *const u16 as usize - base, 8);
+}
@@ -0,0 +1,7 @@
+#![feature(let_else)]
+
+fn main() {
+    let Some(x) = Some(1) else { }; //~ ERROR mismatched types
+    let Some(y) = Some(2) else { Some(3) }; //~ ERROR mismatched types
+    let _ = (x, y);
+}
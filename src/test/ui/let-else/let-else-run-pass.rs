@@ -0,0 +1,50 @@
+// run-pass
+
+#![feature(let_else)]
+
+fn first_even(v: &[u32]) -> Option<u32> {
+    for &x in v {
+        let 0 = x % 2 else { continue };
+        return Some(x);
+    }
+    None
+}
+
+fn sum_pair(v: &[u32]) -> u32 {
+    let &[a, b] = v else { return 0 };
+    a + b
+}
+
+fn annotated(x: Option<u8>) -> u8 {
+    let Some(y): Option<u8> = x else { return 0 };
+    y + 1
+}
+
+fn main() {
+    assert_eq!(first_even(&[1, 3, 4, 5]), Some(4));
+    assert_eq!(first_even(&[1, 3]), None);
+
+    assert_eq!(sum_pair(&[1, 2]), 3);
+    assert_eq!(sum_pair(&[1, 2, 3]), 0);
+
+    assert_eq!(annotated(Some(1)), 2);
+    assert_eq!(annotated(None), 0);
+
+    let mut n = 0;
+    loop {
+        let true = n < 3 else { break };
+        n += 1;
+    }
+    assert_eq!(n, 3);
+
+    // Block-like initializers have to be parenthesized.
+    let Some(m) = (if n == 3 { Some(n) } else { None }) else { panic!() };
+    assert_eq!(m, 3);
+
+    // Bindings of the pattern can be used by the rest of the block, including its tail.
+    let s = {
+        let Ok(s) = "hello".parse::<String>() else { unreachable!() };
+        s
+    };
+    assert_eq!(s, "hello");
+}
@@ -0,0 +1,58 @@
+// run-pass
+// Temporaries of the initializer of a `let...else` are dropped at the end of the statement,
+// not kept alive for the rest of the block.
+
+#![feature(let_else)]
+
+use std::cell::{Cell, RefCell};
+use std::sync::Mutex;
+
+struct Guard<'a>(&'a Cell<u32>);
+
+impl Guard<'_> {
+    fn get(&self) -> Option<u32> {
+        Some(self.0.get())
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn drops(counter: &Cell<u32>) -> u32 {
+    let Some(x) = Guard(counter).get() else { return 0 };
+    // The `Guard` has already been dropped.
+    assert_eq!(counter.get(), x + 1);
+    x
+}
+
+fn main() {
+    let counter = Cell::new(0);
+    assert_eq!(drops(&counter), 0);
+    assert_eq!(counter.get(), 1);
+
+    // The `Ref` returned by `borrow` must not outlive the statement.
+    let cell = RefCell::new(Some(1));
+    let Some(x) = *cell.borrow() else { panic!() };
+    *cell.borrow_mut() = None;
+    assert_eq!(x, 1);
+    assert_eq!(*cell.borrow(), None);
+
+    // Nor may the `MutexGuard`.
+    let mutex = Mutex::new(Some(String::from("hello")));
+    let Some(s) = mutex.lock().unwrap().clone() else { panic!() };
+    *mutex.lock().unwrap() = None;
+    assert_eq!(s, "hello");
+
+    // A reference to a temporary lives as long as the bindings borrowing from it.
+    let Some(ref r) = Some(String::from("world")) else { panic!() };
+    assert_eq!(r, "world");
+
+    // Bindings can still borrow from a local that is matched on directly.
+    let mut v = Some(vec![1, 2]);
+    let Some(ref mut inner) = v else { panic!() };
+    inner.push(3);
+    assert_eq!(v, Some(vec![1, 2, 3]));
+}
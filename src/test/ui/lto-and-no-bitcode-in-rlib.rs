@@ -1,3 +1,4 @@
 // compile-flags: -C lto -C embed-bitcode=no
+// ignore-cranelift no LTO
 
 fn main() {}
@@ -3,6 +3,7 @@
 // aux-build:lto-duplicate-symbols2.rs
 // error-pattern:Linking globals named 'foo': symbol multiply defined!
 // compile-flags: -C lto
+// ignore-cranelift no LTO
 // no-prefer-dynamic
 
 extern crate lto_duplicate_symbols1;
@@ -1,5 +1,6 @@
 // run-pass
 // compile-flags: -C lto -C codegen-units=8
+// ignore-cranelift no LTO
 // no-prefer-dynamic
 
 fn main() {
@@ -1,5 +1,6 @@
 // run-pass
 // compile-flags: -C lto
+// ignore-cranelift no LTO
 // no-prefer-dynamic
 // ignore-emscripten no threads support
 
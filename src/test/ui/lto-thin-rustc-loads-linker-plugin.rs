@@ -1,4 +1,5 @@
 // compile-flags: -C lto=thin
+// ignore-cranelift no LTO
 // aux-build:lto-rustc-loads-linker-plugin.rs
 // run-pass
 // no-prefer-dynamic
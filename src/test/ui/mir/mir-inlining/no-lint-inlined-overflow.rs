@@ -0,0 +1,13 @@
+// build-pass
+// compile-flags: -O -Z inline-mir
+// Code inlined by the MIR inliner must not be linted again in its caller, where const
+// propagation knows the arguments it is called with.
+
+#[inline]
+fn add_one(x: u8) -> u8 {
+    x + 1
+}
+
+fn main() {
+    add_one(255);
+}
@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // compile-flags: -C debug_assertions=yes
 // ignore-wasm32-bare compiled with panic=abort by default
 // ignore-emscripten dies with an LLVM error
@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-emscripten no subprocess support
 
 #![feature(set_stdio)]
@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-emscripten no threads support
 
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -0,0 +1,27 @@
+// compile-flags: -Z print-type-sizes -Z randomize-layout
+// build-pass
+// ignore-pass
+// ^-- needed because `--pass check` does not emit the output needed.
+// normalize-stdout-test "field shuffle seed: \d+" -> "field shuffle seed: $$SEED"
+
+// Check that the seed a type's fields were shuffled with is printed along with its size, and
+// only for types whose layout is randomized. The seed depends on the path of the type, so it
+// is normalized away; each type has a single field, so the shuffle doesn't change the output.
+
+#![feature(start)]
+
+struct Shuffled {
+    x: u32,
+}
+
+#[repr(C)]
+struct Ordered {
+    x: u32,
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _shuffled = Shuffled { x: 0 };
+    let _ordered = Ordered { x: 0 };
+    0
+}
@@ -1,4 +1,5 @@
 // run-pass
+// ignore-cranelift no unwinding
 // aux-build:expand-with-a-macro.rs
 
 // ignore-wasm32-bare compiled with panic=abort by default
@@ -1,4 +1,5 @@
 // needs-sanitizer-support
+// ignore-cranelift no sanitizers
 // needs-sanitizer-address
 //
 // compile-flags: -Z sanitizer=address -O -g
@@ -2,6 +2,7 @@
 // the `#[cfg(sanitize = "option")]` attribute is configured.
 
 // needs-sanitizer-support
+// ignore-cranelift no sanitizers
 // needs-sanitizer-address
 // needs-sanitizer-leak
 // needs-sanitizer-memory
@@ -0,0 +1,7 @@
+// compile-flags: -Z sanitizer=cfi --target x86_64-unknown-linux-gnu
+// ignore-cranelift no sanitizers
+// error-pattern: error: `-Zsanitizer=cfi` requires `-Clto`
+
+#![feature(no_core)]
+#![no_core]
+#![no_main]
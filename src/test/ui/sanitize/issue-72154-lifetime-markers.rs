@@ -4,6 +4,7 @@
 // miscompilation which was subsequently detected by AddressSanitizer as UB.
 //
 // needs-sanitizer-support
+// ignore-cranelift no sanitizers
 // needs-sanitizer-address
 //
 // compile-flags: -Copt-level=0 -Zsanitizer=address
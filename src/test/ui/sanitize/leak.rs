@@ -1,4 +1,5 @@
 // needs-sanitizer-support
+// ignore-cranelift no sanitizers
 // needs-sanitizer-leak
 //
 // compile-flags: -Z sanitizer=leak -O
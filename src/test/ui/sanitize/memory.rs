@@ -1,4 +1,5 @@
 // needs-sanitizer-support
+// ignore-cranelift no sanitizers
 // needs-sanitizer-memory
 //
 // compile-flags: -Z sanitizer=memory -Zsanitizer-memory-track-origins -O
@@ -4,6 +4,7 @@
 //
 // min-llvm-version: 9.0
 // needs-sanitizer-support
+// ignore-cranelift no sanitizers
 // needs-sanitizer-address
 //
 // no-prefer-dynamic
@@ -11,6 +11,7 @@
 // would occasionally fail, making test flaky.
 //
 // needs-sanitizer-support
+// ignore-cranelift no sanitizers
 // needs-sanitizer-thread
 //
 // compile-flags: -Z sanitizer=thread -O
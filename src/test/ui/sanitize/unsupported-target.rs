@@ -1,5 +1,6 @@
 // compile-flags: -Z sanitizer=leak --target i686-unknown-linux-gnu
-// error-pattern: error: `-Zsanitizer=leak` only works with targets:
+// ignore-cranelift no sanitizers
+// error-pattern: error: `-Zsanitizer=leak` is not supported for target `i686-unknown-linux-gnu`
 
 #![feature(no_core)]
 #![no_core]
@@ -0,0 +1,25 @@
+// compile-flags: -Z stack-protector=all -Z print-stack-protectors -C codegen-units=1
+// build-pass
+// ignore-pass
+// ^-- needed because `--pass check` does not emit the output needed.
+// only-x86_64
+
+#![feature(start)]
+
+#[inline(never)]
+fn fill(buf: &mut [u8; 16]) {
+    // Plain indexing on an array doesn't instantiate any generic code in this crate, which
+    // would show up in the report as well.
+    let mut i = 0;
+    while i < 16 {
+        buf[i] = i as u8;
+        i += 1;
+    }
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let mut buf = [0u8; 16];
+    fill(&mut buf);
+    buf[15] as isize
+}
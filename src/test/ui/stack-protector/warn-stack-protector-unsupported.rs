@@ -0,0 +1,7 @@
+// check-pass
+// compile-flags: -Z stack-protector=all --target nvptx64-nvidia-cuda
+// needs-llvm-components: nvptx
+
+#![feature(no_core)]
+#![no_core]
+#![no_main]
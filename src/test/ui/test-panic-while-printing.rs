@@ -1,5 +1,6 @@
 // compile-flags:--test
 // run-pass
+// ignore-cranelift no unwinding
 // ignore-emscripten no subprocess support
 
 use std::fmt;
@@ -1,6 +1,7 @@
 // run-pass
 
 // compile-flags: -Clto=thin
+// ignore-cranelift no LTO
 // no-prefer-dynamic
 
 fn main() {
@@ -1,6 +1,7 @@
 // run-pass
 
 // compile-flags: -C codegen-units=8 -O -C lto=thin
+// ignore-cranelift no LTO
 // aux-build:thin-lto-inlines-aux.rs
 // no-prefer-dynamic
 // ignore-emscripten can't inspect instructions on emscripten
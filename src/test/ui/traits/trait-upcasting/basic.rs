@@ -0,0 +1,106 @@
+// run-pass
+
+#![feature(trait_upcasting)]
+#![allow(incomplete_features)]
+
+use std::fmt::Debug;
+use std::rc::Rc;
+
+trait Foo: Debug + Send + Sync {
+    fn a(&self) -> i32 {
+        10
+    }
+
+    fn z(&self) -> i32 {
+        11
+    }
+
+    fn y(&self) -> i32 {
+        12
+    }
+}
+
+trait Bar: Foo {
+    fn b(&self) -> i32 {
+        20
+    }
+
+    fn w(&self) -> i32 {
+        21
+    }
+}
+
+trait Baz: Bar {
+    fn c(&self) -> i32 {
+        30
+    }
+}
+
+impl Foo for i32 {
+    fn a(&self) -> i32 {
+        100
+    }
+}
+
+impl Bar for i32 {
+    fn b(&self) -> i32 {
+        200
+    }
+}
+
+impl Baz for i32 {
+    fn c(&self) -> i32 {
+        300
+    }
+}
+
+fn main() {
+    let baz: &dyn Baz = &1;
+    let _: &dyn Debug = baz;
+    assert_eq!(format!("{:?}", baz), "1");
+    assert_eq!(baz.a(), 100);
+    assert_eq!(baz.b(), 200);
+    assert_eq!(baz.c(), 300);
+    assert_eq!(baz.w(), 21);
+    assert_eq!(baz.y(), 12);
+
+    let bar: &dyn Bar = baz;
+    let _: &dyn Debug = bar;
+    assert_eq!(format!("{:?}", bar), "1");
+    assert_eq!(bar.a(), 100);
+    assert_eq!(bar.b(), 200);
+    assert_eq!(bar.w(), 21);
+    assert_eq!(bar.z(), 11);
+
+    let foo: &dyn Foo = baz;
+    let _: &dyn Debug = foo;
+    assert_eq!(format!("{:?}", foo), "1");
+    assert_eq!(foo.a(), 100);
+    assert_eq!(foo.z(), 11);
+
+    let foo: &dyn Foo = bar;
+    let _: &dyn Debug = foo;
+    assert_eq!(format!("{:?}", foo), "1");
+    assert_eq!(foo.a(), 100);
+    assert_eq!(foo.y(), 12);
+
+    // Auto traits can be dropped at the same time.
+    let baz: &(dyn Baz + Send) = &2;
+    let bar: &dyn Bar = baz;
+    assert_eq!(bar.b(), 200);
+
+    // Smart pointers and raw pointers can be upcast too.
+    let baz: Box<dyn Baz> = Box::new(3);
+    let foo: Box<dyn Foo> = baz;
+    assert_eq!(format!("{:?}", foo), "3");
+    assert_eq!(foo.a(), 100);
+
+    let baz: Rc<dyn Baz> = Rc::new(4);
+    let bar: Rc<dyn Bar> = baz;
+    assert_eq!(format!("{:?}", bar), "4");
+    assert_eq!(bar.b(), 200);
+
+    let baz: *const dyn Baz = &5;
+    let foo = baz as *const dyn Foo;
+    assert_eq!(unsafe { (*foo).a() }, 100);
+}
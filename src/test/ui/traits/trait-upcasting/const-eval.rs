@@ -0,0 +1,51 @@
+// run-pass
+
+#![feature(trait_upcasting)]
+#![allow(incomplete_features)]
+
+trait A {
+    fn a(&self) -> i32;
+}
+
+trait B: A {
+    fn b(&self) -> i32;
+}
+
+trait C: A {
+    fn c(&self) -> i32;
+}
+
+trait D: B + C {}
+
+impl A for i32 {
+    fn a(&self) -> i32 {
+        *self
+    }
+}
+
+impl B for i32 {
+    fn b(&self) -> i32 {
+        *self * 2
+    }
+}
+
+impl C for i32 {
+    fn c(&self) -> i32 {
+        *self * 3
+    }
+}
+
+impl D for i32 {}
+
+const D_REF: &dyn D = &7;
+const B_REF: &dyn B = D_REF;
+const C_REF: &dyn C = D_REF;
+const A_FROM_B: &dyn A = B_REF;
+const A_FROM_C: &dyn A = C_REF;
+
+fn main() {
+    assert_eq!(B_REF.b(), 14);
+    assert_eq!(C_REF.c(), 21);
+    assert_eq!(A_FROM_B.a(), 7);
+    assert_eq!(A_FROM_C.a(), 7);
+}
@@ -0,0 +1,104 @@
+// run-pass
+
+#![feature(trait_upcasting)]
+#![allow(incomplete_features)]
+
+trait A {
+    fn a(&self) -> i32 {
+        10
+    }
+}
+
+trait B: A {
+    fn b(&self) -> i32 {
+        20
+    }
+}
+
+trait C: A {
+    fn c(&self) -> i32 {
+        30
+    }
+}
+
+trait D: B + C {
+    fn d(&self) -> i32 {
+        40
+    }
+}
+
+impl A for i32 {
+    fn a(&self) -> i32 {
+        100
+    }
+}
+
+impl B for i32 {
+    fn b(&self) -> i32 {
+        200
+    }
+}
+
+impl C for i32 {
+    fn c(&self) -> i32 {
+        300
+    }
+}
+
+impl D for i32 {
+    fn d(&self) -> i32 {
+        400
+    }
+}
+
+trait Get<T> {
+    fn get(&self) -> T;
+}
+
+trait GetBoth: Get<u8> + Get<u16> {}
+
+impl Get<u8> for i32 {
+    fn get(&self) -> u8 {
+        8
+    }
+}
+
+impl Get<u16> for i32 {
+    fn get(&self) -> u16 {
+        16
+    }
+}
+
+impl GetBoth for i32 {}
+
+fn main() {
+    let d: &dyn D = &1;
+    assert_eq!(d.a(), 100);
+    assert_eq!(d.b(), 200);
+    assert_eq!(d.c(), 300);
+    assert_eq!(d.d(), 400);
+
+    let b: &dyn B = d;
+    assert_eq!(b.a(), 100);
+    assert_eq!(b.b(), 200);
+
+    let c: &dyn C = d;
+    assert_eq!(c.a(), 100);
+    assert_eq!(c.c(), 300);
+
+    let a: &dyn A = d;
+    assert_eq!(a.a(), 100);
+
+    let a: &dyn A = b;
+    assert_eq!(a.a(), 100);
+
+    let a: &dyn A = c;
+    assert_eq!(a.a(), 100);
+
+    // The same trait can be a supertrait with different generic arguments.
+    let both: &dyn GetBoth = &2;
+    let get_u8: &dyn Get<u8> = both;
+    assert_eq!(get_u8.get(), 8);
+    let get_u16: &dyn Get<u16> = both;
+    assert_eq!(get_u16.get(), 16);
+}
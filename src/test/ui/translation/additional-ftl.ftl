@@ -0,0 +1,4 @@
+# Overrides the value and one attribute of a message of the built-in catalogs.
+typeck-field-multiply-specified-in-initializer =
+    champ `{$ident}` spécifié plus d'une fois
+    .label = utilisé plus d'une fois
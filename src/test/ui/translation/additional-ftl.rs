@@ -0,0 +1,18 @@
+// Check that `-Z translate-additional-ftl` translates diagnostics with the messages of the
+// given catalog, and falls back to the built-in catalogs for the messages it lacks.
+
+// compile-flags: -Z translate-additional-ftl={{src-base}}/translation/additional-ftl.ftl
+
+struct Foo {
+    x: i32,
+}
+
+fn main() {
+    let _ = Foo {
+        x: 0,
+        x: 0,
+        //~^ ERROR champ `x` spécifié plus d'une fois
+    };
+}
+
+const FOO: u32 = return 0; //~ ERROR return statement outside of function body
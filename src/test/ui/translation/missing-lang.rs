@@ -0,0 +1,7 @@
+// Check that a language without catalogs in the sysroot is an error.
+
+// compile-flags: -Z translate-lang=xx-missing
+// normalize-stderr-test "`.*share.locale.xx-missing`" -> "`$$SYSROOT/share/locale/xx-missing`"
+// error-pattern: failed to load message catalogs
+
+fn main() {}
@@ -154,6 +154,8 @@ impl<'a, 'tcx> Delegate<'tcx> for EscapeDelegate<'a, 'tcx> {
             }
         }
     }
+
+    fn fake_read(&mut self, _: &PlaceWithHirId<'tcx>) {}
 }
 
 impl<'a, 'tcx> EscapeDelegate<'a, 'tcx> {
@@ -1714,6 +1714,8 @@ impl<'tcx> Delegate<'tcx> for MutatePairDelegate<'_, 'tcx> {
             }
         }
     }
+
+    fn fake_read(&mut self, _: &PlaceWithHirId<'tcx>) {}
 }
 
 impl MutatePairDelegate<'_, '_> {
@@ -514,6 +514,11 @@ impl<'tcx> Visitor<'tcx> for PrintVisitor {
                 self.current = value_pat;
                 self.visit_expr(value);
             },
+            ExprKind::ConstBlock(_) => {
+                let value_pat = self.next("value");
+                println!("ConstBlock(ref {}) = {};", value_pat, current);
+                println!("// unimplemented: inline const body check");
+            },
             ExprKind::Err => {
                 println!("Err = {}", current);
             },
@@ -730,6 +735,7 @@ fn desugaring_name(des: hir::MatchSource) -> String {
             contains_else_clause
         ),
         hir::MatchSource::AwaitDesugar => "MatchSource::AwaitDesugar".to_string(),
+        hir::MatchSource::LetElseDesugar => "MatchSource::LetElseDesugar".to_string(),
     }
 }
 
@@ -54,6 +54,7 @@ fn identify_some_pure_patterns(expr: &Expr<'_>) -> bool {
         ) => stmts.is_empty() && identify_some_pure_patterns(expr),
         ExprKind::Box(..)
         | ExprKind::Array(..)
+        | ExprKind::ConstBlock(..)
         | ExprKind::Call(..)
         | ExprKind::MethodCall(..)
         | ExprKind::Binary(..)
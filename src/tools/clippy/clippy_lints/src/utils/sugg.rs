@@ -103,6 +103,7 @@ impl<'a> Sugg<'a> {
             hir::ExprKind::Continue(..)
             | hir::ExprKind::Yield(..)
             | hir::ExprKind::Array(..)
+            | hir::ExprKind::ConstBlock(..)
             | hir::ExprKind::Block(..)
             | hir::ExprKind::Break(..)
             | hir::ExprKind::Call(..)
@@ -170,6 +171,7 @@ impl<'a> Sugg<'a> {
             | ast::ExprKind::TryBlock(..)
             | ast::ExprKind::Tup(..)
             | ast::ExprKind::Array(..)
+            | ast::ExprKind::ConstBlock(..)
             | ast::ExprKind::While(..)
             | ast::ExprKind::Await(..)
             | ast::ExprKind::Err => Sugg::NonParen(snippet),
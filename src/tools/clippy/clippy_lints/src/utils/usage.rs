@@ -79,6 +79,8 @@ impl<'tcx> Delegate<'tcx> for MutVarsDelegate {
     fn mutate(&mut self, cmt: &PlaceWithHirId<'tcx>) {
         self.update(&cmt)
     }
+
+    fn fake_read(&mut self, _: &PlaceWithHirId<'tcx>) {}
 }
 
 pub struct UsedVisitor {
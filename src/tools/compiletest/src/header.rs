@@ -45,6 +45,8 @@ impl EarlyProps {
         let rustc_has_profiler_support = env::var_os("RUSTC_PROFILER_SUPPORT").is_some();
         let rustc_has_sanitizer_support = env::var_os("RUSTC_SANITIZER_SUPPORT").is_some();
         let has_asan = util::ASAN_SUPPORTED_TARGETS.contains(&&*config.target);
+        let has_cfi = util::CFI_SUPPORTED_TARGETS.contains(&&*config.target);
+        let has_hwasan = util::HWASAN_SUPPORTED_TARGETS.contains(&&*config.target);
         let has_lsan = util::LSAN_SUPPORTED_TARGETS.contains(&&*config.target);
         let has_msan = util::MSAN_SUPPORTED_TARGETS.contains(&&*config.target);
         let has_tsan = util::TSAN_SUPPORTED_TARGETS.contains(&&*config.target);
@@ -89,6 +91,14 @@ impl EarlyProps {
                     props.ignore = true;
                 }
 
+                if !has_cfi && config.parse_name_directive(ln, "needs-sanitizer-cfi") {
+                    props.ignore = true;
+                }
+
+                if !has_hwasan && config.parse_name_directive(ln, "needs-sanitizer-hwaddress") {
+                    props.ignore = true;
+                }
+
                 if !has_lsan && config.parse_name_directive(ln, "needs-sanitizer-leak") {
                     props.ignore = true;
                 }
@@ -848,6 +858,7 @@ impl Config {
             (self.target != self.host && name == "cross-compile") ||
             (name == "endian-big" && util::is_big_endian(&self.target)) ||
             (self.remote_test_client.is_some() && name == "remote") ||
+            self.codegen_backend.as_deref() == Some(name) ||    // codegen backend
             match self.compare_mode {
                 Some(CompareMode::Nll) => name == "compare-mode-nll",
                 Some(CompareMode::Polonius) => name == "compare-mode-polonius",
@@ -202,20 +202,40 @@ fn debugger() {
     assert!(parse_rs(&config, "// ignore-lldb").ignore);
 }
 
+#[test]
+fn codegen_backend() {
+    let mut config = config();
+    config.codegen_backend = None;
+    assert!(!parse_rs(&config, "// ignore-cranelift").ignore);
+
+    config.codegen_backend = Some("cranelift".to_owned());
+    assert!(parse_rs(&config, "// ignore-cranelift").ignore);
+    assert!(!parse_rs(&config, "// only-cranelift").ignore);
+}
+
 #[test]
 fn sanitizers() {
     let mut config = config();
 
     // Target that supports all sanitizers:
-    config.target = "x86_64-unknown-linux-gnu".to_owned();
+    config.target = "aarch64-unknown-linux-gnu".to_owned();
     assert!(!parse_rs(&config, "// needs-sanitizer-address").ignore);
+    assert!(!parse_rs(&config, "// needs-sanitizer-cfi").ignore);
+    assert!(!parse_rs(&config, "// needs-sanitizer-hwaddress").ignore);
     assert!(!parse_rs(&config, "// needs-sanitizer-leak").ignore);
     assert!(!parse_rs(&config, "// needs-sanitizer-memory").ignore);
     assert!(!parse_rs(&config, "// needs-sanitizer-thread").ignore);
 
+    // Target that supports all sanitizers but hwaddress:
+    config.target = "x86_64-unknown-linux-gnu".to_owned();
+    assert!(!parse_rs(&config, "// needs-sanitizer-cfi").ignore);
+    assert!(parse_rs(&config, "// needs-sanitizer-hwaddress").ignore);
+
     // Target that doesn't support sanitizers:
     config.target = "wasm32-unknown-emscripten".to_owned();
     assert!(parse_rs(&config, "// needs-sanitizer-address").ignore);
+    assert!(parse_rs(&config, "// needs-sanitizer-cfi").ignore);
+    assert!(parse_rs(&config, "// needs-sanitizer-hwaddress").ignore);
     assert!(parse_rs(&config, "// needs-sanitizer-leak").ignore);
     assert!(parse_rs(&config, "// needs-sanitizer-memory").ignore);
     assert!(parse_rs(&config, "// needs-sanitizer-thread").ignore);
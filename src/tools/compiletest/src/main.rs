@@ -67,8 +67,9 @@ pub fn parse_config(args: Vec<String>) -> Config {
             "",
             "mode",
             "which sort of compile tests to run",
-            "compile-fail | run-fail | run-pass-valgrind | pretty | debug-info | codegen | rustdoc \
-             codegen-units | incremental | run-make | ui | js-doc-test | mir-opt | assembly",
+            "compile-fail | run-fail | run-pass-valgrind | pretty | debug-info | codegen | rustdoc | \
+             rustdoc-json | codegen-units | incremental | run-make | ui | js-doc-test | mir-opt | \
+             assembly",
         )
         .optopt(
             "",
@@ -122,6 +123,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
             "mode describing what file the actual ui output will be compared to",
             "COMPARE MODE",
         )
+        .optopt("", "codegen-backend", "the codegen backend to compile tests with", "NAME")
         .optflag(
             "",
             "rustfix-coverage",
@@ -213,6 +215,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
         runtool: matches.opt_str("runtool"),
         host_rustcflags: matches.opt_str("host-rustcflags"),
         target_rustcflags: matches.opt_str("target-rustcflags"),
+        codegen_backend: matches.opt_str("codegen-backend"),
         target,
         host: opt_str2(matches.opt_str("host")),
         cdb,
@@ -270,6 +273,7 @@ pub fn log_config(config: &Config) {
     logv(c, format!("runtool: {}", opt_str(&config.runtool)));
     logv(c, format!("host-rustcflags: {}", opt_str(&config.host_rustcflags)));
     logv(c, format!("target-rustcflags: {}", opt_str(&config.target_rustcflags)));
+    logv(c, format!("codegen-backend: {}", opt_str(&config.codegen_backend)));
     logv(c, format!("target: {}", config.target));
     logv(c, format!("host: {}", config.host));
     logv(c, format!("android-cross-path: {:?}", config.android_cross_path.display()));
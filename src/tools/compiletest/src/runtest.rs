@@ -3,7 +3,7 @@
 use crate::common::{expected_output_path, UI_EXTENSIONS, UI_FIXED, UI_STDERR, UI_STDOUT};
 use crate::common::{output_base_dir, output_base_name, output_testname_unique};
 use crate::common::{Assembly, Incremental, JsDocTest, MirOpt, RunMake, Ui};
-use crate::common::{Codegen, CodegenUnits, DebugInfo, Debugger, Rustdoc};
+use crate::common::{Codegen, CodegenUnits, DebugInfo, Debugger, Rustdoc, RustdocJson};
 use crate::common::{CompareMode, FailMode, PassMode};
 use crate::common::{CompileFail, Pretty, RunFail, RunPassValgrind};
 use crate::common::{Config, TestPaths};
@@ -341,6 +341,7 @@ impl<'test> TestCx<'test> {
             DebugInfo => self.run_debuginfo_test(),
             Codegen => self.run_codegen_test(),
             Rustdoc => self.run_rustdoc_test(),
+            RustdocJson => self.run_rustdoc_json_test(),
             CodegenUnits => self.run_codegen_units_test(),
             Incremental => self.run_incremental_test(),
             RunMake => self.run_rmake_test(),
@@ -1597,6 +1598,10 @@ impl<'test> TestCx<'test> {
             .arg(&self.testpaths.file)
             .args(&self.props.compile_flags);
 
+        if self.config.mode == RustdocJson {
+            rustdoc.arg("--output-format").arg("json");
+        }
+
         if let Some(ref linker) = self.config.linker {
             rustdoc.arg(format!("-Clinker={}", linker));
         }
@@ -1918,6 +1923,10 @@ impl<'test> TestCx<'test> {
             if self.config.mode == CodegenUnits {
                 rustc.args(&["-Z", "human_readable_cgu_names"]);
             }
+
+            if let Some(ref backend) = self.config.codegen_backend {
+                rustc.arg(&format!("-Zcodegen-backend={}", backend));
+            }
         }
 
         match self.config.mode {
@@ -1955,8 +1964,8 @@ impl<'test> TestCx<'test> {
 
                 rustc.arg(dir_opt);
             }
-            RunFail | RunPassValgrind | Pretty | DebugInfo | Codegen | Rustdoc | RunMake
-            | CodegenUnits | JsDocTest | Assembly => {
+            RunFail | RunPassValgrind | Pretty | DebugInfo | Codegen | Rustdoc | RustdocJson
+            | RunMake | CodegenUnits | JsDocTest | Assembly => {
                 // do not use JSON output
             }
         }
@@ -2327,6 +2336,48 @@ impl<'test> TestCx<'test> {
         }
     }
 
+    fn run_rustdoc_json_test(&self) {
+        assert!(self.revision.is_none(), "revisions not relevant here");
+
+        let out_dir = self.output_base_dir();
+        let _ = fs::remove_dir_all(&out_dir);
+        create_dir_all(&out_dir).unwrap();
+
+        let proc_res = self.document(&out_dir);
+        if !proc_res.status.success() {
+            self.fatal_proc_rec("rustdoc failed!", &proc_res);
+        }
+
+        let root = self.config.find_rust_src_root().unwrap();
+        let mut json_out = out_dir.join(self.testpaths.file.file_stem().unwrap());
+        json_out.set_extension("json");
+
+        // Every test is checked against the schema...
+        let res = self.cmd2procres(
+            Command::new(&self.config.docck_python)
+                .arg(root.join("src/test/rustdoc-json/check_missing_items.py"))
+                .arg(&json_out),
+        );
+        if !res.status.success() {
+            self.fatal_proc_rec("check_missing_items failed!", &res);
+        }
+
+        // ...and against the expected output, if it has any.
+        let expected = self.testpaths.file.with_extension("expected");
+        if expected.exists() {
+            let res = self.cmd2procres(
+                Command::new(&self.config.docck_python)
+                    .arg(root.join("src/test/rustdoc-json/compare.py"))
+                    .arg(&expected)
+                    .arg(&json_out)
+                    .arg(self.testpaths.file.parent().unwrap()),
+            );
+            if !res.status.success() {
+                self.fatal_proc_rec("compare failed!", &res);
+            }
+        }
+    }
+
     fn get_lines<P: AsRef<Path>>(
         &self,
         path: &P,